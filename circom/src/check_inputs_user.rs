@@ -0,0 +1,46 @@
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches};
+use compiler::input_schema;
+use json::JsonValue;
+use crate::VERSION;
+
+pub const CHECK_INPUTS_COMMAND: &str = "check_inputs";
+
+pub fn check_inputs() -> Result<(), ()> {
+    let matches = view();
+    let schema = read_json(matches.value_of("schema").unwrap())?;
+    let input_file = matches.value_of("input").unwrap();
+    let input = read_json(input_file)?;
+    let errors = input_schema::validate(&schema, &input);
+    for error in &errors {
+        eprintln!("{} {}", Colour::Red.paint("Invalid input:"), error);
+    }
+    if errors.is_empty() {
+        println!("{} {}", Colour::Green.paint("Valid input:"), input_file);
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+fn read_json(file: &str) -> Result<JsonValue, ()> {
+    let src = std::fs::read_to_string(file)
+        .map_err(|_| eprintln!("{}", Colour::Red.paint(format!("Could not read the file {}", file))))?;
+    json::parse(&src).map_err(|error| {
+        eprintln!("{}", Colour::Red.paint(format!("The file {} is not valid JSON: {}", file, error)))
+    })
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom check_inputs")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Checks an input file against the inputs schema written by the compiler")
+        .arg(
+            Arg::with_name("schema")
+                .required(true)
+                .help("Schema of the inputs, the <name>_inputs.schema.json file of the circuit"),
+        )
+        .arg(Arg::with_name("input").required(true).help("Input file to check"))
+        .get_matches_from(std::env::args().skip(1))
+}
@@ -29,6 +29,7 @@ pub struct CompilerConfig {
     pub constraint_assert_disabled_flag: bool,
     pub vcp: VCP,
     pub no_asm_flag: bool,
+    pub c_lib_flag: bool,
     pub prime: String,
 }
 
@@ -44,6 +45,7 @@ pub fn compile(config: CompilerConfig) -> Result<(), ()> {
                 wat_flag: config.wat_flag,
                 constraint_assert_disabled_flag: config.constraint_assert_disabled_flag,
                 no_asm_flag: config.no_asm_flag,
+                c_lib_flag: config.c_lib_flag,
                 cvm_multi_assign_flag: config.cvm_multi_assign_flag,
             },
             VERSION
@@ -100,6 +102,16 @@ pub fn compile(config: CompilerConfig) -> Result<(), ()> {
                     );
                 }
             }
+            if config.c_lib_flag {
+                println!(
+                    "{} {}/{}, {} and {}",
+                    Colour::Green.paint("Written successfully:"),
+                    &config.c_folder,
+                    "circom_witness.h".to_string(),
+                    "circom_witness.cpp".to_string(),
+                    "circom_witness_test.c".to_string()
+                );
+            }
         }
         match (config.wat_flag, config.wasm_flag) {
             (true, true) => {
@@ -202,3 +214,291 @@ fn wat_to_wasm(wat_file: &str, wasm_file: &str) -> Result<(), Report> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compiler::num_bigint::{BigInt, Sign};
+    use compiler::witness_calculator::WitnessCalculator;
+    use program_structure::constants::UsefulConstants;
+    use std::convert::TryInto;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn c_config(folder: &Path, vcp: VCP) -> CompilerConfig {
+        let path = |name: &str| folder.join(name).to_str().unwrap().to_string();
+        CompilerConfig {
+            cvm_folder: String::new(),
+            cvm_name: String::new(),
+            cvm_file: String::new(),
+            js_folder: String::new(),
+            wasm_name: String::new(),
+            wat_file: String::new(),
+            wasm_file: String::new(),
+            c_folder: path(""),
+            c_run_name: "circuit".to_string(),
+            c_file: path("circuit.cpp"),
+            dat_file: path("circuit.dat"),
+            wat_flag: false,
+            wasm_flag: false,
+            c_flag: true,
+            cvm_flag: false,
+            cvm_multi_assign_flag: false,
+            debug_output: false,
+            produce_input_log: false,
+            constraint_assert_disabled_flag: false,
+            vcp,
+            no_asm_flag: true,
+            c_lib_flag: true,
+            prime: "bn128".to_string(),
+        }
+    }
+
+    fn run_test_harness(folder: &Path, input: &str) -> std::process::Output {
+        std::fs::write(folder.join("input.txt"), input).unwrap();
+        Command::new(folder.join("circuit_test"))
+            .current_dir(folder)
+            .args(["circuit.dat", "input.txt", "witness.wtns"])
+            .output()
+            .unwrap()
+    }
+
+    fn tools_are_missing() -> bool {
+        let tools = ["make", "g++", "cc"];
+        if tools.iter().any(|tool| Command::new(tool).arg("--version").output().is_err()) {
+            eprintln!("skipped: building the library needs make, g++ and cc");
+            return true;
+        }
+        false
+    }
+
+    // Compiles the circuit src as a C library in folder, with its test
+    // harness, and gives the instance of the circuit
+    fn build_library(folder: &Path, src: &str) -> VCP {
+        std::fs::create_dir_all(folder).unwrap();
+        let circuit = folder.join("circuit.circom");
+        std::fs::write(&circuit, src).unwrap();
+        let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let (mut program, _) =
+            parser::run_parser(circuit.to_str().unwrap().to_string(), "2.2.2", vec![], &prime, false, None)
+                .unwrap_or_else(|_| panic!("The circuit could not be parsed"));
+        type_analysis::check_types::check_types(&mut program).unwrap_or_else(|_| panic!("Type errors in the circuit"));
+        let (_, vcp) = constraint_generation::instantiate(program, &"bn128".to_string())
+            .unwrap_or_else(|_| panic!("The circuit could not be instantiated"));
+        compile(c_config(folder, vcp.clone())).unwrap();
+        let make = Command::new("make").current_dir(folder).arg("circuit_test").output().unwrap();
+        assert!(make.status.success(), "{}", String::from_utf8_lossy(&make.stderr));
+        vcp
+    }
+
+    // The values of the witness, as 32 bytes in little endian
+    fn witness_elements(folder: &Path) -> Vec<Vec<u8>> {
+        let witness = std::fs::read(folder.join("witness.wtns")).unwrap();
+        let u32_at = |position: usize| u32::from_le_bytes(witness[position..position + 4].try_into().unwrap());
+        let u64_at = |position: usize| u64::from_le_bytes(witness[position..position + 8].try_into().unwrap());
+        let mut position = 12;
+        while u32_at(position) != 2 {
+            position += 12 + u64_at(position + 4) as usize;
+        }
+        let values = &witness[position + 12..position + 12 + u64_at(position + 4) as usize];
+        values.chunks(32).map(|value| value.to_vec()).collect()
+    }
+
+    // The lowest 64 bits of each value of the witness
+    fn witness_values(folder: &Path) -> Vec<u64> {
+        let elements = witness_elements(folder);
+        elements.iter().map(|value| u64::from_le_bytes(value[..8].try_into().unwrap())).collect()
+    }
+
+    // Runs the C library of the circuit in folder and the witness calculator
+    // of the compiler with the same inputs, given by name with their values
+    // in order, and checks that both compute the same witness
+    fn assert_same_witness(folder: &Path, vcp: &VCP, inputs: &[(&str, &[u64])]) {
+        let lines: Vec<String> = inputs
+            .iter()
+            .map(|(name, values)| format!("{} {}\n", name, values.iter().map(u64::to_string).collect::<Vec<_>>().join(" ")))
+            .collect();
+        let output = run_test_harness(folder, &lines.concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let expected: Vec<BigInt> =
+            witness_elements(folder).iter().map(|value| BigInt::from_bytes_le(Sign::Plus, value)).collect();
+
+        let config = Config {
+            debug_output: false,
+            produce_input_log: false,
+            wat_flag: false,
+            constraint_assert_disabled_flag: false,
+            no_asm_flag: true,
+            c_lib_flag: true,
+            cvm_multi_assign_flag: false,
+        };
+        let circuit = compiler_interface::run_compiler(vcp.clone(), config, VERSION).unwrap();
+        let calculator = WitnessCalculator::new(&circuit);
+        let mut values = Vec::new();
+        for (name, given) in inputs {
+            let is_element = |input: &String| input == name || input.starts_with(&format!("{}[", name));
+            let positions = calculator.main_inputs().into_iter().filter(|(input, _)| is_element(input));
+            values.extend(positions.zip(given.iter()).map(|((_, position), value)| (position, BigInt::from(*value))));
+        }
+        let signals = calculator.calculate(&values).unwrap_or_else(|error| panic!("{}", error));
+        let witness: Vec<BigInt> =
+            circuit.c_producer.witness_to_signal_list.iter().map(|signal| signals[*signal].clone()).collect();
+        assert_eq!(witness, expected);
+    }
+
+    #[test]
+    fn library_returns_the_failed_asserts() {
+        if tools_are_missing() {
+            return;
+        }
+        let folder = std::env::temp_dir().join(format!("circom_c_lib_{}", std::process::id()));
+        build_library(
+            &folder,
+            "pragma circom 2.0.0;\n\
+             template Double() {\n\
+             \x20   signal input in;\n\
+             \x20   signal output out;\n\
+             \x20   assert(in != 3);\n\
+             \x20   out <== 2 * in;\n\
+             }\n\
+             component main = Double();\n",
+        );
+
+        let valid = run_test_harness(&folder, "in 4\n");
+        assert!(valid.status.success(), "{}", String::from_utf8_lossy(&valid.stderr));
+        // the witness is 1 and out, the input is removed by the simplification
+        assert_eq!(witness_values(&folder), vec![1, 8]);
+
+        let failed = run_test_harness(&folder, "in 3\n");
+        assert_eq!(failed.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&failed.stderr);
+        assert!(stderr.contains("run: an assert of the circuit failed"), "{}", stderr);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn slices_are_copied_by_the_witness_calculator() {
+        if tools_are_missing() {
+            return;
+        }
+        let folder = std::env::temp_dir().join(format!("circom_c_slices_{}", std::process::id()));
+        let vcp = build_library(
+            &folder,
+            r#"pragma circom 2.2.2;
+            template Pairs() {
+                signal input in[4];
+                signal output out[3][2];
+                for (var i = 0; i < 3; i++) {
+                    out[i][0] <== in[i] * 2;
+                    out[i][1] <== in[i + 1] + 1;
+                }
+            }
+            template Main() {
+                signal input m[3][4];
+                signal output a[2][2];
+                signal output b[2];
+                signal output c[2][2];
+                signal output d[2];
+                var v[3][4];
+                for (var i = 0; i < 3; i++) {
+                    for (var j = 0; j < 4; j++) {
+                        v[i][j] = i * 10 + j;
+                    }
+                }
+                var w[2][2] = v[1:3][2:4];
+                a <== m[0:2][1:3];
+                component p = Pairs();
+                p.in[0:2] <== m[2][0:2];
+                p.in[2:4] <== [w[0][0], w[1][1]];
+                b <== p.out[1:3][1];
+                c <== p.out[0:2][0:2];
+                signal e[2][2];
+                e[0:2][1] <== b;
+                e[0:2][0] <== [1, 2];
+                d[0:2] <== e[1][0:2];
+            }
+            component main = Main();
+            "#,
+        );
+        let output = run_test_harness(&folder, "m 1 2 3 4 5 6 7 8 9 10 11 12\n");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        // 1 and the outputs a, b, c and d come first in the witness
+        let values = witness_values(&folder);
+        assert_eq!(values[..13], [1, 2, 3, 6, 7, 13, 24, 18, 11, 20, 13, 2, 24]);
+        assert_same_witness(&folder, &vcp, &[("m", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])]);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn compiler_computes_the_witness_of_the_library() {
+        if tools_are_missing() {
+            return;
+        }
+        let folder = std::env::temp_dir().join(format!("circom_c_witness_{}", std::process::id()));
+        let vcp = build_library(
+            &folder,
+            r#"pragma circom 2.2.2;
+            bus Point() {
+                signal x;
+                signal y;
+            }
+            function weighted(a, n) {
+                var s = 0;
+                for (var i = 0; i < n; i++) {
+                    s += a[i] * (i + 1);
+                }
+                return s;
+            }
+            function powers(v) {
+                return [v, v * v, v * v * v];
+            }
+            template Scale(k) {
+                input Point() p;
+                output Point() q;
+                signal output r[k];
+                q.x <== p.x * k;
+                q.y <== p.y * p.x;
+                for (var i = 0; i < k; i++) {
+                    r[i] <== q.y + i;
+                }
+            }
+            template Weigh(n) {
+                signal input in[n];
+                signal output out;
+                var expected = weighted(in, n);
+                out <-- weighted(in, n);
+                out === expected;
+            }
+            template Main() {
+                signal input a[3];
+                signal input b;
+                signal input m[2][3];
+                signal output o[5];
+                signal output column[2];
+                component s[2];
+                Point() p[2];
+                for (var i = 0; i < 2; i++) {
+                    s[i] = Scale(i + 2);
+                    p[i].x <== a[i];
+                    p[i].y <== b;
+                    s[i].p <== p[i];
+                }
+                o[0] <== s[0].q.x + s[1].q.y;
+                o[1] <== s[1].r[2] - s[0].r[1];
+                component w = Weigh(3);
+                w.in <== a;
+                var t[3] = powers(5);
+                o[2] <== w.out + t[2];
+                o[3] <-- (b \ 3) * a[1];
+                column <== m[0:2][2];
+                signal u <-- weighted([a[0], b], 2);
+                o[4] <== u * a[1];
+            }
+            component main = Main();
+            "#,
+        );
+        assert_same_witness(&folder, &vcp, &[("a", &[3, 5, 7]), ("b", &[11]), ("m", &[1, 2, 3, 4, 5, 6])]);
+        assert_same_witness(&folder, &vcp, &[("a", &[0, 1, 2]), ("b", &[2]), ("m", &[0, 0, 9, 0, 0, 8])]);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
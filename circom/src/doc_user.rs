@@ -0,0 +1,706 @@
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches};
+use constraint_generation::{count_constraints, ConstraintCount};
+use parser::MainComponentSpec;
+use program_structure::ast::{Access, Expression, LogArgument, SignalType, Statement, VariableType};
+use program_structure::utils::constants::{self, UsefulConstants, PRIME_NAMES};
+use program_structure::error_definition::Report;
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::program_archive::ProgramArchive;
+use program_structure::wire_data::{WireInfo, WireType};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use type_analysis::check_types::check_types;
+use crate::VERSION;
+
+pub const DOC_COMMAND: &str = "doc";
+
+// Lines of a doc comment starting with this give the parameters used to
+// count the constraints of a template
+const PARAMS_TAG: &str = "@params";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+struct Wire {
+    name: String,
+    bus: Option<String>,
+    dimensions: Vec<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Default)]
+struct Wires {
+    inputs: Vec<Wire>,
+    outputs: Vec<Wire>,
+    fields: Vec<Wire>,
+}
+
+struct Page {
+    kind: &'static str,
+    name: String,
+    params: Vec<String>,
+    file: String,
+    doc: Vec<String>,
+    wires: Wires,
+    instantiates: BTreeSet<String>,
+    instances: Vec<(String, ConstraintCount)>,
+    // Why the constraints of the template are not counted
+    not_counted: Option<String>,
+}
+
+impl Page {
+    fn folder(kind: &str) -> &'static str {
+        match kind {
+            "template" => "templates",
+            "function" => "functions",
+            _ => "buses",
+        }
+    }
+
+    // Namespaced names like a::Num2Bits are written as a.Num2Bits
+    fn path(kind: &str, name: &str, format: Format) -> String {
+        format!("{}/{}.{}", Page::folder(kind), name.replace("::", "."), format.extension())
+    }
+
+    fn summary(&self) -> &str {
+        description(self).into_iter().find(|line| !line.trim().is_empty()).map_or("", |line| line.trim())
+    }
+}
+
+// Writes a page for every template, function and bus of a project or
+// library, and an index with all of them
+pub fn document_project() -> Result<(), ()> {
+    let matches = view();
+    let prime = matches.value_of("prime").unwrap().to_string();
+    if !constants::is_named_prime(&prime) {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!("invalid prime: {} is not one of {}", prime, PRIME_NAMES.join(", ")))
+        );
+        return Result::Err(());
+    }
+    let format = if matches.value_of("format") == Some("html") { Format::Html } else { Format::Markdown };
+    let output = PathBuf::from(matches.value_of("output").unwrap());
+    let link_libraries: Vec<PathBuf> =
+        matches.values_of("link_libraries").map_or(Vec::new(), |values| values.map(PathBuf::from).collect());
+    let field = UsefulConstants::new(&prime).get_p().clone();
+    let input = matches.value_of("input").unwrap().to_string();
+    let program = match parser::run_library_parser(input, VERSION, link_libraries, &field) {
+        Result::Err((file_library, reports)) => {
+            Report::print_reports(&reports, &file_library);
+            return Result::Err(());
+        }
+        Result::Ok((program, warnings)) => {
+            Report::print_reports(&warnings, &program.file_library);
+            program
+        }
+    };
+
+    let mut failed = false;
+    let mut pages = collect_pages(&program);
+    if matches.is_present("constraints") {
+        for page in pages.iter_mut().filter(|page| page.kind == "template") {
+            let tagged = tagged_inputs(&program, &page.name);
+            if !tagged.is_empty() && !instance_calls(page).is_empty() {
+                let reason = format!("it has inputs with tags ({}), which the main component cannot have", tagged.join(", "));
+                let message = format!("The constraints of {} are not counted: {}", page.name, reason);
+                println!("{}", Colour::Yellow.paint(message));
+                page.not_counted = Some(reason);
+                continue;
+            }
+            for call in instance_calls(page) {
+                match instantiate(&program, &call, &prime) {
+                    Result::Ok(count) => page.instances.push((call, count)),
+                    Result::Err(()) => {
+                        eprintln!("{}", Colour::Red.paint(format!("Could not count the constraints of {}", call)));
+                        failed = true;
+                    }
+                }
+            }
+        }
+    }
+    for kind in ["template", "function", "bus"] {
+        let folder = output.join(Page::folder(kind));
+        if pages.iter().any(|page| page.kind == kind) {
+            create_folder(&folder)?;
+        }
+    }
+    for page in &pages {
+        let text = match format {
+            Format::Markdown => markdown_page(page, &program),
+            Format::Html => html_page(page, &program),
+        };
+        write_file(&output.join(Page::path(page.kind, &page.name, format)), &text)?;
+    }
+    let index = output.join(format!("index.{}", format.extension()));
+    create_folder(&output)?;
+    let text = match format {
+        Format::Markdown => markdown_index(&pages),
+        Format::Html => html_index(&pages),
+    };
+    write_file(&index, &text)?;
+    println!("{} {}", Colour::Green.paint("Written successfully:"), index.display());
+    println!("{} definitions documented", pages.len());
+    if failed {
+        Result::Err(())
+    } else {
+        Result::Ok(())
+    }
+}
+
+fn collect_pages(program: &ProgramArchive) -> Vec<Page> {
+    let mut pages = Vec::new();
+    for (name, template) in program.get_templates() {
+        let mut page = new_page(
+            program,
+            "template",
+            name,
+            template.get_name_of_params(),
+            template.get_file_id(),
+            template.get_param_location(),
+        );
+        collect_statement(program, template.get_body(), &mut page);
+        pages.push(page);
+    }
+    for (name, function) in program.get_functions() {
+        let page = new_page(
+            program,
+            "function",
+            name,
+            function.get_name_of_params(),
+            function.get_file_id(),
+            function.get_param_location(),
+        );
+        pages.push(page);
+    }
+    for (name, bus) in program.get_buses() {
+        let mut page = new_page(
+            program,
+            "bus",
+            name,
+            bus.get_name_of_params(),
+            bus.get_file_id(),
+            bus.get_param_location(),
+        );
+        collect_statement(program, bus.get_body(), &mut page);
+        pages.push(page);
+    }
+    pages.sort_by(|a, b| a.name.cmp(&b.name));
+    pages
+}
+
+fn new_page(
+    program: &ProgramArchive,
+    kind: &'static str,
+    name: &str,
+    params: &[String],
+    file_id: FileID,
+    param_location: FileLocation,
+) -> Page {
+    let file_library = program.get_file_library();
+    let doc = file_library
+        .get_source(file_id)
+        .and_then(|source| parser::doc_comment(source, param_location.start))
+        .map_or(Vec::new(), |doc| doc.lines().map(String::from).collect());
+    let file = file_library.get_name(file_id).unwrap_or("").trim_matches('"');
+    Page {
+        kind,
+        name: name.to_string(),
+        params: params.to_vec(),
+        file: crate::manifest_user::relative_to_current_dir(file),
+        doc,
+        wires: Wires::default(),
+        instantiates: BTreeSet::new(),
+        instances: Vec::new(),
+        not_counted: None,
+    }
+}
+
+// Templates without parameters are always instantiated, the others with
+// the values given in the @params lines of their doc comments
+fn instance_calls(page: &Page) -> Vec<String> {
+    let values: Vec<&str> =
+        page.doc.iter().filter_map(|line| line.trim().strip_prefix(PARAMS_TAG)).map(|values| values.trim()).collect();
+    if page.params.is_empty() {
+        vec![format!("{}()", page.name)]
+    } else {
+        values.iter().map(|values| format!("{}({})", page.name, values)).collect()
+    }
+}
+
+// Inputs of the template with tags, or with tags in the fields of their buses
+fn tagged_inputs(program: &ProgramArchive, template: &str) -> Vec<String> {
+    fn has_tags(program: &ProgramArchive, wires: &WireInfo, name: &str) -> bool {
+        let wire = &wires[name];
+        match wire.get_type() {
+            _ if !wire.get_tags().is_empty() => true,
+            WireType::Bus(bus) => {
+                let fields = program.get_bus_data(&bus).get_fields();
+                fields.keys().any(|field| has_tags(program, fields, field))
+            }
+            WireType::Signal => false,
+        }
+    }
+    let inputs = program.get_template_data(template).get_inputs();
+    let mut tagged: Vec<String> = inputs.keys().filter(|input| has_tags(program, inputs, input)).cloned().collect();
+    tagged.sort();
+    tagged
+}
+
+// Executes the template as the main component of the program
+fn instantiate(program: &ProgramArchive, call: &str, prime: &String) -> Result<ConstraintCount, ()> {
+    let mut program = program.clone();
+    let field = UsefulConstants::new(prime).get_p().clone();
+    let spec = MainComponentSpec { call: call.to_string(), public: Vec::new() };
+    let (file_id, main) = parser::parse_main_call(&spec, &mut program.file_library, &field)
+        .map_err(|reports| Report::print_reports(&reports, &program.file_library))?;
+    program.set_main_component(file_id, main);
+    check_types(&mut program).map_err(|reports| Report::print_reports(&reports, &program.file_library))?;
+    let file_library = program.file_library.clone();
+    count_constraints(program, prime).map_err(|reports| Report::print_reports(&reports, &file_library))
+}
+
+fn collect_statement(program: &ProgramArchive, stmt: &Statement, page: &mut Page) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { cond, if_case, else_case, .. } => {
+            collect_expression(program, cond, page);
+            collect_statement(program, if_case, page);
+            if let Some(else_case) = else_case {
+                collect_statement(program, else_case, page);
+            }
+        }
+        While { cond, stmt, .. } => {
+            collect_expression(program, cond, page);
+            collect_statement(program, stmt, page);
+        }
+        Return { value, .. } => collect_expression(program, value, page),
+        InitializationBlock { initializations, .. } => {
+            for init in initializations {
+                collect_statement(program, init, page);
+            }
+        }
+        Declaration { meta, xtype, name, dimensions, .. } => {
+            let (bus, signal_type, tags) = match xtype {
+                VariableType::Signal(signal_type, tags) => (None, signal_type, tags),
+                VariableType::Bus(bus, signal_type, tags) => (Some(bus.clone()), signal_type, tags),
+                _ => return,
+            };
+            let source = program.get_file_library().get_source(meta.get_file_id()).unwrap_or("");
+            let dimensions = dimensions.iter().map(|dimension| source_text(source, dimension)).collect();
+            let wire = Wire { name: name.clone(), bus, dimensions, tags: tags.clone() };
+            match signal_type {
+                SignalType::Input => page.wires.inputs.push(wire),
+                SignalType::Output => page.wires.outputs.push(wire),
+                SignalType::Intermediate if page.kind == "bus" => page.wires.fields.push(wire),
+                SignalType::Intermediate => {}
+            }
+        }
+        Substitution { access, rhe, .. } => {
+            collect_access(program, access, page);
+            collect_expression(program, rhe, page);
+        }
+        MultSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+            collect_expression(program, lhe, page);
+            collect_expression(program, rhe, page);
+        }
+        UnderscoreSubstitution { rhe, .. } => collect_expression(program, rhe, page),
+        LogCall { args, .. } => collect_log_arguments(program, args, page),
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_statement(program, stmt, page);
+            }
+        }
+        Assert { arg, message, .. } => {
+            collect_expression(program, arg, page);
+            collect_log_arguments(program, message, page);
+        }
+    }
+}
+
+fn collect_expression(program: &ProgramArchive, expr: &Expression, page: &mut Page) {
+    use Expression::*;
+    match expr {
+        InfixOp { lhe, rhe, .. } => {
+            collect_expression(program, lhe, page);
+            collect_expression(program, rhe, page);
+        }
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => collect_expression(program, rhe, page),
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            collect_expression(program, cond, page);
+            collect_expression(program, if_true, page);
+            collect_expression(program, if_false, page);
+        }
+        Variable { access, .. } => collect_access(program, access, page),
+        Number(..) => {}
+        Call { id, args, .. } | AnonymousComp { id, params: args, .. } => {
+            if program.contains_template(id) {
+                page.instantiates.insert(id.clone());
+            }
+            for arg in args {
+                collect_expression(program, arg, page);
+            }
+        }
+        BusCall { args, .. } => {
+            for arg in args {
+                collect_expression(program, arg, page);
+            }
+        }
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
+            for value in values {
+                collect_expression(program, value, page);
+            }
+        }
+        UniformArray { value, dimension, .. } => {
+            collect_expression(program, value, page);
+            collect_expression(program, dimension, page);
+        }
+    }
+}
+
+fn collect_access(program: &ProgramArchive, access: &[Access], page: &mut Page) {
+    for acc in access {
+        match acc {
+            Access::ArrayAccess(index) => collect_expression(program, index, page),
+            Access::ArraySlice(start, length) => {
+                collect_expression(program, start, page);
+                collect_expression(program, length, page);
+            }
+            Access::ComponentAccess(_) => {}
+        }
+    }
+}
+
+fn collect_log_arguments(program: &ProgramArchive, args: &[LogArgument], page: &mut Page) {
+    for arg in args {
+        if let LogArgument::LogExp(expr) = arg {
+            collect_expression(program, expr, page);
+        }
+    }
+}
+
+// Dimensions are shown as they are written in the code
+fn source_text(source: &str, expr: &Expression) -> String {
+    let location = expr.get_meta().location.clone();
+    source.get(location).map_or("?".to_string(), |text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// Lines of the doc comment that are rendered, without the @params lines
+fn description(page: &Page) -> Vec<&str> {
+    page.doc.iter().map(|line| line.as_str()).filter(|line| !line.trim().starts_with(PARAMS_TAG)).collect()
+}
+
+fn header(page: &Page) -> String {
+    format!("{} {}({})", page.kind, page.name, page.params.join(", "))
+}
+
+fn wire_type(wire: &Wire, program: &ProgramArchive, page: &Page, format: Format) -> String {
+    match &wire.bus {
+        Some(bus) if program.contains_bus(bus) => link(bus, &relative_path(page, "bus", bus, format), format),
+        Some(bus) => bus.clone(),
+        None => "signal".to_string(),
+    }
+}
+
+// Path of a page from the folder of another one
+fn relative_path(from: &Page, kind: &str, name: &str, format: Format) -> String {
+    if Page::folder(from.kind) == Page::folder(kind) {
+        Page::path(kind, name, format).split_once('/').unwrap().1.to_string()
+    } else {
+        format!("../{}", Page::path(kind, name, format))
+    }
+}
+
+fn link(text: &str, path: &str, format: Format) -> String {
+    match format {
+        Format::Markdown => format!("[{}]({})", text, path),
+        Format::Html => format!("<a href=\"{}\">{}</a>", escape(path), escape(text)),
+    }
+}
+
+fn wire_rows(page: &Page, program: &ProgramArchive, format: Format) -> Vec<(&'static str, Vec<[String; 4]>)> {
+    let sections = [("Inputs", &page.wires.inputs), ("Outputs", &page.wires.outputs), ("Fields", &page.wires.fields)];
+    sections
+        .iter()
+        .filter(|(_, wires)| !wires.is_empty())
+        .map(|(title, wires)| {
+            let rows = wires
+                .iter()
+                .map(|wire| {
+                    let dimensions: String = wire.dimensions.iter().map(|dimension| format!("[{}]", dimension)).collect();
+                    [wire.name.clone(), wire_type(wire, program, page, format), dimensions, wire.tags.join(", ")]
+                })
+                .collect();
+            (*title, rows)
+        })
+        .collect()
+}
+
+fn markdown_page(page: &Page, program: &ProgramArchive) -> String {
+    let mut text = format!("# `{}`\n\nDefined in `{}`.\n", header(page), page.file);
+    let description = description(page);
+    if !description.is_empty() {
+        text.push('\n');
+        for line in description {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    if !page.params.is_empty() {
+        text.push_str("\n## Parameters\n\n");
+        for param in &page.params {
+            text.push_str(&format!("- `{}`\n", param));
+        }
+    }
+    for (title, rows) in wire_rows(page, program, Format::Markdown) {
+        text.push_str(&format!("\n## {}\n\n| Name | Type | Dimensions | Tags |\n|---|---|---|---|\n", title));
+        for [name, wire_type, dimensions, tags] in rows {
+            text.push_str(&format!("| `{}` | {} | {} | {} |\n", name, wire_type, code(&dimensions, Format::Markdown), tags));
+        }
+    }
+    if !page.instantiates.is_empty() {
+        text.push_str("\n## Instantiates\n\n");
+        for template in &page.instantiates {
+            let path = relative_path(page, "template", template, Format::Markdown);
+            text.push_str(&format!("- {}\n", link(template, &path, Format::Markdown)));
+        }
+    }
+    if let Some(reason) = &page.not_counted {
+        text.push_str(&format!("\n## Constraints\n\nNot counted: {}.\n", reason));
+    }
+    if !page.instances.is_empty() {
+        text.push_str("\n## Constraints\n\n| Instance | Non-linear | Linear |\n|---|---|---|\n");
+        for (call, count) in &page.instances {
+            text.push_str(&format!("| `{}` | {} | {} |\n", call, count.non_linear, count.linear));
+        }
+    }
+    text
+}
+
+fn code(text: &str, format: Format) -> String {
+    match format {
+        _ if text.is_empty() => String::new(),
+        Format::Markdown => format!("`{}`", text),
+        Format::Html => format!("<code>{}</code>", escape(text)),
+    }
+}
+
+fn markdown_index(pages: &[Page]) -> String {
+    let mut text = "# Documentation\n".to_string();
+    for (kind, title) in [("template", "Templates"), ("function", "Functions"), ("bus", "Buses")] {
+        let pages: Vec<&Page> = pages.iter().filter(|page| page.kind == kind).collect();
+        if pages.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\n## {}\n\n", title));
+        for page in pages {
+            let entry = link(&page.name, &Page::path(kind, &page.name, Format::Markdown), Format::Markdown);
+            if page.summary().is_empty() {
+                text.push_str(&format!("- {}\n", entry));
+            } else {
+                text.push_str(&format!("- {}: {}\n", entry, page.summary()));
+            }
+        }
+    }
+    text
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        body
+    )
+}
+
+// Paragraphs of the doc comment are separated by empty lines
+fn html_paragraphs(lines: &[&str]) -> String {
+    let mut text = String::new();
+    for paragraph in lines.split(|line| line.trim().is_empty()).filter(|paragraph| !paragraph.is_empty()) {
+        text.push_str(&format!("<p>{}</p>\n", escape(&paragraph.join("\n"))));
+    }
+    text
+}
+
+fn html_page(page: &Page, program: &ProgramArchive) -> String {
+    let mut body = format!("<h1><code>{}</code></h1>\n", escape(&header(page)));
+    body.push_str(&format!("<p>Defined in <code>{}</code>.</p>\n", escape(&page.file)));
+    body.push_str(&html_paragraphs(&description(page)));
+    if !page.params.is_empty() {
+        body.push_str("<h2>Parameters</h2>\n<ul>\n");
+        for param in &page.params {
+            body.push_str(&format!("<li><code>{}</code></li>\n", escape(param)));
+        }
+        body.push_str("</ul>\n");
+    }
+    for (title, rows) in wire_rows(page, program, Format::Html) {
+        body.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Dimensions</th><th>Tags</th></tr>\n",
+            title
+        ));
+        for [name, wire_type, dimensions, tags] in rows {
+            body.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape(&name),
+                wire_type,
+                code(&dimensions, Format::Html),
+                escape(&tags)
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    if !page.instantiates.is_empty() {
+        body.push_str("<h2>Instantiates</h2>\n<ul>\n");
+        for template in &page.instantiates {
+            let path = relative_path(page, "template", template, Format::Html);
+            body.push_str(&format!("<li>{}</li>\n", link(template, &path, Format::Html)));
+        }
+        body.push_str("</ul>\n");
+    }
+    if let Some(reason) = &page.not_counted {
+        body.push_str(&format!("<h2>Constraints</h2>\n<p>Not counted: {}.</p>\n", escape(reason)));
+    }
+    if !page.instances.is_empty() {
+        body.push_str("<h2>Constraints</h2>\n<table>\n<tr><th>Instance</th><th>Non-linear</th><th>Linear</th></tr>\n");
+        for (call, count) in &page.instances {
+            body.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                escape(call),
+                count.non_linear,
+                count.linear
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    html_document(&header(page), &body)
+}
+
+fn html_index(pages: &[Page]) -> String {
+    let mut body = "<h1>Documentation</h1>\n".to_string();
+    for (kind, title) in [("template", "Templates"), ("function", "Functions"), ("bus", "Buses")] {
+        let pages: Vec<&Page> = pages.iter().filter(|page| page.kind == kind).collect();
+        if pages.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{}</h2>\n<ul>\n", title));
+        for page in pages {
+            let entry = link(&page.name, &Page::path(kind, &page.name, Format::Html), Format::Html);
+            if page.summary().is_empty() {
+                body.push_str(&format!("<li>{}</li>\n", entry));
+            } else {
+                body.push_str(&format!("<li>{}: {}</li>\n", entry, escape(page.summary())));
+            }
+        }
+        body.push_str("</ul>\n");
+    }
+    html_document("Documentation", &body)
+}
+
+fn create_folder(folder: &Path) -> Result<(), ()> {
+    std::fs::create_dir_all(folder)
+        .map_err(|_| eprintln!("{}", Colour::Red.paint(format!("Could not create the folder {}", folder.display()))))
+}
+
+fn write_file(file: &Path, text: &str) -> Result<(), ()> {
+    std::fs::write(file, text)
+        .map_err(|_| eprintln!("{}", Colour::Red.paint(format!("Could not write the file {}", file.display()))))
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom doc")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Writes the documentation of the templates, functions and buses of a project from their doc comments")
+        .arg(
+            Arg::with_name("input")
+                .required(true)
+                .help("Main file of the project or library. Every file it includes is documented"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .default_value("doc")
+                .display_order(10)
+                .help("Folder where the documentation is written"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "html"])
+                .default_value("markdown")
+                .display_order(20)
+                .help("Format of the pages"),
+        )
+        .arg(
+            Arg::with_name("constraints")
+                .long("constraints")
+                .takes_value(false)
+                .display_order(30)
+                .help("Adds the number of constraints of the templates, instantiated with the values of the @params lines of their doc comments"),
+        )
+        .arg(
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(40)
+                .help("Adds directory to library search path"),
+        )
+        .arg(
+            Arg::with_name("prime")
+                .long("prime")
+                .takes_value(true)
+                .default_value("bn128")
+                .display_order(50)
+                .help("Name of the curve used to count the constraints"),
+        )
+        .get_matches_from(std::env::args().skip(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &str = "pragma circom 2.2.2;
+bus Tagged() { signal {binary} b; }
+bus Nested() { Tagged() t; }
+template IsBit() { signal input {binary} in; signal input x; signal output out <== in * x; }
+template TakesBus() { input Nested() n; signal output out <== n.t.b; }
+template Square() { signal input in; signal output out <== in * in; }
+";
+
+    #[test]
+    fn templates_with_tagged_inputs_are_found() {
+        let file = std::env::temp_dir().join(format!("circom_doc_tags_{}.circom", std::process::id()));
+        std::fs::write(&file, LIBRARY).unwrap();
+        let field = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let (program, _) = parser::run_library_parser(file.to_str().unwrap().to_string(), VERSION, vec![], &field)
+            .unwrap_or_else(|_| panic!("The library could not be parsed"));
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(tagged_inputs(&program, "IsBit"), vec!["in"]);
+        assert_eq!(tagged_inputs(&program, "TakesBus"), vec!["n"]);
+        assert!(tagged_inputs(&program, "Square").is_empty());
+        assert!(instantiate(&program, "Square()", &"bn128".to_string()).is_ok());
+    }
+}
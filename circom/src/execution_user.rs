@@ -1,13 +1,16 @@
 use ansi_term::Colour;
 use compiler::hir::very_concrete_program::VCP;
+use compiler::input_schema::{self, DeclaredTags};
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
+use dag::HierarchyFormat;
 use program_structure::program_archive::ProgramArchive;
 
 
 pub struct ExecutionConfig {
     pub r1cs: String,
     pub sym: String,
+    pub sym_index: String,
     pub json_constraints: String,
     pub json_substitutions: String,
     pub no_rounds: usize,
@@ -22,6 +25,16 @@ pub struct ExecutionConfig {
     pub json_substitution_flag: bool,
     pub json_constraint_flag: bool,
     pub prime: String,
+    pub hierarchy_format: Option<HierarchyFormat>,
+    pub hierarchy: String,
+    pub profile_flag: bool,
+    pub profile_json: String,
+    pub profile_folded: String,
+    pub plonk_flag: bool,
+    pub plonk: String,
+    pub certificate_flag: bool,
+    pub certificate: String,
+    pub inputs_schema: String,
 }
 
 pub fn execute_project(
@@ -41,14 +54,27 @@ pub fn execute_project(
         inspect_constraints: config.inspect_constraints_flag,
         flag_old_heuristics: config.flag_old_heuristics,
         prime : config.prime,
+        hierarchy_format: config.hierarchy_format,
+        hierarchy_file: config.hierarchy,
+        flag_profile: config.profile_flag,
+        profile_json: config.profile_json,
+        profile_folded: config.profile_folded,
+        flag_plonk: config.plonk_flag,
+        plonk_file: config.plonk,
+        flag_certificate: config.certificate_flag,
+        certificate_file: config.certificate,
     };
     let custom_gates = program_archive.custom_gates;
+    let public_inputs = program_archive.get_public_inputs_main_component().clone();
+    let declared_tags = DeclaredTags::new(&program_archive);
     let (exporter, vcp) = build_circuit(program_archive, build_config)?;
+    generate_inputs_schema(&config.inputs_schema, &vcp, &public_inputs, &declared_tags)?;
     if config.r1cs_flag {
         generate_output_r1cs(&config.r1cs, exporter.as_ref(), custom_gates)?;
     }
     if config.sym_flag {
         generate_output_sym(&config.sym, exporter.as_ref())?;
+        generate_output_sym_index(&config.sym_index, exporter.as_ref())?;
     }
     if config.json_constraint_flag {
         generate_json_constraints(&debug, exporter.as_ref())?;
@@ -76,6 +102,32 @@ fn generate_output_sym(file: &str, exporter: &dyn ConstraintExporter) -> Result<
     }
 }
 
+fn generate_output_sym_index(file: &str, exporter: &dyn ConstraintExporter) -> Result<(), ()> {
+    if let Result::Ok(()) = exporter.sym_index(file) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        Result::Ok(())
+    } else {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        Result::Err(())
+    }
+}
+
+fn generate_inputs_schema(
+    file: &str,
+    vcp: &VCP,
+    public_inputs: &[String],
+    declared_tags: &DeclaredTags,
+) -> Result<(), ()> {
+    let schema = input_schema::build_input_schema(vcp, public_inputs, declared_tags);
+    if let Result::Ok(()) = std::fs::write(file, schema.pretty(2)) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        Result::Ok(())
+    } else {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        Result::Err(())
+    }
+}
+
 fn generate_json_constraints(
     debug: &DebugWriter,
     exporter: &dyn ConstraintExporter,
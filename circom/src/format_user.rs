@@ -0,0 +1,138 @@
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches};
+use parser::{format_source, FormatConfig, TrailingComma};
+use program_structure::error_definition::Report;
+use program_structure::file_definition::FileLibrary;
+use std::path::{Path, PathBuf};
+use crate::VERSION;
+
+pub const FORMAT_COMMAND: &str = "fmt";
+
+pub fn format_project() -> Result<(), ()> {
+    let matches = view();
+    let config = FormatConfig {
+        indent: get_number(&matches, "indent")?,
+        max_width: get_number(&matches, "max_width")?,
+        trailing_comma: get_trailing_comma(&matches),
+    };
+    let check = matches.is_present("check");
+    let mut files = Vec::new();
+    for input in matches.values_of("input").unwrap() {
+        collect_files(Path::new(input), &mut files)?;
+    }
+    let mut failed = false;
+    let mut unformatted = Vec::new();
+    for file in files {
+        let path = file.display().to_string();
+        let src = std::fs::read_to_string(&file).map_err(|_| {
+            eprintln!("{}", Colour::Red.paint(format!("Could not read the file {}", path)))
+        })?;
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file(path.clone(), src.clone());
+        match format_source(&src, file_id, &config) {
+            Err(reports) => {
+                Report::print_reports(&reports, &file_library);
+                failed = true;
+            }
+            Ok(formatted) if formatted == src => {}
+            Ok(_) if check => unformatted.push(path),
+            Ok(formatted) => {
+                std::fs::write(&file, formatted).map_err(|_| {
+                    eprintln!("{}", Colour::Red.paint(format!("Could not write the file {}", path)))
+                })?;
+                println!("{} {}", Colour::Green.paint("Formatted:"), path);
+            }
+        }
+    }
+    for path in &unformatted {
+        eprintln!("{} {}", Colour::Red.paint("Not formatted:"), path);
+    }
+    if failed || !unformatted.is_empty() {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+// Directories are traversed looking for .circom files
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), ()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        Ok(())
+    } else if path.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(|_| eprintln!("{}", Colour::Red.paint(format!("Could not read the directory {}", path.display()))))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().map_or(false, |ext| ext == "circom") {
+                collect_files(&entry, files)?;
+            }
+        }
+        Ok(())
+    } else {
+        Err(eprintln!("{}", Colour::Red.paint(format!("Input file does not exist: {}", path.display()))))
+    }
+}
+
+fn get_number(matches: &ArgMatches, name: &str) -> Result<usize, ()> {
+    let value = matches.value_of(name).unwrap();
+    value.parse::<usize>().map_err(|_| {
+        eprintln!("{}", Colour::Red.paint(format!("invalid value for --{}: {}", name, value)))
+    })
+}
+
+// clap only accepts the possible values of the option
+fn get_trailing_comma(matches: &ArgMatches) -> TrailingComma {
+    match matches.value_of("trailing_comma") {
+        Some("preserve") => TrailingComma::Preserve,
+        _ => TrailingComma::Never,
+    }
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom fmt")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Formats circom source files")
+        .arg(
+            Arg::with_name("input")
+                .multiple(true)
+                .required(true)
+                .help("Files to format. Directories are traversed looking for .circom files"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .takes_value(false)
+                .display_order(10)
+                .help("Does not write the files, fails if any of them is not formatted"),
+        )
+        .arg(
+            Arg::with_name("indent")
+                .long("indent")
+                .takes_value(true)
+                .default_value("4")
+                .display_order(20)
+                .help("Number of spaces of each indentation level"),
+        )
+        .arg(
+            Arg::with_name("max_width")
+                .long("max_width")
+                .takes_value(true)
+                .default_value("100")
+                .display_order(30)
+                .help("Maximum width of each line"),
+        )
+        .arg(
+            Arg::with_name("trailing_comma")
+                .long("trailing_comma")
+                .takes_value(true)
+                .possible_values(&["never", "preserve"])
+                .default_value("never")
+                .display_order(40)
+                .help("Whether lists of arguments, parameters and array elements end with a comma"),
+        )
+        .get_matches_from(std::env::args().skip(1))
+}
@@ -1,3 +1,4 @@
+use dag::HierarchyFormat;
 use std::path::PathBuf;
 
 pub struct Input {
@@ -14,9 +15,17 @@ pub struct Input {
     pub out_c_code: PathBuf,
     pub out_c_dat: PathBuf,
     pub out_sym: PathBuf,
+    pub out_sym_index: PathBuf,
+    pub out_hierarchy: PathBuf,
+    pub out_profile_json: PathBuf,
+    pub out_profile_folded: PathBuf,
+    pub out_plonk: PathBuf,
+    pub out_certificate: PathBuf,
+    pub out_inputs_schema: PathBuf,
     pub out_cvm_folder: PathBuf,
     pub out_cvm_code: PathBuf,
     pub out_cvm_name: String,
+    pub out_manifest: PathBuf,
     //pub field: &'static str,
     pub c_flag: bool,
     pub cvm_multi_assign_flag: bool,
@@ -24,6 +33,7 @@ pub struct Input {
     pub wasm_flag: bool,
     pub wat_flag: bool,
     pub no_asm_flag: bool,
+    pub c_lib_flag: bool,
     pub r1cs_flag: bool,
     pub sym_flag: bool,
     pub json_constraint_flag: bool,
@@ -40,7 +50,17 @@ pub struct Input {
     pub flag_verbose: bool,
     pub flag_no_init: bool,
     pub prime: String,
-    pub link_libraries : Vec<PathBuf>
+    pub link_libraries : Vec<PathBuf>,
+    pub lint_flag: bool,
+    pub lint_config: Option<PathBuf>,
+    pub watch_flag: bool,
+    pub main_call: Option<String>,
+    pub main_public: Vec<String>,
+    pub hierarchy_format: Option<HierarchyFormat>,
+    pub profile_flag: bool,
+    pub plonk_flag: bool,
+    pub certificate_flag: bool,
+    pub arguments: Vec<String>,
 }
 
 
@@ -52,21 +72,39 @@ const CVM: &'static str = "cvm";
 const JS: &'static str = "js";
 const DAT: &'static str = "dat";
 const SYM: &'static str = "sym";
+const SYMIDX: &'static str = "symidx";
 const JSON: &'static str = "json";
+const DOT: &'static str = "dot";
+const FOLDED: &'static str = "folded";
+const PLONK: &'static str = "plonk";
+const MANIFEST: &'static str = "build-manifest.json";
 
 
 impl Input {
     pub fn new() -> Result<Input, ()> {
+        Input::from_arguments(std::env::args().collect(), None)
+    }
+
+    // Input given by the command line arguments, starting with the name of
+    // the program. The output path, when given, replaces the one of the
+    // arguments
+    pub fn from_arguments(arguments: Vec<String>, output_path: Option<PathBuf>) -> Result<Input, ()> {
         use ansi_term::Colour;
         use input_processing::SimplificationStyle;
-        let matches = input_processing::view();
+        let matches = input_processing::view(&arguments);
         let input = input_processing::get_input(&matches)?;
         let mut file_name = input.file_stem().unwrap().to_str().unwrap().to_string();
-        let output_path = input_processing::get_output_path(&matches)?;
+        let output_path = match output_path {
+            Some(output_path) => output_path,
+            None => input_processing::get_output_path(&matches)?,
+        };
 
         let c_flag = input_processing::get_c(&matches);
+        let prime = input_processing::get_prime(&matches)?;
+        let c_lib_flag = input_processing::get_c_lib(&matches, &prime)?;
+        let reserved_by_c_lib = c_lib_flag && (file_name == "circom_witness" || file_name == "circom_witness_test");
 
-        if c_flag && (file_name == "main" || file_name == "fr" || file_name == "calcwit"){
+        if c_flag && (file_name == "main" || file_name == "fr" || file_name == "calcwit" || reserved_by_c_lib){
             println!("{}", Colour::Yellow.paint(format!("The name {} is reserved in Circom when using de --c flag. The files generated for your circuit will use the name {}_c instead of {}.", file_name, file_name, file_name)));
             file_name = format!("{}_c", file_name)
         };
@@ -75,6 +113,8 @@ impl Input {
         let output_cvm_path = Input::build_folder(&output_path, &file_name, CVM);
         let o_style = input_processing::get_simplification_style(&matches)?;
         let link_libraries = input_processing::get_link_libraries(&matches);
+        let hierarchy_format = input_processing::get_hierarchy_format(&matches)?;
+        let hierarchy_ext = if hierarchy_format == Some(HierarchyFormat::Dot) { DOT } else { JSON };
         Result::Ok(Input {
             //field: P_BN128,
             input_program: input,
@@ -82,6 +122,7 @@ impl Input {
             out_cvm_folder: output_cvm_path.clone(),
             out_cvm_code: Input::build_output(&output_cvm_path, &file_name, CVM),
             out_cvm_name: file_name.clone(),
+            out_manifest: output_path.join(MANIFEST),
             out_wat_code: Input::build_output(&output_js_path, &file_name, WAT),
             out_wasm_code: Input::build_output(&output_js_path, &file_name, WASM),
 	        out_js_folder: output_js_path.clone(),
@@ -91,6 +132,33 @@ impl Input {
             out_c_code: Input::build_output(&output_c_path, &file_name, CPP),
             out_c_dat: Input::build_output(&output_c_path, &file_name, DAT),
             out_sym: Input::build_output(&output_path, &file_name, SYM),
+            out_sym_index: Input::build_output(&output_path, &file_name, SYMIDX),
+            out_hierarchy: Input::build_output(
+                &output_path,
+                &format!("{}_hierarchy", file_name),
+                hierarchy_ext,
+            ),
+            out_profile_json: Input::build_output(
+                &output_path,
+                &format!("{}_profile", file_name),
+                JSON,
+            ),
+            out_profile_folded: Input::build_output(
+                &output_path,
+                &format!("{}_profile", file_name),
+                FOLDED,
+            ),
+            out_plonk: Input::build_output(&output_path, &file_name, PLONK),
+            out_certificate: Input::build_output(
+                &output_path,
+                &format!("{}_certificate", file_name),
+                JSON,
+            ),
+            out_inputs_schema: Input::build_output(
+                &output_path,
+                &format!("{}_inputs.schema", file_name),
+                JSON,
+            ),
             out_json_constraints: Input::build_output(
                 &output_path,
                 &format!("{}_constraints", file_name),
@@ -107,6 +175,7 @@ impl Input {
             cvm_multi_assign_flag: input_processing::get_cvm_multi_assign(&matches),
             cvm_flag: input_processing::get_cvm(&matches),
             no_asm_flag:input_processing::get_no_asm(&matches),
+            c_lib_flag,
             r1cs_flag: input_processing::get_r1cs(&matches),
             sym_flag: input_processing::get_sym(&matches),
             main_inputs_flag: input_processing::get_main_inputs_log(&matches),
@@ -122,8 +191,18 @@ impl Input {
             flag_old_heuristics: input_processing::get_flag_old_heuristics(&matches),
             flag_verbose: input_processing::get_flag_verbose(&matches), 
             flag_no_init: input_processing::get_flag_no_init(&matches), 
-            prime: input_processing::get_prime(&matches)?,
-            link_libraries
+            prime,
+            link_libraries,
+            lint_flag: input_processing::get_lint(&matches),
+            lint_config: input_processing::get_lint_config(&matches)?,
+            watch_flag: input_processing::get_watch(&matches),
+            main_call: input_processing::get_main_call(&matches),
+            main_public: input_processing::get_main_public(&matches),
+            hierarchy_format,
+            profile_flag: input_processing::get_profile(&matches),
+            plonk_flag: input_processing::get_plonk(&matches, o_style)?,
+            certificate_flag: input_processing::get_certificate(&matches, o_style)?,
+            arguments: arguments[1..].to_vec(),
         })
     }
 
@@ -140,6 +219,47 @@ impl Input {
         file
     }
 
+    // Files and folders written by the compilation with the given flags
+    pub fn outputs(&self) -> Vec<PathBuf> {
+        let mut outputs = vec![self.out_inputs_schema.clone()];
+        if self.r1cs_flag {
+            outputs.push(self.out_r1cs.clone());
+        }
+        if self.sym_flag {
+            outputs.push(self.out_sym.clone());
+            outputs.push(self.out_sym_index.clone());
+        }
+        if self.json_constraint_flag {
+            outputs.push(self.out_json_constraints.clone());
+        }
+        if self.json_substitution_flag {
+            outputs.push(self.out_json_substitutions.clone());
+        }
+        if self.hierarchy_format.is_some() {
+            outputs.push(self.out_hierarchy.clone());
+        }
+        if self.profile_flag {
+            outputs.push(self.out_profile_json.clone());
+            outputs.push(self.out_profile_folded.clone());
+        }
+        if self.plonk_flag {
+            outputs.push(self.out_plonk.clone());
+        }
+        if self.certificate_flag {
+            outputs.push(self.out_certificate.clone());
+        }
+        if self.wasm_flag || self.wat_flag {
+            outputs.push(self.out_js_folder.clone());
+        }
+        if self.c_flag {
+            outputs.push(self.out_c_folder.clone());
+        }
+        if self.cvm_flag {
+            outputs.push(self.out_cvm_folder.clone());
+        }
+        outputs
+    }
+
     pub fn get_link_libraries(&self) -> &Vec<PathBuf> {
         &self.link_libraries
     }
@@ -153,6 +273,27 @@ impl Input {
     pub fn sym_file(&self) -> &str {
         self.out_sym.to_str().unwrap()
     }
+    pub fn sym_index_file(&self) -> &str {
+        self.out_sym_index.to_str().unwrap()
+    }
+    pub fn hierarchy_file(&self) -> &str {
+        self.out_hierarchy.to_str().unwrap()
+    }
+    pub fn profile_json_file(&self) -> &str {
+        self.out_profile_json.to_str().unwrap()
+    }
+    pub fn profile_folded_file(&self) -> &str {
+        self.out_profile_folded.to_str().unwrap()
+    }
+    pub fn plonk_file(&self) -> &str {
+        self.out_plonk.to_str().unwrap()
+    }
+    pub fn certificate_file(&self) -> &str {
+        self.out_certificate.to_str().unwrap()
+    }
+    pub fn inputs_schema_file(&self) -> &str {
+        self.out_inputs_schema.to_str().unwrap()
+    }
     pub fn cvm_file(&self) -> &str {
         self.out_cvm_code.to_str().unwrap()
     }
@@ -212,6 +353,9 @@ impl Input {
     pub fn no_asm_flag(&self) -> bool {
         self.no_asm_flag
     }
+    pub fn c_lib_flag(&self) -> bool {
+        self.c_lib_flag
+    }
     pub fn unsimplified_flag(&self) -> bool {
         self.fast_flag
     }
@@ -260,10 +404,44 @@ impl Input {
     pub fn prime(&self) -> String{
         self.prime.clone()
     }
+    pub fn lint_flag(&self) -> bool {
+        self.lint_flag || self.lint_config.is_some()
+    }
+    pub fn lint_config_file(&self) -> Option<&PathBuf> {
+        self.lint_config.as_ref()
+    }
+    pub fn watch_flag(&self) -> bool {
+        self.watch_flag
+    }
+    pub fn hierarchy_format(&self) -> Option<HierarchyFormat> {
+        self.hierarchy_format
+    }
+    pub fn profile_flag(&self) -> bool {
+        self.profile_flag
+    }
+    pub fn plonk_flag(&self) -> bool {
+        self.plonk_flag
+    }
+    pub fn certificate_flag(&self) -> bool {
+        self.certificate_flag
+    }
+    pub fn main_call(&self) -> Option<&str> {
+        self.main_call.as_deref()
+    }
+    pub fn main_public(&self) -> &Vec<String> {
+        &self.main_public
+    }
+    pub fn manifest_file(&self) -> &str {
+        self.out_manifest.to_str().unwrap()
+    }
+    pub fn arguments(&self) -> &Vec<String> {
+        &self.arguments
+    }
 }
 mod input_processing {
     use ansi_term::Colour;
     use clap::{App, Arg, ArgMatches};
+    use dag::HierarchyFormat;
     use std::path::{Path, PathBuf};
     use crate::VERSION;
 
@@ -350,6 +528,18 @@ mod input_processing {
         matches.is_present("print_c")
     }
 
+    // The C code of goldilocks has its own witness calculator without the
+    // library. The prime is the one given by get_prime, also when the
+    // argument is a number
+    pub fn get_c_lib(matches: &ArgMatches, prime: &str) -> Result<bool, ()> {
+        let c_lib = matches.is_present("c_lib");
+        if c_lib && prime == "goldilocks" {
+            eprintln!("{}", Colour::Red.paint("--c_lib can not be used with the prime goldilocks"));
+            return Result::Err(());
+        }
+        Result::Ok(c_lib)
+    }
+
     pub fn get_main_inputs_log(matches: &ArgMatches) -> bool {
         matches.is_present("main_inputs_log")
     }
@@ -369,6 +559,10 @@ mod input_processing {
         matches.is_present("inspect_constraints")
     }
 
+    pub fn get_watch(matches: &ArgMatches) -> bool {
+        matches.is_present("watch")
+    }
+
     pub fn get_flag_verbose(matches: &ArgMatches) -> bool {
         matches.is_present("flag_verbose")
     }
@@ -380,33 +574,127 @@ mod input_processing {
     pub fn get_flag_old_heuristics(matches: &ArgMatches) -> bool {
         matches.is_present("flag_old_heuristics")
     }
+    pub fn get_lint(matches: &ArgMatches) -> bool {
+        matches.is_present("lint")
+    }
+
+    pub fn get_lint_config(matches: &ArgMatches) -> Result<Option<PathBuf>, ()> {
+        match matches.value_of("lint_config") {
+            Some(file) => {
+                let route = Path::new(file).to_path_buf();
+                if route.is_file() {
+                    Result::Ok(Some(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint(format!("Lint configuration file does not exist: {}", file))))
+                }
+            }
+            None => Result::Ok(None),
+        }
+    }
+
+    pub fn get_hierarchy_format(matches: &ArgMatches) -> Result<Option<HierarchyFormat>, ()> {
+        match matches.value_of("hierarchy") {
+            Some("json") => Ok(Some(HierarchyFormat::Json)),
+            Some("dot") => Ok(Some(HierarchyFormat::Dot)),
+            Some(_) => Result::Err(eprintln!("{}", Colour::Red.paint("invalid hierarchy format"))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_profile(matches: &ArgMatches) -> bool {
+        matches.is_present("profile")
+    }
+
+    // The gates are built from the simplified constraints
+    pub fn get_plonk(matches: &ArgMatches, o_style: SimplificationStyle) -> Result<bool, ()> {
+        let plonk = matches.is_present("plonk");
+        if plonk && o_style == SimplificationStyle::O0 {
+            eprintln!("{}", Colour::Red.paint("--plonk can not be used with --O0"));
+            return Result::Err(());
+        }
+        Result::Ok(plonk)
+    }
+
+    // Without simplification there is nothing to certify
+    pub fn get_certificate(matches: &ArgMatches, o_style: SimplificationStyle) -> Result<bool, ()> {
+        let certificate = matches.is_present("simplification_certificate");
+        if certificate && o_style == SimplificationStyle::O0 {
+            eprintln!("{}", Colour::Red.paint("--simplification_certificate can not be used with --O0"));
+            return Result::Err(());
+        }
+        Result::Ok(certificate)
+    }
+
+    pub fn get_main_call(matches: &ArgMatches) -> Option<String> {
+        matches.value_of("main").map(|call| call.to_string())
+    }
+
+    pub fn get_main_public(matches: &ArgMatches) -> Vec<String> {
+        match matches.values_of("public") {
+            Some(values) => values
+                .flat_map(|value| value.split(','))
+                .map(|signal| signal.trim().to_string())
+                .filter(|signal| !signal.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Receives the name of a curve or a prime in decimal or 0x-hexadecimal.
+    // A custom prime is returned in decimal, or as the name of its curve
     pub fn get_prime(matches: &ArgMatches) -> Result<String, ()> {
-        
-        match matches.is_present("prime"){
-            true => 
-               {
-                   let prime_value = matches.value_of("prime").unwrap();
-                   if prime_value == "bn128"
-                      || prime_value == "bls12381"
-                      || prime_value == "goldilocks"
-                      || prime_value == "grumpkin"
-                      || prime_value == "pallas"
-                      || prime_value == "vesta"
-                      || prime_value == "secq256r1"
-                      || prime_value == "bls12377"
-                      {
-                        Ok(String::from(matches.value_of("prime").unwrap()))
-                    }
-                    else{
-                        Result::Err(eprintln!("{}", Colour::Red.paint("invalid prime number")))
-                    }
-               }
-               
-            false => Ok(String::from("bn128")),
+        use program_structure::utils::constants::{self, PRIME_NAMES};
+        let prime_value = matches.value_of("prime").unwrap_or("bn128");
+        if constants::is_named_prime(prime_value) {
+            return Result::Ok(prime_value.to_string());
+        }
+        let prime = match constants::parse_prime(prime_value) {
+            Some(prime) => prime,
+            None => {
+                eprintln!(
+                    "{}",
+                    Colour::Red.paint(format!(
+                        "invalid prime number: {} is neither a curve ({}) nor a decimal or 0x-hexadecimal number",
+                        prime_value,
+                        PRIME_NAMES.join(", ")
+                    ))
+                );
+                return Result::Err(());
+            }
+        };
+        let odd = prime.to_bytes_le().1[0] % 2 == 1;
+        if !odd || !constants::is_prime(&prime) {
+            eprintln!("{}", Colour::Red.paint(format!("invalid prime number: {} is not an odd prime", prime_value)));
+            return Result::Err(());
         }
+        if let Some(name) = constants::name_of_prime(&prime) {
+            return Result::Ok(name.to_string());
+        }
+        // The WebAssembly and the assembly C field code only exist for the
+        // named curves. The generic C field code works for any prime
+        let unsupported = if get_wasm(matches) || get_wat(matches) {
+            Some("--wasm and --wat")
+        } else if get_c(matches) && !get_no_asm(matches) {
+            Some("--c without --no_asm")
+        } else {
+            None
+        };
+        if let Some(backends) = unsupported {
+            eprintln!(
+                "{}",
+                Colour::Red.paint(format!(
+                    "the prime {} is not supported by {}, which only have field code for the curves {}. Use --c with --no_asm or --cvm to generate the witness with a custom prime",
+                    prime_value,
+                    backends,
+                    PRIME_NAMES.join(", ")
+                ))
+            );
+            return Result::Err(());
+        }
+        Result::Ok(prime.to_str_radix(10))
     }
 
-    pub fn view() -> ArgMatches<'static> {
+    pub fn view(arguments: &[String]) -> ArgMatches<'static> {
         App::new("circom compiler")
             .version(VERSION)
             .author("IDEN3")
@@ -415,7 +703,24 @@ mod input_processing {
                 Arg::with_name("input")
                     .multiple(false)
                     .default_value("./circuit.circom")
-                    .help("Path to a circuit with a main component"),
+                    .help("Path to a circuit with a main component, or without it when --main is used"),
+            )
+            .arg(
+                Arg::with_name("main")
+                    .long("main")
+                    .takes_value(true)
+                    .display_order(2)
+                    .help("Call to the template used as main component, e.g. \"Poseidon(3)\". The input file must not declare a main component"),
+            )
+            .arg(
+                Arg::with_name("public")
+                    .long("public")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .requires("main")
+                    .display_order(3)
+                    .help("Public input signals of the main component given with --main, separated by commas"),
             )
             .arg(
                 Arg::with_name("no_simplification")
@@ -492,7 +797,36 @@ mod input_processing {
                     .long("sym")
                     .takes_value(false)
                     .display_order(60)
-                    .help("Outputs witness in sym format"),
+                    .help("Outputs witness in sym format, with its index in symidx format"),
+            )
+            .arg(
+                Arg::with_name("hierarchy")
+                    .long("hierarchy")
+                    .takes_value(true)
+                    .possible_values(&["json", "dot"])
+                    .display_order(125)
+                    .help("Outputs the template instances and the components connecting them in json or dot (Graphviz) format"),
+            )
+            .arg(
+                Arg::with_name("profile")
+                    .long("profile")
+                    .takes_value(false)
+                    .display_order(126)
+                    .help("Outputs the number of final constraints produced by each template and source line in json and collapsed stack formats"),
+            )
+            .arg(
+                Arg::with_name("plonk")
+                    .long("plonk")
+                    .takes_value(false)
+                    .display_order(127)
+                    .help("Outputs the simplified constraints as a table of PLONK gates with their copy constraints"),
+            )
+            .arg(
+                Arg::with_name("simplification_certificate")
+                    .long("simplification_certificate")
+                    .takes_value(false)
+                    .display_order(128)
+                    .help("Outputs and checks a certificate with the original constraints, the substitutions and the final constraints in json format"),
             )
             .arg(
                 Arg::with_name("print_r1cs")
@@ -553,6 +887,14 @@ mod input_processing {
                     .display_order(150)
                     .help("Compiles the circuit to C++"),
             )
+            .arg(
+                Arg::with_name("c_lib")
+                    .long("c_lib")
+                    .takes_value(false)
+                    .requires("print_c")
+                    .display_order(155)
+                    .help("Also generates the C++ witness generator as a library with a C interface"),
+            )
             .arg(
                 Arg::with_name("parallel_simplification")
                     .long("parallel")
@@ -591,6 +933,27 @@ mod input_processing {
                     .display_order(999)
                     .help("Removes initializations to 0 of variables (\"var\") in the witness generation code"),
             )
+            .arg(
+                Arg::with_name("lint")
+                    .long("lint")
+                    .takes_value(false)
+                    .display_order(820)
+                    .help("Runs the lint rules over the reached templates and functions"),
+            )
+            .arg(
+                Arg::with_name("lint_config")
+                    .long("lint_config")
+                    .takes_value(true)
+                    .display_order(821)
+                    .help("Runs the lint rules with the levels given in the file, one \"rule_name = allow|warn|deny\" per line"),
+            )
+            .arg(
+                Arg::with_name("watch")
+                    .long("watch")
+                    .takes_value(false)
+                    .display_order(830)
+                    .help("Compiles the circuit again every time the main file or a file it includes changes"),
+            )
             .arg(
                 Arg::with_name("flag_old_heuristics")
                     .long("use_old_simplification_heuristics")
@@ -605,9 +968,9 @@ mod input_processing {
                     .takes_value(true)
                     .default_value("bn128")
                     .display_order(300)
-                    .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12377, bls12381, goldilocks, grumpkin, pallas, secq256r1, vesta)"),
+                    .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12377, bls12381, goldilocks, grumpkin, pallas, secq256r1, vesta) or a prime number in decimal or 0x-hexadecimal"),
             )
-            .get_matches()
+            .get_matches_from(arguments)
     }
 
     pub fn get_link_libraries(matches: &ArgMatches) -> Vec<PathBuf> {
@@ -621,3 +984,42 @@ mod input_processing {
         link_libraries
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::input_processing;
+
+    fn arguments(args: &[&str]) -> Vec<String> {
+        std::iter::once("circom").chain(args.iter().cloned()).map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn main_component_is_read_from_the_arguments() {
+        let matches = input_processing::view(&arguments(&["circuit.circom", "--main", "Poseidon(3)"]));
+        assert_eq!(input_processing::get_main_call(&matches), Some("Poseidon(3)".to_string()));
+        assert!(input_processing::get_main_public(&matches).is_empty());
+    }
+
+    #[test]
+    fn public_signals_are_split_by_commas() {
+        let args = arguments(&["circuit.circom", "--main", "A()", "--public", "a, b", "--public", "c,"]);
+        let matches = input_processing::view(&args);
+        assert_eq!(input_processing::get_main_public(&matches), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn c_lib_is_rejected_for_goldilocks_given_as_a_number() {
+        let args = arguments(&["circuit.circom", "--c", "--c_lib", "--prime", "18446744069414584321"]);
+        let matches = input_processing::view(&args);
+        let prime = input_processing::get_prime(&matches).unwrap();
+        assert_eq!(prime, "goldilocks");
+        assert!(input_processing::get_c_lib(&matches, &prime).is_err());
+        assert_eq!(input_processing::get_c_lib(&matches, "bn128"), Ok(true));
+    }
+
+    #[test]
+    fn main_component_is_optional() {
+        let matches = input_processing::view(&arguments(&["circuit.circom"]));
+        assert_eq!(input_processing::get_main_call(&matches), None);
+    }
+}
@@ -1,8 +1,14 @@
+mod check_inputs_user;
 mod compilation_user;
+mod doc_user;
 mod execution_user;
+mod format_user;
 mod input_user;
+mod manifest_user;
 mod parser_user;
+mod repl_user;
 mod type_analysis_user;
+mod watch_user;
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -10,7 +16,14 @@ const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 use ansi_term::Colour;
 use input_user::Input;
 fn main() {
-    let result = start();
+    let result = match std::env::args().nth(1) {
+        Some(command) if command == format_user::FORMAT_COMMAND => format_user::format_project(),
+        Some(command) if command == check_inputs_user::CHECK_INPUTS_COMMAND => check_inputs_user::check_inputs(),
+        Some(command) if command == doc_user::DOC_COMMAND => doc_user::document_project(),
+        Some(command) if command == manifest_user::VERIFY_MANIFEST_COMMAND => manifest_user::verify_manifest(),
+        Some(command) if command == repl_user::REPL_COMMAND => repl_user::start_repl(),
+        _ => start(),
+    };
     if result.is_err() {
         eprintln!("{}", Colour::Red.paint("previous errors were found"));
         std::process::exit(1);
@@ -21,11 +34,20 @@ fn main() {
 }
 
 fn start() -> Result<(), ()> {
+    let user_input = Input::new()?;
+    if user_input.watch_flag() {
+        watch_user::watch_project(&user_input)
+    } else {
+        build(&user_input)
+    }
+}
+
+fn build(user_input: &Input) -> Result<(), ()> {
     use compilation_user::CompilerConfig;
     use execution_user::ExecutionConfig;
-    let user_input = Input::new()?;
-    let mut program_archive = parser_user::parse_project(&user_input)?;
-    type_analysis_user::analyse_project(&mut program_archive)?;
+    let mut program_archive = parser_user::parse_project(user_input)?;
+    let sources = manifest_user::source_hashes(&program_archive.file_library);
+    type_analysis_user::analyse_project(&mut program_archive, user_input)?;
 
     let config = ExecutionConfig {
         no_rounds: user_input.no_rounds(),
@@ -40,10 +62,21 @@ fn start() -> Result<(), ()> {
         json_substitution_flag: user_input.json_substitutions_flag(),
         sym_flag: user_input.sym_flag(),
         sym: user_input.sym_file().to_string(),
+        sym_index: user_input.sym_index_file().to_string(),
         r1cs: user_input.r1cs_file().to_string(),
         json_constraints: user_input.json_constraints_file().to_string(),
         json_substitutions: user_input.json_substitutions_file().to_string(),
         prime: user_input.prime(),        
+        hierarchy_format: user_input.hierarchy_format(),
+        hierarchy: user_input.hierarchy_file().to_string(),
+        profile_flag: user_input.profile_flag(),
+        profile_json: user_input.profile_json_file().to_string(),
+        profile_folded: user_input.profile_folded_file().to_string(),
+        plonk_flag: user_input.plonk_flag(),
+        plonk: user_input.plonk_file().to_string(),
+        certificate_flag: user_input.certificate_flag(),
+        certificate: user_input.certificate_file().to_string(),
+        inputs_schema: user_input.inputs_schema_file().to_string(),
     };
     let circuit = execution_user::execute_project(program_archive, config)?;
     let compilation_config = CompilerConfig {
@@ -62,6 +95,7 @@ fn start() -> Result<(), ()> {
         wasm_file: user_input.wasm_file().to_string(),
         produce_input_log: user_input.main_inputs_flag(),
         no_asm_flag: user_input.no_asm_flag(),
+        c_lib_flag: user_input.c_lib_flag(),
         constraint_assert_disabled_flag: user_input.constraint_assert_disabled_flag(),
         cvm_flag: user_input.cvm_flag(),
         cvm_multi_assign_flag: user_input.cvm_multi_assign_flag(),
@@ -71,5 +105,6 @@ fn start() -> Result<(), ()> {
         prime: user_input.prime(),        
     };
     compilation_user::compile(compilation_config)?;
+    manifest_user::write_manifest(user_input, &sources)?;
     Result::Ok(())
 }
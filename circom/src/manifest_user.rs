@@ -0,0 +1,288 @@
+use super::input_user::Input;
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches};
+use json::JsonValue;
+use program_structure::file_definition::FileLibrary;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tiny_keccak::{Hasher, Sha3};
+use crate::VERSION;
+
+pub const VERIFY_MANIFEST_COMMAND: &str = "verify-manifest";
+
+// Path and hash of every file read by the compilation, without repetitions
+pub fn source_hashes(file_library: &FileLibrary) -> Vec<(String, String)> {
+    let mut sources = BTreeMap::new();
+    let mut file_id = 0;
+    while let (Some(name), Some(source)) = (file_library.get_name(file_id), file_library.get_source(file_id)) {
+        // The names of the files are stored between quotes
+        sources.insert(relative_to_current_dir(name.trim_matches('"')), hash(source.as_bytes()));
+        file_id += 1;
+    }
+    sources.into_iter().collect()
+}
+
+pub fn write_manifest(input: &Input, sources: &[(String, String)]) -> Result<(), ()> {
+    let mut manifest = JsonValue::new_object();
+    manifest["compiler"] = "circom".into();
+    manifest["version"] = VERSION.into();
+    manifest["arguments"] = input.arguments().clone().into();
+    manifest["prime"] = input.prime().into();
+    manifest["optimization"] = optimization_level(input).into();
+    manifest["sources"] = files_to_json(sources);
+    manifest["outputs"] = files_to_json(&output_hashes(input)?);
+    let file = input.manifest_file();
+    if let Result::Ok(()) = std::fs::write(file, manifest.pretty(2)) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        Result::Ok(())
+    } else {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        Result::Err(())
+    }
+}
+
+// Compiles again the circuit of a manifest, with the same arguments, in a
+// temporary folder and checks that the sources and the outputs have the
+// same hashes as the ones in the manifest. The outputs next to the manifest
+// are also checked, when they exist
+pub fn verify_manifest() -> Result<(), ()> {
+    let matches = view();
+    verify(matches.value_of("manifest").unwrap())
+}
+
+fn verify(file: &str) -> Result<(), ()> {
+    let manifest = read_json(file)?;
+    if manifest["version"].as_str() != Some(VERSION) {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!(
+                "The manifest {} was written by circom {}, but this is circom {}",
+                file, manifest["version"], VERSION
+            ))
+        );
+        return Result::Err(());
+    }
+    let mut arguments = vec!["circom".to_string()];
+    arguments.extend(manifest["arguments"].members().filter_map(|argument| argument.as_str()).map(String::from));
+    // One folder per manifest, so several manifests can be checked at once
+    let folder_name = format!("circom_verify_manifest_{}_{}", std::process::id(), &hash(file.as_bytes())[..16]);
+    let folder = std::env::temp_dir().join(folder_name);
+    std::fs::create_dir_all(&folder).map_err(|_| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not create the folder {}", folder.display())))
+    })?;
+    let rebuilt = Input::from_arguments(arguments, Some(folder.clone()))
+        .and_then(|input| crate::build(&input).map(|_| input))
+        .and_then(|input| read_json(input.manifest_file()));
+    let _ = std::fs::remove_dir_all(&folder);
+    let rebuilt = rebuilt?;
+    let mut differences = compare_files("source", &manifest["sources"], &rebuilt["sources"]);
+    differences.append(&mut compare_files("output", &manifest["outputs"], &rebuilt["outputs"]));
+    let manifest_folder = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+    differences.append(&mut check_existing_outputs(&manifest["outputs"], manifest_folder));
+    for difference in &differences {
+        eprintln!("{} {}", Colour::Red.paint("Not reproduced:"), difference);
+    }
+    if differences.is_empty() {
+        println!("{} {}", Colour::Green.paint("Reproduced:"), file);
+        Result::Ok(())
+    } else {
+        Result::Err(())
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha3::v256();
+    let mut output = [0u8; 32];
+    hasher.update(bytes);
+    hasher.finalize(&mut output);
+    output.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Included files are stored with their absolute paths, which are made
+// relative to the folder of the compilation when they are inside of it
+pub fn relative_to_current_dir(name: &str) -> String {
+    let current_dir = std::env::current_dir().and_then(std::fs::canonicalize);
+    match current_dir {
+        Result::Ok(dir) => match Path::new(name).strip_prefix(&dir) {
+            Result::Ok(relative) => relative.display().to_string(),
+            Result::Err(_) => name.to_string(),
+        },
+        Result::Err(_) => name.to_string(),
+    }
+}
+
+fn optimization_level(input: &Input) -> String {
+    if input.unsimplified_flag() {
+        "O0".to_string()
+    } else if input.reduced_simplification_flag() {
+        "O1".to_string()
+    } else if input.no_rounds() == usize::MAX {
+        "O2".to_string()
+    } else {
+        format!("O2round {}", input.no_rounds())
+    }
+}
+
+// Hashes of the outputs, with their paths relative to the output folder
+fn output_hashes(input: &Input) -> Result<Vec<(String, String)>, ()> {
+    let output_path = input.out_manifest.parent().unwrap().to_path_buf();
+    let mut files = Vec::new();
+    for output in input.outputs() {
+        collect_files(&output, &mut files);
+    }
+    let mut hashes = Vec::new();
+    for file in files {
+        let bytes = std::fs::read(&file).map_err(|_| {
+            eprintln!("{}", Colour::Red.paint(format!("Could not read the file {}", file.display())))
+        })?;
+        let name = file.strip_prefix(&output_path).unwrap_or(&file).display().to_string();
+        hashes.push((name, hash(&bytes)));
+    }
+    hashes.sort();
+    Result::Ok(hashes)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        if let Result::Ok(entries) = std::fs::read_dir(path) {
+            for entry in entries.flatten() {
+                collect_files(&entry.path(), files);
+            }
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+}
+
+fn files_to_json(files: &[(String, String)]) -> JsonValue {
+    let mut array = JsonValue::new_array();
+    for (path, hash) in files {
+        let mut file = JsonValue::new_object();
+        file["path"] = path.clone().into();
+        file["sha3_256"] = hash.clone().into();
+        let _ = array.push(file);
+    }
+    array
+}
+
+fn json_to_files(files: &JsonValue) -> BTreeMap<String, String> {
+    files
+        .members()
+        .filter_map(|file| Some((file["path"].as_str()?.to_string(), file["sha3_256"].as_str()?.to_string())))
+        .collect()
+}
+
+fn compare_files(kind: &str, expected: &JsonValue, obtained: &JsonValue) -> Vec<String> {
+    let expected = json_to_files(expected);
+    let obtained = json_to_files(obtained);
+    let mut differences = Vec::new();
+    for (path, hash) in &expected {
+        match obtained.get(path) {
+            None => differences.push(format!("the {} {} is missing", kind, path)),
+            Some(other) if other != hash => differences.push(format!("the {} {} has a different hash", kind, path)),
+            _ => {}
+        }
+    }
+    for path in obtained.keys().filter(|path| !expected.contains_key(*path)) {
+        differences.push(format!("the {} {} is not in the manifest", kind, path));
+    }
+    differences
+}
+
+fn check_existing_outputs(outputs: &JsonValue, folder: &Path) -> Vec<String> {
+    let mut differences = Vec::new();
+    for (path, expected) in json_to_files(outputs) {
+        if let Result::Ok(bytes) = std::fs::read(folder.join(&path)) {
+            if hash(&bytes) != expected {
+                differences.push(format!("the output {} next to the manifest has a different hash", path));
+            }
+        }
+    }
+    differences
+}
+
+fn read_json(file: &str) -> Result<JsonValue, ()> {
+    let src = std::fs::read_to_string(file)
+        .map_err(|_| eprintln!("{}", Colour::Red.paint(format!("Could not read the file {}", file))))?;
+    json::parse(&src).map_err(|error| {
+        eprintln!("{}", Colour::Red.paint(format!("The file {} is not valid JSON: {}", file, error)))
+    })
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom verify-manifest")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Compiles again the circuit of a build manifest and checks that its sources and outputs are the same")
+        .arg(
+            Arg::with_name("manifest")
+                .required(true)
+                .help("The build-manifest.json file written by the compilation, checked from the folder where it was compiled"),
+        )
+        .get_matches_from(std::env::args().skip(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCUIT: &str = "pragma circom 2.2.2;\ninclude \"square.circom\";\ncomponent main = Square();\n";
+    const SQUARE: &str = "pragma circom 2.2.2;\ntemplate Square() {\n    signal input in;\n    signal output out <== in * in;\n}\n";
+
+    // Compiles the circuit with its r1cs and wasm in a new folder
+    fn compiled_project(name: &str) -> (PathBuf, Input) {
+        let folder = std::env::temp_dir().join(format!("circom_manifest_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("circuit.circom"), CIRCUIT).unwrap();
+        std::fs::write(folder.join("square.circom"), SQUARE).unwrap();
+        let circuit = folder.join("circuit.circom");
+        let arguments = ["circom", circuit.to_str().unwrap(), "--r1cs", "--wasm", "-o", folder.to_str().unwrap()];
+        let input = Input::from_arguments(arguments.iter().map(|arg| arg.to_string()).collect(), None)
+            .unwrap_or_else(|_| panic!("The arguments could not be read"));
+        crate::build(&input).unwrap();
+        (folder, input)
+    }
+
+    #[test]
+    fn manifest_is_written_in_the_output_folder() {
+        let (folder, input) = compiled_project("write");
+        assert_eq!(Path::new(input.manifest_file()), folder.join("build-manifest.json"));
+        let manifest = read_json(input.manifest_file()).unwrap();
+        assert_eq!(manifest["version"].as_str(), Some(VERSION));
+        assert_eq!(manifest["prime"].as_str(), Some("bn128"));
+        assert_eq!(manifest["optimization"].as_str(), Some("O1"));
+        let sources = json_to_files(&manifest["sources"]);
+        assert_eq!(sources.len(), 2);
+        let square = folder.join("square.circom");
+        assert_eq!(sources[square.to_str().unwrap()], hash(SQUARE.as_bytes()));
+        let outputs = json_to_files(&manifest["outputs"]);
+        let r1cs = std::fs::read(input.r1cs_file()).unwrap();
+        assert_eq!(outputs["circuit.r1cs"], hash(&r1cs));
+        assert!(outputs.contains_key("circuit_js/circuit.wasm"));
+        assert!(!outputs.keys().any(|path| path.contains("manifest")));
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn manifest_of_a_compilation_is_reproduced() {
+        let (folder, input) = compiled_project("verify");
+        assert!(verify(input.manifest_file()).is_ok());
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn tampered_outputs_are_not_reproduced() {
+        let (folder, input) = compiled_project("tampered");
+        let wasm = folder.join("circuit_js").join("circuit.wasm");
+        let mut bytes = std::fs::read(&wasm).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(&wasm, bytes).unwrap();
+        assert!(verify(input.manifest_file()).is_err());
+        let manifest = read_json(input.manifest_file()).unwrap();
+        let differences = check_existing_outputs(&manifest["outputs"], &folder);
+        assert_eq!(differences, vec!["the output circuit_js/circuit.wasm next to the manifest has a different hash"]);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use super::input_user::Input;
+use parser::MainComponentSpec;
 use program_structure::constants::UsefulConstants;
 use program_structure::error_definition::Report;
 use program_structure::program_archive::ProgramArchive;
@@ -10,12 +11,17 @@ pub fn parse_project(input_info: &Input) -> Result<ProgramArchive, ()> {
     //We get the prime number from the input
     let prime = UsefulConstants::new(&input_info.prime()).get_p().clone();
     let flag_no_init = input_info.flag_no_init();
+    let main_spec = input_info.main_call().map(|call| MainComponentSpec {
+        call: call.to_string(),
+        public: input_info.main_public().clone(),
+    });
     let result_program_archive = parser::run_parser(
         initial_file, 
         VERSION, 
         input_info.get_link_libraries().to_vec(), 
         &prime,
-        flag_no_init
+        flag_no_init,
+        main_spec.as_ref(),
     );
     match result_program_archive {
         Result::Err((file_library, report_collection)) => {
@@ -0,0 +1,484 @@
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches};
+use compiler::compiler_interface::{self, Config};
+use compiler::hir::very_concrete_program::{TemplateInstance, VCP};
+use compiler::num_bigint::BigInt;
+use compiler::witness_calculator::WitnessCalculator;
+use constraint_generation::{evaluate_main_expression, instantiate};
+use json::JsonValue;
+use parser::MainComponentSpec;
+use program_structure::ast::SignalType;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::program_archive::ProgramArchive;
+use program_structure::utils::constants::{self, UsefulConstants, PRIME_NAMES};
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use std::path::PathBuf;
+use type_analysis::check_types::{check_expression_types, check_types};
+use crate::VERSION;
+
+pub const REPL_COMMAND: &str = "repl";
+
+// Function generated to evaluate each expression, with the variables of the
+// session declared before returning the expression
+const REPL_FUNCTION: &str = "__repl__";
+
+const HISTORY_FILE: &str = ".circom_repl_history";
+
+const HELP: &str = "\
+<expression>              evaluates an expression, like a call to a function
+var <name> = <expression> stores the value of an expression in a variable
+<Template>(<args>)        instantiates a template, showing its signals and constraints
+<Template>(<args>) <json> also computes its outputs for the inputs given in the JSON object
+:load                     reads the source files again
+:vars                     shows the variables
+:history                  shows the lines entered
+:help                     shows this message
+:quit                     ends the session";
+
+// Dimensions and values of the elements of a value, as returned by the
+// constant evaluation of an expression
+type Value = (Vec<usize>, Vec<BigInt>);
+
+struct Session {
+    input: String,
+    link_libraries: Vec<PathBuf>,
+    prime: String,
+    field: BigInt,
+    program: ProgramArchive,
+    variables: Vec<(String, Value)>,
+}
+
+// Evaluates expressions and instantiates templates of a project, read line
+// by line from the user
+pub fn start_repl() -> Result<(), ()> {
+    let matches = view();
+    let prime = matches.value_of("prime").unwrap().to_string();
+    if !constants::is_named_prime(&prime) {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!("invalid prime: {} is not one of {}", prime, PRIME_NAMES.join(", ")))
+        );
+        return Result::Err(());
+    }
+    let link_libraries: Vec<PathBuf> =
+        matches.values_of("link_libraries").map_or(Vec::new(), |values| values.map(PathBuf::from).collect());
+    let field = UsefulConstants::new(&prime).get_p().clone();
+    let input = matches.value_of("input").unwrap().to_string();
+    let program = load(&input, &link_libraries, &field)?;
+    let mut session = Session { input, link_libraries, prime, field, program, variables: Vec::new() };
+
+    let history = match matches.value_of("history") {
+        Some(file) => Some(PathBuf::from(file)),
+        None => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE)),
+    };
+    let mut editor = Editor::<()>::new();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+    println!("circom {} repl on {}, :help shows the commands", VERSION, session.input);
+    loop {
+        let line = match editor.readline("circom> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(_) => {
+                eprintln!("{}", Colour::Red.paint("Could not read the input"));
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line);
+        match line {
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":history" => {
+                for (index, entry) in editor.history().iter().enumerate() {
+                    println!("{:>4}  {}", index + 1, entry);
+                }
+            }
+            _ => {
+                let _ = session.run(line);
+            }
+        }
+    }
+    if let Some(history) = &history {
+        if editor.save_history(history).is_err() {
+            eprintln!("{}", Colour::Red.paint(format!("Could not write the history in {}", history.display())));
+        }
+    }
+    Result::Ok(())
+}
+
+fn load(input: &str, link_libraries: &[PathBuf], field: &BigInt) -> Result<ProgramArchive, ()> {
+    match parser::run_library_parser(input.to_string(), VERSION, link_libraries.to_vec(), field) {
+        Result::Err((file_library, reports)) => {
+            Report::print_reports(&reports, &file_library);
+            Result::Err(())
+        }
+        Result::Ok((program, warnings)) => {
+            Report::print_reports(&warnings, &program.file_library);
+            Result::Ok(program)
+        }
+    }
+}
+
+impl Session {
+    fn run(&mut self, line: &str) -> Result<(), ()> {
+        let line = line.strip_suffix(';').unwrap_or(line).trim();
+        if line == ":load" {
+            // The previous sources are kept if the new ones have errors
+            self.program = load(&self.input, &self.link_libraries, &self.field)?;
+            println!("{} {}", Colour::Green.paint("Loaded:"), self.input);
+        } else if line == ":vars" {
+            for (name, value) in &self.variables {
+                println!("{} = {}", name, literal(value));
+            }
+        } else if line.starts_with(':') {
+            eprintln!("{}", Colour::Red.paint(format!("Unknown command {}, :help shows the commands", line)));
+            return Result::Err(());
+        } else if let Some(declaration) = line.strip_prefix("var ") {
+            let (name, expression) = declaration.split_once('=').ok_or_else(|| {
+                eprintln!("{}", Colour::Red.paint("Variables are declared as var <name> = <expression>"))
+            })?;
+            let name = name.trim();
+            if !is_identifier(name) {
+                eprintln!("{}", Colour::Red.paint(format!("{} is not a valid name for a variable", name)));
+                return Result::Err(());
+            }
+            let value = self.evaluate(expression.trim())?;
+            println!("{} = {}", name, literal(&value));
+            self.variables.retain(|(variable, _)| variable != name);
+            self.variables.push((name.to_string(), value));
+        } else if let Some((name, args, inputs)) = self.template_call(line) {
+            self.instantiate(name, args, inputs)?;
+        } else {
+            let value = self.evaluate(line)?;
+            println!("{}", literal(&value));
+        }
+        Result::Ok(())
+    }
+
+    // Value of an expression, computed as the result of a function where the
+    // variables of the session are declared
+    fn evaluate(&self, expression: &str) -> Result<Value, ()> {
+        let mut program = self.program.clone();
+        let mut src = format!("function {}() {{ ", REPL_FUNCTION);
+        for (name, value) in &self.variables {
+            let dimensions: String = value.0.iter().map(|length| format!("[{}]", length)).collect();
+            src.push_str(&format!("var {}{} = {}; ", name, dimensions, literal(value)));
+        }
+        src.push_str("return ");
+        let start = src.len();
+        src.push_str(&format!("{}; }}", expression));
+        let expression_file = program.file_library.add_file(format!("{:?}", expression), expression.to_string());
+        let mut generated = ReplSource { expression_file, start, length: expression.len(), main_call: None };
+        parser::parse_definitions(REPL_FUNCTION.to_string(), &src, &mut program, &self.field)
+            .map_err(|reports| generated.print_reports(reports, &program))?;
+        let spec = MainComponentSpec { call: format!("{}()", REPL_FUNCTION), public: Vec::new() };
+        let (file_id, main) = parser::parse_main_call(&spec, &mut program.file_library, &self.field)
+            .map_err(|reports| generated.print_reports(reports, &program))?;
+        generated.main_call = Some(file_id);
+        program.set_main_component(file_id, main);
+        check_expression_types(&mut program).map_err(|reports| generated.print_reports(reports, &program))?;
+        evaluate_main_expression(&program, &self.prime).map_err(|reports| generated.print_reports(reports, &program))
+    }
+
+    // Lines like T(a, b) or T(a, b) {"in": 1}, where T is a template: the
+    // name, the arguments and the text after the call
+    fn template_call<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        let open = line.find('(')?;
+        let name = line[..open].trim();
+        if !self.program.contains_template(name) {
+            return None;
+        }
+        let mut depth = 0;
+        for (index, c) in line.char_indices().skip(open) {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 1 => return Some((name, &line[open + 1..index], line[index + 1..].trim())),
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn instantiate(&self, name: &str, args: &str, inputs: &str) -> Result<(), ()> {
+        let inputs = if inputs.is_empty() {
+            None
+        } else {
+            match json::parse(inputs) {
+                Ok(value) if value.is_object() => Some(value),
+                _ => {
+                    eprintln!("{}", Colour::Red.paint("The inputs must be given as a JSON object"));
+                    return Result::Err(());
+                }
+            }
+        };
+        let mut values = Vec::new();
+        for arg in split_arguments(args) {
+            values.push(literal(&self.evaluate(arg)?));
+        }
+        let call = format!("{}({})", name, values.join(", "));
+
+        let mut program = self.program.clone();
+        let spec = MainComponentSpec { call: call.clone(), public: Vec::new() };
+        let (file_id, main) = parser::parse_main_call(&spec, &mut program.file_library, &self.field)
+            .map_err(|reports| Report::print_reports(&reports, &program.file_library))?;
+        program.set_main_component(file_id, main);
+        check_types(&mut program).map_err(|reports| Report::print_reports(&reports, &program.file_library))?;
+        let file_library = program.file_library.clone();
+        let (count, vcp) =
+            instantiate(program, &self.prime).map_err(|reports| Report::print_reports(&reports, &file_library))?;
+
+        println!("{}", Colour::Green.paint(call));
+        print_signals(&vcp);
+        println!("non-linear constraints: {}", count.non_linear);
+        println!("linear constraints: {}", count.linear);
+        if let Some(inputs) = inputs {
+            self.compute_outputs(vcp, &inputs)?;
+        }
+        Result::Ok(())
+    }
+
+    // Computes the witness of the instance with the compiler and prints the
+    // values of the outputs of the main component
+    fn compute_outputs(&self, vcp: VCP, inputs: &JsonValue) -> Result<(), ()> {
+        let config = Config {
+            debug_output: false,
+            produce_input_log: false,
+            wat_flag: false,
+            constraint_assert_disabled_flag: false,
+            no_asm_flag: false,
+            c_lib_flag: false,
+            cvm_multi_assign_flag: false,
+        };
+        let circuit = compiler_interface::run_compiler(vcp, config, VERSION)?;
+        let calculator = WitnessCalculator::new(&circuit);
+        let mut given = Vec::new();
+        flatten_inputs(inputs, String::new(), &mut given);
+        let expected = calculator.main_inputs();
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for (name, value) in &given {
+            match (expected.iter().find(|(input, _)| input == name), self.parse_value(value)) {
+                (None, _) => errors.push(format!("{} is not an input of the main component", name)),
+                (_, None) => errors.push(format!("the value of {} is not a number", name)),
+                (Some((_, position)), Some(value)) => values.push((*position, value)),
+            }
+        }
+        for (name, _) in &expected {
+            if !given.iter().any(|(input, _)| input == name) {
+                errors.push(format!("missing value for {}", name));
+            }
+        }
+        for error in &errors {
+            eprintln!("{} {}", Colour::Red.paint("Invalid input:"), error);
+        }
+        if !errors.is_empty() {
+            return Result::Err(());
+        }
+        match calculator.calculate(&values) {
+            Ok(witness) => {
+                for (name, position) in calculator.main_outputs() {
+                    println!("{} = {}", name, witness[position]);
+                }
+                Result::Ok(())
+            }
+            Err(error) => {
+                eprintln!("{}", Colour::Red.paint(error.to_string()));
+                Result::Err(())
+            }
+        }
+    }
+
+    // Inputs are given as numbers or as strings with a decimal number, which
+    // can be negative
+    fn parse_value(&self, value: &JsonValue) -> Option<BigInt> {
+        let text = if value.is_number() { value.dump() } else { value.as_str()?.to_string() };
+        let value = BigInt::parse_bytes(text.trim().as_bytes(), 10)?;
+        Some(((value % &self.field) + &self.field) % &self.field)
+    }
+}
+
+// Sources generated to evaluate an expression, added to the program after
+// the file with the expression: the function that returns it, where the
+// expression starts at start, and the call to the function
+struct ReplSource {
+    expression_file: FileID,
+    start: usize,
+    length: usize,
+    main_call: Option<FileID>,
+}
+
+impl ReplSource {
+    // Reports point to the expression typed by the user instead of the
+    // generated sources, and their call traces start at it
+    fn print_reports(&self, mut reports: ReportCollection, program: &ProgramArchive) {
+        let function = format!("->{}", REPL_FUNCTION);
+        let expression = program.file_library.get_source(self.expression_file).unwrap_or("");
+        for report in &mut reports {
+            report.relocate_labels(|file_id, range| self.relocate(file_id, range));
+            report.map_notes(|note| note.replace(&function, &format!("->{}", expression)));
+        }
+        Report::print_reports(&reports, &program.file_library);
+    }
+
+    // Locations in the function are moved into the expression, and the ones
+    // in the call cover the whole expression
+    fn relocate(&self, file_id: FileID, range: FileLocation) -> (FileID, FileLocation) {
+        let end = self.start + self.length;
+        let inside = |position: usize| position.clamp(self.start, end) - self.start;
+        if file_id <= self.expression_file {
+            (file_id, range)
+        } else if self.main_call == Some(file_id) || range.end < self.start || range.start > end {
+            (self.expression_file, 0..self.length)
+        } else {
+            (self.expression_file, inside(range.start)..inside(range.end))
+        }
+    }
+}
+
+fn print_signals(vcp: &VCP) {
+    let main: &TemplateInstance = vcp.get_main_instance().unwrap();
+    for (xtype, kind) in [(SignalType::Input, "input"), (SignalType::Output, "output")] {
+        for wire in main.wires.iter().filter(|wire| wire.xtype() == xtype) {
+            let bus = wire.bus_id().map_or(String::new(), |bus_id| format!("{} ", vcp.buses[bus_id].name));
+            let dimensions: String = wire.lengths().iter().map(|length| format!("[{}]", length)).collect();
+            println!("{} {}{}{}", kind, bus, wire.name(), dimensions);
+        }
+    }
+    println!("intermediate signals: {}", main.number_of_intermediates);
+}
+
+// Names of the elements of the inputs as in the main component: arrays give
+// a[0] and objects, for the fields of buses, a.b
+fn flatten_inputs<'a>(value: &'a JsonValue, name: String, elements: &mut Vec<(String, &'a JsonValue)>) {
+    match value {
+        JsonValue::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                flatten_inputs(value, format!("{}[{}]", name, index), elements);
+            }
+        }
+        JsonValue::Object(object) => {
+            for (key, value) in object.iter() {
+                let name = if name.is_empty() { key.to_string() } else { format!("{}.{}", name, key) };
+                flatten_inputs(value, name, elements);
+            }
+        }
+        _ => elements.push((name, value)),
+    }
+}
+
+// Arguments of a call, split at the commas that are not inside brackets
+fn split_arguments(args: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in args.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(args[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if !args[start..].trim().is_empty() {
+        arguments.push(args[start..].trim());
+    }
+    arguments
+}
+
+// Writes a value as a circom expression: a number or nested arrays
+fn literal((dimensions, values): &Value) -> String {
+    match dimensions.split_first() {
+        None => values[0].to_string(),
+        Some((length, rest)) => {
+            let size = rest.iter().product::<usize>();
+            let elements: Vec<String> = (0..*length)
+                .map(|index| literal(&(rest.to_vec(), values[index * size..(index + 1) * size].to_vec())))
+                .collect();
+            format!("[{}]", elements.join(", "))
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let name = name.trim_start_matches(|c| c == '$' || c == '_');
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '$' || c == '_')
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom repl")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Evaluates expressions and instantiates templates of a project interactively")
+        .arg(
+            Arg::with_name("input")
+                .required(true)
+                .help("Main file of the project or library. The templates and functions of every file it includes can be used"),
+        )
+        .arg(
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(10)
+                .help("Adds directory to library search path"),
+        )
+        .arg(
+            Arg::with_name("prime")
+                .long("prime")
+                .takes_value(true)
+                .default_value("bn128")
+                .display_order(20)
+                .help("Name of the curve whose field is used in the computations"),
+        )
+        .arg(
+            Arg::with_name("history")
+                .long("history")
+                .takes_value(true)
+                .display_order(30)
+                .help("File where the lines entered are kept. By default, .circom_repl_history in the home directory"),
+        )
+        .get_matches_from(std::env::args().skip(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_are_split_outside_brackets() {
+        assert_eq!(split_arguments("f(1, 2), [3, 4],x"), vec!["f(1, 2)", "[3, 4]", "x"]);
+        assert!(split_arguments(" ").is_empty());
+    }
+
+    #[test]
+    fn values_are_written_as_nested_arrays() {
+        let values: Vec<BigInt> = (1..=6).map(BigInt::from).collect();
+        assert_eq!(literal(&(vec![2, 3], values)), "[[1, 2, 3], [4, 5, 6]]");
+        assert_eq!(literal(&(Vec::new(), vec![BigInt::from(7)])), "7");
+    }
+
+    #[test]
+    fn reports_point_to_the_expression() {
+        // function __repl__() { return b + 1; } with the expression in file 3
+        let source = ReplSource { expression_file: 3, start: 29, length: 5, main_call: Some(5) };
+        assert_eq!(source.relocate(2, 29..30), (2, 29..30));
+        assert_eq!(source.relocate(4, 29..30), (3, 0..1));
+        assert_eq!(source.relocate(4, 31..40), (3, 2..5));
+        assert_eq!(source.relocate(4, 0..10), (3, 0..5));
+        assert_eq!(source.relocate(5, 17..27), (3, 0..5));
+    }
+}
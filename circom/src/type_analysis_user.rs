@@ -1,9 +1,17 @@
+use ansi_term::Colour;
 use program_structure::error_definition::Report;
 use program_structure::program_archive::ProgramArchive;
-use type_analysis::check_types::check_types;
+use type_analysis::check_types::{check_types, check_types_with_lints};
+use type_analysis::lints::LintConfig;
+use crate::input_user::Input;
 
-pub fn analyse_project(program_archive: &mut ProgramArchive) -> Result<(), ()> {
-    let analysis_result = check_types(program_archive);
+pub fn analyse_project(program_archive: &mut ProgramArchive, input: &Input) -> Result<(), ()> {
+    let analysis_result = if input.lint_flag() {
+        let lint_config = load_lint_config(input)?;
+        check_types_with_lints(program_archive, &lint_config)
+    } else {
+        check_types(program_archive)
+    };
     match analysis_result {
         Err(errs) => {
             Report::print_reports(&errs, program_archive.get_file_library());
@@ -15,3 +23,17 @@ pub fn analyse_project(program_archive: &mut ProgramArchive) -> Result<(), ()> {
         }
     }
 }
+
+fn load_lint_config(input: &Input) -> Result<LintConfig, ()> {
+    match input.lint_config_file() {
+        Some(file) => {
+            let src = std::fs::read_to_string(file).map_err(|_| {
+                eprintln!("{}", Colour::Red.paint(format!("Could not read the file {}", file.display())))
+            })?;
+            LintConfig::parse(&src).map_err(|msg| {
+                eprintln!("{}", Colour::Red.paint(format!("Invalid lint configuration {}: {}", file.display(), msg)))
+            })
+        }
+        None => Ok(LintConfig::new()),
+    }
+}
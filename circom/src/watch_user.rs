@@ -0,0 +1,266 @@
+use super::input_user::Input;
+use ansi_term::Colour;
+use program_structure::constants::UsefulConstants;
+use program_structure::error_definition::Report;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const POLLING_INTERVAL: Duration = Duration::from_millis(300);
+const BACKUP_EXTENSION: &str = "watch_backup";
+
+// Reports of the previous compilations by their keys, with their summaries
+#[derive(Default)]
+struct KnownReports {
+    reports: HashMap<String, String>,
+    warnings: HashMap<String, String>,
+}
+
+impl KnownReports {
+    // Replaces the known reports with the ones of a compilation and gives
+    // the summaries of the resolved reports and the number of unchanged ones.
+    // A failed compilation may stop before the phase that gave a warning, so
+    // only its errors are resolved.
+    fn update(&mut self, reports: &[Report], failed: bool) -> (Vec<String>, usize) {
+        let entry = |report: &Report| (report.key(), report.summary());
+        let mut current: HashMap<String, String> = reports.iter().map(entry).collect();
+        let warnings = reports.iter().filter(|report| report.is_warning()).map(entry);
+        if failed {
+            current.extend(self.warnings.clone());
+            self.warnings.extend(warnings);
+        } else {
+            self.warnings = warnings.collect();
+        }
+        let mut resolved: Vec<(&String, &String)> =
+            self.reports.iter().filter(|(key, _)| !current.contains_key(*key)).collect();
+        resolved.sort();
+        let resolved = resolved.into_iter().map(|(_, summary)| summary.clone()).collect();
+        let unchanged = self.reports.keys().filter(|key| current.contains_key(*key)).count();
+        self.reports = current;
+        (resolved, unchanged)
+    }
+
+    fn keys(&self) -> HashSet<String> {
+        self.reports.keys().cloned().collect()
+    }
+}
+
+// Compiles the project every time one of the files it reads changes. Only
+// the reports that were not given by the previous compilation are printed,
+// and the outputs of the previous compilation are kept if the new one fails.
+pub fn watch_project(input: &Input) -> Result<(), ()> {
+    let mut known = KnownReports::default();
+    loop {
+        compile(input, &mut known)?;
+        let files = watched_files(input);
+        println!("Watching {} files for changes", files.len());
+        wait_for_changes(&files);
+    }
+}
+
+// One compilation of the watch loop, the error is only returned when the
+// outputs of the previous compilation could not be kept
+fn compile(input: &Input, known: &mut KnownReports) -> Result<(), ()> {
+    let backups = backup_outputs(input)?;
+    Report::start_recording(known.keys());
+    let result = crate::build(input);
+    let reports = Report::stop_recording();
+    let (resolved, unchanged) = known.update(&reports, result.is_err());
+    for summary in resolved {
+        println!("{} {}", Colour::Green.paint("Resolved:"), summary);
+    }
+    if unchanged > 0 {
+        println!("{} reports did not change since the previous compilation", unchanged);
+    }
+    if result.is_ok() {
+        remove_backups(&backups);
+        println!("{}", Colour::Green.paint("Everything went okay"));
+    } else {
+        restore_backups(input, &backups);
+        eprintln!("{}", Colour::Red.paint("previous errors were found, the previous outputs are kept"));
+    }
+    Result::Ok(())
+}
+
+// The main file, every file it includes and the lint configuration
+fn watched_files(input: &Input) -> Vec<PathBuf> {
+    let field = UsefulConstants::new(&input.prime()).get_p().clone();
+    let mut files = parser::find_included_files(
+        input.input_file().to_string(),
+        input.get_link_libraries().to_vec(),
+        &field,
+    );
+    let main_file = std::fs::canonicalize(&input.input_program).unwrap_or_else(|_| input.input_program.clone());
+    if !files.contains(&main_file) {
+        files.push(main_file);
+    }
+    if let Some(lint_config) = input.lint_config_file() {
+        files.push(lint_config.clone());
+    }
+    files
+}
+
+fn modification_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files.iter().map(|file| std::fs::metadata(file).and_then(|data| data.modified()).ok()).collect()
+}
+
+fn wait_for_changes(files: &[PathBuf]) {
+    let times = modification_times(files);
+    while modification_times(files) == times {
+        std::thread::sleep(POLLING_INTERVAL);
+    }
+}
+
+// Outputs of the compilation, including its manifest
+fn outputs(input: &Input) -> Vec<PathBuf> {
+    let mut outputs = input.outputs();
+    outputs.push(input.out_manifest.clone());
+    outputs
+}
+
+fn backup_path(output: &Path) -> PathBuf {
+    let mut name = output.file_name().unwrap().to_os_string();
+    name.push(".");
+    name.push(BACKUP_EXTENSION);
+    output.with_file_name(name)
+}
+
+fn remove_output(output: &Path) -> std::io::Result<()> {
+    if output.is_dir() {
+        std::fs::remove_dir_all(output)
+    } else {
+        std::fs::remove_file(output)
+    }
+}
+
+// Moves the existing outputs away, so the compilation writes new ones
+fn backup_outputs(input: &Input) -> Result<Vec<(PathBuf, PathBuf)>, ()> {
+    let mut backups = Vec::new();
+    for output in outputs(input) {
+        if !output.exists() {
+            continue;
+        }
+        let backup = backup_path(&output);
+        if backup.exists() {
+            let _ = remove_output(&backup);
+        }
+        if std::fs::rename(&output, &backup).is_err() {
+            eprintln!("{}", Colour::Red.paint(format!("Could not keep a copy of {}", output.display())));
+            restore_backups(input, &backups);
+            return Result::Err(());
+        }
+        backups.push((output, backup));
+    }
+    Result::Ok(backups)
+}
+
+fn remove_backups(backups: &[(PathBuf, PathBuf)]) {
+    for (_, backup) in backups {
+        let _ = remove_output(backup);
+    }
+}
+
+// Removes the outputs of a failed compilation and puts the previous ones back
+fn restore_backups(input: &Input, backups: &[(PathBuf, PathBuf)]) {
+    for output in outputs(input) {
+        if output.exists() {
+            let _ = remove_output(&output);
+        }
+    }
+    for (output, backup) in backups {
+        if std::fs::rename(backup, output).is_err() {
+            eprintln!("{}", Colour::Red.paint(format!("Could not restore {}", output.display())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_structure::error_code::ReportCode;
+
+    fn warning_at(start: usize) -> Report {
+        let mut report = Report::warning("Unused parameter n".to_string(), ReportCode::LintUnusedParameter);
+        report.add_primary(start..start + 1, 0, "unused".to_string());
+        report
+    }
+
+    fn error_at(start: usize) -> Report {
+        let mut report = Report::error("Missing semicolon".to_string(), ReportCode::MissingSemicolon);
+        report.add_primary(start..start + 1, 0, "here".to_string());
+        report
+    }
+
+    fn project(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("circom_watch_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn input_for(folder: &Path) -> Input {
+        let circuit = folder.join("circuit.circom");
+        let arguments = ["circom", circuit.to_str().unwrap(), "--r1cs", "--sym"];
+        Input::from_arguments(arguments.iter().map(|arg| arg.to_string()).collect(), Some(folder.to_path_buf()))
+            .unwrap_or_else(|_| panic!("The arguments could not be read"))
+    }
+
+    const CIRCUIT: &str = "pragma circom 2.2.2;\ninclude \"square.circom\";\ncomponent main = Square();\n";
+    const SQUARE: &str = "pragma circom 2.2.2;\ntemplate Square() {\n    signal input in;\n    signal output out <== in * in;\n}\n";
+
+    #[test]
+    fn equal_messages_at_different_places_are_different_reports() {
+        let mut known = KnownReports::default();
+        let (resolved, unchanged) = known.update(&[warning_at(10), warning_at(20)], false);
+        assert!(resolved.is_empty());
+        assert_eq!(unchanged, 0);
+        assert_eq!(known.keys().len(), 2);
+        let (resolved, unchanged) = known.update(&[warning_at(20), warning_at(30)], false);
+        assert_eq!(resolved, vec![warning_at(10).summary()]);
+        assert_eq!(unchanged, 1);
+        assert_eq!(known.keys(), vec![warning_at(20).key(), warning_at(30).key()].into_iter().collect());
+    }
+
+    #[test]
+    fn failed_compilations_only_resolve_errors() {
+        let mut known = KnownReports::default();
+        known.update(&[warning_at(10), error_at(5)], true);
+        let (resolved, unchanged) = known.update(&[error_at(40)], true);
+        assert_eq!(resolved, vec![error_at(5).summary()]);
+        assert_eq!(unchanged, 1);
+        let (resolved, unchanged) = known.update(&[], false);
+        assert_eq!(resolved, vec![error_at(40).summary(), warning_at(10).summary()]);
+        assert_eq!(unchanged, 0);
+        assert!(known.keys().is_empty());
+    }
+
+    #[test]
+    fn watch_loop_keeps_the_outputs_of_failed_compilations() {
+        let folder = project("outputs");
+        std::fs::write(folder.join("circuit.circom"), CIRCUIT).unwrap();
+        std::fs::write(folder.join("square.circom"), SQUARE).unwrap();
+        let input = input_for(&folder);
+        let files = watched_files(&input);
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().any(|file| file.ends_with("square.circom")));
+
+        let mut known = KnownReports::default();
+        compile(&input, &mut known).unwrap();
+        let r1cs = std::fs::read(input.r1cs_file()).unwrap();
+        let sym = std::fs::read(input.sym_file()).unwrap();
+        assert!(known.keys().is_empty());
+
+        std::fs::write(folder.join("square.circom"), SQUARE.replace("in * in;", "in * in")).unwrap();
+        compile(&input, &mut known).unwrap();
+        assert_eq!(known.keys().len(), 1);
+        assert_eq!(std::fs::read(input.r1cs_file()).unwrap(), r1cs);
+        assert_eq!(std::fs::read(input.sym_file()).unwrap(), sym);
+        assert!(!backup_path(Path::new(input.r1cs_file())).exists());
+
+        std::fs::write(folder.join("square.circom"), SQUARE.replace("in * in", "in * in + 1")).unwrap();
+        compile(&input, &mut known).unwrap();
+        assert!(known.keys().is_empty());
+        assert_ne!(std::fs::read(input.r1cs_file()).unwrap(), r1cs);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,17 +1,21 @@
+use super::field_element::FieldElement;
 use super::modular_arithmetic;
 pub use super::modular_arithmetic::ArithmeticError;
 use num_bigint::BigInt;
-use num_traits::{ToPrimitive, Zero};
+use num_traits::ToPrimitive;
 use std::collections::{HashMap, HashSet, BTreeSet};
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 
-pub enum ArithmeticExpression<C>
+// The coefficients are elements of F, which is BigInt unless a fixed-size
+// representation of the prime is chosen
+#[derive(Default)]
+pub enum ArithmeticExpression<C, F = BigInt>
 where
     C: Hash + Eq,
 {
     Number {
-        value: BigInt,
+        value: F,
     },
     Signal {
         symbol: C,
@@ -20,23 +24,24 @@ where
         // Represents the expression: c1*s1 + .. + cn*sn + C
         // where c1..cn are integers modulo a prime and
         // s1..sn are signals. C is a constant value
-        coefficients: HashMap<C, BigInt>,
+        coefficients: HashMap<C, F>,
     },
     Quadratic {
         // Is a quadratic expression of the form:
         //              a*b + c
         // Where a,b and c are linear expression
-        a: HashMap<C, BigInt>,
-        b: HashMap<C, BigInt>,
-        c: HashMap<C, BigInt>,
+        a: HashMap<C, F>,
+        b: HashMap<C, F>,
+        c: HashMap<C, F>,
     },
+    #[default]
     NonQuadratic,
 }
-impl<C: Default + Clone + Display + Hash + Eq> Display for ArithmeticExpression<C> {
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> Display for ArithmeticExpression<C, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         use ArithmeticExpression::*;
         let msg = match self {
-            Number { value } => value.to_str_radix(10),
+            Number { value } => value.to_string(),
             Signal { symbol } => format!("{}", symbol),
             NonQuadratic => "Non quadratic".to_string(),
             Linear { coefficients } => ArithmeticExpression::string_from_coefficients(coefficients),
@@ -51,7 +56,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Display for ArithmeticExpression<
     }
 }
 
-impl<C: Default + Clone + Display + Hash + Eq> Clone for ArithmeticExpression<C> {
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> Clone for ArithmeticExpression<C, F> {
     fn clone(&self) -> Self {
         use ArithmeticExpression::*;
         match self {
@@ -64,8 +69,8 @@ impl<C: Default + Clone + Display + Hash + Eq> Clone for ArithmeticExpression<C>
     }
 }
 
-impl<C: Default + Clone + Display + Hash + Eq> Eq for ArithmeticExpression<C> {}
-impl<C: Default + Clone + Display + Hash + Eq> PartialEq for ArithmeticExpression<C> {
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> Eq for ArithmeticExpression<C, F> {}
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> PartialEq for ArithmeticExpression<C, F> {
     fn eq(&self, other: &Self) -> bool {
         use ArithmeticExpression::*;
         match (self, other) {
@@ -80,27 +85,22 @@ impl<C: Default + Clone + Display + Hash + Eq> PartialEq for ArithmeticExpressio
     }
 }
 
-impl<C: Default + Clone + Display + Hash + Eq> Default for ArithmeticExpression<C> {
-    fn default() -> Self {
-        ArithmeticExpression::NonQuadratic
-    }
-}
 
-impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
-    pub fn new() -> ArithmeticExpression<C> {
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> ArithmeticExpression<C, F> {
+    pub fn new() -> ArithmeticExpression<C, F> {
         ArithmeticExpression::default()
     }
 
     // printing utils
-    fn string_from_coefficients(coefficients: &HashMap<C, BigInt>) -> String {
+    fn string_from_coefficients(coefficients: &HashMap<C, F>) -> String {
         let mut string_coefficients = "".to_string();
         for (signal, value) in coefficients {
             let component_string = if value.is_zero() {
                 "".to_string()
-            } else if signal.eq(&ArithmeticExpression::constant_coefficient()) {
-                format!("{}+", value.to_str_radix(10))
+            } else if signal.eq(&Self::constant_coefficient()) {
+                format!("{}+", value)
             } else {
-                format!("{}*{}+", signal, value.to_str_radix(10))
+                format!("{}*{}+", signal, value)
             };
             string_coefficients.push_str(component_string.as_str());
         }
@@ -111,9 +111,9 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
     // constraint generation utils
     // transforms constraints into a constraint, None if the expression was non-quadratic
     pub fn transform_expression_to_constraint_form(
-        arithmetic_expression: ArithmeticExpression<C>,
+        arithmetic_expression: ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> Option<Constraint<C>> {
+    ) -> Option<Constraint<C, F>> {
         use ArithmeticExpression::*;
         let mut a = HashMap::new();
         let mut b = HashMap::new();
@@ -131,16 +131,16 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 c = old_c;
             }
             Number { value } => {
-                c.insert(ArithmeticExpression::constant_coefficient(), value);
+                c.insert(Self::constant_coefficient(), value);
             }
             Signal { symbol } => {
-                c.insert(symbol, BigInt::from(1));
+                c.insert(symbol, F::one());
             }
             Linear { coefficients } => {
                 c = coefficients;
             }
         }
-        ArithmeticExpression::multiply_coefficients_by_constant(&BigInt::from(-1), &mut c, field);
+        ArithmeticExpression::multiply_coefficients_by_constant(&F::one().prefix_sub(field), &mut c, field);
         Option::Some(Constraint::new(a, b, c))
     }
 
@@ -155,53 +155,53 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
     fn constant_coefficient() -> C {
         C::default()
     }
-    fn initialize_hashmap_for_expression(initial: &mut HashMap<C, BigInt>) {
+    fn initialize_hashmap_for_expression(initial: &mut HashMap<C, F>) {
         initial
-            .entry(ArithmeticExpression::constant_coefficient())
-            .or_insert_with(|| BigInt::from(0));
+            .entry(Self::constant_coefficient())
+            .or_insert_with(|| F::zero());
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(initial));
     }
-    fn valid_hashmap_for_expression(h: &HashMap<C, BigInt>) -> bool {
-        let cc = ArithmeticExpression::constant_coefficient();
+    fn valid_hashmap_for_expression(h: &HashMap<C, F>) -> bool {
+        let cc = Self::constant_coefficient();
         h.contains_key(&cc)
     }
-    fn initialize_symbol_in_coefficients(symbol: &C, coefficients: &mut HashMap<C, BigInt>) {
+    fn initialize_symbol_in_coefficients(symbol: &C, coefficients: &mut HashMap<C, F>) {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
         if !coefficients.contains_key(symbol) {
-            coefficients.insert(symbol.clone(), BigInt::from(0));
+            coefficients.insert(symbol.clone(), F::zero());
         }
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
     }
     fn add_constant_to_coefficients(
-        value: &BigInt,
-        coefficients: &mut HashMap<C, BigInt>,
+        value: &F,
+        coefficients: &mut HashMap<C, F>,
         field: &BigInt,
     ) {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
-        let cc: C = ArithmeticExpression::constant_coefficient();
+        let cc: C = Self::constant_coefficient();
         coefficients.insert(
             cc.clone(),
-            modular_arithmetic::add(coefficients.get(&cc).unwrap(), value, field),
+            coefficients.get(&cc).unwrap().add(value, field),
         );
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
     }
     fn add_symbol_to_coefficients(
         symbol: &C,
-        coefficient: &BigInt,
-        coefficients: &mut HashMap<C, BigInt>,
+        coefficient: &F,
+        coefficients: &mut HashMap<C, F>,
         field: &BigInt,
     ) {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
         ArithmeticExpression::initialize_symbol_in_coefficients(symbol, coefficients);
         coefficients.insert(
             symbol.clone(),
-            modular_arithmetic::add(coefficients.get(symbol).unwrap(), coefficient, field),
+            coefficients.get(symbol).unwrap().add(coefficient, field),
         );
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
     }
     fn add_coefficients_to_coefficients(
-        coefficients_0: &HashMap<C, BigInt>,
-        coefficients_1: &mut HashMap<C, BigInt>,
+        coefficients_0: &HashMap<C, F>,
+        coefficients_1: &mut HashMap<C, F>,
         field: &BigInt,
     ) {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients_0));
@@ -218,44 +218,40 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients_1));
     }
     fn multiply_coefficients_by_constant(
-        constant: &BigInt,
-        coefficients: &mut HashMap<C, BigInt>,
+        constant: &F,
+        coefficients: &mut HashMap<C, F>,
         field: &BigInt,
     ) {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
         for value in coefficients.values_mut() {
-            *value = modular_arithmetic::mul(value, constant, field);
+            *value = value.mul(constant, field);
         }
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
     }
     fn divide_coefficients_by_constant(
-        constant: &BigInt,
-        coefficients: &mut HashMap<C, BigInt>,
+        constant: &F,
+        coefficients: &mut HashMap<C, F>,
         field: &BigInt,
     ) -> Result<(), ArithmeticError> {
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
-        let inverse_constant = modular_arithmetic::div(
-            &BigInt::from(1),
-            constant,
-            &field
-        )?;
+        let inverse_constant = constant.inverse(field)?;
         ArithmeticExpression::multiply_coefficients_by_constant(&inverse_constant, coefficients, field);
         debug_assert!(ArithmeticExpression::valid_hashmap_for_expression(coefficients));
         Result::Ok(())
     }
 
     pub fn add(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         match (left, right) {
             (NonQuadratic, _) | (_, NonQuadratic) | (Quadratic { .. }, Quadratic { .. }) => {
                 NonQuadratic
             }
             (Number { value: v_0 }, Number { value: v_1 }) => {
-                Number { value: modular_arithmetic::add(v_0, v_1, field) }
+                Number { value: v_0.add(v_1, field) }
             }
             (Number { value }, Signal { symbol }) | (Signal { symbol }, Number { value }) => {
                 let mut coefficients = HashMap::new();
@@ -263,7 +259,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 ArithmeticExpression::add_constant_to_coefficients(value, &mut coefficients, field);
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut coefficients,
                     field,
                 );
@@ -290,13 +286,13 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 ArithmeticExpression::initialize_hashmap_for_expression(&mut coefficients);
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol_0,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut coefficients,
                     field,
                 );
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol_1,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut coefficients,
                     field,
                 );
@@ -307,7 +303,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 let mut n_coefficients = coefficients.clone();
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut n_coefficients,
                     field,
                 );
@@ -318,7 +314,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 let mut coefficients = c.clone();
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut coefficients,
                     field,
                 );
@@ -347,10 +343,10 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
     }
 
     pub fn mul(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         match (left, right) {
             (NonQuadratic, _)
@@ -361,7 +357,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
             | (Quadratic { .. }, Signal { .. })
             | (Signal { .. }, Quadratic { .. }) => NonQuadratic,
             (Number { value: value_0 }, Number { value: value_1 }) => {
-                Number { value: modular_arithmetic::mul(value_0, value_1, field) }
+                Number { value: value_0.mul(value_1, field) }
             }
             (Number { value }, Signal { symbol }) | (Signal { symbol }, Number { value }) => {
                 let mut coefficients = HashMap::new();
@@ -402,13 +398,13 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 ArithmeticExpression::initialize_hashmap_for_expression(&mut c);
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol_0,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut a,
                     field,
                 );
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol_1,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut b,
                     field,
                 );
@@ -423,7 +419,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 ArithmeticExpression::initialize_hashmap_for_expression(&mut c);
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut b,
                     field,
                 );
@@ -439,25 +435,25 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
         }
     }
     pub fn sub(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
-        let minus_one = Number { value: BigInt::from(-1) };
+        let minus_one = Number { value: F::one().prefix_sub(field) };
         let step_one = ArithmeticExpression::mul(&minus_one, right, field);
         ArithmeticExpression::add(left, &step_one, field)
     }
 
     pub fn div(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> Result<ArithmeticExpression<C>, ArithmeticError> {
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
         use ArithmeticExpression::*;
         match (left, right) {
             (Number { value: value_0 }, Number { value: value_1 }) => {
-                let value = modular_arithmetic::div(value_0, value_1, field)?;
+                let value = value_0.div(value_1, field)?;
                 Result::Ok(Number { value })
             }
             (Signal { symbol }, Number { value }) => {
@@ -465,7 +461,7 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
                 ArithmeticExpression::initialize_hashmap_for_expression(&mut coefficients);
                 ArithmeticExpression::add_symbol_to_coefficients(
                     symbol,
-                    &BigInt::from(1),
+                    &F::one(),
                     &mut coefficients,
                     field,
                 );
@@ -496,264 +492,265 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
             _ => Result::Ok(NonQuadratic),
         }
     }
-    pub fn idiv(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
-        field: &BigInt,
-    ) -> Result<ArithmeticExpression<C>, ArithmeticError> {
+    pub fn prefix_sub(elem: &ArithmeticExpression<C, F>, field: &BigInt) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
-        match (left, right) {
-            (Number { value: value_0 }, Number { value: value_1 }) => {
-                let value = modular_arithmetic::idiv(value_0, value_1, field)?;
-                Result::Ok(Number { value })
+        let minus_one = Number { value: F::one().prefix_sub(field) };
+        ArithmeticExpression::mul(elem, &minus_one, field)
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, ArithmeticExpression::Number { .. })
+    }
+    pub fn is_nonquadratic(&self) -> bool {
+        matches!(self, ArithmeticExpression::NonQuadratic)
+    }
+    pub fn is_quadratic(&self) -> bool {
+        matches!(self, ArithmeticExpression::Quadratic { .. })
+    }
+    pub fn is_linear(&self) -> bool {
+        matches!(self, ArithmeticExpression::Linear { .. })
+    }
+
+    pub fn hashmap_into_arith(mut map: HashMap<C, F>) -> ArithmeticExpression<C, F> {
+        let c: C = Self::constant_coefficient();
+        let expr = if HashMap::len(&map) == 1 && HashMap::contains_key(&map, &c) {
+            let value = HashMap::remove(&mut map, &c).unwrap();
+            ArithmeticExpression::Number { value }
+        } else if HashMap::len(&map) == 1 {
+            let mut values: Vec<_> = map.values().cloned().collect();
+            let mut symbols: Vec<_> = map.keys().cloned().collect();
+            let symbol = symbols.pop().unwrap();
+            let value = values.pop().unwrap();
+            if value == F::one() {
+                ArithmeticExpression::Signal { symbol }
+            } else {
+                ArithmeticExpression::initialize_hashmap_for_expression(&mut map);
+                ArithmeticExpression::Linear { coefficients: map }
             }
-            _ => Result::Ok(NonQuadratic),
+        } else {
+            ArithmeticExpression::initialize_hashmap_for_expression(&mut map);
+            ArithmeticExpression::Linear { coefficients: map }
+        };
+        expr
+    }
+
+    // The same expression with its numbers in another representation
+    pub fn convert_field<G: FieldElement>(&self, field: &BigInt) -> ArithmeticExpression<C, G> {
+        use ArithmeticExpression::*;
+        match self {
+            Number { value } => Number { value: G::from_bigint(&value.to_bigint(field), field) },
+            Signal { symbol } => Signal { symbol: symbol.clone() },
+            Linear { coefficients } => Linear { coefficients: convert_coefficients(coefficients, field) },
+            Quadratic { a, b, c } => Quadratic {
+                a: convert_coefficients(a, field),
+                b: convert_coefficients(b, field),
+                c: convert_coefficients(c, field),
+            },
+            NonQuadratic => NonQuadratic,
         }
     }
-    pub fn mod_op(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+}
+
+// The operations that are not defined over the field elements are computed
+// by modular_arithmetic on the value of the numbers
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> ArithmeticExpression<C, F> {
+    fn on_numbers(
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> Result<ArithmeticExpression<C>, ArithmeticError> {
+        operation: fn(&BigInt, &BigInt, &BigInt) -> BigInt,
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::mod_op(value_0, value_1, field)?;
-            Result::Ok(Number { value })
+            let value = operation(&value_0.to_bigint(field), &value_1.to_bigint(field), field);
+            Number { value: F::from_bigint(&value, field) }
+        } else {
+            NonQuadratic
+        }
+    }
+    fn try_on_numbers(
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
+        field: &BigInt,
+        operation: fn(&BigInt, &BigInt, &BigInt) -> Result<BigInt, ArithmeticError>,
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
+        use ArithmeticExpression::*;
+        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
+            let value = operation(&value_0.to_bigint(field), &value_1.to_bigint(field), field)?;
+            Result::Ok(Number { value: F::from_bigint(&value, field) })
         } else {
             Result::Ok(NonQuadratic)
         }
     }
+    fn is_two(value: &F, field: &BigInt) -> bool {
+        value.to_bigint(field) == BigInt::from(2)
+    }
+
+    pub fn idiv(
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
+        field: &BigInt,
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
+        ArithmeticExpression::try_on_numbers(left, right, field, modular_arithmetic::idiv)
+    }
+    pub fn mod_op(
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
+        field: &BigInt,
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
+        ArithmeticExpression::try_on_numbers(left, right, field, modular_arithmetic::mod_op)
+    }
     pub fn pow(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         match (left, right) {
-            (Number { value: value_0 }, Number { value: value_1 }) => {
-                let value = modular_arithmetic::pow(value_0, value_1, field);
-                Number { value }
-            }
-            (Signal { symbol }, Number { value }) if *value == BigInt::from(2) => {      
-                let left = Signal { symbol: symbol.clone() };
-                let right = Signal { symbol: symbol.clone() };
-                ArithmeticExpression::mul(&left, &right, field)
+            (Number { .. }, Number { .. }) => {
+                ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::pow)
             }
-            (Linear { coefficients }, Number {value}) if *value == BigInt::from(2) => {
-                let left = Linear { coefficients: coefficients.clone() };
-                let right = Linear { coefficients: coefficients.clone() };
-                ArithmeticExpression::mul(&left, &right, field)
+            (Signal { .. }, Number { value }) | (Linear { .. }, Number { value }) if Self::is_two(value, field) => {
+                ArithmeticExpression::mul(left, left, field)
             }
             _ => NonQuadratic,
         }
     }
-    pub fn prefix_sub(elem: &ArithmeticExpression<C>, field: &BigInt) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        let minus_one = Number { value: BigInt::from(-1) };
-        ArithmeticExpression::mul(elem, &minus_one, field)
-    }
-
     // Bit operations
     pub fn complement(
-        elem: &ArithmeticExpression<C>,
+        elem: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         if let Number { value } = elem {
-            Number { value: modular_arithmetic::complement(value, field) }
+            let value = modular_arithmetic::complement(&value.to_bigint(field), field);
+            Number { value: F::from_bigint(&value, field) }
         } else {
             NonQuadratic
         }
     }
     pub fn shift_l(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> Result<ArithmeticExpression<C>, ArithmeticError> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let shifted_elem = modular_arithmetic::shift_l(value_0, value_1, field)?;
-            Result::Ok(Number { value: shifted_elem })
-        } else {
-            Result::Ok(NonQuadratic)
-        }
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
+        ArithmeticExpression::try_on_numbers(left, right, field, modular_arithmetic::shift_l)
     }
     pub fn shift_r(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> Result<ArithmeticExpression<C>, ArithmeticError> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let shifted_elem = modular_arithmetic::shift_r(value_0, value_1, field)?;
-            Result::Ok(Number { value: shifted_elem })
-        } else {
-            Result::Ok(NonQuadratic)
-        }
+    ) -> Result<ArithmeticExpression<C, F>, ArithmeticError> {
+        ArithmeticExpression::try_on_numbers(left, right, field, modular_arithmetic::shift_r)
     }
     pub fn bit_or(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::bit_or(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::bit_or)
     }
     pub fn bit_and(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::bit_and(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::bit_and)
     }
     pub fn bit_xor(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::bit_xor(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::bit_xor)
     }
 
     // Boolean operations
-    pub fn get_boolean_equivalence(elem: &ArithmeticExpression<C>, field: &BigInt) -> Option<bool> {
+    pub fn get_boolean_equivalence(elem: &ArithmeticExpression<C, F>, field: &BigInt) -> Option<bool> {
         use ArithmeticExpression::*;
         if let Number { value } = elem {
-            Option::Some(modular_arithmetic::as_bool(value, field))
+            Option::Some(modular_arithmetic::as_bool(&value.to_bigint(field), field))
         } else {
             Option::None
         }
     }
-    pub fn not(elem: &ArithmeticExpression<C>, field: &BigInt) -> ArithmeticExpression<C> {
+    pub fn not(elem: &ArithmeticExpression<C, F>, field: &BigInt) -> ArithmeticExpression<C, F> {
         use ArithmeticExpression::*;
         if let Number { value } = elem {
-            let value = modular_arithmetic::not(value, field);
-            Number { value }
+            let value = modular_arithmetic::not(&value.to_bigint(field), field);
+            Number { value: F::from_bigint(&value, field) }
         } else {
             NonQuadratic
         }
     }
     pub fn bool_or(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::bool_or(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::bool_or)
     }
     pub fn bool_and(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::bool_and(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::bool_and)
     }
     pub fn eq(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::eq(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::eq)
     }
     pub fn not_eq(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::not_eq(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::not_eq)
     }
     pub fn lesser(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::lesser(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::lesser)
     }
     pub fn lesser_eq(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::lesser_eq(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::lesser_eq)
     }
     pub fn greater(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
-        use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::greater(value_0, value_1, field);
-            Number { value }
-        } else {
-            NonQuadratic
-        }
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::greater)
     }
     pub fn greater_eq(
-        left: &ArithmeticExpression<C>,
-        right: &ArithmeticExpression<C>,
+        left: &ArithmeticExpression<C, F>,
+        right: &ArithmeticExpression<C, F>,
         field: &BigInt,
-    ) -> ArithmeticExpression<C> {
+    ) -> ArithmeticExpression<C, F> {
+        ArithmeticExpression::on_numbers(left, right, field, modular_arithmetic::greater_eq)
+    }
+
+    pub fn get_usize(expr: &ArithmeticExpression<C, F>, field: &BigInt) -> Option<usize> {
         use ArithmeticExpression::*;
-        if let (Number { value: value_0 }, Number { value: value_1 }) = (left, right) {
-            let value = modular_arithmetic::greater_eq(value_0, value_1, field);
-            Number { value }
+        if let Number { value } = expr {
+            value.to_bigint(field).to_usize()
         } else {
-            NonQuadratic
+            Option::None
         }
     }
+}
 
+impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
     // Utils
     pub fn apply_substitutions(
         expr: &mut ArithmeticExpression<C>,
@@ -780,49 +777,6 @@ impl<C: Default + Clone + Display + Hash + Eq> ArithmeticExpression<C> {
             _ => {}
         }
     }
-    pub fn get_usize(expr: &ArithmeticExpression<C>) -> Option<usize> {
-        use ArithmeticExpression::*;
-        if let Number { value } = expr {
-            value.to_usize()
-        } else {
-            Option::None
-        }
-    }
-    pub fn is_number(&self) -> bool {
-        matches!(self, ArithmeticExpression::Number { .. })
-    }
-    pub fn is_nonquadratic(&self) -> bool {
-        matches!(self, ArithmeticExpression::NonQuadratic)
-    }
-    pub fn is_quadratic(&self) -> bool {
-        matches!(self, ArithmeticExpression::Quadratic { .. })
-    }
-    pub fn is_linear(&self) -> bool {
-        matches!(self, ArithmeticExpression::Linear { .. })
-    }
-
-    pub fn hashmap_into_arith(mut map: HashMap<C, BigInt>) -> ArithmeticExpression<C> {
-        let c: C = ArithmeticExpression::constant_coefficient();
-        let expr = if HashMap::len(&map) == 1 && HashMap::contains_key(&map, &c) {
-            let value = HashMap::remove(&mut map, &c).unwrap();
-            ArithmeticExpression::Number { value }
-        } else if HashMap::len(&map) == 1 {
-            let mut values: Vec<_> = map.values().cloned().collect();
-            let mut symbols: Vec<_> = map.keys().cloned().collect();
-            let symbol = symbols.pop().unwrap();
-            let value = values.pop().unwrap();
-            if value == BigInt::from(1) {
-                ArithmeticExpression::Signal { symbol }
-            } else {
-                ArithmeticExpression::initialize_hashmap_for_expression(&mut map);
-                ArithmeticExpression::Linear { coefficients: map }
-            }
-        } else {
-            ArithmeticExpression::initialize_hashmap_for_expression(&mut map);
-            ArithmeticExpression::Linear { coefficients: map }
-        };
-        expr
-    }
 }
 
 // ******************************** Constraint Definition ********************************
@@ -846,7 +800,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
         match to {
             Number { value } => {
                 let mut to = HashMap::new();
-                to.insert(ArithmeticExpression::constant_coefficient(), value);
+                to.insert(ArithmeticExpression::<C>::constant_coefficient(), value);
                 Option::Some(Substitution { from, to })
             }
             Signal { symbol } => {
@@ -872,7 +826,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
     }
 
     pub fn constant_coefficient() -> C {
-        ArithmeticExpression::constant_coefficient()
+        ArithmeticExpression::<C>::constant_coefficient()
     }
 
     pub fn apply_correspondence<K>(
@@ -898,13 +852,13 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
         let symbol = substitution.from;
         let mut coefficients = substitution.to;
         ArithmeticExpression::initialize_hashmap_for_expression(&mut coefficients);
-        coefficients.insert(symbol, BigInt::from(-1 % field));
+        coefficients.insert(symbol, -1 % field);
         let arith = ArithmeticExpression::Linear { coefficients };
         ArithmeticExpression::transform_expression_to_constraint_form(arith, field).unwrap()
     }
 
     pub fn decompose(substitution: Substitution<C>) -> (C, ArithmeticExpression<C>) {
-        let c: C = ArithmeticExpression::constant_coefficient();
+        let c: C = ArithmeticExpression::<C>::constant_coefficient();
         let mut to = substitution.to;
         let right = if HashMap::len(&to) == 1 && HashMap::contains_key(&to, &c) {
             let value = HashMap::remove(&mut to, &c).unwrap();
@@ -945,7 +899,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
     }
 
     pub fn take_cloned_signals(&self) -> HashSet<C> {
-        let cq: C = ArithmeticExpression::constant_coefficient();
+        let cq: C = ArithmeticExpression::<C>::constant_coefficient();
         let mut signals = HashSet::new();
         for s in self.to.keys() {
             if cq != *s {
@@ -956,7 +910,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
     }
 
     pub fn take_signals(&self) -> HashSet<&C> {
-        let cq: C = ArithmeticExpression::constant_coefficient();
+        let cq: C = ArithmeticExpression::<C>::constant_coefficient();
         let mut signals = HashSet::new();
         for s in self.to.keys() {
             if cq != *s {
@@ -973,7 +927,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Substitution<C> {
 
 impl<C: Default + Clone + Display + Hash + Eq + std::cmp::Ord> Substitution<C> {
     pub fn take_cloned_signals_ordered(&self) -> BTreeSet<C> {
-        let cq: C = ArithmeticExpression::constant_coefficient();
+        let cq: C = ArithmeticExpression::<C>::constant_coefficient();
         let mut signals = BTreeSet::new();
         for s in self.to.keys() {
             if cq != *s {
@@ -999,22 +953,22 @@ impl Substitution<usize> {
     where A,B and C are linear expression.
 */
 #[derive(Clone)]
-pub struct Constraint<C>
+pub struct Constraint<C, F = BigInt>
 where
     C: Hash + Eq,
 {
-    pub(crate) a: HashMap<C, BigInt>,
-    pub(crate) b: HashMap<C, BigInt>,
-    pub(crate) c: HashMap<C, BigInt>,
+    pub(crate) a: HashMap<C, F>,
+    pub(crate) b: HashMap<C, F>,
+    pub(crate) c: HashMap<C, F>,
 }
 
-impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
-    fn new(a: HashMap<C, BigInt>, b: HashMap<C, BigInt>, c: HashMap<C, BigInt>) -> Constraint<C> {
+impl<C: Default + Clone + Display + Hash + Eq, F: FieldElement> Constraint<C, F> {
+    pub fn new(a: HashMap<C, F>, b: HashMap<C, F>, c: HashMap<C, F>) -> Constraint<C, F> {
         Constraint { a, b, c }
     }
 
-    pub fn empty() -> Constraint<C> {
-        Constraint::new(
+    pub fn empty() -> Constraint<C, F> {
+        Self::new(
             HashMap::with_capacity(0),
             HashMap::with_capacity(0),
             HashMap::with_capacity(0),
@@ -1022,12 +976,12 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
     }
 
     pub fn constant_coefficient() -> C {
-        ArithmeticExpression::constant_coefficient()
+        ArithmeticExpression::<C, F>::constant_coefficient()
     }
     pub fn apply_correspondence_and_drop<K>(
-        constraint: Constraint<C>,
+        constraint: Constraint<C, F>,
         symbol_correspondence: &HashMap<C, K>,
-    ) -> Constraint<K>
+    ) -> Constraint<K, F>
     where
         K: Default + Clone + Display + Hash + Eq,
     {
@@ -1035,9 +989,9 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
     }
 
     pub fn apply_correspondence<K>(
-        constraint: &Constraint<C>,
+        constraint: &Constraint<C, F>,
         symbol_correspondence: &HashMap<C, K>,
-    ) -> Constraint<K>
+    ) -> Constraint<K, F>
     where
         K: Default + Clone + Display + Hash + Eq,
     {
@@ -1049,32 +1003,10 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
 
     // Constraint simplifications
 
-    pub fn is_linear(constraint: &Constraint<C>) -> bool {
+    pub fn is_linear(constraint: &Constraint<C, F>) -> bool {
         constraint.a.is_empty() && constraint.b.is_empty()
     }
 
-    pub fn clear_signal_from_linear(
-        constraint: Constraint<C>,
-        signal: &C,
-        field: &BigInt,
-    ) -> Substitution<C> {
-        debug_assert!(Constraint::is_linear(&constraint));
-        debug_assert!(constraint.c.contains_key(signal));
-        let raw_expression = Constraint::clear_signal(constraint.c, &signal, field);
-        Substitution { from: signal.clone(), to: raw_expression }
-    }
-
-    pub fn clear_signal_from_linear_not_normalized(
-        constraint: Constraint<C>,
-        signal: &C,
-        field: &BigInt,
-    ) -> (BigInt, Substitution<C>) {
-        debug_assert!(Constraint::is_linear(&constraint));
-        debug_assert!(constraint.c.contains_key(signal));
-        let (coefficient, raw_expression) = Constraint::clear_signal_not_normalized(constraint.c, &signal, field);
-        (coefficient, Substitution {from: signal.clone(), to: raw_expression})
-    }
-
     pub fn take_cloned_signals(&self) -> HashSet<C> {
         let mut signals = HashSet::new();
         for signal in self.a().keys() {
@@ -1086,11 +1018,11 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
         for signal in self.c().keys() {
             signals.insert(signal.clone());
         }
-        signals.remove(&Constraint::constant_coefficient());
+        signals.remove(&Self::constant_coefficient());
         signals
     }
     pub fn take_signals(&self) -> HashSet<&C> {
-        let cc: C = Constraint::constant_coefficient();
+        let cc: C = Self::constant_coefficient();
         let mut signals = HashSet::new();
         for signal in self.a().keys() {
             signals.insert(signal);
@@ -1105,54 +1037,13 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
         signals
     }
 
-    fn clear_signal(
-        mut symbols: HashMap<C, BigInt>,
-        key: &C,
-        field: &BigInt,
-    ) -> HashMap<C, BigInt> {
-        let key_value = symbols.remove(&key).unwrap();
-        assert!(!key_value.is_zero());
-        let value_to_the_right = modular_arithmetic::mul(&key_value, &BigInt::from(-1), field);
-        ArithmeticExpression::initialize_hashmap_for_expression(&mut symbols);
-        let arithmetic_result = ArithmeticExpression::divide_coefficients_by_constant(
-            &value_to_the_right,
-            &mut symbols,
-            field,
-        );
-        assert!(arithmetic_result.is_ok());
-        remove_zero_value_coefficients(symbols)
-    }
-
-    fn clear_signal_not_normalized(
-        mut symbols: HashMap<C, BigInt>,
-        key: &C,
-        field: &BigInt,
-    ) -> (BigInt, HashMap<C, BigInt>) {
-        let key_value = symbols.remove(&key).unwrap();
-        assert!(!key_value.is_zero());
-        let value_to_the_right = modular_arithmetic::mul(&key_value, &BigInt::from(-1), field);
-        ArithmeticExpression::initialize_hashmap_for_expression(&mut symbols);
-        (value_to_the_right, symbols)
-    }
-
-    pub fn apply_substitution(
-        constraint: &mut Constraint<C>,
-        substitution: &Substitution<C>,
-        field: &BigInt,
-    ) {
-        raw_substitution(&mut constraint.a, substitution, field);
-        raw_substitution(&mut constraint.b, substitution, field);
-        raw_substitution(&mut constraint.c, substitution, field);
-        //Constraint::fix_constraint(constraint, field);
-    }
-
-    pub fn remove_zero_value_coefficients(constraint: &mut Constraint<C>) {
+    pub fn remove_zero_value_coefficients(constraint: &mut Constraint<C, F>) {
         constraint.a = remove_zero_value_coefficients(std::mem::take(&mut constraint.a));
         constraint.b = remove_zero_value_coefficients(std::mem::take(&mut constraint.b));
         constraint.c = remove_zero_value_coefficients(std::mem::take(&mut constraint.c));
     }
 
-    pub fn fix_constraint(constraint: &mut Constraint<C>, field: &BigInt) {
+    pub fn fix_constraint(constraint: &mut Constraint<C, F>, field: &BigInt) {
         fix_raw_constraint(&mut constraint.a, &mut constraint.b, &mut constraint.c, field);
     }
 
@@ -1161,19 +1052,19 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
     }
 
     pub fn has_constant_coefficient(&self) -> bool {
-        self.a.contains_key(&Constraint::constant_coefficient())
-            || self.b.contains_key(&Constraint::constant_coefficient())
-            || self.a.contains_key(&Constraint::constant_coefficient())
+        self.a.contains_key(&Self::constant_coefficient())
+            || self.b.contains_key(&Self::constant_coefficient())
+            || self.a.contains_key(&Self::constant_coefficient())
     }
 
-    pub fn a(&self) -> &HashMap<C, BigInt> {
+    pub fn a(&self) -> &HashMap<C, F> {
         &self.a
     }
-    pub fn b(&self) -> &HashMap<C, BigInt> {
+    pub fn b(&self) -> &HashMap<C, F> {
         &self.b
     }
 
-    pub fn c(&self) -> &HashMap<C, BigInt> {
+    pub fn c(&self) -> &HashMap<C, F> {
         &self.c
     }
 
@@ -1185,7 +1076,7 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
         signal_equals_constant(&self.a, &self.b, &self.c)
     }
 
-    pub fn into_arithmetic_expressions(self) -> (ArithmeticExpression<C>, ArithmeticExpression<C>, ArithmeticExpression<C>) {
+    pub fn into_arithmetic_expressions(self) -> (ArithmeticExpression<C, F>, ArithmeticExpression<C, F>, ArithmeticExpression<C, F>) {
         (
             ArithmeticExpression::Linear { coefficients: self.a },
             ArithmeticExpression::Linear { coefficients: self.b },
@@ -1193,9 +1084,81 @@ impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
         )
     }
 
+    // The same constraint with its coefficients in another representation
+    pub fn convert_field<G: FieldElement>(&self, field: &BigInt) -> Constraint<C, G> {
+        let a = convert_coefficients(&self.a, field);
+        let b = convert_coefficients(&self.b, field);
+        let c = convert_coefficients(&self.c, field);
+        Constraint::new(a, b, c)
+    }
 }
 
-impl<C: Default + Clone + Display + Hash + Eq + std::cmp::Ord> Constraint<C> {
+impl<C: Default + Clone + Display + Hash + Eq> Constraint<C> {
+    pub fn clear_signal_from_linear(
+        constraint: Constraint<C>,
+        signal: &C,
+        field: &BigInt,
+    ) -> Substitution<C> {
+        debug_assert!(Constraint::is_linear(&constraint));
+        debug_assert!(constraint.c.contains_key(signal));
+        let raw_expression = Constraint::clear_signal(constraint.c, signal, field);
+        Substitution { from: signal.clone(), to: raw_expression }
+    }
+
+    pub fn clear_signal_from_linear_not_normalized(
+        constraint: Constraint<C>,
+        signal: &C,
+        field: &BigInt,
+    ) -> (BigInt, Substitution<C>) {
+        debug_assert!(Constraint::is_linear(&constraint));
+        debug_assert!(constraint.c.contains_key(signal));
+        let (coefficient, raw_expression) = Constraint::clear_signal_not_normalized(constraint.c, signal, field);
+        (coefficient, Substitution {from: signal.clone(), to: raw_expression})
+    }
+
+    fn clear_signal(
+        mut symbols: HashMap<C, BigInt>,
+        key: &C,
+        field: &BigInt,
+    ) -> HashMap<C, BigInt> {
+        let key_value = symbols.remove(key).unwrap();
+        assert!(!key_value.is_zero());
+        let value_to_the_right = modular_arithmetic::mul(&key_value, &BigInt::from(-1), field);
+        ArithmeticExpression::initialize_hashmap_for_expression(&mut symbols);
+        let arithmetic_result = ArithmeticExpression::divide_coefficients_by_constant(
+            &value_to_the_right,
+            &mut symbols,
+            field,
+        );
+        assert!(arithmetic_result.is_ok());
+        remove_zero_value_coefficients(symbols)
+    }
+
+    fn clear_signal_not_normalized(
+        mut symbols: HashMap<C, BigInt>,
+        key: &C,
+        field: &BigInt,
+    ) -> (BigInt, HashMap<C, BigInt>) {
+        let key_value = symbols.remove(key).unwrap();
+        assert!(!key_value.is_zero());
+        let value_to_the_right = modular_arithmetic::mul(&key_value, &BigInt::from(-1), field);
+        ArithmeticExpression::initialize_hashmap_for_expression(&mut symbols);
+        (value_to_the_right, symbols)
+    }
+
+    pub fn apply_substitution(
+        constraint: &mut Constraint<C>,
+        substitution: &Substitution<C>,
+        field: &BigInt,
+    ) {
+        raw_substitution(&mut constraint.a, substitution, field);
+        raw_substitution(&mut constraint.b, substitution, field);
+        raw_substitution(&mut constraint.c, substitution, field);
+        //Constraint::fix_constraint(constraint, field);
+    }
+}
+
+impl<C: Default + Clone + Display + Hash + Eq + std::cmp::Ord, F: FieldElement> Constraint<C, F> {
     pub fn take_cloned_signals_ordered(&self) -> BTreeSet<C> {
         let mut signals = BTreeSet::new();
         for signal in self.a().keys() {
@@ -1207,7 +1170,7 @@ impl<C: Default + Clone + Display + Hash + Eq + std::cmp::Ord> Constraint<C> {
         for signal in self.c().keys() {
             signals.insert(signal.clone());
         }
-        signals.remove(&Constraint::constant_coefficient());
+        signals.remove(&Self::constant_coefficient());
         signals
     }
 
@@ -1220,7 +1183,7 @@ impl Constraint<usize> {
         let c = apply_raw_offset(&self.c, offset);
         Constraint::new(a, b, c)
     }
-    pub fn apply_witness(&self, witness: &Vec<usize>) -> Constraint<usize> {
+    pub fn apply_witness(&self, witness: &[usize]) -> Constraint<usize> {
         let a = apply_vectored_correspondence(&self.a, witness);
         let b = apply_vectored_correspondence(&self.b, witness);
         let c = apply_vectored_correspondence(&self.c, witness);
@@ -1229,11 +1192,20 @@ impl Constraint<usize> {
 }
 
 // model utils
-type RawExpr<C> = HashMap<C, BigInt>;
+type RawExpr<C, F = BigInt> = HashMap<C, F>;
+
+fn convert_coefficients<C, F, G>(coefficients: &HashMap<C, F>, field: &BigInt) -> HashMap<C, G>
+where
+    C: Clone + Hash + Eq,
+    F: FieldElement,
+    G: FieldElement,
+{
+    coefficients.iter().map(|(symbol, value)| (symbol.clone(), G::from_bigint(&value.to_bigint(field), field))).collect()
+}
 
 fn apply_vectored_correspondence(
     symbols: &HashMap<usize, BigInt>,
-    map: &Vec<usize>,
+    map: &[usize],
 ) -> HashMap<usize, BigInt> {
     let mut mapped = HashMap::new();
     for (s, v) in symbols {
@@ -1242,21 +1214,22 @@ fn apply_vectored_correspondence(
     mapped
 }
 
-fn apply_raw_correspondence<C, K>(
-    symbols: &HashMap<C, BigInt>,
+fn apply_raw_correspondence<C, K, F>(
+    symbols: &HashMap<C, F>,
     map: &HashMap<C, K>,
-) -> HashMap<K, BigInt>
+) -> HashMap<K, F>
 where
     K: Default + Clone + Display + Hash + Eq,
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
-    let constant_coefficient: C = ArithmeticExpression::constant_coefficient();
+    let constant_coefficient: C = ArithmeticExpression::<C, F>::constant_coefficient();
     let mut coefficients_as_correspondence = HashMap::new();
     for (key, value) in symbols {
         let id = if key.eq(&constant_coefficient) {
-            ArithmeticExpression::constant_coefficient()
+            ArithmeticExpression::<K, F>::constant_coefficient()
         } else {
-            map.get(&key).expect(&format!("Unknown signal: {}", key)).clone()
+            map.get(key).unwrap_or_else(|| panic!("Unknown signal: {}", key)).clone()
         };
         coefficients_as_correspondence.insert(id, value.clone());
     }
@@ -1265,7 +1238,7 @@ where
 
 fn apply_raw_offset(h: &HashMap<usize, BigInt>, offset: usize) -> HashMap<usize, BigInt> {
     let mut new = HashMap::new();
-    let constant: usize = Constraint::constant_coefficient();
+    let constant: usize = Constraint::<usize>::constant_coefficient();
     for (k, v) in h {
         if *k == constant {
             new.insert(*k, v.clone());
@@ -1293,9 +1266,10 @@ fn raw_substitution<C>(
     //*change = remove_zero_value_coefficients(std::mem::take(change));
 }
 
-fn remove_zero_value_coefficients<C>(raw_expression: HashMap<C, BigInt>) -> HashMap<C, BigInt>
+fn remove_zero_value_coefficients<C, F>(raw_expression: HashMap<C, F>) -> HashMap<C, F>
 where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
     let mut clean_raw = HashMap::new();
     for (key, val) in raw_expression {
@@ -1306,9 +1280,10 @@ where
     clean_raw
 }
 
-fn fix_raw_constraint<C>(a: &mut RawExpr<C>, b: &mut RawExpr<C>, c: &mut RawExpr<C>, field: &BigInt)
+fn fix_raw_constraint<C, F>(a: &mut RawExpr<C, F>, b: &mut RawExpr<C, F>, c: &mut RawExpr<C, F>, field: &BigInt)
 where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
     *a = remove_zero_value_coefficients(std::mem::take(a));
     *b = remove_zero_value_coefficients(std::mem::take(b));
@@ -1323,47 +1298,50 @@ where
     }
 }
 
-fn constant_linear_linear_reduction<C>(
-    a: &mut RawExpr<C>,
-    b: &mut RawExpr<C>,
-    c: &mut RawExpr<C>,
+fn constant_linear_linear_reduction<C, F>(
+    a: &mut RawExpr<C, F>,
+    b: &mut RawExpr<C, F>,
+    c: &mut RawExpr<C, F>,
     field: &BigInt,
 ) where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
-    let cq: C = ArithmeticExpression::constant_coefficient();
+    let cq: C = ArithmeticExpression::<C, F>::constant_coefficient();
     ArithmeticExpression::initialize_hashmap_for_expression(c);
     ArithmeticExpression::initialize_hashmap_for_expression(b);
     let constant = HashMap::remove(a, &cq).unwrap();
     ArithmeticExpression::multiply_coefficients_by_constant(&constant, b, field);
-    ArithmeticExpression::multiply_coefficients_by_constant(&BigInt::from(-1), b, field);
+    ArithmeticExpression::multiply_coefficients_by_constant(&F::one().prefix_sub(field), b, field);
     ArithmeticExpression::add_coefficients_to_coefficients(b, c, field);
     *c = remove_zero_value_coefficients(std::mem::take(c));
     HashMap::clear(a);
     HashMap::clear(b);
 }
 
-fn signal_equals_signal<C>(a: &RawExpr<C>, b: &RawExpr<C>, c: &RawExpr<C>, field: &BigInt) -> bool
+fn signal_equals_signal<C, F>(a: &RawExpr<C, F>, b: &RawExpr<C, F>, c: &RawExpr<C, F>, field: &BigInt) -> bool
 where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
-    let cq: C = ArithmeticExpression::constant_coefficient();
+    let cq: C = ArithmeticExpression::<C, F>::constant_coefficient();
     if a.is_empty() && b.is_empty() && !HashMap::contains_key(c, &cq) && c.len() == 2 {
         let signals: Vec<_> = c.keys().cloned().collect();
         let c0 = HashMap::get(c, &signals[0]).unwrap();
         let c1 = HashMap::get(c, &signals[1]).unwrap();
-        let c1_p = modular_arithmetic::mul(&BigInt::from(-1), c1, field);
+        let c1_p = c1.prefix_sub(field);
         c1_p == *c0
     } else {
         false
     }
 }
 
-fn signal_equals_constant<C>(a: &RawExpr<C>, b: &RawExpr<C>, c: &RawExpr<C>) -> bool
+fn signal_equals_constant<C, F>(a: &RawExpr<C, F>, b: &RawExpr<C, F>, c: &RawExpr<C, F>) -> bool
 where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
-    let cq: C = ArithmeticExpression::constant_coefficient();
+    let cq: C = ArithmeticExpression::<C, F>::constant_coefficient();
     HashMap::is_empty(a)
         && HashMap::is_empty(b)
         && 
@@ -1371,25 +1349,67 @@ where
         	(!HashMap::contains_key(c, &cq) && HashMap::len(c) == 1))
 }
 
-fn is_constant_expression<C>(expr: &RawExpr<C>) -> bool
+fn is_constant_expression<C, F>(expr: &RawExpr<C, F>) -> bool
 where
     C: Default + Clone + Display + Hash + Eq,
+    F: FieldElement,
 {
-    let cq: C = ArithmeticExpression::constant_coefficient();
+    let cq: C = ArithmeticExpression::<C, F>::constant_coefficient();
     HashMap::contains_key(expr, &cq) && HashMap::len(expr) == 1
 }
 
-pub fn normalize(c: Constraint<usize>, _field: &BigInt) -> Constraint<usize> {
-    use std::collections::LinkedList;
-    let _a: LinkedList<_> = c.a.iter().clone().collect();
-    let _b: LinkedList<_> = c.b.iter().clone().collect();
-    let _c: LinkedList<_> = c.c.iter().clone().collect();
-    todo!()
+// Canonical form of a constraint: A and B are divided by their coefficient of
+// the smallest signal other than the constant, C is divided by the product of both and A and B are
+// ordered. A linear constraint is divided by the coefficient of its smallest
+// signal in the same way. Two constraints that only differ in these factors or in the order
+// of A and B have the same canonical form.
+pub fn normalize(c: Constraint<usize>, field: &BigInt) -> Constraint<usize> {
+    let mut c = c;
+    Constraint::fix_constraint(&mut c, field);
+    if Constraint::is_linear(&c) {
+        let factor = smallest_signal_coefficient(&c.c);
+        c.c = divide_coefficients(c.c, &factor, field);
+        return c;
+    }
+    let factor_a = smallest_signal_coefficient(&c.a);
+    let factor_b = smallest_signal_coefficient(&c.b);
+    let factor_c = modular_arithmetic::mul(&factor_a, &factor_b, field);
+    let a = divide_coefficients(c.a, &factor_a, field);
+    let b = divide_coefficients(c.b, &factor_b, field);
+    let c = divide_coefficients(c.c, &factor_c, field);
+    if sorted_coefficients(&b) < sorted_coefficients(&a) {
+        Constraint::new(b, a, c)
+    } else {
+        Constraint::new(a, b, c)
+    }
+}
+
+// The constant is only taken if there are no signals
+fn smallest_signal_coefficient(expr: &RawExpr<usize>) -> BigInt {
+    let constant = ArithmeticExpression::<usize>::constant_coefficient();
+    expr.iter()
+        .min_by_key(|(signal, _)| (**signal == constant, **signal))
+        .map_or(BigInt::from(1), |(_, value)| value.clone())
+}
+
+fn divide_coefficients(expr: RawExpr<usize>, factor: &BigInt, field: &BigInt) -> RawExpr<usize> {
+    let mut divided = HashMap::with_capacity(expr.len());
+    for (signal, value) in expr {
+        let value = modular_arithmetic::div(&value, factor, field).unwrap_or(value);
+        divided.insert(signal, value);
+    }
+    divided
+}
+
+fn sorted_coefficients(expr: &RawExpr<usize>) -> Vec<(usize, &BigInt)> {
+    let mut coefficients: Vec<_> = expr.iter().map(|(signal, value)| (*signal, value)).collect();
+    coefficients.sort();
+    coefficients
 }
 
 #[cfg(test)]
 mod test {
-    use crate::algebra::{ArithmeticExpression, Constraint, Substitution};
+    use crate::algebra::{normalize, ArithmeticExpression, Constraint, Substitution};
     use crate::modular_arithmetic;
     use num_bigint::BigInt;
     use std::collections::HashMap;
@@ -1491,4 +1511,29 @@ mod test {
         assert_eq!(*y_c, expected_y_c);
         assert_eq!(*constant_c, expected_constant_c);
     }
+
+    #[test]
+    fn algebra_constraint_normalize() {
+        let field = BigInt::parse_bytes(FIELD.as_bytes(), 10)
+            .expect("generating the big int was not possible");
+        let (x, y, z) = (1, 2, 3);
+        let expression = |terms: &[(usize, i64)]| -> HashMap<usize, BigInt> {
+            terms.iter().map(|(signal, value)| (*signal, BigInt::from(*value))).collect()
+        };
+
+        // (2x) * (3y + 3) = 6z and (y + 1) * x = z
+        let scaled = C::new(expression(&[(x, 2)]), expression(&[(y, 3), (0, 3)]), expression(&[(z, 6)]));
+        let swapped = C::new(expression(&[(y, 1), (0, 1)]), expression(&[(x, 1)]), expression(&[(z, 1)]));
+        let scaled = normalize(scaled, &field);
+        let swapped = normalize(swapped, &field);
+        assert_eq!(scaled.a, swapped.a);
+        assert_eq!(scaled.b, swapped.b);
+        assert_eq!(scaled.c, swapped.c);
+
+        // 4x - 4z = 0 and z - x = 0
+        let linear = normalize(C::new(HashMap::new(), HashMap::new(), expression(&[(x, 4), (z, 253)])), &field);
+        let reversed = normalize(C::new(HashMap::new(), HashMap::new(), expression(&[(x, 256), (z, 1)])), &field);
+        assert_eq!(linear.c, reversed.c);
+        assert_eq!(linear.c, expression(&[(x, 1), (z, 256)]));
+    }
 }
@@ -1,37 +1,41 @@
 use crate::algebra::Constraint;
+use crate::field_element::FieldElement;
 use crate::num_bigint::BigInt;
 use constant_tracking::{ConstantTracker, CID};
 use std::collections::LinkedList;
+use std::marker::PhantomData;
 
 mod logic;
 
 type RawField = Vec<u8>;
 type FieldTracker = ConstantTracker<RawField>;
 type S = usize;
-type C = Constraint<usize>;
+type C<F> = Constraint<usize, F>;
 
-type CompressedExpr = Vec<(CID, S)>;
-type CompressedConstraint = (CompressedExpr, CompressedExpr, CompressedExpr); // A, B, C
+pub type CompressedExpr = Vec<(CID, S)>;
+pub type CompressedConstraint = (CompressedExpr, CompressedExpr, CompressedExpr); // A, B, C
 
 pub type ConstraintID = usize;
-pub struct ConstraintStorage {
+// The coefficients are stored once each, in the raw representation of F
+pub struct ConstraintStorage<F = BigInt> {
     field_tracker: FieldTracker,
     constraints: Vec<CompressedConstraint>,
+    field: PhantomData<F>,
 }
 
-impl ConstraintStorage {
-    pub fn new() -> ConstraintStorage {
-        ConstraintStorage { field_tracker: FieldTracker::new(), constraints: Vec::new() }
+impl<F: FieldElement> ConstraintStorage<F> {
+    pub fn new() -> ConstraintStorage<F> {
+        ConstraintStorage { field_tracker: FieldTracker::new(), constraints: Vec::new(), field: PhantomData }
     }
 
-    pub fn add_constraint(&mut self, constraint: C) -> ConstraintID {
+    pub fn add_constraint(&mut self, constraint: C<F>) -> ConstraintID {
         let id = self.constraints.len();
         let compressed = logic::code_constraint(constraint, &mut self.field_tracker);
         self.constraints.push(compressed);
         id
     }
 
-    pub fn read_constraint(&self, id: ConstraintID) -> Option<C> {
+    pub fn read_constraint(&self, id: ConstraintID) -> Option<C<F>> {
         if id < self.constraints.len() {
             Some(logic::decode_constraint(&self.constraints[id], &self.field_tracker))
         } else {
@@ -39,24 +43,45 @@ impl ConstraintStorage {
         }
     }
 
-    pub fn replace(&mut self, id: ConstraintID, new: C) {
+    // The constraint as it is stored: each coefficient is the id of a constant
+    pub fn read_compressed(&self, id: ConstraintID) -> Option<&CompressedConstraint> {
+        self.constraints.get(id)
+    }
+
+    pub fn raw_constant(&self, id: CID) -> Option<&[u8]> {
+        self.field_tracker.get_constant(id).map(|raw| raw.as_slice())
+    }
+
+    pub fn is_linear(&self, id: ConstraintID) -> bool {
+        let (a, b, _) = &self.constraints[id];
+        a.is_empty() && b.is_empty()
+    }
+
+    pub fn replace(&mut self, id: ConstraintID, new: C<F>) {
         if id < self.constraints.len() {
             self.constraints[id] = logic::code_constraint(new, &mut self.field_tracker);
         }
     }
 
-    pub fn extract_with(&mut self, filter: &dyn Fn(&C) -> bool) -> LinkedList<C> {
+    pub fn extract_with(&mut self, filter: &dyn Fn(&C<F>) -> bool) -> LinkedList<C<F>> {
+        self.extract_with_kept_ids(filter).0
+    }
+
+    // Also returns the ids that the constraints kept had before the extraction
+    pub fn extract_with_kept_ids(&mut self, filter: &dyn Fn(&C<F>) -> bool) -> (LinkedList<C<F>>, Vec<ConstraintID>) {
         let old = std::mem::take(&mut self.constraints);
         let mut removed = LinkedList::new();
-        for c in old {
+        let mut kept = Vec::new();
+        for (id, c) in old.into_iter().enumerate() {
             let decoded = logic::decode_constraint(&c, &self.field_tracker);
             if filter(&decoded) {
                 removed.push_back(decoded);
             } else {
                 self.constraints.push(c);
+                kept.push(id);
             }
         }
-        removed
+        (removed, kept)
     }
 
     pub fn get_ids(&self) -> Vec<ConstraintID> {
@@ -66,4 +91,21 @@ impl ConstraintStorage {
     pub fn no_constants(&self) -> CID {
         self.field_tracker.next_id()
     }
+
+    // The same constraints with the coefficients in another representation,
+    // each constant is converted once
+    pub fn convert_field<G: FieldElement>(self, field: &BigInt) -> ConstraintStorage<G> {
+        let mut field_tracker = FieldTracker::new();
+        let ids: Vec<CID> = (0..self.field_tracker.next_id())
+            .map(|id| {
+                let value = F::from_raw(self.field_tracker.get_constant(id).unwrap()).to_bigint(field);
+                field_tracker.insert(G::from_bigint(&value, field).to_raw())
+            })
+            .collect();
+        let rename = |expr: CompressedExpr| -> CompressedExpr {
+            expr.into_iter().map(|(id, signal)| (ids[id], signal)).collect()
+        };
+        let constraints = self.constraints.into_iter().map(|(a, b, c)| (rename(a), rename(b), rename(c))).collect();
+        ConstraintStorage { field_tracker, constraints, field: PhantomData }
+    }
 }
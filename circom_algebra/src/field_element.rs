@@ -0,0 +1,489 @@
+use super::modular_arithmetic::{self, ArithmeticError};
+use num_bigint::{BigInt, Sign};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+// Values of the field used by the algebra. The operations receive the prime
+// like the ones of modular_arithmetic: BigInt works for any prime, while the
+// Montgomery elements are specialised for one prime and ignore it.
+pub trait FieldElement: Clone + Eq + Hash + Display {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_bigint(value: &BigInt, field: &BigInt) -> Self;
+    fn to_bigint(&self, field: &BigInt) -> BigInt;
+    fn is_zero(&self) -> bool;
+    fn add(&self, other: &Self, field: &BigInt) -> Self;
+    fn sub(&self, other: &Self, field: &BigInt) -> Self;
+    fn mul(&self, other: &Self, field: &BigInt) -> Self;
+    fn prefix_sub(&self, field: &BigInt) -> Self;
+    fn inverse(&self, field: &BigInt) -> Result<Self, ArithmeticError>;
+    fn div(&self, other: &Self, field: &BigInt) -> Result<Self, ArithmeticError> {
+        Result::Ok(self.mul(&other.inverse(field)?, field))
+    }
+    // Representation used by the ConstraintStorage
+    fn to_raw(&self) -> Vec<u8>;
+    fn from_raw(raw: &[u8]) -> Self;
+}
+
+impl FieldElement for BigInt {
+    fn zero() -> Self {
+        BigInt::from(0)
+    }
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+    fn from_bigint(value: &BigInt, field: &BigInt) -> Self {
+        modular_arithmetic::add(value, &BigInt::from(0), field)
+    }
+    fn to_bigint(&self, _field: &BigInt) -> BigInt {
+        self.clone()
+    }
+    fn is_zero(&self) -> bool {
+        num_traits::Zero::is_zero(self)
+    }
+    fn add(&self, other: &Self, field: &BigInt) -> Self {
+        modular_arithmetic::add(self, other, field)
+    }
+    fn sub(&self, other: &Self, field: &BigInt) -> Self {
+        modular_arithmetic::sub(self, other, field)
+    }
+    fn mul(&self, other: &Self, field: &BigInt) -> Self {
+        modular_arithmetic::mul(self, other, field)
+    }
+    fn prefix_sub(&self, field: &BigInt) -> Self {
+        modular_arithmetic::prefix_sub(self, field)
+    }
+    fn inverse(&self, field: &BigInt) -> Result<Self, ArithmeticError> {
+        modular_arithmetic::div(&BigInt::from(1), self, field)
+    }
+    fn div(&self, other: &Self, field: &BigInt) -> Result<Self, ArithmeticError> {
+        modular_arithmetic::div(self, other, field)
+    }
+    fn to_raw(&self) -> Vec<u8> {
+        self.to_signed_bytes_le()
+    }
+    fn from_raw(raw: &[u8]) -> Self {
+        BigInt::from_signed_bytes_le(raw)
+    }
+}
+
+// Primes with a Montgomery representation in N limbs of 64 bits
+pub trait MontgomeryParameters<const N: usize>: 'static {
+    const NAME: &'static str;
+    // Little endian limbs of the prime
+    const MODULUS: [u64; N];
+    // -MODULUS^-1 mod 2^64
+    const INV: u64 = montgomery_inv(Self::MODULUS[0]);
+    // 2^(128 * N) mod MODULUS, which takes a value into the Montgomery form
+    const R2: [u64; N] = montgomery_r2(&Self::MODULUS);
+}
+
+// Element x stored as x * 2^(64 * N) mod p in fixed-size limbs, so the
+// operations do not allocate and reduce with at most one subtraction
+pub struct Montgomery<P, const N: usize> {
+    limbs: [u64; N],
+    parameters: PhantomData<fn() -> P>,
+}
+
+macro_rules! montgomery_field {
+    ($element: ident, $parameters: ident, $name: expr, $n: expr, [$($limb: expr),*]) => {
+        pub struct $parameters;
+        impl MontgomeryParameters<$n> for $parameters {
+            const NAME: &'static str = $name;
+            const MODULUS: [u64; $n] = [$($limb),*];
+        }
+        pub type $element = Montgomery<$parameters, $n>;
+    };
+}
+
+montgomery_field!(Bn128, Bn128Parameters, "bn128", 4, [
+    0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029
+]);
+montgomery_field!(Bls12381, Bls12381Parameters, "bls12381", 4, [
+    0xffffffff00000001, 0x53bda402fffe5bfe, 0x3339d80809a1d805, 0x73eda753299d7d48
+]);
+montgomery_field!(Goldilocks, GoldilocksParameters, "goldilocks", 1, [0xffffffff00000001]);
+montgomery_field!(Grumpkin, GrumpkinParameters, "grumpkin", 4, [
+    0x3c208c16d87cfd47, 0x97816a916871ca8d, 0xb85045b68181585d, 0x30644e72e131a029
+]);
+montgomery_field!(Pallas, PallasParameters, "pallas", 4, [
+    0x992d30ed00000001, 0x224698fc094cf91b, 0x0000000000000000, 0x4000000000000000
+]);
+montgomery_field!(Vesta, VestaParameters, "vesta", 4, [
+    0x8c46eb2100000001, 0x224698fc0994a8dd, 0x0000000000000000, 0x4000000000000000
+]);
+montgomery_field!(Secq256r1, Secq256r1Parameters, "secq256r1", 4, [
+    0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001
+]);
+montgomery_field!(Bls12377, Bls12377Parameters, "bls12377", 4, [
+    0x0a11800000000001, 0x59aa76fed0000001, 0x60b44d1e5c37b001, 0x12ab655e9a2ca556
+]);
+
+// Evaluates the body with $element as the FieldElement used for the prime:
+// the Montgomery elements for the named primes and BigInt for the others
+#[macro_export]
+macro_rules! with_field_element {
+    ($prime: expr, $element: ident => $body: expr) => {
+        match $prime {
+            "bn128" => { type $element = $crate::field_element::Bn128; $body }
+            "bls12381" => { type $element = $crate::field_element::Bls12381; $body }
+            "goldilocks" => { type $element = $crate::field_element::Goldilocks; $body }
+            "grumpkin" => { type $element = $crate::field_element::Grumpkin; $body }
+            "pallas" => { type $element = $crate::field_element::Pallas; $body }
+            "vesta" => { type $element = $crate::field_element::Vesta; $body }
+            "secq256r1" => { type $element = $crate::field_element::Secq256r1; $body }
+            "bls12377" => { type $element = $crate::field_element::Bls12377; $body }
+            _ => { type $element = $crate::num_bigint::BigInt; $body }
+        }
+    };
+}
+
+// p0^(2^63 - 1) is the inverse of p0 in the 2^63 odd residues modulo 2^64
+const fn montgomery_inv(p0: u64) -> u64 {
+    let mut inv: u64 = 1;
+    let mut i = 0;
+    while i < 63 {
+        inv = inv.wrapping_mul(inv).wrapping_mul(p0);
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+const fn montgomery_r2<const N: usize>(modulus: &[u64; N]) -> [u64; N] {
+    let mut r = [0u64; N];
+    r[0] = 1;
+    let mut i = 0;
+    while i < 128 * N {
+        // r < p, so 2r - p < p
+        let mut doubled = [0u64; N];
+        let mut carry = 0;
+        let mut j = 0;
+        while j < N {
+            doubled[j] = (r[j] << 1) | carry;
+            carry = r[j] >> 63;
+            j += 1;
+        }
+        r = if carry != 0 || !less_than(&doubled, modulus) { sub_limbs(&doubled, modulus).0 } else { doubled };
+        i += 1;
+    }
+    r
+}
+
+const fn less_than<const N: usize>(left: &[u64; N], right: &[u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if left[i] != right[i] {
+            return left[i] < right[i];
+        }
+    }
+    false
+}
+
+const fn sub_limbs<const N: usize>(left: &[u64; N], right: &[u64; N]) -> ([u64; N], bool) {
+    let mut result = [0u64; N];
+    let mut borrow = false;
+    let mut i = 0;
+    while i < N {
+        let (partial, borrow_0) = left[i].overflowing_sub(right[i]);
+        let (partial, borrow_1) = partial.overflowing_sub(borrow as u64);
+        result[i] = partial;
+        borrow = borrow_0 || borrow_1;
+        i += 1;
+    }
+    (result, borrow)
+}
+
+fn add_limbs<const N: usize>(left: &[u64; N], right: &[u64; N]) -> ([u64; N], bool) {
+    let mut result = [0u64; N];
+    let mut carry = false;
+    for i in 0..N {
+        let (partial, carry_0) = left[i].overflowing_add(right[i]);
+        let (partial, carry_1) = partial.overflowing_add(carry as u64);
+        result[i] = partial;
+        carry = carry_0 || carry_1;
+    }
+    (result, carry)
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Montgomery<P, N> {
+    fn new(limbs: [u64; N]) -> Self {
+        Montgomery { limbs, parameters: PhantomData }
+    }
+
+    pub fn name() -> &'static str {
+        P::NAME
+    }
+
+    pub fn modulus() -> BigInt {
+        limbs_to_bigint(&P::MODULUS)
+    }
+
+    // Coarsely Integrated Operand Scanning: computes a * b * 2^(-64 * N) mod p
+    // interleaving the product and the reduction
+    fn montgomery_mul(left: &[u64; N], right: &[u64; N]) -> [u64; N] {
+        let mut t = [0u64; N];
+        let mut t_n: u64 = 0;
+        for right_limb in right {
+            let mut carry: u128 = 0;
+            for j in 0..N {
+                let sum = t[j] as u128 + (left[j] as u128) * (*right_limb as u128) + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t_n as u128 + carry;
+            t_n = sum as u64;
+            let t_n_1 = (sum >> 64) as u64;
+
+            let m = t[0].wrapping_mul(P::INV);
+            let mut carry = (t[0] as u128 + (m as u128) * (P::MODULUS[0] as u128)) >> 64;
+            for j in 1..N {
+                let sum = t[j] as u128 + (m as u128) * (P::MODULUS[j] as u128) + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t_n as u128 + carry;
+            t[N - 1] = sum as u64;
+            t_n = t_n_1 + (sum >> 64) as u64;
+        }
+        Self::reduce_once(t, t_n != 0)
+    }
+
+    // Takes a value below 2p, whose bit 64 * N is overflow, below p
+    fn reduce_once(value: [u64; N], overflow: bool) -> [u64; N] {
+        if overflow || !less_than(&value, &P::MODULUS) {
+            sub_limbs(&value, &P::MODULUS).0
+        } else {
+            value
+        }
+    }
+
+    fn pow(&self, exponent: &[u64; N]) -> Self {
+        let mut result = <Self as FieldElement>::one();
+        for limb in exponent.iter().rev() {
+            for bit in (0..64).rev() {
+                result = Self::new(Self::montgomery_mul(&result.limbs, &result.limbs));
+                if (limb >> bit) & 1 == 1 {
+                    result = Self::new(Self::montgomery_mul(&result.limbs, &self.limbs));
+                }
+            }
+        }
+        result
+    }
+}
+
+fn limbs_to_bigint(limbs: &[u64]) -> BigInt {
+    let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+    BigInt::from_bytes_le(Sign::Plus, &bytes)
+}
+
+fn limbs_from_bytes<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut limbs = [0u64; N];
+    for (i, byte) in bytes.iter().enumerate().take(8 * N) {
+        limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+    }
+    limbs
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> FieldElement for Montgomery<P, N> {
+    fn zero() -> Self {
+        Self::new([0u64; N])
+    }
+    fn one() -> Self {
+        let mut one = [0u64; N];
+        one[0] = 1;
+        Self::new(Self::montgomery_mul(&one, &P::R2))
+    }
+    fn from_bigint(value: &BigInt, _field: &BigInt) -> Self {
+        let modulus = Self::modulus();
+        let value = ((value % &modulus) + &modulus) % &modulus;
+        let limbs = limbs_from_bytes(&value.to_bytes_le().1);
+        Self::new(Self::montgomery_mul(&limbs, &P::R2))
+    }
+    fn to_bigint(&self, _field: &BigInt) -> BigInt {
+        let mut one = [0u64; N];
+        one[0] = 1;
+        limbs_to_bigint(&Self::montgomery_mul(&self.limbs, &one))
+    }
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|limb| *limb == 0)
+    }
+    fn add(&self, other: &Self, _field: &BigInt) -> Self {
+        let (sum, carry) = add_limbs(&self.limbs, &other.limbs);
+        Self::new(Self::reduce_once(sum, carry))
+    }
+    fn sub(&self, other: &Self, _field: &BigInt) -> Self {
+        let (difference, borrow) = sub_limbs(&self.limbs, &other.limbs);
+        if borrow {
+            Self::new(add_limbs(&difference, &P::MODULUS).0)
+        } else {
+            Self::new(difference)
+        }
+    }
+    fn mul(&self, other: &Self, _field: &BigInt) -> Self {
+        Self::new(Self::montgomery_mul(&self.limbs, &other.limbs))
+    }
+    fn prefix_sub(&self, field: &BigInt) -> Self {
+        Self::zero().sub(self, field)
+    }
+    // Fermat: x^(p - 2) = x^-1
+    fn inverse(&self, _field: &BigInt) -> Result<Self, ArithmeticError> {
+        if FieldElement::is_zero(self) {
+            return Result::Err(ArithmeticError::DivisionByZero);
+        }
+        let mut two = [0u64; N];
+        two[0] = 2;
+        Result::Ok(self.pow(&sub_limbs(&P::MODULUS, &two).0))
+    }
+    fn to_raw(&self) -> Vec<u8> {
+        self.limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect()
+    }
+    fn from_raw(raw: &[u8]) -> Self {
+        Self::new(limbs_from_bytes(raw))
+    }
+}
+
+impl<P, const N: usize> Clone for Montgomery<P, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P, const N: usize> Copy for Montgomery<P, N> {}
+
+impl<P, const N: usize> PartialEq for Montgomery<P, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+impl<P, const N: usize> Eq for Montgomery<P, N> {}
+
+impl<P, const N: usize> Hash for Montgomery<P, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.limbs.hash(state);
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Display for Montgomery<P, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = self.to_bigint(&Self::modulus());
+        f.write_str(&value.to_str_radix(10))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Debug for Montgomery<P, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", P::NAME, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::{ArithmeticExpression, Constraint};
+    use crate::constraint_storage::ConstraintStorage;
+
+    // Values near 0, near p and spread over the field
+    fn samples(field: &BigInt) -> Vec<BigInt> {
+        let mut values: Vec<BigInt> = (0..4).map(BigInt::from).collect();
+        values.extend((1..4).map(|i| field - i));
+        let mut value = BigInt::from(0x9e3779b97f4a7c15u64);
+        for _ in 0..8 {
+            value = (&value * &value + 7) % field;
+            values.push(value.clone());
+        }
+        values
+    }
+
+    fn check_against_modular_arithmetic<P: MontgomeryParameters<N>, const N: usize>() {
+        let field = Montgomery::<P, N>::modulus();
+        let values = samples(&field);
+        for left in &values {
+            let x = Montgomery::<P, N>::from_bigint(left, &field);
+            assert_eq!(x.to_bigint(&field), *left);
+            assert_eq!(Montgomery::<P, N>::from_raw(&x.to_raw()), x);
+            assert_eq!(x.prefix_sub(&field).to_bigint(&field), modular_arithmetic::prefix_sub(left, &field));
+            for right in &values {
+                let y = Montgomery::<P, N>::from_bigint(right, &field);
+                assert_eq!(x.add(&y, &field).to_bigint(&field), modular_arithmetic::add(left, right, &field));
+                assert_eq!(x.sub(&y, &field).to_bigint(&field), modular_arithmetic::sub(left, right, &field));
+                assert_eq!(x.mul(&y, &field).to_bigint(&field), modular_arithmetic::mul(left, right, &field));
+            }
+            match x.inverse(&field) {
+                Result::Ok(inverse) => assert!(x.mul(&inverse, &field) == Montgomery::<P, N>::one()),
+                Result::Err(_) => assert!(FieldElement::is_zero(&x)),
+            }
+        }
+        assert_eq!(Montgomery::<P, N>::from_bigint(&BigInt::from(-1), &field).to_bigint(&field), &field - 1);
+    }
+
+    const PRIMES: [(&str, &str); 8] = [
+        ("bn128", "21888242871839275222246405745257275088548364400416034343698204186575808495617"),
+        ("bls12381", "52435875175126190479447740508185965837690552500527637822603658699938581184513"),
+        ("goldilocks", "18446744069414584321"),
+        ("grumpkin", "21888242871839275222246405745257275088696311157297823662689037894645226208583"),
+        ("pallas", "28948022309329048855892746252171976963363056481941560715954676764349967630337"),
+        ("vesta", "28948022309329048855892746252171976963363056481941647379679742748393362948097"),
+        ("secq256r1", "115792089210356248762697446949407573530086143415290314195533631308867097853951"),
+        ("bls12377", "8444461749428370424248824938781546531375899335154063827935233455917409239041"),
+    ];
+
+    #[test]
+    fn montgomery_elements_agree_with_modular_arithmetic() {
+        check_against_modular_arithmetic::<Bn128Parameters, 4>();
+        check_against_modular_arithmetic::<Bls12381Parameters, 4>();
+        check_against_modular_arithmetic::<GoldilocksParameters, 1>();
+        check_against_modular_arithmetic::<GrumpkinParameters, 4>();
+        check_against_modular_arithmetic::<PallasParameters, 4>();
+        check_against_modular_arithmetic::<VestaParameters, 4>();
+        check_against_modular_arithmetic::<Secq256r1Parameters, 4>();
+        check_against_modular_arithmetic::<Bls12377Parameters, 4>();
+        let moduli = [
+            (Bn128::name(), Bn128::modulus()),
+            (Bls12381::name(), Bls12381::modulus()),
+            (Goldilocks::name(), Goldilocks::modulus()),
+            (Grumpkin::name(), Grumpkin::modulus()),
+            (Pallas::name(), Pallas::modulus()),
+            (Vesta::name(), Vesta::modulus()),
+            (Secq256r1::name(), Secq256r1::modulus()),
+            (Bls12377::name(), Bls12377::modulus()),
+        ];
+        for ((name, modulus), (expected_name, prime)) in moduli.iter().zip(PRIMES.iter()) {
+            assert_eq!(name, expected_name);
+            assert_eq!(*modulus, BigInt::parse_bytes(prime.as_bytes(), 10).unwrap());
+        }
+    }
+
+    // (3x + 2) * (y - 1) - 5z
+    fn build_constraint<F: FieldElement>(field: &BigInt) -> Constraint<usize, F> {
+        type A<F> = ArithmeticExpression<usize, F>;
+        let number = |value: i64| A::Number { value: F::from_bigint(&BigInt::from(value), field) };
+        let signal = |symbol: usize| A::Signal { symbol };
+        let left = A::add(&A::mul(&number(3), &signal(1), field), &number(2), field);
+        let right = A::sub(&signal(2), &number(1), field);
+        let product = A::mul(&left, &right, field);
+        let expression = A::sub(&product, &A::mul(&number(5), &signal(3), field), field);
+        A::transform_expression_to_constraint_form(expression, field).unwrap()
+    }
+
+    #[test]
+    fn constraints_are_the_same_in_both_representations() {
+        let field = Bn128::modulus();
+        let constraint = build_constraint::<Bn128>(&field);
+        let expected = build_constraint::<BigInt>(&field);
+        let converted: Constraint<usize> = constraint.convert_field(&field);
+        assert_eq!(converted.a(), expected.a());
+        assert_eq!(converted.b(), expected.b());
+        assert_eq!(converted.c(), expected.c());
+
+        let mut storage = ConstraintStorage::<Bn128>::new();
+        let id = storage.add_constraint(constraint.clone());
+        let stored = storage.read_constraint(id).unwrap();
+        assert!(stored.a() == constraint.a() && stored.b() == constraint.b() && stored.c() == constraint.c());
+
+        let mut storage = ConstraintStorage::<BigInt>::new();
+        let id = storage.add_constraint(expected.clone());
+        let converted = storage.convert_field::<Bn128>(&field).read_constraint(id).unwrap();
+        assert!(converted.a() == constraint.a() && converted.b() == constraint.b() && converted.c() == constraint.c());
+    }
+}
@@ -140,30 +140,32 @@ fn substitution_process_2(
     *constraints = lconst;
 }
 
-fn substitution_process_3(
+fn substitution_process_3<O>(
     signals: &mut SignalDefinition,
-    constraints: &mut LinkedList<C>,
+    constraints: &mut LinkedList<(C, O)>,
     substitutions: &mut SHNotNormalized,
     field: &BigInt,
 ) {
     let mut lconst = LinkedList::new();
-    while let Option::Some(actual_constraint) = LinkedList::pop_back(constraints) {
-        treat_constraint_3(signals, substitutions, &mut lconst, actual_constraint, field);
+    while let Option::Some((actual_constraint, origin)) = LinkedList::pop_back(constraints) {
+        treat_constraint_3(signals, substitutions, &mut lconst, actual_constraint, origin, field);
     }
     *constraints = lconst;
 }
 
-fn substitution_process_4(
+fn substitution_process_4<O>(
     signals: &mut SignalDefinition4,
-    constraints: &mut LinkedList<C>,
+    constraints: &mut LinkedList<(C, O)>,
     substitutions: &mut SHNotNormalized,
     num_signals: usize,
     field: &BigInt,
 ) {
     let mut lconst = LinkedList::new();
     let mut vec_constraints = Vec::new();
-    for c in &mut *constraints{
-        vec_constraints.push(c.clone());
+    let mut vec_origins = Vec::new();
+    while let Option::Some((c, origin)) = LinkedList::pop_front(constraints) {
+        vec_constraints.push(c);
+        vec_origins.push(origin);
     }
 
     let (mut info_ocurrences, uniques) = SignalsInformation::new(&vec_constraints, signals, num_signals);
@@ -176,9 +178,9 @@ fn substitution_process_4(
     }
 
     while !vec_constraints.is_empty(){
-        if let Option::Some(actual_constraint) = Vec::pop(&mut vec_constraints) {
+        if let (Option::Some(actual_constraint), Option::Some(origin)) = (Vec::pop(&mut vec_constraints), Vec::pop(&mut vec_origins)) {
             info_ocurrences.remove_constraint(&actual_constraint, signals);    
-            treat_constraint_4(signals, substitutions, &mut lconst, actual_constraint, &mut info_ocurrences, field);
+            treat_constraint_4(signals, substitutions, &mut lconst, actual_constraint, origin, &mut info_ocurrences, field);
         }
     }
     *constraints = lconst;
@@ -256,11 +258,13 @@ fn treat_constraint_2(
     }
 }
 
-fn treat_constraint_3(
+// The constraint that remains, if any, keeps the origin of the one treated
+fn treat_constraint_3<O>(
     signals: &mut SignalDefinition,
     substitutions: &mut SHNotNormalized,
-    lconst: &mut LinkedList<C>,
+    lconst: &mut LinkedList<(C, O)>,
     mut work: C,
+    origin: O,
     field: &BigInt,
 ) {
     loop {
@@ -269,7 +273,7 @@ fn treat_constraint_3(
         }
         let out = take_signal_3(signals, &work);
         if out.is_none() {
-            LinkedList::push_back(lconst, work);
+            LinkedList::push_back(lconst, (work, origin));
             break;
         }
         let out = out.unwrap();
@@ -293,10 +297,10 @@ fn treat_constraint_3(
     }
 }
 
-fn treat_unique_constraint_4(
+fn treat_unique_constraint_4<O>(
     signals: &mut SignalDefinition4,
     substitutions: &mut SHNotNormalized,
-    _lconst: &mut LinkedList<C>,
+    _lconst: &mut LinkedList<(C, O)>,
     work: C,
     info_ocurrences: &mut SignalsInformation,
     signal: usize,
@@ -309,11 +313,12 @@ fn treat_unique_constraint_4(
     signals.delete(signal);
 }
 
-fn treat_constraint_4(
+fn treat_constraint_4<O>(
     signals: &mut SignalDefinition4,
     substitutions: &mut SHNotNormalized,
-    lconst: &mut LinkedList<C>,
+    lconst: &mut LinkedList<(C, O)>,
     mut work: C,
+    origin: O,
     info_ocurrences: &mut SignalsInformation,
     field: &BigInt,
 ) {
@@ -323,7 +328,7 @@ fn treat_constraint_4(
         }
         let out = take_signal_4(signals, info_ocurrences, &work);
         if out.is_none() {
-            LinkedList::push_back(lconst, work);
+            LinkedList::push_back(lconst, (work, origin));
             break;
         }
         let out = out.unwrap();
@@ -526,21 +531,23 @@ pub fn build_encoded_fast_substitutions(fast_sub: LinkedList<S>) -> HashMap<usiz
     encoded
 }
 
-pub struct Config<T> {
+// Each constraint comes with its origin, an opaque value that the
+// constraints left after the simplification keep
+pub struct Config<T, O> {
     pub field: BigInt,
-    pub constraints: LinkedList<C>,
+    pub constraints: LinkedList<(C, O)>,
     pub forbidden: T,
     pub num_signals: usize,
     pub use_old_heuristics: bool,
 }
 
-pub struct Simplified {
-    pub constraints: LinkedList<C>,
+pub struct Simplified<O> {
+    pub constraints: LinkedList<(C, O)>,
     pub substitutions: LinkedList<S>,
     pub removed: LinkedList<usize>,
 }
 
-pub fn full_simplification<T>(config: Config<T>) -> Simplified
+pub fn full_simplification<T, O>(config: Config<T, O>) -> Simplified<O>
 where
     T: AsRef<HashSet<usize>>,
 {
@@ -570,6 +577,9 @@ where
         normalized_holder = normalize_substitutions(holder, &field);
         non_overlapping = create_nonoverlapping_substitutions(normalized_holder, &field);
     }
+    // Sorted by signal, so the output does not depend on the order of the map
+    let mut non_overlapping: Vec<_> = non_overlapping.into_iter().collect();
+    non_overlapping.sort_by_key(|(s, _)| *s);
     let mut substitutions = LinkedList::new();
     let mut removed = LinkedList::new();
     for (s, v) in non_overlapping {
@@ -580,9 +590,10 @@ where
     Simplified { constraints, substitutions, removed }
 }
 
-pub fn debug_new_substitutions<T>(config: &Config<T>)
+pub fn debug_new_substitutions<T, O>(config: &Config<T, O>)
 where
     T: AsRef<HashSet<usize>>,
+    O: Clone,
 {
     let field = config.field.clone();
     // build the subs using always the complete new version
@@ -0,0 +1,56 @@
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use constraint_list::Certificate;
+
+pub const CHECK_COMMAND: &str = "check";
+// Failures listed unless --verbose
+const MAX_LISTED: usize = 10;
+
+pub fn check_certificate(matches: &ArgMatches) -> Result<(), ()> {
+    let file = matches.value_of("certificate").unwrap();
+    let certificate = Certificate::read(file).map_err(|err| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not read the certificate {}: {}", file, err)))
+    })?;
+    let check = certificate.check();
+    println!("{} {}", Colour::Blue.paint("components:"), certificate.components.len());
+    println!("{} {}", Colour::Blue.paint("original constraints:"), check.checked);
+    println!("{} {}", Colour::Blue.paint("substitutions:"), certificate.substitutions.len());
+    println!("{} {}", Colour::Blue.paint("final constraints:"), certificate.constraints.len());
+    if check.is_valid() {
+        println!("{}", Colour::Green.paint("Every original constraint is implied by the final constraints"));
+        return Result::Ok(());
+    }
+    let limit = if matches.is_present("verbose") { usize::MAX } else { MAX_LISTED };
+    for signal in check.invalid_substitutions.iter().take(limit) {
+        println!("  invalid substitution of the signal {}", signal);
+    }
+    if check.invalid_substitutions.len() > limit {
+        println!("  ... and {} more invalid substitutions", check.invalid_substitutions.len() - limit);
+    }
+    for failure in check.failures.iter().take(limit) {
+        println!("  constraint {} of {} is not implied", failure.index, failure.path);
+    }
+    if check.failures.len() > limit {
+        println!("  ... and {} more constraints not implied", check.failures.len() - limit);
+    }
+    eprintln!("{}", Colour::Red.paint("The simplification certificate is not valid"));
+    Result::Err(())
+}
+
+pub fn view() -> App<'static, 'static> {
+    SubCommand::with_name(CHECK_COMMAND)
+        .about("Checks that the original constraints of a simplification certificate follow from the final ones")
+        .arg(
+            Arg::with_name("certificate")
+                .required(true)
+                .index(1)
+                .help("Certificate written by circom with --simplification_certificate"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .takes_value(false)
+                .display_order(10)
+                .help("Lists all the constraints that are not implied"),
+        )
+}
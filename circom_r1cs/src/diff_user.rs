@@ -0,0 +1,115 @@
+use crate::r1cs_diff::{self, DiffReport, NamedR1CS};
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use constraint_writers::r1cs_reader::read_r1cs;
+use constraint_writers::sym_reader::read_sym;
+use std::path::Path;
+
+pub const DIFF_COMMAND: &str = "diff";
+const SYM: &str = "sym";
+// Constraints listed by kind of difference and component, unless --verbose
+const MAX_LISTED: usize = 10;
+
+pub fn diff_r1cs(matches: &ArgMatches) -> Result<(), ()> {
+    let old = load(matches.value_of("old").unwrap(), matches.value_of("old_sym"))?;
+    let new = load(matches.value_of("new").unwrap(), matches.value_of("new_sym"))?;
+    let report = r1cs_diff::compare(&old, &new);
+    let limit = if matches.is_present("verbose") { usize::MAX } else { MAX_LISTED };
+    print_report(&report, limit);
+    if matches.is_present("check") && !report.is_equivalent() {
+        eprintln!("{}", Colour::Red.paint("The constraint systems are different"));
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+// By default the sym file is the one next to the R1CS file
+fn load(r1cs: &str, sym: Option<&str>) -> Result<NamedR1CS, ()> {
+    let default_sym = Path::new(r1cs).with_extension(SYM).display().to_string();
+    let sym = sym.unwrap_or(&default_sym);
+    let data = read_r1cs(r1cs).map_err(|err| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not read the R1CS file {}: {}", r1cs, err)))
+    })?;
+    let symbols = read_sym(sym).map_err(|err| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not read the sym file {}: {}", sym, err)))
+    })?;
+    Ok(NamedR1CS::new(data, symbols))
+}
+
+fn print_report(report: &DiffReport, limit: usize) {
+    if report.interface_differences.is_empty() {
+        println!("{} identical", Colour::Blue.paint("public interface:"));
+    } else {
+        println!("{} different", Colour::Blue.paint("public interface:"));
+        for difference in &report.interface_differences {
+            println!("  {}", difference);
+        }
+    }
+    println!("{} {} -> {}", Colour::Blue.paint("constraints:"), report.old_constraints, report.new_constraints);
+    for (group, diff) in &report.groups {
+        println!(
+            "{} {} removed, {} added, {} changed",
+            Colour::Yellow.paint(format!("{}:", group)),
+            diff.removed.len(),
+            diff.added.len(),
+            diff.changed.len()
+        );
+        print_constraints("-", &diff.removed, limit);
+        print_constraints("+", &diff.added, limit);
+        let changed: Vec<String> =
+            diff.changed.iter().map(|(old, new)| format!("{}\n    ~> {}", old, new)).collect();
+        print_constraints("~", &changed, limit);
+    }
+    if report.is_equivalent() {
+        println!("{}", Colour::Green.paint("The constraint systems are equivalent"));
+    } else {
+        println!("{}", Colour::Yellow.paint("The constraint systems are different"));
+    }
+}
+
+fn print_constraints(mark: &str, constraints: &[String], limit: usize) {
+    for constraint in constraints.iter().take(limit) {
+        println!("  {} {}", mark, constraint);
+    }
+    if constraints.len() > limit {
+        println!("  {} ... and {} more", mark, constraints.len() - limit);
+    }
+}
+
+pub fn view() -> App<'static, 'static> {
+    SubCommand::with_name(DIFF_COMMAND)
+        .about("Compares the constraints of two R1CS files matching their signals by name")
+        .arg(Arg::with_name("old").required(true).index(1).help("R1CS file of the previous compilation"))
+        .arg(Arg::with_name("new").required(true).index(2).help("R1CS file of the new compilation"))
+        .arg(
+            Arg::with_name("old_sym")
+                .long("old_sym")
+                .takes_value(true)
+                .value_name("old_sym")
+                .display_order(10)
+                .help("Sym file of the previous compilation. By default, the one next to its R1CS file"),
+        )
+        .arg(
+            Arg::with_name("new_sym")
+                .long("new_sym")
+                .takes_value(true)
+                .value_name("new_sym")
+                .display_order(20)
+                .help("Sym file of the new compilation. By default, the one next to its R1CS file"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .takes_value(false)
+                .display_order(30)
+                .help("Lists all the different constraints of each component"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .takes_value(false)
+                .display_order(40)
+                .help("Fails if the constraint systems are different"),
+        )
+}
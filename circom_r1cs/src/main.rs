@@ -0,0 +1,41 @@
+mod check_user;
+mod diff_user;
+mod optimize_user;
+mod r1cs_diff;
+mod r1cs_optimization;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+use ansi_term::Colour;
+use clap::{App, AppSettings, ArgMatches};
+
+fn main() {
+    let matches = view();
+    let result = match matches.subcommand() {
+        (optimize_user::OPTIMIZE_COMMAND, Some(matches)) => optimize_user::optimize_r1cs(matches),
+        (diff_user::DIFF_COMMAND, Some(matches)) => diff_user::diff_r1cs(matches),
+        (check_user::CHECK_COMMAND, Some(matches)) => check_user::check_certificate(matches),
+        _ => {
+            eprintln!("{}", Colour::Red.paint("unknown command"));
+            Result::Err(())
+        }
+    };
+    if result.is_err() {
+        eprintln!("{}", Colour::Red.paint("previous errors were found"));
+        std::process::exit(1);
+    } else {
+        println!("{}", Colour::Green.paint("Everything went okay"));
+    }
+}
+
+fn view() -> ArgMatches<'static> {
+    App::new("circom-r1cs")
+        .version(VERSION)
+        .author("IDEN3")
+        .about("Processes R1CS files produced by circom or other compilers")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(optimize_user::view())
+        .subcommand(diff_user::view())
+        .subcommand(check_user::view())
+        .get_matches()
+}
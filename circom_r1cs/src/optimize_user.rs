@@ -0,0 +1,103 @@
+use crate::r1cs_optimization::{self, OptimizationConfig};
+use ansi_term::Colour;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use constraint_writers::r1cs_reader::read_r1cs;
+use constraint_writers::sym_reader::read_sym;
+use std::path::Path;
+
+pub const OPTIMIZE_COMMAND: &str = "optimize";
+const SYM: &str = "sym";
+
+pub fn optimize_r1cs(matches: &ArgMatches) -> Result<(), ()> {
+    let input = matches.value_of("input").unwrap();
+    let output = matches.value_of("output").unwrap();
+    let (flag_s, no_rounds) = get_simplification_style(matches)?;
+    let json_substitutions = substitutions_file(output);
+    let data = read_r1cs(input).map_err(|err| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not read the R1CS file {}: {}", input, err)))
+    })?;
+    let symbols = match matches.value_of("sym") {
+        Some(file) => Some(read_sym(file).map_err(|err| {
+            eprintln!("{}", Colour::Red.paint(format!("Could not read the sym file {}: {}", file, err)))
+        })?),
+        None => None,
+    };
+
+    let config = OptimizationConfig { flag_s, no_rounds, json_substitutions: json_substitutions.clone() };
+    let optimized = r1cs_optimization::optimize(&data, config);
+    println!("{} {}", Colour::Green.paint("Written successfully:"), json_substitutions);
+    r1cs_optimization::write_r1cs(output, &data, &optimized).map_err(|_| {
+        eprintln!("{}", Colour::Red.paint(format!("Could not write the file {}", output)))
+    })?;
+    println!("{} {}", Colour::Green.paint("Written successfully:"), output);
+    if let Some(symbols) = symbols {
+        let sym = Path::new(output).with_extension(SYM).display().to_string();
+        r1cs_optimization::write_sym(&sym, symbols, &optimized).map_err(|_| {
+            eprintln!("{}", Colour::Red.paint(format!("Could not write the file {}", sym)))
+        })?;
+        println!("{} {}", Colour::Green.paint("Written successfully:"), sym);
+    }
+
+    let header = &data.header_data;
+    println!("{} {} -> {}", Colour::Blue.paint("constraints:"), header.number_of_constraints, optimized.no_constraints());
+    println!("{} {}", Colour::Blue.paint("linear constraints:"), optimized.linear);
+    println!("{} {} -> {}", Colour::Blue.paint("wires:"), header.total_wires, optimized.no_wires());
+    println!("{} {} -> {}", Colour::Blue.paint("private inputs:"), header.private_inputs, optimized.private_inputs);
+    Ok(())
+}
+
+// Same styles as the compiler, but the full simplification is the default
+fn get_simplification_style(matches: &ArgMatches) -> Result<(bool, usize), ()> {
+    if matches.is_present("reduced_simplification") {
+        return Ok((true, 0));
+    }
+    match matches.value_of("simplification_rounds") {
+        Some(value) => {
+            let no_rounds = value.parse::<usize>().map_err(|_| {
+                eprintln!("{}", Colour::Red.paint("invalid number of rounds"))
+            })?;
+            Ok((no_rounds == 0, no_rounds))
+        }
+        None => Ok((false, usize::MAX)),
+    }
+}
+
+fn substitutions_file(output: &str) -> String {
+    let output = Path::new(output);
+    let stem = output.file_stem().and_then(|stem| stem.to_str()).unwrap_or("circuit");
+    output.with_file_name(format!("{}_substitutions.json", stem)).display().to_string()
+}
+
+pub fn view() -> App<'static, 'static> {
+    SubCommand::with_name(OPTIMIZE_COMMAND)
+        .about("Simplifies the constraints of an R1CS file keeping its public signals")
+        .arg(Arg::with_name("input").required(true).index(1).help("R1CS file to optimize"))
+        .arg(
+            Arg::with_name("output")
+                .required(true)
+                .index(2)
+                .help("Optimized R1CS file. The substitutions are written next to it"),
+        )
+        .arg(
+            Arg::with_name("sym")
+                .long("sym")
+                .takes_value(true)
+                .value_name("sym")
+                .display_order(10)
+                .help("Sym file of the input. An updated one is written next to the output"),
+        )
+        .arg(
+            Arg::with_name("reduced_simplification")
+                .long("O1")
+                .takes_value(false)
+                .display_order(20)
+                .help("Only applies signal to signal and signal to constant simplification"),
+        )
+        .arg(
+            Arg::with_name("simplification_rounds")
+                .long("O2round")
+                .takes_value(true)
+                .display_order(30)
+                .help("Maximum number of rounds of the simplification process"),
+        )
+}
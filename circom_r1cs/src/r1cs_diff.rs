@@ -0,0 +1,353 @@
+use circom_algebra::algebra::normalize;
+use circom_algebra::num_bigint::BigInt;
+use constraint_writers::r1cs_reader::{Constraint, R1CSData};
+use constraint_writers::sym_writer::SymElem;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+type C = circom_algebra::algebra::Constraint<usize>;
+type Coefficients = Vec<(usize, BigInt)>;
+// A, B and C of a normalized constraint over the common signals
+type Key = (Coefficients, Coefficients, Coefficients);
+
+const CONSTANT: &str = "one";
+
+// R1CS file whose wires are identified by the names of their signals
+pub struct NamedR1CS {
+    pub data: R1CSData,
+    // Name of the signal of each wire
+    pub names: Vec<String>,
+}
+
+impl NamedR1CS {
+    // The wires are matched to the symbols through their labels. The wires
+    // without symbol are named after their label.
+    pub fn new(data: R1CSData, symbols: Vec<SymElem>) -> NamedR1CS {
+        let symbols: HashMap<i64, String> =
+            symbols.into_iter().map(|elem| (elem.original, elem.symbol)).collect();
+        let mut names = Vec::with_capacity(data.signals.len());
+        for (wire, label) in data.signals.iter().enumerate() {
+            let name = match symbols.get(&(*label as i64)) {
+                _ if wire == 0 => CONSTANT.to_string(),
+                Some(symbol) => symbol.clone(),
+                None => format!("label_{}", label),
+            };
+            names.push(name);
+        }
+        NamedR1CS { data, names }
+    }
+
+    fn interface(&self) -> Vec<(&'static str, &[String])> {
+        let header = &self.data.header_data;
+        let outputs = 1 + header.public_outputs;
+        let public_inputs = outputs + header.public_inputs;
+        let private_inputs = public_inputs + header.private_inputs;
+        vec![
+            ("public outputs", self.names.get(1..outputs).unwrap_or(&[])),
+            ("public inputs", self.names.get(outputs..public_inputs).unwrap_or(&[])),
+            ("private inputs", self.names.get(public_inputs..private_inputs).unwrap_or(&[])),
+        ]
+    }
+}
+
+#[derive(Default)]
+pub struct GroupDiff {
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+    // Constraints over the same signals with different coefficients
+    pub changed: Vec<(String, String)>,
+}
+
+impl GroupDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub struct DiffReport {
+    pub old_constraints: usize,
+    pub new_constraints: usize,
+    pub interface_differences: Vec<String>,
+    // Component declaration, without array indexes -> differences
+    pub groups: BTreeMap<String, GroupDiff>,
+}
+
+impl DiffReport {
+    pub fn is_equivalent(&self) -> bool {
+        self.interface_differences.is_empty() && self.groups.values().all(GroupDiff::is_empty)
+    }
+}
+
+struct SignalIndex {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl SignalIndex {
+    // The constant keeps the id 0 that the algebra reserves for it
+    fn new() -> SignalIndex {
+        let mut index = SignalIndex { names: Vec::new(), ids: HashMap::new() };
+        index.id(CONSTANT);
+        index
+    }
+
+    fn id(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+}
+
+struct Counter {
+    group: String,
+    old: usize,
+    new: usize,
+}
+
+pub fn compare(old: &NamedR1CS, new: &NamedR1CS) -> DiffReport {
+    let mut index = SignalIndex::new();
+    let mut counters: HashMap<Key, Counter> = HashMap::new();
+    for (circuit, is_old) in [(old, true), (new, false)] {
+        let field = &circuit.data.header_data.field;
+        let wires: Vec<usize> = circuit.names.iter().map(|name| index.id(name)).collect();
+        for (a, b, c) in &circuit.data.constraints {
+            let key = normalized_key(a, b, c, &wires, field);
+            let counter = counters.entry(key).or_insert_with_key(|key| Counter {
+                group: group_of(key, &index),
+                old: 0,
+                new: 0,
+            });
+            if is_old {
+                counter.old += 1;
+            } else {
+                counter.new += 1;
+            }
+        }
+    }
+
+    let mut changes: BTreeMap<String, (Vec<Key>, Vec<Key>)> = BTreeMap::new();
+    for (key, counter) in counters {
+        let (removed, added) = changes.entry(counter.group).or_default();
+        for _ in counter.new..counter.old {
+            removed.push(key.clone());
+        }
+        for _ in counter.old..counter.new {
+            added.push(key.clone());
+        }
+    }
+    let field = &new.data.header_data.field;
+    let mut groups = BTreeMap::new();
+    for (group, (mut removed, mut added)) in changes {
+        if removed.is_empty() && added.is_empty() {
+            continue;
+        }
+        removed.sort();
+        added.sort();
+        groups.insert(group, diff_group(removed, added, &index, field));
+    }
+
+    DiffReport {
+        old_constraints: old.data.constraints.len(),
+        new_constraints: new.data.constraints.len(),
+        interface_differences: compare_interfaces(old, new),
+        groups,
+    }
+}
+
+fn compare_interfaces(old: &NamedR1CS, new: &NamedR1CS) -> Vec<String> {
+    let mut differences = Vec::new();
+    if old.data.header_data.field != new.data.header_data.field {
+        differences.push(format!(
+            "prime: {} -> {}",
+            old.data.header_data.field, new.data.header_data.field
+        ));
+    }
+    for ((kind, old_names), (_, new_names)) in old.interface().into_iter().zip(new.interface()) {
+        if old_names != new_names {
+            differences.push(format!("{}: [{}] -> [{}]", kind, old_names.join(", "), new_names.join(", ")));
+        }
+    }
+    differences
+}
+
+fn normalized_key(a: &Constraint, b: &Constraint, c: &Constraint, wires: &[usize], field: &BigInt) -> Key {
+    let rename = |expression: &Constraint| -> HashMap<usize, BigInt> {
+        expression.iter().map(|(wire, value)| (wires[*wire], value.clone())).collect()
+    };
+    let constraint = normalize(C::new(rename(a), rename(b), rename(c)), field);
+    (sorted(constraint.a()), sorted(constraint.b()), sorted(constraint.c()))
+}
+
+fn sorted(expression: &HashMap<usize, BigInt>) -> Coefficients {
+    let mut coefficients: Coefficients =
+        expression.iter().map(|(signal, value)| (*signal, value.clone())).collect();
+    coefficients.sort();
+    coefficients
+}
+
+fn signals_of(key: &Key) -> BTreeSet<usize> {
+    let (a, b, c) = key;
+    a.iter().chain(b).chain(c).map(|(signal, _)| *signal).filter(|signal| *signal != 0).collect()
+}
+
+// Innermost component containing all the signals of the constraint, where
+// the components of an array are taken together
+fn group_of(key: &Key, index: &SignalIndex) -> String {
+    let mut common: Option<Vec<&str>> = None;
+    for signal in signals_of(key) {
+        let name = &index.names[signal];
+        let component: Vec<&str> = match name.rsplit_once('.') {
+            Some((component, _)) => component.split('.').collect(),
+            None => Vec::new(),
+        };
+        common = Some(match common {
+            None => component,
+            Some(common) => common.iter().zip(&component).take_while(|(a, b)| a == b).map(|(a, _)| *a).collect(),
+        });
+    }
+    let component = common.map_or(String::new(), |common| common.join("."));
+    if component.is_empty() {
+        "main".to_string()
+    } else {
+        without_indexes(&component)
+    }
+}
+
+fn without_indexes(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut depth = 0;
+    for c in name.chars() {
+        match c {
+            '[' => {
+                if depth == 0 {
+                    result.push_str("[]");
+                }
+                depth += 1;
+            }
+            ']' => depth -= 1,
+            _ if depth == 0 => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+// A removed and an added constraint over the same signals are reported as a
+// change of the coefficients
+fn diff_group(removed: Vec<Key>, added: Vec<Key>, index: &SignalIndex, field: &BigInt) -> GroupDiff {
+    let mut added: Vec<Option<Key>> = added.into_iter().map(Some).collect();
+    let mut diff = GroupDiff::default();
+    for old in removed {
+        let signals = signals_of(&old);
+        let position = added.iter().position(|new| new.as_ref().is_some_and(|new| signals_of(new) == signals));
+        match position.and_then(|position| added[position].take()) {
+            Some(new) => diff.changed.push((show_constraint(&old, index, field), show_constraint(&new, index, field))),
+            None => diff.removed.push(show_constraint(&old, index, field)),
+        }
+    }
+    diff.added = added.iter().flatten().map(|new| show_constraint(new, index, field)).collect();
+    diff
+}
+
+fn show_constraint(key: &Key, index: &SignalIndex, field: &BigInt) -> String {
+    let (a, b, c) = key;
+    if a.is_empty() && b.is_empty() {
+        format!("0 = {}", show_expression(c, index, field))
+    } else {
+        format!(
+            "({}) * ({}) = {}",
+            show_expression(a, index, field),
+            show_expression(b, index, field),
+            show_expression(c, index, field)
+        )
+    }
+}
+
+// Coefficients larger than half of the prime are shown as negative numbers
+fn show_expression(expression: &Coefficients, index: &SignalIndex, field: &BigInt) -> String {
+    if expression.is_empty() {
+        return "0".to_string();
+    }
+    let half = field / BigInt::from(2);
+    let mut shown = String::new();
+    for (position, (signal, value)) in expression.iter().enumerate() {
+        let (negative, value) = if *value > half { (true, field - value) } else { (false, value.clone()) };
+        let sign = match (position, negative) {
+            (0, true) => "-",
+            (0, false) => "",
+            (_, true) => " - ",
+            (_, false) => " + ",
+        };
+        shown.push_str(sign);
+        if *signal == 0 {
+            shown.push_str(&value.to_string());
+        } else if value == BigInt::from(1) {
+            shown.push_str(&index.names[*signal]);
+        } else {
+            shown.push_str(&format!("{}*{}", value, index.names[*signal]));
+        }
+    }
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use constraint_writers::r1cs_reader::HeaderData;
+
+    type Terms<'a> = &'a [(usize, i64)];
+
+    fn circuit(constraints: Vec<(Terms, Terms, Terms)>, names: &[&str]) -> NamedR1CS {
+        let expression = |terms: Terms| -> Constraint {
+            terms.iter().map(|(wire, value)| (*wire, BigInt::from(*value))).collect()
+        };
+        let data = R1CSData {
+            header_data: HeaderData {
+                field: BigInt::from(257),
+                field_size: 8,
+                total_wires: names.len() + 1,
+                public_outputs: 1,
+                public_inputs: 0,
+                private_inputs: 1,
+                number_of_labels: names.len() + 1,
+                number_of_constraints: constraints.len(),
+            },
+            constraints: constraints.into_iter().map(|(a, b, c)| (expression(a), expression(b), expression(c))).collect(),
+            signals: (0..=names.len()).collect(),
+            custom_gates: false,
+            custom_gates_used_data: None,
+            custom_gates_applied_data: None,
+        };
+        let symbols = names.iter().enumerate().map(|(index, name)| SymElem {
+            original: index as i64 + 1,
+            witness: index as i64 + 1,
+            node_id: 0,
+            symbol: name.to_string(),
+        });
+        NamedR1CS::new(data, symbols.collect())
+    }
+
+    #[test]
+    fn scaled_constraints_over_renumbered_wires_are_equivalent() {
+        let old = circuit(vec![(&[(2, 1)], &[(3, 1)], &[(1, 1)])], &["main.out", "main.in", "main.sq.x"]);
+        let new = circuit(vec![(&[(3, 2)], &[(2, 1)], &[(1, 2)])], &["main.out", "main.sq.x", "main.in"]);
+        let report = compare(&old, &new);
+        assert!(report.groups.values().all(GroupDiff::is_empty));
+        assert_eq!(report.interface_differences.len(), 1);
+    }
+
+    #[test]
+    fn differences_are_grouped_by_component() {
+        let names = ["main.out", "main.in", "main.c[0].x", "main.c[1].x"];
+        let old = circuit(vec![(&[(3, 1)], &[(3, 1)], &[(3, 1)]), (&[(2, 1)], &[(4, 1)], &[(1, 1)])], &names);
+        let new = circuit(vec![(&[(3, 1)], &[(3, 1)], &[(3, 2)]), (&[(2, 1)], &[(4, 1)], &[(1, 1)])], &names);
+        let report = compare(&old, &new);
+        assert!(report.interface_differences.is_empty());
+        let diff = &report.groups["main.c[]"];
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.removed.is_empty() && diff.added.is_empty());
+        assert!(!report.groups.contains_key("main"));
+    }
+}
@@ -0,0 +1,248 @@
+use circom_algebra::num_bigint::BigInt;
+use constraint_list::{DAGEncoding, EncodingNode, Simplifier};
+use constraint_writers::r1cs_reader::{Constraint, R1CSData};
+use constraint_writers::r1cs_writer::{ConstraintSection, HeaderData, R1CSWriter, SignalSection};
+use constraint_writers::sym_writer::{SymElem, SymFile};
+use std::collections::{HashMap, HashSet, LinkedList};
+
+type C = circom_algebra::algebra::Constraint<usize>;
+
+pub struct OptimizationConfig {
+    pub flag_s: bool,
+    pub no_rounds: usize,
+    pub json_substitutions: String,
+}
+
+pub struct OptimizedR1CS {
+    pub field: BigInt,
+    pub constraints: Vec<(Constraint, Constraint, Constraint)>,
+    // Wire of the input -> wire of the output, for the wires that are kept
+    pub wire_map: HashMap<usize, usize>,
+    pub private_inputs: usize,
+    pub linear: usize,
+}
+
+impl OptimizedR1CS {
+    pub fn no_wires(&self) -> usize {
+        self.wire_map.len()
+    }
+
+    pub fn no_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+}
+
+// The wires of the input play the role of the signals of a circuit with a
+// single component. The constant, the public signals and the signals used by
+// custom gates can not be removed.
+pub fn optimize(data: &R1CSData, config: OptimizationConfig) -> OptimizedR1CS {
+    let header = &data.header_data;
+    let field = header.field.clone();
+    let mut forbidden: HashSet<usize> = (0..=header.public_outputs + header.public_inputs).collect();
+    if let Some(applications) = &data.custom_gates_applied_data {
+        for (_, signals) in applications {
+            forbidden.extend(signals.iter().copied());
+        }
+    }
+
+    let mut linear = LinkedList::new();
+    let mut equalities = LinkedList::new();
+    let mut cons_equalities = LinkedList::new();
+    let mut non_linear = LinkedList::new();
+    for (a, b, c) in &data.constraints {
+        let mut constraint = C::new(a.clone(), b.clone(), c.clone());
+        C::fix_constraint(&mut constraint, &field);
+        if constraint.is_empty() {
+            continue;
+        } else if C::is_constant_equality(&constraint) {
+            cons_equalities.push_back((constraint, None));
+        } else if C::is_equality(&constraint, &field) {
+            equalities.push_back((constraint, None));
+        } else if C::is_linear(&constraint) {
+            linear.push_back((constraint, None));
+        } else {
+            non_linear.push_back(constraint);
+        }
+    }
+
+    let main = EncodingNode {
+        id: 0,
+        name: "main".to_string(),
+        parameters: Vec::new(),
+        signals: Vec::new(),
+        ordered_signals: Vec::new(),
+        non_linear,
+        non_linear_indexes: Vec::new(),
+        is_custom_gate: false,
+    };
+    let dag_encoding = DAGEncoding {
+        init: 0,
+        no_constraints: main.non_linear.len(),
+        nodes: vec![main],
+        adjacency: vec![Vec::new()],
+    };
+    let list = Simplifier {
+        field,
+        dag_encoding,
+        no_public_inputs: header.public_inputs,
+        no_public_outputs: header.public_outputs,
+        no_private_inputs: header.private_inputs,
+        forbidden,
+        cons_equalities,
+        equalities,
+        linear,
+        max_signal: header.total_wires,
+        no_rounds: config.no_rounds,
+        parallel_flag: false,
+        flag_s: config.flag_s,
+        flag_old_heuristics: false,
+        port_substitution: true,
+        json_substitutions: config.json_substitutions,
+        keep_substitutions: false,
+    }
+    .simplify_constraints();
+
+    let ids = list.constraints.get_ids();
+    let mut constraints = Vec::with_capacity(ids.len());
+    let mut no_linear = 0;
+    for c_id in ids {
+        let constraint = list.constraints.read_constraint(c_id).unwrap();
+        let constraint = C::apply_correspondence(&constraint, &list.signal_map);
+        if C::is_linear(&constraint) {
+            no_linear += 1;
+        }
+        constraints.push((constraint.a().clone(), constraint.b().clone(), constraint.c().clone()));
+    }
+    OptimizedR1CS {
+        field: list.field,
+        constraints,
+        wire_map: list.signal_map,
+        private_inputs: list.no_private_inputs_witness,
+        linear: no_linear,
+    }
+}
+
+// The labels of the wires and the custom gates of the input are kept
+pub fn write_r1cs(output: &str, data: &R1CSData, optimized: &OptimizedR1CS) -> Result<(), ()> {
+    let header = &data.header_data;
+    let custom_gates = data.custom_gates_used_data.is_some() && data.custom_gates_applied_data.is_some();
+    let r1cs = R1CSWriter::new(output.to_string(), header.field_size, custom_gates)?;
+    let mut constraint_section = R1CSWriter::start_constraints_section(r1cs)?;
+    for (a, b, c) in &optimized.constraints {
+        ConstraintSection::write_constraint_usize(&mut constraint_section, a, b, c)?;
+    }
+    let r1cs = constraint_section.end_section()?;
+
+    let mut header_section = R1CSWriter::start_header_section(r1cs)?;
+    header_section.write_section(HeaderData {
+        field: optimized.field.clone(),
+        total_wires: optimized.no_wires(),
+        public_outputs: header.public_outputs,
+        public_inputs: header.public_inputs,
+        private_inputs: optimized.private_inputs,
+        number_of_labels: header.number_of_labels,
+        number_of_constraints: optimized.no_constraints(),
+    })?;
+    let r1cs = header_section.end_section()?;
+
+    let mut labels = vec![0; optimized.no_wires()];
+    for (wire, new_wire) in &optimized.wire_map {
+        labels[*new_wire] = *data.signals.get(*wire).ok_or(())?;
+    }
+    let mut signal_section = R1CSWriter::start_signal_section(r1cs)?;
+    for label in labels {
+        SignalSection::write_signal_usize(&mut signal_section, label)?;
+    }
+    let r1cs = signal_section.end_section()?;
+
+    match (&data.custom_gates_used_data, &data.custom_gates_applied_data) {
+        (Some(usages), Some(applications)) => {
+            let mut used_section = R1CSWriter::start_custom_gates_used_section(r1cs)?;
+            used_section.write_custom_gates_usages(usages.clone())?;
+            let r1cs = used_section.end_section()?;
+            let mut new_applications = Vec::with_capacity(applications.len());
+            for (index, signals) in applications {
+                let signals: Option<Vec<usize>> =
+                    signals.iter().map(|signal| optimized.wire_map.get(signal).copied()).collect();
+                new_applications.push((*index, signals.ok_or(())?));
+            }
+            let mut applied_section = R1CSWriter::start_custom_gates_applied_section(r1cs)?;
+            applied_section.write_custom_gates_applications(new_applications)?;
+            let r1cs = applied_section.end_section()?;
+            R1CSWriter::finish_writing(r1cs)
+        }
+        _ => R1CSWriter::finish_writing(r1cs),
+    }
+}
+
+// The symbols keep their number and point to the wire of the output, or to -1
+// if their wire was removed
+pub fn write_sym(output: &str, symbols: Vec<SymElem>, optimized: &OptimizedR1CS) -> Result<(), ()> {
+    let mut sym = SymFile::new(output)?;
+    for elem in symbols {
+        let witness = if elem.witness < 0 {
+            None
+        } else {
+            optimized.wire_map.get(&(elem.witness as usize))
+        };
+        let witness = witness.map_or(-1, |wire| *wire as i64);
+        SymFile::write_sym_elem(&mut sym, SymElem { witness, ..elem })?;
+    }
+    SymFile::finish_writing(sym)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use constraint_writers::r1cs_reader::HeaderData;
+
+    fn linear_combination(terms: &[(usize, i64)]) -> Constraint {
+        terms.iter().map(|(wire, value)| (*wire, BigInt::from(*value))).collect()
+    }
+
+    // out = x * y, with x = in + 1 and y = x through intermediate wires
+    #[test]
+    fn intermediate_wires_are_removed() {
+        let field = BigInt::parse_bytes(b"21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        let minus_one = &field - BigInt::from(1);
+        let mut x_def = linear_combination(&[(0, 1), (2, 1)]);
+        x_def.insert(3, minus_one.clone());
+        let mut y_def = linear_combination(&[(3, 1)]);
+        y_def.insert(4, minus_one);
+        let data = R1CSData {
+            header_data: HeaderData {
+                field: field.clone(),
+                field_size: 32,
+                total_wires: 5,
+                public_outputs: 1,
+                public_inputs: 1,
+                private_inputs: 0,
+                number_of_labels: 5,
+                number_of_constraints: 3,
+            },
+            constraints: vec![
+                (Constraint::new(), Constraint::new(), x_def),
+                (Constraint::new(), Constraint::new(), y_def),
+                (linear_combination(&[(3, 1)]), linear_combination(&[(4, 1)]), linear_combination(&[(1, 1)])),
+            ],
+            signals: vec![0, 1, 2, 3, 4],
+            custom_gates: false,
+            custom_gates_used_data: None,
+            custom_gates_applied_data: None,
+        };
+        let substitutions = std::env::temp_dir().join("circom_r1cs_optimization_substitutions.json");
+        let config = OptimizationConfig {
+            flag_s: false,
+            no_rounds: usize::MAX,
+            json_substitutions: substitutions.display().to_string(),
+        };
+        let optimized = optimize(&data, config);
+        assert_eq!(optimized.no_constraints(), 1);
+        assert_eq!(optimized.linear, 0);
+        assert_eq!(optimized.no_wires(), 3);
+        for wire in 0..3 {
+            assert_eq!(optimized.wire_map.get(&wire), Some(&wire));
+        }
+        let _ = std::fs::remove_file(substitutions);
+    }
+}
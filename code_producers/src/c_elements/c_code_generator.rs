@@ -439,6 +439,18 @@ pub fn build_failed_assert_message(line: usize) -> String{
      )
 }
 
+// The message of the assert is printed between these two parts
+pub fn build_failed_assert_message_header(line: usize) -> String {
+    format!("std::cout << \"Failed assert in template/function \" << {} << \" line {}: \"",
+        MY_TEMPLATE_NAME,
+        line
+    )
+}
+
+pub fn build_failed_assert_message_trace() -> String {
+    format!("std::cout << \". Followed trace of components: \" << {} << std::endl", generate_my_trace())
+}
+
 
 pub fn build_conditional(
     cond: Vec<String>,
@@ -1157,6 +1169,7 @@ pub fn generate_make_file(
             &json!({
                 "run_name": run_name,
                 "has_parallelism": producer.has_parallelism,
+                "c_lib": producer.c_lib,
             }),
         )
         .expect("must render");
@@ -1170,6 +1183,24 @@ pub fn generate_make_file(
     Ok(())
 }
 
+// The C ABI of the witness generator as a library and its test harness
+// The C code of goldilocks has its own witness calculator without the library
+pub fn generate_witness_library_files(c_folder: &std::path::Path, producer: &CProducer) -> std::io::Result<()> {
+    if producer.prime_str == "goldilocks" {
+        let message = "the witness library can not be generated for the prime goldilocks";
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, message));
+    }
+    let files = [
+        ("circom_witness.h", include_str!("common/circom_witness.h")),
+        ("circom_witness.cpp", include_str!("common/circom_witness.cpp")),
+        ("circom_witness_test.c", include_str!("common/circom_witness_test.c")),
+    ];
+    for (name, code) in files {
+        std::fs::write(c_folder.join(name), code)?;
+    }
+    Ok(())
+}
+
 pub fn generate_json2bin64(c_folder: &PathBuf, producer: &CProducer) -> std::io::Result<()> {
     use std::io::BufWriter;
     let mut file_path = c_folder.clone();
@@ -1264,4 +1295,24 @@ mod tests {
         let _rc = generate_c_file(pathc, &producer);
         assert!(true);
     }
+
+    #[test]
+    fn makefile_has_library_targets_only_in_library_mode() {
+        let folder = Path::new(LOCATION).join("c_lib");
+        std::fs::create_dir_all(&folder).unwrap();
+        let mut producer = create_producer();
+        producer.no_asm = true;
+        generate_make_file(&folder, "circuit", &producer).unwrap();
+        let makefile = std::fs::read_to_string(folder.join("Makefile")).unwrap();
+        assert!(!makefile.contains("libcircuit"));
+        producer.c_lib = true;
+        generate_make_file(&folder, "circuit", &producer).unwrap();
+        generate_witness_library_files(&folder, &producer).unwrap();
+        let makefile = std::fs::read_to_string(folder.join("Makefile")).unwrap();
+        assert!(makefile.contains("libcircuit.so: $(LIB_O)"));
+        assert!(makefile.contains("./circuit_test circuit.dat $(TEST_INPUT) $(TEST_WITNESS)"));
+        assert!(folder.join("circom_witness.h").is_file());
+        producer.prime_str = "goldilocks".to_string();
+        assert!(generate_witness_library_files(&folder, &producer).is_err());
+    }
 }
@@ -12,6 +12,16 @@
 
 #define NMUTEXES 32 //512
 
+// The witness generator library compiles the circuit with CIRCOM_WITNESS_LIB,
+// so that a failed assert throws instead of aborting the process and
+// circom_witness_run can return it as a status
+#ifdef CIRCOM_WITNESS_LIB
+struct Circom_AssertFailed {};
+#define circom_assert(e) ((e) ? (void)0 : throw Circom_AssertFailed())
+#else
+#define circom_assert(e) assert(e)
+#endif
+
 u64 fnv1a(std::string s);
 
 class Circom_CalcWit {
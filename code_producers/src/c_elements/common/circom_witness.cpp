@@ -0,0 +1,278 @@
+#define CIRCOM_WITNESS_LIB
+
+#include <string.h>
+#include <fstream>
+#include <iterator>
+#include <string>
+#include <vector>
+
+#include "circom_witness.h"
+#include "calcwit.hpp"
+#include "circom.hpp"
+
+extern void run(Circom_CalcWit* ctx);
+
+struct circom_witness_context {
+  Circom_Circuit *circuit;
+  Circom_CalcWit *calcwit;
+  std::vector<bool> inputIsSet;
+  u64 remainingInputs;
+  bool done;
+  bool failed;
+};
+
+// Reads the .dat file as main.cpp does, but checking its size
+class DatReader {
+  const u8 *data;
+  size_t size;
+  size_t pos;
+
+public:
+  DatReader(const u8 *aData, size_t aSize) : data(aData), size(aSize), pos(0) {}
+
+  bool read(void *to, size_t n) {
+    if (n > size - pos) return false;
+    memcpy(to, data + pos, n);
+    pos += n;
+    return true;
+  }
+
+  bool readU32(u32 &value) {
+    return read(&value, sizeof(u32));
+  }
+
+  bool readFieldDefs(IOFieldDefPair &pair) {
+    if (!readU32(pair.len)) return false;
+    pair.defs = new IOFieldDef[pair.len]();
+    for (u32 j = 0; j < pair.len; j++) {
+      IOFieldDef &def = pair.defs[j];
+      if (!readU32(def.offset) || !readU32(def.len)) return false;
+      if (def.len > (size - pos) / sizeof(u32)) return false;
+      def.lengths = new u32[def.len];
+      if (!read(def.lengths, def.len * sizeof(u32))) return false;
+      if (!readU32(def.size) || !readU32(def.busId)) return false;
+    }
+    return true;
+  }
+};
+
+static void releaseFieldDefs(IOFieldDefPair &pair) {
+  if (pair.defs == NULL) return;
+  for (u32 j = 0; j < pair.len; j++) {
+    delete [] pair.defs[j].lengths;
+  }
+  delete [] pair.defs;
+}
+
+static void releaseCircuit(Circom_Circuit *circuit) {
+  delete [] circuit->InputHashMap;
+  delete [] circuit->witness2SignalList;
+  delete [] circuit->circuitConstants;
+  for (auto &entry : circuit->templateInsId2IOSignalInfo) {
+    releaseFieldDefs(entry.second);
+  }
+  if (circuit->busInsId2FieldInfo != NULL) {
+    for (uint i = 0; i < get_size_of_bus_field_map(); i++) {
+      releaseFieldDefs(circuit->busInsId2FieldInfo[i]);
+    }
+    delete [] circuit->busInsId2FieldInfo;
+  }
+  delete circuit;
+}
+
+static Circom_Circuit *loadCircuit(const u8 *data, size_t size) {
+  DatReader reader(data, size);
+  Circom_Circuit *circuit = new Circom_Circuit;
+  circuit->InputHashMap = new HashSignalInfo[get_size_of_input_hashmap()];
+  circuit->witness2SignalList = new u64[get_size_of_witness()];
+  circuit->circuitConstants = new FrElement[get_size_of_constants()];
+  circuit->busInsId2FieldInfo = NULL;
+  bool valid =
+    reader.read(circuit->InputHashMap, get_size_of_input_hashmap() * sizeof(HashSignalInfo)) &&
+    reader.read(circuit->witness2SignalList, get_size_of_witness() * sizeof(u64)) &&
+    reader.read(circuit->circuitConstants, get_size_of_constants() * sizeof(FrElement));
+  if (valid && get_size_of_io_map() > 0) {
+    std::vector<u32> index(get_size_of_io_map());
+    valid = reader.read(index.data(), index.size() * sizeof(u32));
+    for (uint i = 0; valid && i < index.size(); i++) {
+      valid = reader.readFieldDefs(circuit->templateInsId2IOSignalInfo[index[i]]);
+    }
+    circuit->busInsId2FieldInfo = new IOFieldDefPair[get_size_of_bus_field_map()]();
+    for (uint i = 0; valid && i < get_size_of_bus_field_map(); i++) {
+      valid = reader.readFieldDefs(circuit->busInsId2FieldInfo[i]);
+    }
+  }
+  for (uint i = 0; valid && i < get_size_of_witness(); i++) {
+    valid = circuit->witness2SignalList[i] < get_total_signal_no();
+  }
+  if (!valid) {
+    releaseCircuit(circuit);
+    return NULL;
+  }
+  return circuit;
+}
+
+// Position of an input in the hash map of the .dat file, -1 if there is none
+static int64_t findInput(const Circom_Circuit *circuit, u64 hash) {
+  uint n = get_size_of_input_hashmap();
+  for (uint i = 0; i < n; i++) {
+    uint pos = (uint)((hash % (u64)n + i) % (u64)n);
+    if (circuit->InputHashMap[pos].hash == hash) return pos;
+    if (circuit->InputHashMap[pos].signalid == 0) return -1;
+  }
+  return -1;
+}
+
+static circom_witness_status setInput(circom_witness_context *ctx, u64 hash, u64 index, FrElement &value) {
+  int64_t pos = findInput(ctx->circuit, hash);
+  if (pos < 0) return CIRCOM_WITNESS_UNKNOWN_SIGNAL;
+  const HashSignalInfo &info = ctx->circuit->InputHashMap[pos];
+  if (index >= info.signalsize) return CIRCOM_WITNESS_INDEX_OUT_OF_RANGE;
+  if (ctx->done) return CIRCOM_WITNESS_ALREADY_RUN;
+  u64 signal = info.signalid + index;
+  u64 position = signal - get_main_input_signal_start();
+  if (ctx->inputIsSet[position]) return CIRCOM_WITNESS_ALREADY_SET;
+  // The circuit is run by circom_witness_run and not as soon as the last
+  // input is set, as Circom_CalcWit::setInputSignal does
+  ctx->calcwit->signalValues[signal] = value;
+  ctx->inputIsSet[position] = true;
+  ctx->remainingInputs--;
+  return CIRCOM_WITNESS_OK;
+}
+
+static bool isNumber(const std::string &s, uint base) {
+  if (s.empty()) return false;
+  for (char c : s) {
+    bool valid = base == 16
+      ? ('0' <= c && c <= '9') || ('a' <= c && c <= 'f') || ('A' <= c && c <= 'F')
+      : '0' <= c && c <= '9';
+    if (!valid) return false;
+  }
+  return true;
+}
+
+extern "C" {
+
+uint32_t circom_witness_abi_version(void) {
+  return CIRCOM_WITNESS_ABI_VERSION;
+}
+
+const char *circom_witness_status_message(circom_witness_status status) {
+  switch (status) {
+  case CIRCOM_WITNESS_OK: return "ok";
+  case CIRCOM_WITNESS_INVALID_DAT: return "the .dat file does not belong to the circuit";
+  case CIRCOM_WITNESS_UNKNOWN_SIGNAL: return "the circuit has no input with this hash";
+  case CIRCOM_WITNESS_INDEX_OUT_OF_RANGE: return "the index exceeds the size of the input";
+  case CIRCOM_WITNESS_ALREADY_SET: return "the input value was already set";
+  case CIRCOM_WITNESS_INVALID_VALUE: return "the value is not a field element";
+  case CIRCOM_WITNESS_MISSING_INPUTS: return "not all the inputs have been set";
+  case CIRCOM_WITNESS_NOT_RUN: return "the witness has not been computed";
+  case CIRCOM_WITNESS_ALREADY_RUN: return "the witness was already computed";
+  case CIRCOM_WITNESS_ASSERT_FAILED: return "an assert of the circuit failed";
+  }
+  return "unknown status";
+}
+
+uint32_t circom_witness_n64(void) {
+  return Fr_N64;
+}
+
+void circom_witness_prime(uint64_t *limbs) {
+  memcpy(limbs, Fr_q.longVal, Fr_N64 * sizeof(uint64_t));
+}
+
+uint64_t circom_witness_size(void) {
+  return get_size_of_witness();
+}
+
+uint64_t circom_witness_input_count(void) {
+  return get_main_input_signal_no();
+}
+
+uint64_t circom_witness_hash(const char *name) {
+  return fnv1a(std::string(name));
+}
+
+circom_witness_status circom_witness_create_from_buffer(const uint8_t *dat, size_t size, circom_witness_context **ctx) {
+  Circom_Circuit *circuit = loadCircuit(dat, size);
+  if (circuit == NULL) return CIRCOM_WITNESS_INVALID_DAT;
+  circom_witness_context *context = new circom_witness_context;
+  context->circuit = circuit;
+  context->calcwit = new Circom_CalcWit(circuit);
+  context->inputIsSet.assign(get_main_input_signal_no(), false);
+  context->remainingInputs = get_main_input_signal_no();
+  context->done = false;
+  context->failed = false;
+  *ctx = context;
+  return CIRCOM_WITNESS_OK;
+}
+
+circom_witness_status circom_witness_create(const char *dat_file, circom_witness_context **ctx) {
+  std::ifstream in(dat_file, std::ios::binary);
+  if (!in) return CIRCOM_WITNESS_INVALID_DAT;
+  std::vector<uint8_t> dat((std::istreambuf_iterator<char>(in)), std::istreambuf_iterator<char>());
+  return circom_witness_create_from_buffer(dat.data(), dat.size(), ctx);
+}
+
+void circom_witness_destroy(circom_witness_context *ctx) {
+  if (ctx == NULL) return;
+  delete ctx->calcwit;
+  releaseCircuit(ctx->circuit);
+  delete ctx;
+}
+
+circom_witness_status circom_witness_set_input(circom_witness_context *ctx, uint64_t hash, uint64_t index, const uint64_t *limbs) {
+  // only the canonical representatives, below the prime
+  for (int i = Fr_N64 - 1; i >= 0; i--) {
+    if (limbs[i] < Fr_q.longVal[i]) break;
+    if (limbs[i] > Fr_q.longVal[i] || i == 0) return CIRCOM_WITNESS_INVALID_VALUE;
+  }
+  FrElement value;
+  value.shortVal = 0;
+  value.type = Fr_LONG;
+  memcpy(value.longVal, limbs, Fr_N64 * sizeof(uint64_t));
+  return setInput(ctx, hash, index, value);
+}
+
+circom_witness_status circom_witness_set_input_str(circom_witness_context *ctx, uint64_t hash, uint64_t index, const char *value) {
+  std::string s(value);
+  uint base = 10;
+  if (s.size() > 2 && s[0] == '0' && (s[1] == 'x' || s[1] == 'X')) {
+    s = s.substr(2);
+    base = 16;
+  }
+  if (!isNumber(s, base)) return CIRCOM_WITNESS_INVALID_VALUE;
+  FrElement element;
+  Fr_str2element(&element, s.c_str(), base);
+  return setInput(ctx, hash, index, element);
+}
+
+uint64_t circom_witness_remaining_inputs(const circom_witness_context *ctx) {
+  return ctx->remainingInputs;
+}
+
+circom_witness_status circom_witness_run(circom_witness_context *ctx) {
+  if (ctx->failed) return CIRCOM_WITNESS_ASSERT_FAILED;
+  if (ctx->done) return CIRCOM_WITNESS_ALREADY_RUN;
+  if (ctx->remainingInputs != 0) return CIRCOM_WITNESS_MISSING_INPUTS;
+  try {
+    run(ctx->calcwit);
+  } catch (const Circom_AssertFailed &) {
+    ctx->failed = true;
+    return CIRCOM_WITNESS_ASSERT_FAILED;
+  }
+  ctx->done = true;
+  return CIRCOM_WITNESS_OK;
+}
+
+circom_witness_status circom_witness_get(const circom_witness_context *ctx, uint64_t index, uint64_t *limbs) {
+  if (!ctx->done) return CIRCOM_WITNESS_NOT_RUN;
+  if (index >= get_size_of_witness()) return CIRCOM_WITNESS_INDEX_OUT_OF_RANGE;
+  FrElement value;
+  ctx->calcwit->getWitness(index, &value);
+  Fr_toLongNormal(&value, &value);
+  memcpy(limbs, value.longVal, Fr_N64 * sizeof(uint64_t));
+  return CIRCOM_WITNESS_OK;
+}
+
+}
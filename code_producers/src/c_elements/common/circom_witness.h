@@ -0,0 +1,79 @@
+#ifndef CIRCOM_WITNESS_H
+#define CIRCOM_WITNESS_H
+
+/*
+Witness generator of a circom circuit as a library.
+
+A context holds the values of the signals of one witness. It is created from
+the .dat file of the circuit, its inputs are set by the hash of their name
+(circom_witness_hash) and the index of the value inside the signal, and the
+witness is computed by circom_witness_run. Field elements are given as
+circom_witness_n64() 64-bit limbs in little endian, in normal (not Montgomery)
+form. Different contexts can be used from different threads, but a context
+must not be used by several threads at the same time.
+
+The functions of this header keep their signature and meaning while
+CIRCOM_WITNESS_ABI_VERSION does not change.
+*/
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define CIRCOM_WITNESS_ABI_VERSION 1
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct circom_witness_context circom_witness_context;
+
+typedef enum {
+  CIRCOM_WITNESS_OK = 0,
+  CIRCOM_WITNESS_INVALID_DAT = 1,
+  CIRCOM_WITNESS_UNKNOWN_SIGNAL = 2,
+  CIRCOM_WITNESS_INDEX_OUT_OF_RANGE = 3,
+  CIRCOM_WITNESS_ALREADY_SET = 4,
+  CIRCOM_WITNESS_INVALID_VALUE = 5,
+  CIRCOM_WITNESS_MISSING_INPUTS = 6,
+  CIRCOM_WITNESS_NOT_RUN = 7,
+  CIRCOM_WITNESS_ALREADY_RUN = 8,
+  CIRCOM_WITNESS_ASSERT_FAILED = 9
+} circom_witness_status;
+
+uint32_t circom_witness_abi_version(void);
+const char *circom_witness_status_message(circom_witness_status status);
+
+/* Number of 64-bit limbs of a field element */
+uint32_t circom_witness_n64(void);
+/* Writes the prime of the field in circom_witness_n64() limbs */
+void circom_witness_prime(uint64_t *limbs);
+/* Number of values of the witness */
+uint64_t circom_witness_size(void);
+/* Number of values of all the inputs of the main component */
+uint64_t circom_witness_input_count(void);
+/* Hash of the name of an input, like "in" or "in.p[0].x" for a bus field */
+uint64_t circom_witness_hash(const char *name);
+
+circom_witness_status circom_witness_create(const char *dat_file, circom_witness_context **ctx);
+circom_witness_status circom_witness_create_from_buffer(const uint8_t *dat, size_t size, circom_witness_context **ctx);
+void circom_witness_destroy(circom_witness_context *ctx);
+
+/* Sets the value at position index of an input, flattening its dimensions */
+circom_witness_status circom_witness_set_input(circom_witness_context *ctx, uint64_t hash, uint64_t index, const uint64_t *limbs);
+/* Same as circom_witness_set_input with a decimal or 0x-hexadecimal number, reduced modulo the prime */
+circom_witness_status circom_witness_set_input_str(circom_witness_context *ctx, uint64_t hash, uint64_t index, const char *value);
+uint64_t circom_witness_remaining_inputs(const circom_witness_context *ctx);
+
+/* Computes the witness once all the inputs are set. A failed assert of the
+   circuit prints its message and returns CIRCOM_WITNESS_ASSERT_FAILED, then
+   the context has no witness. An assert failing inside a component that
+   runs in parallel still aborts the process */
+circom_witness_status circom_witness_run(circom_witness_context *ctx);
+/* Writes the value at position index of the witness */
+circom_witness_status circom_witness_get(const circom_witness_context *ctx, uint64_t index, uint64_t *limbs);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // CIRCOM_WITNESS_H
@@ -0,0 +1,110 @@
+/*
+Test harness of the witness generator library. It reads the inputs from a
+text file with one input per line: its name, as in circom_witness.h, followed
+by all its values separated by spaces, in decimal or 0x-hexadecimal. Empty
+lines and lines starting with # are ignored. The witness is written in the
+same .wtns format as the witness generator program, so both can be compared.
+*/
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "circom_witness.h"
+
+static int check(circom_witness_status status, const char *what) {
+  if (status != CIRCOM_WITNESS_OK) {
+    fprintf(stderr, "%s: %s\n", what, circom_witness_status_message(status));
+    return 0;
+  }
+  return 1;
+}
+
+static int read_inputs(circom_witness_context *ctx, const char *input_file) {
+  FILE *in = fopen(input_file, "r");
+  char *line = NULL;
+  size_t capacity = 0;
+  int ok = 1;
+  if (in == NULL) {
+    fprintf(stderr, "Could not read the file %s\n", input_file);
+    return 0;
+  }
+  while (ok && getline(&line, &capacity, in) != -1) {
+    const char *separators = " \t\r\n";
+    char *name = strtok(line, separators);
+    char *value;
+    uint64_t hash, index = 0;
+    if (name == NULL || name[0] == '#') continue;
+    hash = circom_witness_hash(name);
+    while (ok && (value = strtok(NULL, separators)) != NULL) {
+      ok = check(circom_witness_set_input_str(ctx, hash, index, value), name);
+      index++;
+    }
+  }
+  free(line);
+  fclose(in);
+  return ok;
+}
+
+static int write_witness(circom_witness_context *ctx, const char *wtns_file) {
+  FILE *out = fopen(wtns_file, "wb");
+  uint32_t n64 = circom_witness_n64();
+  uint32_t n8 = n64 * 8;
+  uint32_t version = 2, sections = 2, header = 1, data = 2;
+  uint64_t size = circom_witness_size();
+  uint32_t n_vars = (uint32_t)size;
+  uint64_t header_length = 8 + n8;
+  uint64_t data_length = n8 * size;
+  uint64_t *limbs = malloc(n8);
+  uint64_t i;
+  int ok = 1;
+  if (out == NULL) {
+    fprintf(stderr, "Could not write the file %s\n", wtns_file);
+    free(limbs);
+    return 0;
+  }
+  fwrite("wtns", 4, 1, out);
+  fwrite(&version, 4, 1, out);
+  fwrite(&sections, 4, 1, out);
+  fwrite(&header, 4, 1, out);
+  fwrite(&header_length, 8, 1, out);
+  fwrite(&n8, 4, 1, out);
+  circom_witness_prime(limbs);
+  fwrite(limbs, n8, 1, out);
+  fwrite(&n_vars, 4, 1, out);
+  fwrite(&data, 4, 1, out);
+  fwrite(&data_length, 8, 1, out);
+  for (i = 0; ok && i < size; i++) {
+    ok = check(circom_witness_get(ctx, i, limbs), "witness");
+    fwrite(limbs, n8, 1, out);
+  }
+  free(limbs);
+  fclose(out);
+  return ok;
+}
+
+int main(int argc, char *argv[]) {
+  circom_witness_context *ctx;
+  int ok;
+  if (argc != 4) {
+    fprintf(stderr, "Usage: %s <circuit.dat> <input.txt> <output.wtns>\n", argv[0]);
+    return EXIT_FAILURE;
+  }
+  if (circom_witness_abi_version() != CIRCOM_WITNESS_ABI_VERSION) {
+    fprintf(stderr, "The library was built for another version of circom_witness.h\n");
+    return EXIT_FAILURE;
+  }
+  if (!check(circom_witness_create(argv[1], &ctx), argv[1])) {
+    return EXIT_FAILURE;
+  }
+  ok = read_inputs(ctx, argv[2]);
+  if (ok && circom_witness_remaining_inputs(ctx) != 0) {
+    fprintf(stderr, "Not all inputs have been set. Only %llu out of %llu\n",
+            (unsigned long long)(circom_witness_input_count() - circom_witness_remaining_inputs(ctx)),
+            (unsigned long long)circom_witness_input_count());
+    ok = 0;
+  }
+  ok = ok && check(circom_witness_run(ctx), "run");
+  ok = ok && write_witness(ctx, argv[3]);
+  circom_witness_destroy(ctx);
+  return ok ? EXIT_SUCCESS : EXIT_FAILURE;
+}
@@ -14,7 +14,7 @@ module.exports = async function builder(code, options) {
     let wc;
 
     let errStr = "";
-    let msgStr = "";
+    let msgParts = [];
     
     const instance = await WebAssembly.instantiate(wasmModule, {
         runtime: {
@@ -38,22 +38,24 @@ module.exports = async function builder(code, options) {
                 throw new Error(err + errStr);
             },
 	    printErrorMessage : function() {
+		// The message of a failed assert is not ended by a \n, its parts
+		// are added to the error as they are, without spaces
+		if (msgParts.length > 0) {
+			errStr += msgParts.join("") + "\n";
+			msgParts = [];
+		}
 		errStr += getMessage() + "\n";
                 // console.error(getMessage());
 	    },
 	    writeBufferMessage : function() {
 			const msg = getMessage();
-			// Any calls to `log()` will always end with a `\n`, so that's when we print and reset
+			// Any calls to `log()` will always end with a `\n`, so that's when we print
+			// the items separated by spaces and reset
 			if (msg === "\n") {
-				console.log(msgStr);
-				msgStr = "";
+				console.log(msgParts.join(" "));
+				msgParts = [];
 			} else {
-				// If we've buffered other content, put a space in between the items
-				if (msgStr !== "") {
-					msgStr += " "
-				}
-				// Then append the message to the message we are creating
-				msgStr += msg;
+				msgParts.push(msg);
 			}
 	    },
 	    showSharedRWMemory : function() {
@@ -95,12 +97,7 @@ module.exports = async function builder(code, options) {
 	    arr[shared_rw_memory_size-1-j] = instance.exports.readSharedRWMemory(j);
 	}
 
-	// If we've buffered other content, put a space in between the items
-	if (msgStr !== "") {
-		msgStr += " "
-	}
-	// Then append the value to the message we are creating
-	msgStr += (fromArray32(arr).toString());
+	msgParts.push(fromArray32(arr).toString());
 	}
 
 };
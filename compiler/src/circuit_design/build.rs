@@ -276,8 +276,9 @@ fn initialize_cvm_producer(vcp: &VCP, database: &TemplateDB, cvm_multi_assign_fl
         "pallas" => 1948,
         "vesta" => 1948,
         "secq256r1" => 1948,
-        "bls12-377" => 1948,
-        _ => unreachable!()
+        "bls12377" => 1948,
+        // Custom primes, whose arithmetic is done by the VM
+        _ => 1948,
     };
     //producer.fr_memory_size = 412 if goldilocks and 1948 for bn128 and bls12381
     // for each created component we store three u32, for each son we store a u32 in its father
@@ -332,7 +333,9 @@ fn initialize_wasm_producer(vcp: &VCP, database: &TemplateDB, wat_flag:bool, ver
         "vesta" => 1948,
         "secq256r1" => 1948,
         "bls12377" => 1948,
-        _ => unreachable!()
+        // Custom primes have no WebAssembly field code, so this circuit is
+        // never written
+        _ => 1948,
     };
     //producer.fr_memory_size = 412 if goldilocks and 1948 for bn128 and bls12381
     // for each created component we store three u32, for each son we store a u32 in its father
@@ -368,7 +371,7 @@ fn initialize_wasm_producer(vcp: &VCP, database: &TemplateDB, wat_flag:bool, ver
     producer
 }
 
-fn initialize_c_producer(vcp: &VCP, database: &TemplateDB, no_asm_flag: bool, version: &str) -> CProducer {
+fn initialize_c_producer(vcp: &VCP, database: &TemplateDB, no_asm_flag: bool, c_lib_flag: bool, version: &str) -> CProducer {
     use program_structure::utils::constants::UsefulConstants;
     let initial_node = vcp.get_main_id();
     let prime = UsefulConstants::new(&vcp.prime).get_p().clone();
@@ -404,6 +407,7 @@ fn initialize_c_producer(vcp: &VCP, database: &TemplateDB, no_asm_flag: bool, ve
     producer.template_instance_list = build_template_list_parallel(vcp);
     producer.field_tracking.clear();
     producer.no_asm = no_asm_flag;
+    producer.c_lib = c_lib_flag;
     
     (producer.major_version, producer.minor_version, producer.patch_version) = get_number_version(version);
     producer
@@ -717,7 +721,7 @@ pub fn build_circuit(vcp: VCP, flag: CompilationFlags, version: &str) -> Circuit
     let mut circuit = Circuit::default();
     circuit.cvm_producer = initialize_cvm_producer(&vcp, &template_database, flag.cvm_multi_assign_flag, version);
     circuit.wasm_producer = initialize_wasm_producer(&vcp, &template_database, flag.wat_flag, version);
-    circuit.c_producer = initialize_c_producer(&vcp, &template_database, flag.no_asm_flag, version);
+    circuit.c_producer = initialize_c_producer(&vcp, &template_database, flag.no_asm_flag, flag.c_lib_flag, version);
 
     let field_tracker = FieldTracker::new();
     let circuit_info = CircuitInfo {
@@ -734,6 +738,7 @@ pub fn build_circuit(vcp: VCP, flag: CompilationFlags, version: &str) -> Circuit
 
     let table_usize_to_string = create_table_usize_to_string(table_string_to_usize);
     circuit.wasm_producer.set_string_table(table_usize_to_string.clone());
+    circuit.cvm_producer.set_string_table(table_usize_to_string.clone());
     circuit.c_producer.set_string_table(table_usize_to_string);
     for i in 0..field_tracker.next_id() {
         let constant = field_tracker.get_constant(i).unwrap().clone();
@@ -13,6 +13,7 @@ pub struct CompilationFlags {
     pub main_inputs_log: bool,
     pub wat_flag:bool,
     pub no_asm_flag: bool,
+    pub c_lib_flag: bool,
     pub constraint_assert_disabled_flag: bool,
     pub cvm_multi_assign_flag: bool
 }
@@ -667,6 +668,9 @@ impl Circuit {
         c_code_generator::generate_calcwit_cpp_file(&c_folder_path,&self.c_producer).map_err(|_err| {})?;
         c_code_generator::generate_fr_asm_file(&c_folder_path, &self.c_producer.prime_str,&self.c_producer).map_err(|_err| {})?;
         c_code_generator::generate_make_file(&c_folder_path,run_name,&self.c_producer).map_err(|_err| {})?;
+        if self.c_producer.c_lib {
+            c_code_generator::generate_witness_library_files(&c_folder_path,&self.c_producer).map_err(|_err| {})?;
+        }
         if self.c_producer.prime_str == "goldilocks" {
             c_code_generator::generate_json2bin64(&c_folder_path,&self.c_producer).map_err(|_err| {})?;
         }
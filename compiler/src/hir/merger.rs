@@ -63,7 +63,8 @@ fn add_instance(name: &str, args: Vec<Param>, state: &mut State) {
 
         let new_vcf = VCF {
             name: name.to_string(),
-            header: format!("{}_{}", name, state.vcf_collector.len()),
+            // Qualified names are not valid identifiers in the generated code
+            header: format!("{}_{}", name.replace("::", "__"), state.vcf_collector.len()),
             params_types: args.to_vec(),
             return_type: inferred,
             body,
@@ -155,8 +156,13 @@ fn produce_vcf_return(stmt: &Statement, state: &mut State, environment: &E) {
 
 fn produce_vcf_assert(stmt: &Statement, state: &mut State, environment: &E) {
     use Statement::Assert;
-    if let Assert { arg, .. } = stmt {
+    if let Assert { arg, message, .. } = stmt {
         produce_vcf_expr(arg, state, environment);
+        for arglog in message {
+            if let LogArgument::LogExp(arg) = arglog {
+                produce_vcf_expr(arg, state, environment);
+            }
+        }
     } else {
         unreachable!();
     }
@@ -193,6 +199,9 @@ fn produce_vcf_substitution(stmt: &Statement, state: &mut State, environment: &E
         for a in access {
             if let Access::ArrayAccess(index) = a {
                 produce_vcf_expr(index, state, environment);
+            } else if let Access::ArraySlice(start, length) = a {
+                produce_vcf_expr(start, state, environment);
+                produce_vcf_expr(length, state, environment);
             }
         }
         
@@ -299,12 +308,15 @@ fn produce_vcf_bus_call(expr: &Expression, state: &mut State, environment: &E) {
 }
 
 fn produce_vcf_variable(expr: &Expression, state: &mut State, environment: &E) {
-    use Access::ArrayAccess;
+    use Access::{ArrayAccess, ArraySlice};
     use Expression::Variable;
     if let Variable { access, .. } = expr {
         for a in access {
             if let ArrayAccess(index) = a {
                 produce_vcf_expr(index, state, environment);
+            } else if let ArraySlice(start, length) = a {
+                produce_vcf_expr(start, state, environment);
+                produce_vcf_expr(length, state, environment);
             }
         }
     } else {
@@ -448,8 +460,13 @@ fn link_log_call(stmt: &mut Statement, state: &State, env: &mut E) {
 
 fn link_assert(stmt: &mut Statement, state: &State, env: &mut E) {
     use Statement::Assert;
-    if let Assert { arg, .. } = stmt {
+    if let Assert { arg, message, .. } = stmt {
         link_expression(arg, state, env);
+        for arglog in message {
+            if let LogArgument::LogExp(arg) = arglog {
+                link_expression(arg, state, env);
+            }
+        }
     } else {
         unreachable!();
     }
@@ -494,6 +511,9 @@ fn link_substitution(stmt: &mut Statement, state: &State, env: &mut E) {
         for acc in access {
             if let Access::ArrayAccess(e) = acc {
                 link_expression(e, state, env);
+            } else if let Access::ArraySlice(start, length) = acc {
+                link_expression(start, state, env);
+                link_expression(length, state, env);
             }
         }
     }
@@ -572,6 +592,9 @@ fn link_variable(expr: &mut Expression, state: &State, env: &E) {
         for acc in access {
             if let Access::ArrayAccess(e) = acc {
                 link_expression(e, state, env);
+            } else if let Access::ArraySlice(start, length) = acc {
+                link_expression(start, state, env);
+                link_expression(length, state, env);
             }
         }
     } else {
@@ -640,6 +663,8 @@ fn cast_type_variable(expr: &Expression, state: &State, environment: &E) -> VCT
     if let Variable { name, access, .. } = expr {
         let mut xtype = environment.get_variable(name).unwrap().clone();
         xtype.reverse();
+        // the dimensions kept by the slices go first
+        let mut sliced = Vec::new();
         let mut possible_bus_info = if state.buses_info.contains_key(name){
             Some(state.buses_info.get(name).unwrap())
         } else{
@@ -650,6 +675,10 @@ fn cast_type_variable(expr: &Expression, state: &State, environment: &E) -> VCT
                 Access::ArrayAccess(_) => {
                     xtype.pop();
                 }
+                Access::ArraySlice(_, length) => {
+                    xtype.pop();
+                    sliced.push(cast_dimension(length).unwrap());
+                }
                 Access::ComponentAccess(signal) => {
                     if possible_bus_info.is_some(){
                         // case buses
@@ -684,6 +713,8 @@ fn cast_type_variable(expr: &Expression, state: &State, environment: &E) -> VCT
             }
         }
         xtype.reverse();
+        sliced.append(&mut xtype);
+        let mut xtype = sliced;
         if possible_bus_info.is_some(){
             xtype.push(possible_bus_info.unwrap().size);
         }
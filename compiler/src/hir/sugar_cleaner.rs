@@ -139,6 +139,14 @@ fn extend_conditional(
     }
 }
 
+fn index_expressions(acc: &mut Access) -> Vec<&mut Expression> {
+    match acc {
+        Access::ArrayAccess(e) => vec![e],
+        Access::ArraySlice(start, length) => vec![start, length],
+        Access::ComponentAccess(_) => vec![],
+    }
+}
+
 fn extend_substitution(
     stmt: &mut Statement,
     state: &mut State,
@@ -149,8 +157,8 @@ fn extend_substitution(
         let mut expands = extend_expression(rhe, state, context).initializations;
         
         let mut inits = vec![];
-        for acc in access {
-            if let Access::ArrayAccess(e) = acc {
+        for acc in access.iter_mut() {
+            for e in index_expressions(acc) {
                 let mut expand = extend_expression(e, state, context);
                 inits.append(&mut expand.initializations);
                 let mut expr = vec![e.clone()];
@@ -159,6 +167,11 @@ fn extend_substitution(
             }
         }
         expands.append(&mut inits);
+        // the slices followed by other accesses are assigned element by element
+        if has_split_slice(access) {
+            let id = state.produce_id();
+            *stmt = split_slice_substitution(&id, stmt.clone(), &mut expands);
+        }
         expands
     } else {
         unreachable!()
@@ -207,8 +220,15 @@ fn extend_log_call(stmt: &mut Statement, state: &mut State, context: &Context) -
 
 fn extend_assert(stmt: &mut Statement, state: &mut State, context: &Context) -> Vec<Statement> {
     use Statement::Assert;
-    if let Assert { arg, .. } = stmt {
-        extend_expression(arg, state, context).initializations
+    if let Assert { arg, message, .. } = stmt {
+        let mut initializations = extend_expression(arg, state, context).initializations;
+        for arglog in message {
+            if let LogArgument::LogExp(arg) = arglog {
+                let mut exp = extend_expression(arg, state, context);
+                initializations.append(&mut exp.initializations);
+            }
+        }
+        initializations
     } else {
         unreachable!()
     }
@@ -251,9 +271,9 @@ fn extend_variable(expr: &mut Expression, state: &mut State, context: &Context)
         let old_state_mixed = state.inside_mixed_component; // to recover at the end
         let is_mixed_component = context.mixed_components.contains(name);
         state.inside_mixed_component |= is_mixed_component; // to extend the indexes
-        for acc in access {
+        for acc in access.iter_mut() {
             
-            if let Access::ArrayAccess(e) = acc {
+            for e in index_expressions(acc) {
                 let mut expand = extend_expression(e, state, context);
                 inits.append(&mut expand.initializations);
                 let mut expr = vec![e.clone()];
@@ -264,6 +284,13 @@ fn extend_variable(expr: &mut Expression, state: &mut State, context: &Context)
         // recover all mixed_component state
         state.inside_mixed_component = old_state_mixed;
 
+        // the slices followed by other accesses are copied element by element
+        if has_split_slice(access) {
+            let id = state.produce_id();
+            *expr = rmv_slice_sugar(&id, expr.clone(), &mut inits);
+            return ExtendedSyntax { initializations: inits };
+        }
+
         // in this case we move the value to initializations and return 
         // the new variable instead of the expression
         // new_id = expr
@@ -470,6 +497,140 @@ fn rmv_sugar(fresh_variable: &str, expr: Expression, buffer: &mut Vec<Statement>
     new_arg
 }
 
+// A slice that is not the last access selects cells that are not contiguous
+fn has_split_slice(access: &[Access]) -> bool {
+    let first_slice = access.iter().position(|acc| matches!(acc, Access::ArraySlice(..)));
+    first_slice.is_some_and(|position| position + 1 < access.len())
+}
+
+// Accesses to each element of the slices in access, together with the
+// position of the element in the dimensions kept by the slices, whose
+// lengths are slice_lengths
+fn slice_elements(access: &[Access], slice_lengths: &[usize]) -> Vec<(Vec<Access>, Vec<usize>)> {
+    use num_bigint_dig::BigInt;
+    let mut elements = vec![(Vec::new(), Vec::new())];
+    for acc in access {
+        if let Access::ArraySlice(start, _) = acc {
+            let length = slice_lengths[elements[0].1.len()];
+            let mut new_elements = Vec::with_capacity(elements.len() * length);
+            for (element_access, position) in &elements {
+                for index in 0..length {
+                    let mut index_meta = start.get_meta().clone();
+                    index_meta.get_mut_memory_knowledge().set_concrete_dimensions(vec![]);
+                    let expr_index = Expression::Number(index_meta, BigInt::from(index));
+                    let mut element_access = element_access.clone();
+                    element_access.push(Access::ArrayAccess(add_index(start, expr_index)));
+                    let mut position = position.clone();
+                    position.push(index);
+                    new_elements.push((element_access, position));
+                }
+            }
+            elements = new_elements;
+        } else {
+            for (element_access, _) in &mut elements {
+                element_access.push(acc.clone());
+            }
+        }
+    }
+    elements
+}
+
+fn position_access(meta: &Meta, position: &[usize]) -> Vec<Access> {
+    use num_bigint_dig::BigInt;
+    let mut index_meta = meta.clone();
+    index_meta.get_mut_memory_knowledge().set_concrete_dimensions(vec![]);
+    index_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+    position
+        .iter()
+        .map(|index| Access::ArrayAccess(Expression::Number(index_meta.clone(), BigInt::from(*index))))
+        .collect()
+}
+
+// Declares the fresh variable with the dimensions lengths
+fn fresh_array_declaration(fresh_variable: &str, meta: &Meta, lengths: &[usize]) -> Statement {
+    use num_bigint_dig::BigInt;
+    let mut declaration_meta = meta.clone();
+    declaration_meta.get_mut_memory_knowledge().set_concrete_dimensions(lengths.to_vec());
+    declaration_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+    let dimensions = lengths.iter().map(|v| Expression::Number(meta.clone(), BigInt::from(*v))).collect();
+    Statement::Declaration {
+        meta: declaration_meta,
+        xtype: VariableType::Var,
+        name: fresh_variable.to_string(),
+        dimensions,
+        is_constant: false,
+        is_anonymous: false,
+    }
+}
+
+// The slices of expr are copied element by element into the fresh variable,
+// whose dimensions are the dimensions of expr
+fn rmv_slice_sugar(fresh_variable: &str, expr: Expression, buffer: &mut Vec<Statement>) -> Expression {
+    use Expression::Variable;
+    use Statement::Substitution;
+    if let Variable { meta, name, access } = expr {
+        let lengths = meta.get_memory_knowledge().get_concrete_dimensions().to_vec();
+        let number_of_slices = access.iter().filter(|acc| matches!(acc, Access::ArraySlice(..))).count();
+        buffer.push(fresh_array_declaration(fresh_variable, &meta, &lengths));
+        let mut substitution_meta = meta.clone();
+        substitution_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+        for (element_access, position) in slice_elements(&access, &lengths[..number_of_slices]) {
+            let mut element_meta = meta.clone();
+            element_meta.get_mut_memory_knowledge().set_concrete_dimensions(lengths[number_of_slices..].to_vec());
+            let element = Variable { meta: element_meta, name: name.clone(), access: element_access };
+            buffer.push(Substitution {
+                meta: substitution_meta.clone(),
+                var: fresh_variable.to_string(),
+                access: position_access(&meta, &position),
+                op: AssignOp::AssignVar,
+                rhe: element,
+            });
+        }
+        let mut variable_meta = meta;
+        variable_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+        Variable { meta: variable_meta, name: fresh_variable.to_string(), access: vec![] }
+    } else {
+        unreachable!()
+    }
+}
+
+// The value is stored in the fresh variable, which is assigned element by
+// element to the slices of the substitution
+fn split_slice_substitution(fresh_variable: &str, stmt: Statement, buffer: &mut Vec<Statement>) -> Statement {
+    use Expression::Variable;
+    use Statement::{Block, Substitution};
+    if let Substitution { meta, var, access, op, rhe } = stmt {
+        let lengths = rhe.get_meta().get_memory_knowledge().get_concrete_dimensions().to_vec();
+        let number_of_slices = access.iter().filter(|acc| matches!(acc, Access::ArraySlice(..))).count();
+        let value_meta = rhe.get_meta().clone();
+        buffer.push(fresh_array_declaration(fresh_variable, &value_meta, &lengths));
+        let mut initialization_meta = meta.clone();
+        initialization_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+        buffer.push(Substitution {
+            meta: initialization_meta,
+            var: fresh_variable.to_string(),
+            access: vec![],
+            op: AssignOp::AssignVar,
+            rhe,
+        });
+        let mut stmts = Vec::new();
+        for (element_access, position) in slice_elements(&access, &lengths[..number_of_slices]) {
+            let mut element_meta = value_meta.clone();
+            element_meta.get_mut_memory_knowledge().set_concrete_dimensions(lengths[number_of_slices..].to_vec());
+            element_meta.get_mut_type_knowledge().set_reduces_to(TypeReduction::Variable);
+            let element = Variable {
+                meta: element_meta,
+                name: fresh_variable.to_string(),
+                access: position_access(&value_meta, &position),
+            };
+            stmts.push(Substitution { meta: meta.clone(), var: var.clone(), access: element_access, op, rhe: element });
+        }
+        Block { meta, stmts }
+    } else {
+        unreachable!()
+    }
+}
+
 // mappings
 fn map_stmts_with_sugar(stmts: &mut Vec<Statement>, state: &mut State, context: &Context) {
     let work = std::mem::take(stmts);
@@ -634,6 +795,34 @@ fn cast_dimension(ae_index: &Expression) -> Option<usize> {
     }
 }
 
+// Access to the element index of the array accessed by access. When the
+// access ends with a slice a[start:end], the element is a[start + index]
+fn access_element(access: &[Access], expr_index: Expression) -> Vec<Access> {
+    let mut accessed_with = access.to_vec();
+    if let Option::Some(Access::ArraySlice(start, _)) = accessed_with.last() {
+        let index = add_index(start, expr_index);
+        accessed_with.pop();
+        accessed_with.push(Access::ArrayAccess(index));
+    } else {
+        accessed_with.push(Access::ArrayAccess(expr_index));
+    }
+    accessed_with
+}
+
+// Index start + index of the element of a slice
+fn add_index(start: &Expression, expr_index: Expression) -> Expression {
+    if let (Expression::Number(meta, start), Expression::Number(_, index)) = (start, &expr_index) {
+        Expression::Number(meta.clone(), start + index)
+    } else {
+        Expression::InfixOp {
+            meta: expr_index.get_meta().clone(),
+            lhe: Box::new(start.clone()),
+            infix_op: ExpressionInfixOpcode::Add,
+            rhe: Box::new(expr_index),
+        }
+    }
+}
+
 fn rhe_array_case(stmt: Statement, stmts: &mut Vec<Statement>) {
     use num_bigint_dig::BigInt;
     use Expression::{ArrayInLine, Number, UniformArray};
@@ -645,9 +834,7 @@ fn rhe_array_case(stmt: Statement, stmts: &mut Vec<Statement>) {
                 let mut index_meta = meta.clone();
                 index_meta.get_mut_memory_knowledge().set_concrete_dimensions(vec![]);
                 let expr_index = Number(index_meta, BigInt::from(index));
-                let as_access = Access::ArrayAccess(expr_index);
-                let mut accessed_with = access.clone();
-                accessed_with.push(as_access);
+                let accessed_with = access_element(&access, expr_index);
                 let sub = Substitution {
                     op,
                     var: var.clone(),
@@ -669,9 +856,7 @@ fn rhe_array_case(stmt: Statement, stmts: &mut Vec<Statement>) {
                 let mut index_meta = meta.clone();
                 index_meta.get_mut_memory_knowledge().set_concrete_dimensions(vec![]);
                 let expr_index = Number(index_meta, BigInt::from(index));
-                let as_access = Access::ArrayAccess(expr_index);
-                let mut accessed_with = access.clone();
-                accessed_with.push(as_access);
+                let accessed_with = access_element(&access, expr_index);
                 let sub = Substitution {
                     op,
                     var: var.clone(),
@@ -738,9 +923,7 @@ fn lhe_array_ce(meta: &Meta, expr_array: &Expression, other_expr: &Expression, s
                 let mut index_meta = meta.clone();
                 index_meta.get_mut_memory_knowledge().set_concrete_dimensions(vec![]);
                 let expr_index = Number(index_meta, BigInt::from(i));
-                let as_access = Access::ArrayAccess(expr_index);
-                let mut accessed_with = access.clone();
-                accessed_with.push(as_access);
+                let accessed_with = access_element(access, expr_index);
                 let ce = ConstraintEquality {
                     lhe: values_l[i].clone(),
                     rhe: Variable {name: name.clone(), access: accessed_with, meta: meta_var.clone()},
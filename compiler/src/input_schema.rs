@@ -0,0 +1,372 @@
+// JSON Schema (draft 2020-12) of the input file of the main component, and a
+// validator of input files for the schemas written by the compiler
+use crate::hir::very_concrete_program::{BusInstance, VCP};
+use json::JsonValue;
+use program_structure::ast::{Expression, SignalType};
+use program_structure::program_archive::ProgramArchive;
+use regex::Regex;
+use std::collections::HashMap;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+const FIELD: &str = "field";
+
+// The tags declared for the inputs of the main template and for the fields of
+// the buses, which are not kept in the VCP
+#[derive(Default)]
+pub struct DeclaredTags {
+    inputs: HashMap<String, Vec<String>>,
+    bus_fields: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl DeclaredTags {
+    pub fn new(program_archive: &ProgramArchive) -> DeclaredTags {
+        fn sorted(tags: &program_structure::program_library::wire_data::TagInfo) -> Vec<String> {
+            let mut tags: Vec<String> = tags.iter().cloned().collect();
+            tags.sort();
+            tags
+        }
+        let mut declared = DeclaredTags::default();
+        if let Expression::Call { id, .. } = program_archive.get_main_expression() {
+            if program_archive.contains_template(id) {
+                for (name, data) in program_archive.get_template_data(id).get_inputs() {
+                    declared.inputs.insert(name.clone(), sorted(data.get_tags()));
+                }
+            }
+        }
+        for (bus, data) in program_archive.get_buses() {
+            let fields = data.get_fields().iter().map(|(name, data)| (name.clone(), sorted(data.get_tags())));
+            declared.bus_fields.insert(bus.clone(), fields.collect());
+        }
+        declared
+    }
+}
+
+pub fn build_input_schema(vcp: &VCP, public_inputs: &[String], tags: &DeclaredTags) -> JsonValue {
+    let main = vcp.get_main_instance().unwrap();
+    let bus_names = definition_names(&vcp.buses);
+    let mut properties = JsonValue::new_object();
+    let mut required = JsonValue::new_array();
+    for wire in &main.wires {
+        if wire.xtype() != SignalType::Input {
+            continue;
+        }
+        let mut schema = wire_schema(wire.lengths(), wire.bus_id(), &vcp.buses, &bus_names);
+        let mut info = JsonValue::new_object();
+        info["public"] = public_inputs.contains(wire.name()).into();
+        info["dimensions"] = wire.lengths().clone().into();
+        if let Some(bus_id) = wire.bus_id() {
+            info["bus"] = vcp.buses[bus_id].name.clone().into();
+        }
+        info["tags"] = tags.inputs.get(wire.name()).cloned().unwrap_or_default().into();
+        schema["x-circom"] = info;
+        properties[wire.name().as_str()] = schema;
+        required.push(wire.name().clone()).unwrap();
+    }
+
+    let mut definitions = JsonValue::new_object();
+    definitions[FIELD] = field_schema();
+    for (bus_id, bus) in vcp.buses.iter().enumerate() {
+        let no_tags = HashMap::new();
+        let field_tags = tags.bus_fields.get(&bus.name).unwrap_or(&no_tags);
+        definitions[bus_names[bus_id].as_str()] = bus_schema(bus, field_tags, &vcp.buses, &bus_names);
+    }
+
+    let mut info = JsonValue::new_object();
+    info["template"] = main.template_name.clone().into();
+    info["prime"] = vcp.prime.clone().into();
+    let mut schema = JsonValue::new_object();
+    schema["$schema"] = DRAFT.into();
+    schema["title"] = format!("Inputs of {}", main.template_name).into();
+    schema["type"] = "object".into();
+    schema["properties"] = properties;
+    schema["required"] = required;
+    schema["additionalProperties"] = false.into();
+    schema["$defs"] = definitions;
+    schema["x-circom"] = info;
+    schema
+}
+
+// The buses are named after their template, followed by their position in the
+// table of buses when several instances of the same template are used
+fn definition_names(buses: &[BusInstance]) -> Vec<String> {
+    let mut instances: HashMap<&str, usize> = HashMap::new();
+    for bus in buses {
+        *instances.entry(bus.name.as_str()).or_insert(0) += 1;
+    }
+    buses
+        .iter()
+        .enumerate()
+        .map(|(bus_id, bus)| {
+            if instances[bus.name.as_str()] == 1 && bus.name != FIELD {
+                bus.name.clone()
+            } else {
+                format!("{}_{}", bus.name, bus_id)
+            }
+        })
+        .collect()
+}
+
+// An integer, a decimal string or a hexadecimal string
+fn field_schema() -> JsonValue {
+    let mut decimal = JsonValue::new_object();
+    decimal["type"] = "string".into();
+    decimal["pattern"] = "^-?[0-9]+$".into();
+    let mut hexadecimal = JsonValue::new_object();
+    hexadecimal["type"] = "string".into();
+    hexadecimal["pattern"] = "^0[xX][0-9a-fA-F]+$".into();
+    let mut integer = JsonValue::new_object();
+    integer["type"] = "integer".into();
+    let mut schema = JsonValue::new_object();
+    schema["anyOf"] = JsonValue::Array(vec![integer, decimal, hexadecimal]);
+    schema
+}
+
+fn reference(name: &str) -> JsonValue {
+    let mut schema = JsonValue::new_object();
+    schema["$ref"] = format!("#/$defs/{}", name).into();
+    schema
+}
+
+// The values of all the signals in a single array, in declaration order
+fn serialized_schema(size: usize) -> JsonValue {
+    array_schema(&[size], reference(FIELD))
+}
+
+fn array_schema(dimensions: &[usize], element: JsonValue) -> JsonValue {
+    if let Some((length, rest)) = dimensions.split_first() {
+        let mut schema = JsonValue::new_object();
+        schema["type"] = "array".into();
+        schema["minItems"] = (*length).into();
+        schema["maxItems"] = (*length).into();
+        schema["items"] = array_schema(rest, element);
+        schema
+    } else {
+        element
+    }
+}
+
+fn wire_schema(dimensions: &[usize], bus_id: Option<usize>, buses: &[BusInstance], names: &[String]) -> JsonValue {
+    match bus_id {
+        None => array_schema(dimensions, reference(FIELD)),
+        Some(bus_id) if !dimensions.is_empty() => {
+            // an array of buses may also be given serialized as a whole
+            let size = dimensions.iter().fold(buses[bus_id].size, |size, length| size * length);
+            let mut schema = JsonValue::new_object();
+            schema["anyOf"] =
+                JsonValue::Array(vec![array_schema(dimensions, reference(&names[bus_id])), serialized_schema(size)]);
+            schema
+        }
+        Some(bus_id) => reference(&names[bus_id]),
+    }
+}
+
+fn bus_schema(
+    bus: &BusInstance,
+    field_tags: &HashMap<String, Vec<String>>,
+    buses: &[BusInstance],
+    names: &[String],
+) -> JsonValue {
+    let mut fields: Vec<_> = bus.fields.iter().collect();
+    fields.sort_by_key(|(_, info)| info.field_id);
+    let mut properties = JsonValue::new_object();
+    let mut required = JsonValue::new_array();
+    for (name, info) in fields {
+        let mut schema = wire_schema(&info.dimensions, info.bus_id, buses, names);
+        let mut circom_info = JsonValue::new_object();
+        circom_info["dimensions"] = info.dimensions.clone().into();
+        if let Some(bus_id) = info.bus_id {
+            circom_info["bus"] = buses[bus_id].name.clone().into();
+        }
+        circom_info["tags"] = field_tags.get(name).cloned().unwrap_or_default().into();
+        schema["x-circom"] = circom_info;
+        properties[name.as_str()] = schema;
+        required.push(name.clone()).unwrap();
+    }
+    let mut by_fields = JsonValue::new_object();
+    by_fields["type"] = "object".into();
+    by_fields["properties"] = properties;
+    by_fields["required"] = required;
+    by_fields["additionalProperties"] = false.into();
+    let mut schema = JsonValue::new_object();
+    schema["anyOf"] = JsonValue::Array(vec![by_fields, serialized_schema(bus.size)]);
+    schema
+}
+
+// Checks a value against the schema. It understands the keywords used in the
+// schemas of the inputs: $ref to $defs, type, properties, required,
+// additionalProperties, items, minItems, maxItems, anyOf, oneOf, allOf, enum
+// and pattern. Every error is given with the path of the value it refers to.
+pub fn validate(schema: &JsonValue, value: &JsonValue) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_value(schema, schema, value, "", &mut errors);
+    errors
+}
+
+fn describe(path: &str) -> &str {
+    if path.is_empty() {
+        "the input"
+    } else {
+        path
+    }
+}
+
+fn has_type(value: &JsonValue, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+        _ => false,
+    }
+}
+
+fn validate_value(root: &JsonValue, schema: &JsonValue, value: &JsonValue, path: &str, errors: &mut Vec<String>) {
+    if let JsonValue::Boolean(accepted) = schema {
+        if !accepted {
+            errors.push(format!("{}: no value is allowed", describe(path)));
+        }
+        return;
+    }
+    if let Some(target) = schema["$ref"].as_str() {
+        match target.strip_prefix("#/$defs/").map(|name| &root["$defs"][name]) {
+            Some(definition) if !definition.is_null() => validate_value(root, definition, value, path, errors),
+            _ => errors.push(format!("{}: unknown reference {}", describe(path), target)),
+        }
+    }
+    if !schema["type"].is_null() {
+        let types: Vec<&str> = if schema["type"].is_array() {
+            schema["type"].members().filter_map(|t| t.as_str()).collect()
+        } else {
+            schema["type"].as_str().into_iter().collect()
+        };
+        if !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{}: expected {}, found {}", describe(path), types.join(" or "), value.dump()));
+            return;
+        }
+    }
+    if schema["enum"].is_array() && !schema["enum"].members().any(|option| option == value) {
+        errors.push(format!("{}: {} is not one of the allowed values", describe(path), value.dump()));
+    }
+    if let (Some(pattern), Some(text)) = (schema["pattern"].as_str(), value.as_str()) {
+        match Regex::new(pattern) {
+            Ok(regex) if regex.is_match(text) => {}
+            Ok(_) => errors.push(format!("{}: \"{}\" does not match {}", describe(path), text, pattern)),
+            Err(_) => errors.push(format!("{}: invalid pattern {} in the schema", describe(path), pattern)),
+        }
+    }
+    if value.is_object() {
+        for name in schema["required"].members().filter_map(|name| name.as_str()) {
+            if !value.has_key(name) {
+                errors.push(format!("{}: missing {}", describe(path), name));
+            }
+        }
+        for (name, field) in value.entries() {
+            let field_path = format!("{}/{}", path, name);
+            let property = &schema["properties"][name];
+            if !property.is_null() {
+                validate_value(root, property, field, &field_path, errors);
+            } else if schema["additionalProperties"] == false {
+                errors.push(format!("{}: unknown input", field_path));
+            } else if schema["additionalProperties"].is_object() {
+                validate_value(root, &schema["additionalProperties"], field, &field_path, errors);
+            }
+        }
+    }
+    if value.is_array() {
+        if let Some(minimum) = schema["minItems"].as_usize() {
+            if value.len() < minimum {
+                errors.push(format!("{}: expected at least {} values, found {}", describe(path), minimum, value.len()));
+            }
+        }
+        if let Some(maximum) = schema["maxItems"].as_usize() {
+            if value.len() > maximum {
+                errors.push(format!("{}: expected at most {} values, found {}", describe(path), maximum, value.len()));
+            }
+        }
+        if !schema["items"].is_null() {
+            for (index, item) in value.members().enumerate() {
+                validate_value(root, &schema["items"], item, &format!("{}/{}", path, index), errors);
+            }
+        }
+    }
+    for subschema in schema["allOf"].members() {
+        validate_value(root, subschema, value, path, errors);
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if !schema[keyword].is_array() {
+            continue;
+        }
+        let results: Vec<Vec<String>> = schema[keyword]
+            .members()
+            .map(|subschema| {
+                let mut found = Vec::new();
+                validate_value(root, subschema, value, path, &mut found);
+                found
+            })
+            .collect();
+        let matches = results.iter().filter(|found| found.is_empty()).count();
+        if matches == 0 {
+            // the errors of the alternative that went deeper into the value,
+            // the first one among those that went equally deep
+            let depth = |error: &String| error.split(':').next().unwrap().matches('/').count();
+            let closest = results.iter().rev().max_by_key(|found| found.iter().map(depth).max());
+            errors.extend(closest.into_iter().flatten().cloned());
+        } else if keyword == "oneOf" && matches > 1 {
+            errors.push(format!("{}: matches more than one of the allowed forms", describe(path)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> JsonValue {
+        json::parse(
+            r##"{
+            "type": "object",
+            "properties": {
+                "a": {"$ref": "#/$defs/field"},
+                "b": {"anyOf": [
+                    {"type": "object", "properties": {"x": {"$ref": "#/$defs/field"}},
+                     "required": ["x"], "additionalProperties": false},
+                    {"type": "array", "minItems": 1, "maxItems": 1, "items": {"$ref": "#/$defs/field"}}
+                ]},
+                "c": {"type": "array", "minItems": 2, "maxItems": 2,
+                      "items": {"type": "array", "minItems": 1, "maxItems": 1, "items": {"$ref": "#/$defs/field"}}}
+            },
+            "required": ["a", "b", "c"],
+            "additionalProperties": false
+        }"##,
+        )
+        .map(|mut schema| {
+            schema["$defs"][FIELD] = field_schema();
+            schema
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn valid_inputs_have_no_errors() {
+        let schema = schema();
+        let input = json::parse(r#"{"a": "-12", "b": {"x": 3}, "c": [["0x1f"], [4]]}"#).unwrap();
+        assert!(validate(&schema, &input).is_empty());
+        let serialized = json::parse(r#"{"a": 1, "b": ["7"], "c": [["1"], ["2"]]}"#).unwrap();
+        assert!(validate(&schema, &serialized).is_empty());
+    }
+
+    #[test]
+    fn errors_point_to_the_wrong_values() {
+        let schema = schema();
+        let input = json::parse(r#"{"a": "1.5", "b": {"y": 3}, "c": [["1"]], "d": 0}"#).unwrap();
+        let errors = validate(&schema, &input);
+        assert!(errors.iter().any(|e| e.starts_with("/a: ")));
+        assert!(errors.contains(&"/b: missing x".to_string()));
+        assert!(errors.contains(&"/b/y: unknown input".to_string()));
+        assert!(errors.contains(&"/c: expected at least 2 values, found 1".to_string()));
+        assert!(errors.contains(&"/d: unknown input".to_string()));
+    }
+}
@@ -10,6 +10,8 @@ pub struct AssertBucket {
     pub line: usize,
     pub message_id: usize,
     pub evaluate: InstructionPointer,
+    // Printed when the assert fails, empty if there is no message
+    pub message: Vec<LogBucketArg>,
 }
 
 impl IntoInstruction for AssertBucket {
@@ -38,7 +40,13 @@ impl ToString for AssertBucket {
         let line = self.line.to_string();
         let template_id = self.message_id.to_string();
         let evaluate = self.evaluate.to_string();
-        format!("ASSERT(line: {},template_id: {},evaluate: {})", line, template_id, evaluate)
+        let mut message = String::new();
+        for arg in &self.message {
+            if let LogBucketArg::LogExp(exp) = arg {
+                message = format!("{},message: {}", message, exp.to_string());
+            }
+        }
+        format!("ASSERT(line: {},template_id: {},evaluate: {}{})", line, template_id, evaluate, message)
     }
 }
 
@@ -54,6 +62,26 @@ impl WriteWasm for AssertBucket {
         instructions.push(call("$Fr_isTrue"));
         instructions.push(eqz32());
         instructions.push(add_if());
+        if !self.message.is_empty() {
+            // the parts of the message are not ended by a \n as in the log
+            // statements, so printErrorMessage adds them to the error
+            for arg in &self.message {
+                match arg {
+                    LogBucketArg::LogExp(exp) => {
+                        instructions.append(&mut exp.produce_wasm(producer));
+                        instructions.push(call("$copyFr2SharedRWMemory"));
+                        instructions.push(call("$showSharedRWMemory"));
+                    }
+                    LogBucketArg::LogStr(string_id) => {
+                        let pos = producer.get_string_list_start()
+                            + string_id * producer.get_size_of_message_in_bytes();
+                        instructions.push(set_constant(&pos.to_string()));
+                        instructions.push(call("$buildLogMessage"));
+                        instructions.push(call("$writeBufferMessage"));
+                    }
+                }
+            }
+        }
         instructions.push(set_constant(&self.message_id.to_string()));
         instructions.push(set_constant(&self.line.to_string()));
         instructions.push(call("$buildBufferMessage"));
@@ -73,12 +101,39 @@ impl WriteC for AssertBucket {
         use c_code_generator::*;
         let (mut prologue, value) = self.evaluate.produce_c(producer, parallel);
         let is_true = build_call("Fr_isTrue".to_string(), vec![value]);
-        let if_condition = format!("if (!{}) {};", is_true, build_failed_assert_message(self.line));    
-        let assertion = format!("{};", build_call("assert".to_string(), vec![is_true]));
+        // the library catches the failed asserts, see calcwit.hpp
+        let assert_name = if producer.c_lib { "circom_assert" } else { "assert" };
+        let assertion = format!("{};", build_call(assert_name.to_string(), vec![is_true.clone()]));
         let mut assert_c = vec![];
         assert_c.push(format!("{{"));
         assert_c.append(&mut prologue);
-        assert_c.push(if_condition);
+        if self.message.is_empty() {
+            assert_c.push(format!("if (!{}) {};", is_true, build_failed_assert_message(self.line)));
+        } else {
+            // the values of the message are only computed when the assert fails
+            assert_c.push(format!("if (!{}) {{", is_true));
+            assert_c.push(format!("{};", build_failed_assert_message_header(self.line)));
+            for arg in &self.message {
+                match arg {
+                    LogBucketArg::LogExp(exp) => {
+                        let (mut argument_code, argument_result) = exp.produce_c(producer, parallel);
+                        let to_string_call = build_call("Fr_element2str".to_string(), vec![argument_result]);
+                        assert_c.append(&mut argument_code);
+                        assert_c.push("{".to_string());
+                        assert_c.push(format!("char* temp = {};", to_string_call));
+                        assert_c.push("std::cout << temp;".to_string());
+                        assert_c.push("delete [] temp;".to_string());
+                        assert_c.push("}".to_string());
+                    }
+                    LogBucketArg::LogStr(string_id) => {
+                        let string_value = &producer.get_string_table()[*string_id];
+                        assert_c.push(format!("std::cout << \"{}\";", string_value));
+                    }
+                }
+            }
+            assert_c.push(format!("{};", build_failed_assert_message_trace()));
+            assert_c.push("}".to_string());
+        }
         assert_c.push(assertion);
         assert_c.push(format!("}}"));
         (assert_c, "".to_string())
@@ -101,7 +156,23 @@ impl WriteCVM for AssertBucket{
         }
         instructions.push(format!("{} = {} {}", cvar, eqzff(), avar));
         instructions.push(format!("{} {}", add_ifff(), cvar));
-        instructions.push(exception(&"i64.0".to_string()));
+        let mut error = exception(&"i64.0".to_string());
+        if !self.message.is_empty() {
+            // the values of the message are only computed when the assert fails
+            let mut values = vec![];
+            for arg in &self.message {
+                if let LogBucketArg::LogExp(exp) = arg {
+                    let (mut instructions_exp, value) = exp.produce_cvm(producer);
+                    instructions.append(&mut instructions_exp);
+                    values.push(value);
+                }
+            }
+            error = format!("{} \"{}\"", error, cvm_assert_message(producer, &self.message));
+            for value in values {
+                error = format!("{} {}", error, value);
+            }
+        }
+        instructions.push(error);
         instructions.push(add_end());
         if producer.needs_comments() {
             instructions.push(";; end of assert bucket".to_string());
@@ -109,3 +180,15 @@ impl WriteCVM for AssertBucket{
         (instructions,"".to_string())
     }
 }
+
+// The message with a {} in place of each value
+fn cvm_assert_message(producer: &CVMProducer, message: &[LogBucketArg]) -> String {
+    let mut text = String::new();
+    for arg in message {
+        match arg {
+            LogBucketArg::LogExp(_) => text.push_str("{}"),
+            LogBucketArg::LogStr(string_id) => text.push_str(&producer.get_string_table()[*string_id]),
+        }
+    }
+    text
+}
@@ -4,6 +4,7 @@ use crate::intermediate_representation::log_bucket::LogBucketArg;
 use crate::intermediate_representation::types::SizeOption;
 use constant_tracking::ConstantTracker;
 use num_bigint_dig::BigInt;
+use num_traits::ToPrimitive;
 use program_structure::ast::*;
 use program_structure::file_definition::FileLibrary;
 use program_structure::utils::environment::VarEnvironment;
@@ -734,8 +735,13 @@ fn translate_constraint_equality(stmt: Statement, state: &mut State, context: &C
             }
             .allocate();
             let assert_instruction =
-                AssertBucket { line: starts_at, message_id: state.message_id, evaluate: equality }
-                    .allocate();
+                AssertBucket {
+                    line: starts_at,
+                    message_id: state.message_id,
+                    evaluate: equality,
+                    message: Vec::new(),
+                }
+                .allocate();
             state.code.push(assert_instruction);
         }
         
@@ -746,10 +752,11 @@ fn translate_constraint_equality(stmt: Statement, state: &mut State, context: &C
 
 fn translate_assert(stmt: Statement, state: &mut State, context: &Context) {
     use Statement::Assert;
-    if let Assert { meta, arg, .. } = stmt {
+    if let Assert { meta, arg, message } = stmt {
         let line = context.files.get_line(meta.start, meta.get_file_id()).unwrap();
         let code = translate_expression(arg, state, context);
-        let assert = AssertBucket { line, message_id: state.message_id, evaluate: code }.allocate();
+        let message = translate_log_arguments(message, state, context);
+        let assert = AssertBucket { line, message_id: state.message_id, evaluate: code, message }.allocate();
         state.code.push(assert);
     }
 }
@@ -758,26 +765,7 @@ fn translate_log(stmt: Statement, state: &mut State, context: &Context) {
     use Statement::LogCall;
     if let LogCall { meta, args, .. } = stmt {
         let line = context.files.get_line(meta.start, meta.get_file_id()).unwrap();
-        let mut logbucket_args = Vec::new();
-        for arglog in args {
-            match arglog {
-                LogArgument::LogExp(arg) => {
-                    let code = translate_expression(arg, state, context);
-                    logbucket_args.push(LogBucketArg::LogExp(code));
-                }
-                LogArgument::LogStr(exp) => {
-                    match state.string_table.get(&exp) {
-                        Some( idx) => {logbucket_args.push(LogBucketArg::LogStr(*idx));},
-                        None => {
-                            logbucket_args.push(LogBucketArg::LogStr(state.string_table.len()));
-                            state.string_table.insert(exp, state.string_table.len());
-                        },
-                    }
-                    
-                }
-            }
-        }
-        
+        let logbucket_args = translate_log_arguments(args, state, context);
         let log = LogBucket {
             line,
             message_id: state.message_id,
@@ -787,6 +775,29 @@ fn translate_log(stmt: Statement, state: &mut State, context: &Context) {
     }
 }
 
+// The strings are stored once in the string table, shared by log and assert
+fn translate_log_arguments(args: Vec<LogArgument>, state: &mut State, context: &Context) -> Vec<LogBucketArg> {
+    let mut logbucket_args = Vec::new();
+    for arglog in args {
+        match arglog {
+            LogArgument::LogExp(arg) => {
+                let code = translate_expression(arg, state, context);
+                logbucket_args.push(LogBucketArg::LogExp(code));
+            }
+            LogArgument::LogStr(exp) => {
+                match state.string_table.get(&exp) {
+                    Some( idx) => {logbucket_args.push(LogBucketArg::LogStr(*idx));},
+                    None => {
+                        logbucket_args.push(LogBucketArg::LogStr(state.string_table.len()));
+                        state.string_table.insert(exp, state.string_table.len());
+                    },
+                }
+            }
+        }
+    }
+    logbucket_args
+}
+
 fn translate_return(stmt: Statement, state: &mut State, context: &Context) {
     use Statement::Return;
     if let Return { meta, value, .. } = stmt {
@@ -911,7 +922,7 @@ fn check_tag_access(name_signal: &String, access: &Vec<Access>, state: &mut Stat
     if !symbol_info.is_component{
         for acc in access {
             match acc {
-                ArrayAccess(..) => {return None},
+                ArrayAccess(..) | ArraySlice(..) => {return None},
                 ComponentAccess(name) => {
                     complete_access.push(name.clone());
                 }
@@ -1070,6 +1081,14 @@ fn build_signal_location(
     }
 }
 
+fn cast_slice_length(length: &Expression) -> Option<usize> {
+    if let Expression::Number(_, value) = length {
+        value.to_usize()
+    } else {
+        Option::None
+    }
+}
+
 struct SymbolDef {
     meta: Meta,
     symbol: String,
@@ -1164,6 +1183,23 @@ impl ProcessedSymbol {
                     current_index.push(translate_expression(exp, state, context));
                 }
 
+                ArraySlice(start, length) => {
+                    // the slice is accessed from its first element and keeps
+                    // the accessed dimension with the length of the slice. The
+                    // execution computes the length of every slice or reports
+                    // it, and the sugar cleaner leaves the slices at the end
+                    let slice_length = cast_slice_length(&length)
+                        .expect("the execution computes the length of the slices");
+                    let possible_sizes = possible_status.possible_sizes.iter_mut();
+                    for (possible_length, possible_size) in possible_status.possible_lengths.iter_mut().zip(possible_sizes){
+                        let aux_length = possible_length.pop();
+                        *possible_size = *possible_size / aux_length.unwrap() * slice_length;
+                        possible_length.push(slice_length);
+                    }
+
+                    current_index.push(translate_expression(start, state, context));
+                }
+
                 ComponentAccess(name) => {
                     // we distinguish the cases component and bus
                     if is_component{
@@ -126,7 +126,13 @@ pub fn build_log(bucket: &mut LogBucket, fresh: usize) -> usize {
 }
 
 pub fn build_assert(bucket: &mut AssertBucket, fresh: usize) -> usize {
-    build_instruction(&mut bucket.evaluate, fresh)
+    let mut in_assert = build_instruction(&mut bucket.evaluate, fresh);
+    for arglog in &mut bucket.message {
+        if let LogBucketArg::LogExp(exp) = arglog {
+            in_assert = std::cmp::max(in_assert, build_instruction(exp, fresh));
+        }
+    }
+    in_assert
 }
 
 pub fn build_store(bucket: &mut StoreBucket, fresh: usize) -> usize {
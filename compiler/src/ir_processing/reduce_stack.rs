@@ -102,6 +102,16 @@ pub fn reduce_log(mut bucket: LogBucket) -> Instruction {
 
 pub fn reduce_assert(mut bucket: AssertBucket) -> Instruction {
     bucket.evaluate = Allocate::allocate(reduce_instruction(*bucket.evaluate));
+    let mut new_message = Vec::new();
+    for arglog in bucket.message {
+        match arglog {
+            LogBucketArg::LogExp(exp) => {
+                new_message.push(LogBucketArg::LogExp(Allocate::allocate(reduce_instruction(*exp))));
+            }
+            LogBucketArg::LogStr(s) => new_message.push(LogBucketArg::LogStr(s)),
+        }
+    }
+    bucket.message = new_message;
     IntoInstruction::into_instruction(bucket)
 }
 
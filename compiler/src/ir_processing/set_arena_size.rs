@@ -87,6 +87,11 @@ pub fn visit_log(bucket: &mut LogBucket, function_to_arena_size: &HashMap<String
 
 pub fn visit_assert(bucket: &mut AssertBucket, function_to_arena_size: &HashMap<String, usize>) {
     visit_instruction(&mut bucket.evaluate, function_to_arena_size);
+    for arglog in &mut bucket.message {
+        if let LogBucketArg::LogExp(exp) = arglog {
+            visit_instruction(exp, function_to_arena_size);
+        }
+    }
 }
 
 pub fn visit_store(bucket: &mut StoreBucket, function_to_arena_size: &HashMap<String, usize>) {
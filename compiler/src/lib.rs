@@ -7,4 +7,6 @@ pub extern crate num_traits;
 
 pub mod compiler_interface;
 pub mod hir;
+pub mod input_schema;
+pub mod witness_calculator;
 mod translating_traits;
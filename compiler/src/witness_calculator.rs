@@ -0,0 +1,626 @@
+// Computes the values of the signals of a circuit by interpreting its
+// intermediate representation, following the sequential C witness generator
+use crate::circuit_design::circuit::Circuit;
+use crate::circuit_design::function::FunctionCodeInfo;
+use crate::circuit_design::template::TemplateCodeInfo;
+use crate::hir::very_concrete_program::Wire;
+use crate::intermediate_representation::ir_interface::*;
+use circom_algebra::modular_arithmetic::{self, ArithmeticError};
+use code_producers::components::FieldData;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use std::collections::HashMap;
+use std::fmt;
+
+pub enum WitnessError {
+    AssertFailed { name: String, line: usize, message: String, trace: String },
+    Arithmetic { name: String, line: usize, error: ArithmeticError },
+    InvalidAccess { name: String, line: usize },
+    MissingComponent { name: String, line: usize },
+    // Instructions that the code generators never produce where they are found
+    UnexpectedInstruction { name: String, line: usize },
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use WitnessError::*;
+        match self {
+            AssertFailed { name, line, message, trace } if message.is_empty() => write!(
+                f,
+                "Failed assert in template/function {} line {}. Followed trace of components: {}",
+                name, line, trace
+            ),
+            AssertFailed { name, line, message, trace } => write!(
+                f,
+                "Failed assert in template/function {} line {}: {}. Followed trace of components: {}",
+                name, line, message, trace
+            ),
+            Arithmetic { name, line, error } => {
+                let error = match error {
+                    ArithmeticError::DivisionByZero => "division by zero",
+                    ArithmeticError::BitOverFlowInShift => "overflow in a shift",
+                };
+                write!(f, "Error in template/function {} line {}: {}", name, line, error)
+            }
+            InvalidAccess { name, line } => {
+                write!(f, "Error in template/function {} line {}: access out of bounds", name, line)
+            }
+            MissingComponent { name, line } => write!(
+                f,
+                "Error in template/function {} line {}: the component is not created",
+                name, line
+            ),
+            UnexpectedInstruction { name, line } => write!(
+                f,
+                "Error in template/function {} line {}: the witness calculator does not support this instruction",
+                name, line
+            ),
+        }
+    }
+}
+
+type WitnessResult<T> = Result<T, WitnessError>;
+
+pub struct WitnessCalculator<'a> {
+    circuit: &'a Circuit,
+    field: BigInt,
+    constants: Vec<BigInt>,
+    templates: HashMap<usize, &'a TemplateCodeInfo>,
+    functions: HashMap<&'a str, &'a FunctionCodeInfo>,
+    main: &'a TemplateCodeInfo,
+}
+
+impl<'a> WitnessCalculator<'a> {
+    pub fn new(circuit: &'a Circuit) -> WitnessCalculator<'a> {
+        let producer = &circuit.c_producer;
+        let parse = |value: &String| BigInt::parse_bytes(value.as_bytes(), 10).unwrap();
+        let templates: HashMap<_, _> = circuit.templates.iter().map(|t| (t.id, t.as_ref())).collect();
+        let main = circuit.templates.iter().find(|t| t.header == producer.main_header).unwrap();
+        WitnessCalculator {
+            circuit,
+            field: parse(&producer.prime),
+            constants: producer.field_tracking.iter().map(parse).collect(),
+            templates,
+            functions: circuit.functions.iter().map(|f| (f.header.as_str(), f.as_ref())).collect(),
+            main,
+        }
+    }
+
+    // Names and positions in the witness of every input signal of the main
+    // component, with the fields of the buses as in a.b[0].c
+    pub fn main_inputs(&self) -> Vec<(String, usize)> {
+        self.main_wires(&self.main.inputs)
+    }
+
+    pub fn main_outputs(&self) -> Vec<(String, usize)> {
+        self.main_wires(&self.main.outputs)
+    }
+
+    fn main_wires(&self, wires: &[Wire]) -> Vec<(String, usize)> {
+        let buses = &self.circuit.c_producer.busid_field_info;
+        let mut signals = Vec::new();
+        for wire in wires {
+            let start = self.circuit.c_producer.main_signal_offset + wire.local_id();
+            let size = wire.size() / wire.lengths().iter().product::<usize>().max(1);
+            expand_wire(wire.name(), wire.lengths(), start, size, wire.bus_id(), buses, &mut signals);
+        }
+        signals
+    }
+
+    // Values of all the signals of the circuit, given the values of the
+    // inputs of the main component by their positions
+    pub fn calculate(&self, inputs: &[(usize, BigInt)]) -> WitnessResult<Vec<BigInt>> {
+        let producer = &self.circuit.c_producer;
+        let mut execution = Execution {
+            calculator: self,
+            signals: vec![BigInt::zero(); producer.total_number_of_signals.max(1)],
+            components: Vec::new(),
+        };
+        execution.signals[0] = BigInt::from(1);
+        for (position, value) in inputs {
+            execution.signals[*position] = modular_arithmetic::add(value, &BigInt::zero(), &self.field);
+        }
+        let main = execution.create(self.main.id, producer.main_signal_offset, "main".to_string(), None)?;
+        if self.main.number_of_inputs > 0 {
+            execution.run(main)?;
+        }
+        Ok(execution.signals)
+    }
+}
+
+fn expand_wire(
+    name: &str,
+    lengths: &[usize],
+    start: usize,
+    size: usize,
+    bus_id: Option<usize>,
+    buses: &[Vec<FieldData>],
+    signals: &mut Vec<(String, usize)>,
+) {
+    let number_of_elements: usize = lengths.iter().product();
+    for element in 0..number_of_elements {
+        let mut indexes = String::new();
+        let mut rest = element;
+        for dim in 0..lengths.len() {
+            let inner: usize = lengths[dim + 1..].iter().product();
+            indexes.push_str(&format!("[{}]", rest / inner));
+            rest %= inner;
+        }
+        let element_name = format!("{}{}", name, indexes);
+        let element_start = start + element * size;
+        if let Some(bus_id) = bus_id {
+            for field in &buses[bus_id] {
+                let field_name = format!("{}.{}", element_name, field.name);
+                let field_start = element_start + field.offset;
+                expand_wire(&field_name, &field.dimensions, field_start, field.size, field.bus_id, buses, signals);
+            }
+        } else {
+            signals.push((element_name, element_start));
+        }
+    }
+}
+
+struct Component {
+    template_id: usize,
+    signal_start: usize,
+    input_counter: usize,
+    subcomponents: Vec<Option<usize>>,
+    name: String,
+    father: Option<usize>,
+}
+
+// Local state of a template or function under execution
+struct Frame<'a> {
+    name: &'a str,
+    component: usize,
+    vars: Vec<BigInt>,
+    destination: Vec<BigInt>,
+    // subcomponent of the last load, which gives the sizes of the comparisons
+    last_load: Option<usize>,
+}
+
+enum Flow {
+    Continue,
+    Return,
+}
+
+struct Execution<'a> {
+    calculator: &'a WitnessCalculator<'a>,
+    signals: Vec<BigInt>,
+    components: Vec<Component>,
+}
+
+impl<'a> Execution<'a> {
+    fn field(&self) -> &'a BigInt {
+        &self.calculator.field
+    }
+
+    fn create(&mut self, template_id: usize, signal_start: usize, name: String, father: Option<usize>) -> WitnessResult<usize> {
+        let calculator = self.calculator;
+        let template = calculator.templates[&template_id];
+        self.components.push(Component {
+            template_id,
+            signal_start,
+            input_counter: template.number_of_inputs,
+            subcomponents: vec![None; template.number_of_components],
+            name,
+            father,
+        });
+        let id = self.components.len() - 1;
+        if template.number_of_inputs == 0 {
+            self.run(id)?;
+        }
+        Ok(id)
+    }
+
+    fn run(&mut self, component: usize) -> WitnessResult<()> {
+        let calculator = self.calculator;
+        let template = calculator.templates[&self.components[component].template_id];
+        let mut frame = Frame {
+            name: &template.name,
+            component,
+            vars: vec![BigInt::zero(); template.var_stack_depth],
+            destination: Vec::new(),
+            last_load: None,
+        };
+        self.execute_list(&template.body, &mut frame)?;
+        Ok(())
+    }
+
+    fn trace(&self, component: usize) -> String {
+        let mut names = vec![self.components[component].name.as_str()];
+        let mut current = self.components[component].father;
+        while let Some(father) = current {
+            names.push(&self.components[father].name);
+            current = self.components[father].father;
+        }
+        names.reverse();
+        names.join("->")
+    }
+
+    fn execute_list(&mut self, instructions: &[InstructionPointer], frame: &mut Frame) -> WitnessResult<Flow> {
+        for instruction in instructions {
+            if let Flow::Return = self.execute(instruction, frame)? {
+                return Ok(Flow::Return);
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn execute(&mut self, instruction: &Instruction, frame: &mut Frame) -> WitnessResult<Flow> {
+        use Instruction::*;
+        match instruction {
+            Store(bucket) => {
+                let values = self.evaluate(&bucket.src, frame)?;
+                self.write(&bucket.dest_address_type, &bucket.dest, &bucket.context, values, bucket.line, frame)?;
+            }
+            Call(bucket) => {
+                if let ReturnType::Final(data) = &bucket.return_info {
+                    let size = match &data.context.size {
+                        SizeOption::Single(size) => *size,
+                        SizeOption::Multiple(sizes) => {
+                            let component = self.subcomponent(&data.dest_address_type, bucket.line, frame)?;
+                            self.size_for(sizes, component)
+                        }
+                    };
+                    let values = self.call(bucket, size, frame)?;
+                    self.write(&data.dest_address_type, &data.dest, &data.context, values, bucket.line, frame)?;
+                } else {
+                    self.evaluate(instruction, frame)?;
+                }
+            }
+            Branch(bucket) => {
+                let body = if self.condition(&bucket.cond, frame)? { &bucket.if_branch } else { &bucket.else_branch };
+                return self.execute_list(body, frame);
+            }
+            Loop(bucket) => {
+                while self.condition(&bucket.continue_condition, frame)? {
+                    if let Flow::Return = self.execute_list(&bucket.body, frame)? {
+                        return Ok(Flow::Return);
+                    }
+                }
+            }
+            Return(bucket) => {
+                let values = self.evaluate(&bucket.value, frame)?;
+                let size = bucket.with_size.max(1).min(values.len()).min(frame.destination.len());
+                frame.destination[..size].clone_from_slice(&values[..size]);
+                return Ok(Flow::Return);
+            }
+            Assert(bucket) => {
+                if !self.condition(&bucket.evaluate, frame)? {
+                    let message = self.message(&bucket.message, "", frame)?;
+                    return Err(WitnessError::AssertFailed {
+                        name: frame.name.to_string(),
+                        line: bucket.line,
+                        message,
+                        trace: self.trace(frame.component),
+                    });
+                }
+            }
+            Log(bucket) => {
+                let message = self.message(&bucket.argsprint, " ", frame)?;
+                println!("{}", message);
+            }
+            CreateCmp(bucket) => self.create_components(bucket, frame)?,
+            Value(_) | Load(_) | Compute(_) => {
+                self.evaluate(instruction, frame)?;
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn message(&mut self, args: &[LogBucketArg], separator: &str, frame: &mut Frame) -> WitnessResult<String> {
+        let mut parts = Vec::new();
+        for arg in args {
+            match arg {
+                LogBucketArg::LogExp(expression) => parts.push(self.evaluate(expression, frame)?[0].to_string()),
+                LogBucketArg::LogStr(id) => parts.push(self.calculator.circuit.c_producer.get_string_table()[*id].clone()),
+            }
+        }
+        Ok(parts.join(separator))
+    }
+
+    fn condition(&mut self, instruction: &Instruction, frame: &mut Frame) -> WitnessResult<bool> {
+        let value = self.evaluate(instruction, frame)?;
+        Ok(modular_arithmetic::as_bool(&value[0], self.field()))
+    }
+
+    fn address(&mut self, instruction: &Instruction, frame: &mut Frame) -> WitnessResult<usize> {
+        let value = self.evaluate(instruction, frame)?;
+        value[0].to_usize().ok_or_else(|| invalid_access(frame, instruction))
+    }
+
+    fn evaluate(&mut self, instruction: &Instruction, frame: &mut Frame) -> WitnessResult<Vec<BigInt>> {
+        use Instruction::*;
+        match instruction {
+            Value(bucket) => match bucket.parse_as {
+                ValueType::BigInt => Ok(vec![self.calculator.constants[bucket.value].clone()]),
+                ValueType::U32 => Ok(vec![BigInt::from(bucket.value)]),
+            },
+            Load(bucket) => self.load(bucket, frame),
+            Compute(bucket) => self.compute(bucket, frame),
+            Call(bucket) => self.call(bucket, 1, frame),
+            _ => Err(unexpected_instruction(frame, instruction.get_line())),
+        }
+    }
+
+    fn load(&mut self, bucket: &LoadBucket, frame: &mut Frame) -> WitnessResult<Vec<BigInt>> {
+        let component = match &bucket.address_type {
+            AddressType::SubcmpSignal { .. } => {
+                let component = self.subcomponent(&bucket.address_type, bucket.line, frame)?;
+                frame.last_load = Some(component);
+                Some(component)
+            }
+            _ => None,
+        };
+        let size = self.size(&bucket.context.size, component, bucket.line, frame)?;
+        let position = self.location(&bucket.src, component, bucket.line, frame)?;
+        let memory = match &bucket.address_type {
+            AddressType::Variable => &frame.vars,
+            _ => &self.signals,
+        };
+        let start = match &bucket.address_type {
+            AddressType::Variable => position,
+            AddressType::Signal => self.components[frame.component].signal_start + position,
+            AddressType::SubcmpSignal { .. } => self.signal_start(component, bucket.line, frame)? + position,
+        };
+        memory.get(start..start + size).map(|values| values.to_vec()).ok_or_else(|| WitnessError::InvalidAccess {
+            name: frame.name.to_string(),
+            line: bucket.line,
+        })
+    }
+
+    // Stores the values in the destination and runs the subcomponent when
+    // its last input is assigned
+    fn write(
+        &mut self,
+        address_type: &AddressType,
+        location: &LocationRule,
+        context: &InstrContext,
+        values: Vec<BigInt>,
+        line: usize,
+        frame: &mut Frame,
+    ) -> WitnessResult<()> {
+        let component = match address_type {
+            AddressType::SubcmpSignal { .. } => Some(self.subcomponent(address_type, line, frame)?),
+            _ => None,
+        };
+        let size = self.size(&context.size, component, line, frame)?.min(values.len());
+        let position = self.location(location, component, line, frame)?;
+        let (memory, start) = match address_type {
+            AddressType::Variable => (&mut frame.vars, position),
+            AddressType::Signal => (&mut self.signals, self.components[frame.component].signal_start + position),
+            AddressType::SubcmpSignal { .. } => {
+                let start = self.signal_start(component, line, frame)? + position;
+                (&mut self.signals, start)
+            }
+        };
+        match memory.get_mut(start..start + size) {
+            Some(destination) => destination.clone_from_slice(&values[..size]),
+            None => return Err(WitnessError::InvalidAccess { name: frame.name.to_string(), line }),
+        }
+        if let (
+            AddressType::SubcmpSignal { input_information: InputInformation::Input { status, needs_decrement }, .. },
+            Some(component),
+        ) = (address_type, component)
+        {
+            if *needs_decrement || matches!(status, StatusInput::Unknown) {
+                let counter = &mut self.components[component].input_counter;
+                *counter = counter.saturating_sub(size);
+            }
+            let run = match status {
+                StatusInput::NoLast => false,
+                StatusInput::Last => true,
+                StatusInput::Unknown => self.components[component].input_counter == 0,
+            };
+            if run {
+                self.run(component)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn subcomponent(&mut self, address_type: &AddressType, line: usize, frame: &mut Frame) -> WitnessResult<usize> {
+        if let AddressType::SubcmpSignal { cmp_address, .. } = address_type {
+            let index = self.address(cmp_address, frame)?;
+            let subcomponents = &self.components[frame.component].subcomponents;
+            match subcomponents.get(index) {
+                Some(Some(component)) => Ok(*component),
+                Some(None) => Err(WitnessError::MissingComponent { name: frame.name.to_string(), line }),
+                None => Err(WitnessError::InvalidAccess { name: frame.name.to_string(), line }),
+            }
+        } else {
+            Err(unexpected_instruction(frame, line))
+        }
+    }
+
+    // Sizes that depend on the template of a subcomponent need the
+    // subcomponent of the access
+    fn size(&self, size: &SizeOption, component: Option<usize>, line: usize, frame: &Frame) -> WitnessResult<usize> {
+        match (size, component) {
+            (SizeOption::Single(size), _) => Ok(*size),
+            (SizeOption::Multiple(sizes), Some(component)) => Ok(self.size_for(sizes, component)),
+            (SizeOption::Multiple(_), None) => Err(unexpected_instruction(frame, line)),
+        }
+    }
+
+    fn signal_start(&self, component: Option<usize>, line: usize, frame: &Frame) -> WitnessResult<usize> {
+        let component = component.ok_or_else(|| unexpected_instruction(frame, line))?;
+        Ok(self.components[component].signal_start)
+    }
+
+    fn size_for(&self, sizes: &[(usize, usize)], component: usize) -> usize {
+        let template_id = self.components[component].template_id;
+        sizes.iter().find(|(id, _)| *id == template_id).map_or(0, |(_, size)| *size)
+    }
+
+    // Position of the access relative to the start of the memory of the
+    // variables or of the signals of the component
+    fn location(
+        &mut self,
+        location: &LocationRule,
+        component: Option<usize>,
+        line: usize,
+        frame: &mut Frame,
+    ) -> WitnessResult<usize> {
+        match location {
+            LocationRule::Indexed { location, .. } => self.address(location, frame),
+            LocationRule::Mapped { signal_code, indexes } => {
+                let calculator = self.calculator;
+                let producer = &calculator.circuit.c_producer;
+                let component = component.ok_or_else(|| unexpected_instruction(frame, line))?;
+                let template_id = self.components[component].template_id;
+                let def = producer.io_map.get(&template_id).and_then(|defs| defs.get(*signal_code));
+                let def = def.ok_or_else(|| WitnessError::InvalidAccess { name: frame.name.to_string(), line })?;
+                let mut position = def.offset;
+                let (mut lengths, mut size, mut bus_id) = (&def.lengths, def.size, def.bus_id);
+                for access in indexes {
+                    match access {
+                        AccessType::Indexed(info) => {
+                            let mut map_index = 0;
+                            for (i, index) in info.indexes.iter().enumerate() {
+                                let index = self.address(index, frame)?;
+                                map_index = if i == 0 { index } else { map_index * lengths[i] + index };
+                            }
+                            for length in &lengths[info.indexes.len()..info.symbol_dim] {
+                                map_index *= length;
+                            }
+                            position += map_index * size;
+                        }
+                        AccessType::Qualified(field) => {
+                            let fields = bus_id.and_then(|id| producer.busid_field_info.get(id));
+                            let field = fields.and_then(|fields| fields.get(*field));
+                            let field =
+                                field.ok_or_else(|| WitnessError::InvalidAccess { name: frame.name.to_string(), line })?;
+                            position += field.offset;
+                            lengths = &field.dimensions;
+                            size = field.size;
+                            bus_id = field.bus_id;
+                        }
+                    }
+                }
+                Ok(position)
+            }
+        }
+    }
+
+    fn compute(&mut self, bucket: &ComputeBucket, frame: &mut Frame) -> WitnessResult<Vec<BigInt>> {
+        use OperatorType::*;
+        let mut operands = Vec::with_capacity(bucket.stack.len());
+        for operand in &bucket.stack {
+            operands.push(self.evaluate(operand, frame)?);
+        }
+        let field = self.field();
+        let arithmetic = |result: Result<BigInt, ArithmeticError>| {
+            result.map_err(|error| WitnessError::Arithmetic { name: frame.name.to_string(), line: bucket.line, error })
+        };
+        let operand = |position: usize| {
+            let operand = operands.get(position).and_then(|operand| operand.first());
+            operand.ok_or_else(|| unexpected_instruction(frame, bucket.line))
+        };
+        let left = operand(0)?;
+        let right = || operand(1);
+        let value = match &bucket.op {
+            AddAddress => left + right()?,
+            MulAddress => left * right()?,
+            ToAddress => left.clone(),
+            Eq(size) => {
+                let size = self.size(size, frame.last_load, bucket.line, frame)?;
+                let other = operands.get(1).ok_or_else(|| unexpected_instruction(frame, bucket.line))?;
+                let equal = (0..size).all(|i| operands[0].get(i) == other.get(i));
+                BigInt::from(equal as u8)
+            }
+            Mul => modular_arithmetic::mul(left, right()?, field),
+            Div => arithmetic(modular_arithmetic::div(left, right()?, field))?,
+            Add => modular_arithmetic::add(left, right()?, field),
+            Sub => modular_arithmetic::sub(left, right()?, field),
+            Pow => modular_arithmetic::pow(left, right()?, field),
+            IntDiv => arithmetic(modular_arithmetic::idiv(left, right()?, field))?,
+            Mod => arithmetic(modular_arithmetic::mod_op(left, right()?, field))?,
+            ShiftL => arithmetic(modular_arithmetic::shift_l(left, right()?, field))?,
+            ShiftR => arithmetic(modular_arithmetic::shift_r(left, right()?, field))?,
+            LesserEq => modular_arithmetic::lesser_eq(left, right()?, field),
+            GreaterEq => modular_arithmetic::greater_eq(left, right()?, field),
+            Lesser => modular_arithmetic::lesser(left, right()?, field),
+            Greater => modular_arithmetic::greater(left, right()?, field),
+            NotEq => modular_arithmetic::not_eq(left, right()?, field),
+            BoolOr => modular_arithmetic::bool_or(left, right()?, field),
+            BoolAnd => modular_arithmetic::bool_and(left, right()?, field),
+            BitOr => modular_arithmetic::bit_or(left, right()?, field),
+            BitAnd => modular_arithmetic::bit_and(left, right()?, field),
+            BitXor => modular_arithmetic::bit_xor(left, right()?, field),
+            PrefixSub => modular_arithmetic::prefix_sub(left, field),
+            BoolNot => modular_arithmetic::not(left, field),
+            Complement => modular_arithmetic::complement(left, field),
+        };
+        Ok(vec![value])
+    }
+
+    // Runs the function with the arguments copied in its memory, returning
+    // the first size elements of its result
+    fn call(&mut self, bucket: &CallBucket, size: usize, frame: &mut Frame) -> WitnessResult<Vec<BigInt>> {
+        let calculator = self.calculator;
+        let function = calculator.functions[bucket.symbol.as_str()];
+        let mut vars = Vec::with_capacity(bucket.arena_size.max(function.max_number_of_vars));
+        for (argument, argument_type) in bucket.arguments.iter().zip(&bucket.argument_types) {
+            let values = self.evaluate(argument, frame)?;
+            // Arguments with sizes that depend on the template are signals of
+            // subcomponents, loaded by the evaluation of the argument
+            let argument_size = self.size(&argument_type.size, frame.last_load, bucket.line, frame)?;
+            vars.extend(values.into_iter().chain(std::iter::repeat(BigInt::zero())).take(argument_size));
+        }
+        let arena_size = bucket.arena_size.max(function.max_number_of_vars).max(vars.len());
+        vars.resize(arena_size, BigInt::zero());
+        let mut function_frame = Frame {
+            name: &function.name,
+            component: frame.component,
+            vars,
+            destination: vec![BigInt::zero(); size],
+            last_load: None,
+        };
+        self.execute_list(&function.body, &mut function_frame)?;
+        Ok(function_frame.destination)
+    }
+
+    fn create_components(&mut self, bucket: &CreateCmpBucket, frame: &mut Frame) -> WitnessResult<()> {
+        let first = self.address(&bucket.sub_cmp_id, frame)?;
+        let positions: Vec<usize> = if bucket.defined_positions.len() == bucket.number_of_cmp {
+            (0..bucket.number_of_cmp).collect()
+        } else {
+            bucket.defined_positions.iter().map(|(position, _)| *position).collect()
+        };
+        let mut signal_start = self.components[frame.component].signal_start + bucket.signal_offset;
+        for position in positions {
+            let (index, name) = if bucket.number_of_cmp > 1 {
+                (first + position, format!("{}{}", bucket.name_subcomponent, array_position(position, &bucket.dimensions)))
+            } else {
+                (first, bucket.name_subcomponent.clone())
+            };
+            if index >= self.components[frame.component].subcomponents.len() {
+                return Err(WitnessError::InvalidAccess { name: frame.name.to_string(), line: bucket.line });
+            }
+            let component = self.create(bucket.template_id, signal_start, name, Some(frame.component))?;
+            self.components[frame.component].subcomponents[index] = Some(component);
+            signal_start += bucket.signal_offset_jump;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_access(frame: &Frame, instruction: &Instruction) -> WitnessError {
+    WitnessError::InvalidAccess { name: frame.name.to_string(), line: instruction.get_line() }
+}
+
+fn unexpected_instruction(frame: &Frame, line: usize) -> WitnessError {
+    WitnessError::UnexpectedInstruction { name: frame.name.to_string(), line }
+}
+
+// Indexes of a position in an array with the given dimensions, as in [1][0]
+fn array_position(mut position: usize, dimensions: &[usize]) -> String {
+    let mut indexes = vec![0; dimensions.len()];
+    for (i, length) in dimensions.iter().enumerate().rev() {
+        indexes[i] = position % length;
+        position /= length;
+    }
+    indexes.iter().map(|index| format!("[{}]", index)).collect()
+}
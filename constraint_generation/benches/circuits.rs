@@ -0,0 +1,146 @@
+// Compares the build with the Montgomery elements of bn128 with the one with
+// BigInt on the circuits of benches/circuits. The same prime given by its
+// value is not one of the named ones, so it is built with BigInt.
+// Run with: cargo bench -p constraint_generation
+use constraint_generation::{build_circuit, BuildConfig};
+use program_structure::constants::UsefulConstants;
+use program_structure::program_archive::ProgramArchive;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::convert::TryInto;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        pointer
+    }
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const CIRCUITS: [(&str, &str); 2] = [
+    ("sha256 (1 block)", "sha256.circom"),
+    ("poseidon (100 hashes)", "poseidon.circom"),
+];
+
+fn compile(file: &str) -> ProgramArchive {
+    let path = format!("{}/benches/circuits/{}", env!("CARGO_MANIFEST_DIR"), file);
+    let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+    let (mut program, _) = parser::run_parser(path, "2.2.2", vec![], &prime, false, None)
+        .unwrap_or_else(|_| panic!("Could not parse {}", file));
+    type_analysis::check_types::check_types(&mut program).unwrap_or_else(|_| panic!("Type errors in {}", file));
+    program
+}
+
+struct Measure {
+    time: Duration,
+    peak_memory: usize,
+    r1cs: Vec<u8>,
+}
+
+// Execution of the circuit, simplification of its constraints with --O1 and
+// writing of the r1cs file
+fn measure(runs: usize, program: &ProgramArchive, prime: &str, output: &Path) -> Measure {
+    let mut time = Duration::MAX;
+    let mut peak_memory = 0;
+    let output = output.to_str().unwrap();
+    for _ in 0..runs {
+        let program = program.clone();
+        let base = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(base, Ordering::Relaxed);
+        let start = Instant::now();
+        let (writer, _) = build_circuit(program, config(prime)).unwrap_or_else(|_| panic!("The circuit could not be built"));
+        writer.r1cs(output, false).unwrap();
+        time = std::cmp::min(time, start.elapsed());
+        peak_memory = PEAK.load(Ordering::Relaxed) - base;
+    }
+    Measure { time, peak_memory, r1cs: std::fs::read(output).unwrap() }
+}
+
+fn config(prime: &str) -> BuildConfig {
+    BuildConfig {
+        no_rounds: 0,
+        flag_json_sub: false,
+        json_substitutions: String::new(),
+        flag_s: true,
+        flag_f: false,
+        flag_p: false,
+        flag_verbose: false,
+        flag_old_heuristics: false,
+        inspect_constraints: false,
+        prime: prime.to_string(),
+        hierarchy_format: None,
+        hierarchy_file: String::new(),
+        flag_profile: false,
+        profile_json: String::new(),
+        profile_folded: String::new(),
+        flag_plonk: false,
+        plonk_file: String::new(),
+        flag_certificate: false,
+        certificate_file: String::new(),
+    }
+}
+
+// The header section contains the field size, the prime, the number of
+// wires, public outputs, public inputs, private inputs, labels and constraints
+fn number_of_constraints(r1cs: &[u8]) -> u32 {
+    let u32_at = |position: usize| u32::from_le_bytes(r1cs[position..position + 4].try_into().unwrap());
+    let u64_at = |position: usize| u64::from_le_bytes(r1cs[position..position + 8].try_into().unwrap());
+    let mut position = 12;
+    while u32_at(position) != 1 {
+        position += 12 + u64_at(position + 4) as usize;
+    }
+    let header = position + 12;
+    let field_size = u32_at(header) as usize;
+    u32_at(header + 4 + field_size + 24)
+}
+
+fn main() {
+    let montgomery_prime = "bn128".to_string();
+    let big_int_prime = UsefulConstants::new(&montgomery_prime).get_p().to_str_radix(10);
+    let output = std::env::temp_dir().join(format!("circom_bench_{}", std::process::id()));
+    std::fs::create_dir_all(&output).unwrap();
+    let r1cs = output.join("circuit.r1cs");
+    let runs = 3;
+    let mut results = Vec::new();
+    for (name, file) in CIRCUITS.iter() {
+        let program = compile(file);
+        let big_int = measure(runs, &program, &big_int_prime, &r1cs);
+        let montgomery = measure(runs, &program, &montgomery_prime, &r1cs);
+        assert!(big_int.r1cs == montgomery.r1cs, "The r1cs of {} depends on the representation", name);
+        results.push((name, number_of_constraints(&montgomery.r1cs), big_int, montgomery));
+    }
+    std::fs::remove_dir_all(&output).unwrap();
+    println!(
+        "{:<22} {:>11} {:>12} {:>12} {:>12} {:>12} {:>8}",
+        "circuit", "constraints", "BigInt", "Montgomery", "BigInt mem", "Mont. mem", "speedup"
+    );
+    for (name, constraints, big_int, montgomery) in results {
+        println!(
+            "{:<22} {:>11} {:>10.1}ms {:>10.1}ms {:>10}KB {:>10}KB {:>7.2}x",
+            name,
+            constraints,
+            big_int.time.as_secs_f64() * 1000.0,
+            montgomery.time.as_secs_f64() * 1000.0,
+            big_int.peak_memory / 1000,
+            montgomery.peak_memory / 1000,
+            big_int.time.as_secs_f64() / montgomery.time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+pragma circom 2.0.0;
+
+// Chain of Poseidon hashes of two inputs with the structure of circomlib:
+// width 3, 8 full rounds, 57 partial rounds and x^5 as S-box. The round
+// constants and the Cauchy MDS matrix are computed by functions instead of
+// being read from the reference tables, the constraints have the same shape.
+
+function roundConstant(r, j) {
+    var x = 3 * r + j + 1;
+    return x ** 5 + 0x9e3779b97f4a7c15;
+}
+
+function mds(i, j) {
+    return 1 / (i + j + 3);
+}
+
+template Pow5() {
+    signal input in;
+    signal output out;
+    signal in2;
+    signal in4;
+    in2 <== in * in;
+    in4 <== in2 * in2;
+    out <== in4 * in;
+}
+
+template Poseidon(nInputs) {
+    var t = nInputs + 1;
+    var nRoundsF = 8;
+    var nRoundsP = 57;
+    signal input inputs[nInputs];
+    signal output out;
+
+    component full[nRoundsF][t];
+    component partial[nRoundsP];
+    var state[t];
+    state[0] = 0;
+    for (var j = 0; j < nInputs; j++) {
+        state[j + 1] = inputs[j];
+    }
+    for (var r = 0; r < nRoundsF + nRoundsP; r++) {
+        for (var j = 0; j < t; j++) {
+            state[j] += roundConstant(r, j);
+        }
+        if (r < nRoundsF / 2 || r >= nRoundsF / 2 + nRoundsP) {
+            var f = r < nRoundsF / 2 ? r : r - nRoundsP;
+            for (var j = 0; j < t; j++) {
+                full[f][j] = Pow5();
+                full[f][j].in <== state[j];
+                state[j] = full[f][j].out;
+            }
+        } else {
+            partial[r - nRoundsF / 2] = Pow5();
+            partial[r - nRoundsF / 2].in <== state[0];
+            state[0] = partial[r - nRoundsF / 2].out;
+        }
+        var mixed[t];
+        for (var i = 0; i < t; i++) {
+            mixed[i] = 0;
+            for (var j = 0; j < t; j++) {
+                mixed[i] += mds(i, j) * state[j];
+            }
+        }
+        for (var i = 0; i < t; i++) {
+            state[i] = mixed[i];
+        }
+    }
+    out <== state[0];
+}
+
+template PoseidonChain(n) {
+    signal input in[n];
+    signal output out;
+    component hashes[n];
+    for (var i = 0; i < n; i++) {
+        hashes[i] = Poseidon(2);
+        if (i == 0) {
+            hashes[i].inputs[0] <== 0;
+        } else {
+            hashes[i].inputs[0] <== hashes[i - 1].out;
+        }
+        hashes[i].inputs[1] <== in[i];
+    }
+    out <== hashes[n - 1].out;
+}
+
+component main = PoseidonChain(100);
@@ -0,0 +1,210 @@
+pragma circom 2.0.0;
+
+// Compression of one block of SHA-256 with the construction of circomlib:
+// the words are arrays of 32 bits, least significant bit first, and the
+// rotations and shifts only select bits
+
+function sha256K(t) {
+    var k[64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+    ];
+    return k[t];
+}
+
+function sha256H(j) {
+    var h[8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
+    ];
+    return h[j];
+}
+
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var lc = 0;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        lc += out[i] * (1 << i);
+    }
+    lc === in;
+}
+
+// Sum of ops words and the constant k modulo 2^32, ops + 1 < 8
+template Sum32(ops, k) {
+    signal input in[ops][32];
+    signal output out[32];
+    signal carry[3];
+    var lin = k;
+    for (var j = 0; j < ops; j++) {
+        for (var i = 0; i < 32; i++) {
+            lin += in[j][i] * (1 << i);
+        }
+    }
+    var lout = 0;
+    for (var i = 0; i < 32; i++) {
+        out[i] <-- (lin >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        lout += out[i] * (1 << i);
+    }
+    for (var i = 0; i < 3; i++) {
+        carry[i] <-- (lin >> (32 + i)) & 1;
+        carry[i] * (carry[i] - 1) === 0;
+        lout += carry[i] * (1 << (32 + i));
+    }
+    lin === lout;
+}
+
+template Xor3() {
+    signal input a[32];
+    signal input b[32];
+    signal input c[32];
+    signal output out[32];
+    signal mid[32];
+    for (var i = 0; i < 32; i++) {
+        mid[i] <== b[i] * c[i];
+        out[i] <== a[i] * (1 - 2 * b[i] - 2 * c[i] + 4 * mid[i]) + b[i] + c[i] - 2 * mid[i];
+    }
+}
+
+// rotr(x, ra) ^ rotr(x, rb) ^ rotr(x, rc), or shr(x, rc) in the last
+// term when shift is 1
+template Sigma(ra, rb, rc, shift) {
+    signal input in[32];
+    signal output out[32];
+    component xor = Xor3();
+    for (var i = 0; i < 32; i++) {
+        xor.a[i] <== in[(i + ra) % 32];
+        xor.b[i] <== in[(i + rb) % 32];
+        if (shift == 0) {
+            xor.c[i] <== in[(i + rc) % 32];
+        } else if (i + rc < 32) {
+            xor.c[i] <== in[i + rc];
+        } else {
+            xor.c[i] <== 0;
+        }
+    }
+    out <== xor.out;
+}
+
+template Ch() {
+    signal input e[32];
+    signal input f[32];
+    signal input g[32];
+    signal output out[32];
+    for (var i = 0; i < 32; i++) {
+        out[i] <== e[i] * (f[i] - g[i]) + g[i];
+    }
+}
+
+template Maj() {
+    signal input a[32];
+    signal input b[32];
+    signal input c[32];
+    signal output out[32];
+    signal mid[32];
+    for (var i = 0; i < 32; i++) {
+        mid[i] <== b[i] * c[i];
+        out[i] <== a[i] * (b[i] + c[i] - 2 * mid[i]) + mid[i];
+    }
+}
+
+// Hash of a block of 16 words that is already padded
+template Sha256Block() {
+    signal input in[16];
+    signal output out[8];
+
+    signal w[64][32];
+    component words[16];
+    for (var t = 0; t < 16; t++) {
+        words[t] = Num2Bits(32);
+        words[t].in <== in[t];
+        w[t] <== words[t].out;
+    }
+    component sigma0[48];
+    component sigma1[48];
+    component schedule[48];
+    for (var t = 16; t < 64; t++) {
+        sigma0[t - 16] = Sigma(7, 18, 3, 1);
+        sigma1[t - 16] = Sigma(17, 19, 10, 1);
+        schedule[t - 16] = Sum32(4, 0);
+        sigma0[t - 16].in <== w[t - 15];
+        sigma1[t - 16].in <== w[t - 2];
+        schedule[t - 16].in[0] <== sigma1[t - 16].out;
+        schedule[t - 16].in[1] <== w[t - 7];
+        schedule[t - 16].in[2] <== sigma0[t - 16].out;
+        schedule[t - 16].in[3] <== w[t - 16];
+        w[t] <== schedule[t - 16].out;
+    }
+
+    // a, b, c, d, e, f, g, h before each round
+    signal state[65][8][32];
+    for (var j = 0; j < 8; j++) {
+        for (var i = 0; i < 32; i++) {
+            state[0][j][i] <== (sha256H(j) >> i) & 1;
+        }
+    }
+    component bigSigma0[64];
+    component bigSigma1[64];
+    component ch[64];
+    component maj[64];
+    component t1[64];
+    component nextA[64];
+    component nextE[64];
+    for (var t = 0; t < 64; t++) {
+        bigSigma1[t] = Sigma(6, 11, 25, 0);
+        bigSigma1[t].in <== state[t][4];
+        ch[t] = Ch();
+        ch[t].e <== state[t][4];
+        ch[t].f <== state[t][5];
+        ch[t].g <== state[t][6];
+        t1[t] = Sum32(4, sha256K(t));
+        t1[t].in[0] <== state[t][7];
+        t1[t].in[1] <== bigSigma1[t].out;
+        t1[t].in[2] <== ch[t].out;
+        t1[t].in[3] <== w[t];
+
+        bigSigma0[t] = Sigma(2, 13, 22, 0);
+        bigSigma0[t].in <== state[t][0];
+        maj[t] = Maj();
+        maj[t].a <== state[t][0];
+        maj[t].b <== state[t][1];
+        maj[t].c <== state[t][2];
+        nextA[t] = Sum32(3, 0);
+        nextA[t].in[0] <== t1[t].out;
+        nextA[t].in[1] <== bigSigma0[t].out;
+        nextA[t].in[2] <== maj[t].out;
+        nextE[t] = Sum32(2, 0);
+        nextE[t].in[0] <== state[t][3];
+        nextE[t].in[1] <== t1[t].out;
+
+        state[t + 1][0] <== nextA[t].out;
+        state[t + 1][1] <== state[t][0];
+        state[t + 1][2] <== state[t][1];
+        state[t + 1][3] <== state[t][2];
+        state[t + 1][4] <== nextE[t].out;
+        state[t + 1][5] <== state[t][4];
+        state[t + 1][6] <== state[t][5];
+        state[t + 1][7] <== state[t][6];
+    }
+
+    component digest[8];
+    for (var j = 0; j < 8; j++) {
+        digest[j] = Sum32(1, sha256H(j));
+        digest[j].in[0] <== state[64][j];
+        var lc = 0;
+        for (var i = 0; i < 32; i++) {
+            lc += digest[j].out[i] * (1 << i);
+        }
+        out[j] <== lc;
+    }
+}
+
+component main = Sha256Block();
@@ -148,6 +148,38 @@ pub fn perform_tag_propagation_bus(tag_data: &mut BusTagInfo, assigned_tags: &Ta
 }
 
 
+// Assignment to the range [start, start + length) of the dimension indexed
+// by the last value of array_access, where start is that value
+pub fn perform_signal_slice_assignment(
+    signal_slice: &mut SignalSlice, 
+    array_access: &[SliceCapacity], 
+    slice_lengths: &[Option<SliceCapacity>],
+    new_route: &[SliceCapacity],
+    conditions_assignment: &AssignmentState
+)-> Result<(), MemoryError>{
+    let element_route = check_slice_route(slice_lengths, new_route)?;
+    for element_access in SignalSlice::get_range_accesses(array_access, slice_lengths) {
+        perform_signal_assignment(signal_slice, &element_access, element_route, conditions_assignment)?;
+    }
+    Ok(())
+}
+
+// The route assigned to a slice starts with the lengths of the slices, the
+// rest of the route is assigned to each element of the slices
+pub fn check_slice_route<'a>(
+    slice_lengths: &[Option<SliceCapacity>],
+    new_route: &'a [SliceCapacity],
+) -> Result<&'a [SliceCapacity], MemoryError> {
+    let mut dim = 0;
+    for length in slice_lengths.iter().flatten() {
+        if new_route.get(dim) != Some(length) {
+            return Err(MemoryError::MismatchedDimensions(new_route.get(dim).copied().unwrap_or(0), *length));
+        }
+        dim += 1;
+    }
+    Ok(&new_route[dim..])
+}
+
 pub fn perform_signal_assignment(
     signal_slice: &mut SignalSlice, 
     array_access: &[SliceCapacity], 
@@ -313,7 +313,11 @@ impl ComponentRepresentation {
             } else{*/
                 // case signals
                 // We access to the selected signal if it is an array
-                let accessed_slice_result = SignalSlice::access_values(signal_slice, &remaining_access.array_access);
+                let accessed_slice_result = SignalSlice::access_values_ranges(
+                    signal_slice,
+                    &remaining_access.array_access,
+                    &remaining_access.slice_lengths,
+                );
                 match accessed_slice_result{
                     Ok(slice) =>{
                         let folded_slice = FoldedResult::Signal(slice);
@@ -19,13 +19,14 @@ use crate::execution_data::type_definitions::TagWire;
 use crate::ast::Meta;
 use std::collections::BTreeMap;
 use crate::environment_utils::slice_types::BigInt;
+use circom_algebra::field_element::FieldElement;
 
 
 pub type ExecutionEnvironmentError = CircomEnvironmentError;
-pub type ExecutionEnvironment = CircomEnvironment<ComponentSlice, (SignalTagInfo, SignalSlice), (TagInfo, AExpressionSlice), (BusTagInfo, BusSlice)>;
+pub type ExecutionEnvironment<F = BigInt> = CircomEnvironment<ComponentSlice, (SignalTagInfo, SignalSlice), (TagInfo, AExpressionSlice<F>), (BusTagInfo, BusSlice)>;
 
-pub fn environment_shortcut_add_component(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_component<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     component_name: &str,
     dimensions: &[SliceCapacity],
 ) {
@@ -33,8 +34,8 @@ pub fn environment_shortcut_add_component(
     environment.add_component(component_name, slice);
 }
 
-pub fn environment_shortcut_add_input(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_input<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     input_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagInfo,
@@ -54,8 +55,8 @@ pub fn environment_shortcut_add_input(
 
     environment.add_input(input_name, (tag_info,  slice));
 }
-pub fn environment_shortcut_add_output(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_output<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     output_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagInfo,
@@ -74,8 +75,8 @@ pub fn environment_shortcut_add_output(
     };
     environment.add_output(output_name, (tag_info, slice));
 }
-pub fn environment_shortcut_add_intermediate(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_intermediate<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     intermediate_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagInfo,
@@ -94,8 +95,8 @@ pub fn environment_shortcut_add_intermediate(
     };
     environment.add_intermediate(intermediate_name, (tag_info, slice));
 }
-pub fn environment_shortcut_add_bus_input(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_bus_input<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     input_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagWire,
@@ -130,8 +131,8 @@ pub fn environment_shortcut_add_bus_input(
     
     environment.add_input_bus(input_name, (generate_tags_data(tags),  slice));
 }
-pub fn environment_shortcut_add_bus_output(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_bus_output<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     output_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagInfo,
@@ -152,8 +153,8 @@ pub fn environment_shortcut_add_bus_output(
     };
     environment.add_output_bus(output_name, (tag_info, slice));
 }
-pub fn environment_shortcut_add_bus_intermediate(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_bus_intermediate<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     intermediate_name: &str,
     dimensions: &[SliceCapacity],
     tags: &TagInfo,
@@ -175,8 +176,8 @@ pub fn environment_shortcut_add_bus_intermediate(
     };
     environment.add_intermediate_bus(intermediate_name, (tag_info, slice));
 }
-pub fn environment_shortcut_add_variable(
-    environment: &mut ExecutionEnvironment,
+pub fn environment_shortcut_add_variable<F: FieldElement>(
+    environment: &mut ExecutionEnvironment<F>,
     variable_name: &str,
     dimensions: &[SliceCapacity],
 ) {
@@ -184,7 +185,7 @@ pub fn environment_shortcut_add_variable(
     environment.add_variable(variable_name, (TagInfo::new(), slice));
 }
 
-pub fn environment_check_all_components_assigned(environment: &ExecutionEnvironment)-> Result<(), (MemoryError, Meta)>{
+pub fn environment_check_all_components_assigned<F: FieldElement>(environment: &ExecutionEnvironment<F>)-> Result<(), (MemoryError, Meta)>{
     use program_structure::memory_slice::MemorySlice;
     for (name, slice) in environment.get_components_ref(){
         for i in 0..MemorySlice::get_number_of_cells(slice){
@@ -198,7 +199,7 @@ pub fn environment_check_all_components_assigned(environment: &ExecutionEnvironm
 }
 
 
-pub fn environment_get_value_tags_signal(environment: &ExecutionEnvironment, name: &String) -> Vec<(Vec<String>, BigInt)>{
+pub fn environment_get_value_tags_signal<F: FieldElement>(environment: &ExecutionEnvironment<F>, name: &String) -> Vec<(Vec<String>, BigInt)>{
     let mut to_add = Vec::new();
     let (tag_data, _) = environment.get_signal(name).unwrap();
     for (tag, value) in &tag_data.tags{
@@ -212,7 +213,7 @@ pub fn environment_get_value_tags_signal(environment: &ExecutionEnvironment, nam
     to_add
 }
 
-pub fn environment_get_value_tags_bus(environment: &ExecutionEnvironment, name: &String) -> Vec<(Vec<String>, BigInt)>{
+pub fn environment_get_value_tags_bus<F: FieldElement>(environment: &ExecutionEnvironment<F>, name: &String) -> Vec<(Vec<String>, BigInt)>{
     fn get_value_tags_data(tag_data: &BusTagInfo, name: &Vec<String>)-> Vec<(Vec<String>, BigInt)>{
         let mut  to_add = Vec::new();
         for (tag, value) in &tag_data.tags{
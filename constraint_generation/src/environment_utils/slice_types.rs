@@ -44,7 +44,7 @@ pub enum AssignmentState {
 }
 
 
-pub type AExpressionSlice = MemorySlice<ArithmeticExpression<String>>;
+pub type AExpressionSlice<F = BigInt> = MemorySlice<ArithmeticExpression<String, F>>;
 // The boolean is true if the signal contains a value
 pub type SignalSlice = MemorySlice<AssignmentState>;
 pub type ComponentSlice = MemorySlice<ComponentRepresentation>;
@@ -29,11 +29,15 @@ use super::execution_data::type_definitions::{AccessingInformationBus, Accessing
 use super::{
     ast::*, ArithmeticError, FileID, ProgramArchive, Report, ReportCode, ReportCollection
 };
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_bigint::BigInt;
 use std::collections::{HashMap, BTreeMap};
 use crate::FlagsExecution;
-type AExpr = ArithmeticExpressionGen<String>;
+type AExpr<F> = ArithmeticExpressionGen<String, F>;
 type AnonymousComponentsInfo = BTreeMap<String, (Meta, Vec<Expression>)>;
+// Value returned, whether the statement was executed and value of the condition
+type ConditionalResult<F> = (Option<FoldedValue<F>>, bool, Option<bool>);
+type IndexingResult<F> = (Vec<AExpr<F>>, Option<String>, usize);
 
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 enum BlockType {
@@ -41,22 +45,22 @@ enum BlockType {
     Unknown,
 }
 
-struct RuntimeInformation {
+struct RuntimeInformation<F> {
     pub block_type: BlockType,
     pub conditions_state: Vec<(usize, bool)>,
     pub unknown_counter: usize,
-    pub analysis: Analysis,
+    pub analysis: Analysis<F>,
     pub public_inputs: Vec<String>,
     pub constants: UsefulConstants,
     pub call_trace: Vec<String>,
     pub current_file: FileID,
     pub runtime_errors: ReportCollection,
-    pub environment: ExecutionEnvironment,
+    pub environment: ExecutionEnvironment<F>,
     pub exec_program: ExecutedProgram,
     pub anonymous_components: AnonymousComponentsInfo,
 }
-impl RuntimeInformation {
-    pub fn new(current_file: FileID, id_max: usize, prime: &String) -> RuntimeInformation {
+impl<F: FieldElement> RuntimeInformation<F> {
+    pub fn new(current_file: FileID, id_max: usize, prime: &String) -> RuntimeInformation<F> {
         RuntimeInformation {
             current_file,
             block_type: BlockType::Known,
@@ -74,38 +78,38 @@ impl RuntimeInformation {
     }
 }
 
-struct FoldedValue {
-    pub arithmetic_slice: Option<AExpressionSlice>,
+struct FoldedValue<F> {
+    pub arithmetic_slice: Option<AExpressionSlice<F>>,
     pub bus_slice: Option<(String, BusSlice)>, // stores the name of the bus and the value
     pub node_pointer: Option<NodePointer>,
     pub bus_node_pointer: Option<NodePointer>,
     pub is_parallel: Option<bool>,
     pub tags: Option<TagWire>,
 }
-impl FoldedValue {
-    pub fn valid_arithmetic_slice(f_value: &FoldedValue) -> bool {
+impl<F: FieldElement> FoldedValue<F> {
+    pub fn valid_arithmetic_slice(f_value: &FoldedValue<F>) -> bool {
         f_value.arithmetic_slice.is_some() && f_value.node_pointer.is_none() && 
             f_value.is_parallel.is_none() && f_value.bus_node_pointer.is_none()
             && f_value.bus_slice.is_none()
     }
-    pub fn valid_bus_slice(f_value: &FoldedValue) -> bool {
+    pub fn valid_bus_slice(f_value: &FoldedValue<F>) -> bool {
         f_value.bus_slice.is_some() && f_value.node_pointer.is_none() && 
             f_value.is_parallel.is_none() && f_value.bus_node_pointer.is_none()
             && f_value.arithmetic_slice.is_none()
     }
-    pub fn valid_node_pointer(f_value: &FoldedValue) -> bool {
+    pub fn valid_node_pointer(f_value: &FoldedValue<F>) -> bool {
         f_value.node_pointer.is_some() && f_value.is_parallel.is_some() &&
             f_value.arithmetic_slice.is_none() && f_value.bus_node_pointer.is_none()
             && f_value.bus_slice.is_none()
     }
-    pub fn valid_bus_node_pointer(f_value: &FoldedValue) -> bool{
+    pub fn valid_bus_node_pointer(f_value: &FoldedValue<F>) -> bool{
         f_value.bus_node_pointer.is_some() && f_value.node_pointer.is_none() && 
             f_value.is_parallel.is_none() && f_value.arithmetic_slice.is_none()
             && f_value.bus_slice.is_none()
     }
 }
 
-impl Default for FoldedValue {
+impl<F> Default for FoldedValue<F> {
     fn default() -> Self {
         FoldedValue { 
             arithmetic_slice: Option::None, 
@@ -125,8 +129,11 @@ enum ExecutionError {
     TagAssignmentInUnknown,
     UnknownTemplate,
     NonValidTagAssignment,
-    FalseAssert,
-    ArraySizeTooBig
+    FalseAssert(Option<String>),
+    ArraySizeTooBig,
+    UnknownSliceLength,
+    VaryingSliceLength,
+    EmptySlice,
 }
 
 enum ExecutionWarning {
@@ -136,13 +143,23 @@ enum ExecutionWarning {
 
 
 
+// The program is executed with the Montgomery elements of the prime when it
+// is one of the named ones, and with BigInt for the custom primes
 pub fn constraint_execution(
     program_archive: &ProgramArchive,
     flags: FlagsExecution, 
     prime: &String,
+) -> Result<(ExecutedProgram, ReportCollection), ReportCollection> {
+    circom_algebra::with_field_element!(prime.as_str(), F => execute_program::<F>(program_archive, flags, prime))
+}
+
+fn execute_program<F: FieldElement>(
+    program_archive: &ProgramArchive,
+    flags: FlagsExecution, 
+    prime: &String,
 ) -> Result<(ExecutedProgram, ReportCollection), ReportCollection> {    
     let main_file_id = program_archive.get_file_id_main();
-    let mut runtime_information = RuntimeInformation::new(*main_file_id, program_archive.id_max, prime);
+    let mut runtime_information = RuntimeInformation::<F>::new(*main_file_id, program_archive.id_max, prime);
     use Expression::Call;
 
     runtime_information.public_inputs = program_archive.get_public_inputs_main_component().clone();
@@ -203,14 +220,45 @@ pub fn execute_constant_expression(
     }
 }
 
+// Same as execute_constant_expression, for expressions whose value can also
+// be an array: returns its dimensions and the values of its elements
+pub fn execute_constant_array_expression(
+    expression: &Expression,
+    program_archive: &ProgramArchive,
+    environment: ExecutionEnvironment,
+    flags: FlagsExecution,
+    prime: &String,
+) -> Result<(Vec<SliceCapacity>, Vec<BigInt>), ReportCollection> {
+    let current_file = expression.get_meta().get_file_id();
+    let mut runtime_information = RuntimeInformation::new(current_file, program_archive.id_max, prime);
+    runtime_information.environment = environment;
+    let folded_value_result =
+        execute_expression(expression, program_archive, &mut runtime_information, flags);
+    match folded_value_result {
+        Result::Err(_) => Result::Err(runtime_information.runtime_errors),
+        Result::Ok(folded_value) => {
+            let (route, values) = safe_unwrap_to_arithmetic_slice(folded_value, line!()).destruct();
+            let mut numbers = Vec::new();
+            for value in values {
+                if let AExpr::Number { value } = value {
+                    numbers.push(value);
+                } else {
+                    unreachable!();
+                }
+            }
+            Result::Ok((route, numbers))
+        }
+    }
+}
+
 // returns the value and if it can be simplified
-fn execute_statement(
+fn execute_statement<F: FieldElement>(
     stmt: &Statement,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut Option<ExecutedTemplate>,
     flags: FlagsExecution,
-) -> Result<(Option<FoldedValue>, bool), ()> {
+) -> Result<(Option<FoldedValue<F>>, bool), ()> {
     use Statement::*;
     let id = stmt.get_meta().elem_id;
     Analysis::reached(&mut runtime.analysis, id);
@@ -265,7 +313,7 @@ fn execute_statement(
                         &runtime.call_trace,
                     )?;
                     let usable_dimensions =
-                        if let Option::Some(dimensions) = cast_indexing(&arithmetic_values) {
+                        if let Option::Some(dimensions) = cast_indexing(&arithmetic_values, runtime.constants.get_p()) {
                             dimensions
                         } else {
                             let err = Result::Err(ExecutionError::ArraySizeTooBig);
@@ -435,7 +483,7 @@ fn execute_statement(
                                 let symbol = signal_left;
                                 let expr = AExpr::sub(&symbol, &value_right, &p);
                                 let ctr = AExpr::transform_expression_to_constraint_form(expr, &p).unwrap();
-                                node.add_constraint(ctr);
+                                node.add_constraint(ctr.convert_field(&p), meta);
                             }
                         } else if let AssignOp::AssignSignal = op {// needs fix, check case arrays
                             //debug_assert!(possible_constraint.is_some());
@@ -623,7 +671,7 @@ fn execute_statement(
                 )
                 .unwrap();
                 if let Option::Some(node) = actual_node {
-                    node.add_constraint(constraint_expression);
+                    node.add_constraint(constraint_expression.convert_field(runtime.constants.get_p()), meta);
                 }    
             }
             Option::None
@@ -730,12 +778,19 @@ fn execute_statement(
             }
             Option::None
         }
-        Assert { arg, meta, .. } => {
+        Assert { arg, meta, message } => {
             let f_result = execute_expression(arg, program_archive, runtime, flags)?;
             let arith = safe_unwrap_to_single_arithmetic_expression(f_result, line!());
             let possible_bool = AExpr::get_boolean_equivalence(&arith, runtime.constants.get_p());
             let result = match possible_bool {
-                Some(b) if !b => Err(ExecutionError::FalseAssert),
+                Some(b) if !b => {
+                    let message = if message.is_empty() {
+                        Option::None
+                    } else {
+                        Option::Some(execute_assert_message(message, program_archive, runtime, flags)?)
+                    };
+                    Err(ExecutionError::FalseAssert(message))
+                },
                 Some(b) if b => Ok(None),
                 _ => {
                     can_be_simplified = false;
@@ -828,10 +883,10 @@ fn execute_statement(
     Result::Ok((res, can_be_simplified))
 }
 
-fn execute_bus_statement(
+fn execute_bus_statement<F: FieldElement>(
     stmt: &Statement,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut ExecutedBus,
     flags: FlagsExecution
 )-> Result<(), ()>{
@@ -864,7 +919,7 @@ fn execute_bus_statement(
                 &runtime.call_trace,
             )?;
             let usable_dimensions =
-            if let Option::Some(dimensions) = cast_indexing(&arithmetic_values) {
+            if let Option::Some(dimensions) = cast_indexing(&arithmetic_values, runtime.constants.get_p()) {
                 dimensions
             } else {
                 let err = Result::Err(ExecutionError::ArraySizeTooBig);
@@ -920,17 +975,43 @@ fn execute_bus_statement(
     Result::Ok(())
 }
 
-fn execute_expression(
+// The message of a false assert, with the values that are not known at this
+// point printed as Unknown
+fn execute_assert_message<F: FieldElement>(
+    message: &[LogArgument],
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation<F>,
+    flags: FlagsExecution
+) -> Result<String, ()> {
+    let mut text = String::new();
+    for arglog in message {
+        match arglog {
+            LogArgument::LogExp(arg) => {
+                let f_result = execute_expression(arg, program_archive, runtime, flags)?;
+                let arith = safe_unwrap_to_single_arithmetic_expression(f_result, line!());
+                if AExpr::is_number(&arith) {
+                    text.push_str(&arith.to_string());
+                } else {
+                    text.push_str("Unknown");
+                }
+            }
+            LogArgument::LogStr(s) => text.push_str(s),
+        }
+    }
+    Result::Ok(text)
+}
+
+fn execute_expression<F: FieldElement>(
     expr: &Expression,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     use Expression::*;
     let mut can_be_simplified = true;
     let res = match expr {
         Number(_, value) => {
-            let a_value = AExpr::Number { value: value.clone() };
+            let a_value = AExpr::Number { value: F::from_bigint(value, runtime.constants.get_p()) };
             let ae_slice = AExpressionSlice::new(&a_value);
             FoldedValue { arithmetic_slice: Option::Some(ae_slice), ..FoldedValue::default() }
         }
@@ -983,7 +1064,7 @@ fn execute_expression(
         UniformArray { meta, value, dimension, .. } => {
             let f_dimension = execute_expression(dimension, program_archive, runtime, flags)?;
             let arithmetic_dimension = safe_unwrap_to_single_arithmetic_expression(f_dimension, line!());
-            let usable_dimension = if let Option::Some(dimension) = cast_index(&arithmetic_dimension) {
+            let usable_dimension = if let Option::Some(dimension) = cast_index(&arithmetic_dimension, runtime.constants.get_p()) {
                 dimension
             } else {
                 unreachable!()
@@ -1054,6 +1135,10 @@ fn execute_expression(
                     execute_expression(if_false, program_archive, runtime, flags)?
                 }
             } else {
+                // the branches are computed by the witness calculator, which
+                // needs the lengths of their slices
+                treat_unexecuted_slice_lengths(if_true, program_archive, runtime, flags)?;
+                treat_unexecuted_slice_lengths(if_false, program_archive, runtime, flags)?;
                 let arithmetic_slice = Option::Some(AExpressionSlice::new(&AExpr::NonQuadratic));
                 FoldedValue { arithmetic_slice, ..FoldedValue::default() }
             }
@@ -1090,14 +1175,14 @@ fn execute_expression(
 
 //************************************************* Statement execution support *************************************************
 
-fn execute_call(
+fn execute_call<F: FieldElement>(
     id: &String,
     meta: &Meta,
     args: &Vec<Expression>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution,
-) -> Result<(FoldedValue, bool), ()> {
+) -> Result<(FoldedValue<F>, bool), ()> {
     let mut arg_values = Vec::new();
 
     let is_template = program_archive.contains_template(id);
@@ -1143,14 +1228,14 @@ fn execute_call(
     }
 }
 
-fn execute_template_call_complete(
+fn execute_template_call_complete<F: FieldElement>(
     id: &String,
-    arg_values: Vec<AExpressionSlice>,
+    arg_values: Vec<AExpressionSlice<F>>,
     tags: HashMap<String, TagWire>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution,
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     if program_archive.contains_template(id){ // in this case we execute
         let new_environment = prepare_environment_for_call(id, &arg_values, program_archive);
         let previous_environment = std::mem::replace(&mut runtime.environment, new_environment);
@@ -1174,11 +1259,11 @@ fn execute_template_call_complete(
     }
 }
 
-fn execute_component_declaration(
+fn execute_component_declaration<F: FieldElement>(
     component_name: &str,
     dimensions: &[SliceCapacity],
     is_anonymous: bool,
-    environment: &mut ExecutionEnvironment,
+    environment: &mut ExecutionEnvironment<F>,
     actual_node: &mut Option<ExecutedTemplate>,
 ) {
     if let Option::Some(node) = actual_node {
@@ -1189,24 +1274,24 @@ fn execute_component_declaration(
     }
 }
 
-fn execute_anonymous_component_declaration(
+fn execute_anonymous_component_declaration<F: FieldElement>(
     component_name: &str,
     meta: Meta,
     dimensions: &Vec<Expression>,
-    environment: &mut ExecutionEnvironment,
+    environment: &mut ExecutionEnvironment<F>,
     anonymous_components: &mut AnonymousComponentsInfo,
 ) {
     environment_shortcut_add_component(environment, component_name, &Vec::new());
     anonymous_components.insert(component_name.to_string(), (meta, dimensions.clone()));
 }
 
-fn execute_bus_call_complete(
+fn execute_bus_call_complete<F: FieldElement>(
     id: &String,
     args: &Vec<Expression>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution,
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     let mut arg_values = Vec::new();
     
     for arg_expression in args.iter() {
@@ -1237,12 +1322,12 @@ fn execute_bus_call_complete(
     }
 }
 
-fn execute_signal_declaration(
+fn execute_signal_declaration<F: FieldElement>(
     signal_name: &str,
     dimensions: &[SliceCapacity],
     list_tags: &Vec<String>,
     signal_type: SignalType,
-    environment: &mut ExecutionEnvironment,
+    environment: &mut ExecutionEnvironment<F>,
     actual_node: &mut Option<ExecutedTemplate>,
 ) {
     use SignalType::*;
@@ -1274,11 +1359,11 @@ fn execute_signal_declaration(
     }
 }
 
-fn execute_declaration_bus(
+fn execute_declaration_bus<F: FieldElement>(
     signal_name: &str,
     dimensions: &[SliceCapacity],
     list_tags: &Vec<String>,
-    environment: &mut ExecutionEnvironment,
+    environment: &mut ExecutionEnvironment<F>,
     actual_node: &mut ExecutedBus,
     is_bus: bool,
 ) {
@@ -1297,12 +1382,12 @@ fn execute_declaration_bus(
     }
 }
 
-fn execute_bus_declaration(
+fn execute_bus_declaration<F: FieldElement>(
     bus_name: &str,
     dimensions: &[SliceCapacity],
     list_tags: &Vec<String>,
     signal_type: SignalType,
-    environment: &mut ExecutionEnvironment,
+    environment: &mut ExecutionEnvironment<F>,
     actual_node: &mut Option<ExecutedTemplate>,
 ) {
     use SignalType::*;
@@ -1350,9 +1435,9 @@ enum ExecutedStructure<'a>{
     None
 }
 
-struct Constrained {
-    left: AExpressionSlice,
-    right: AExpressionSlice,
+struct Constrained<F> {
+    left: AExpressionSlice<F>,
+    right: AExpressionSlice<F>,
 }
 
 struct TypesAccess{
@@ -1361,17 +1446,17 @@ struct TypesAccess{
 }
 
 
-fn perform_assign(
+fn perform_assign<F: FieldElement>(
     meta: &Meta,
     symbol: &str,
     op: AssignOp,
     accessing_information: &TypesAccess,
-    r_folded: FoldedValue,
+    r_folded: FoldedValue<F>,
     actual_node: &mut ExecutedStructure,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     program_archive: &ProgramArchive,
     flags: FlagsExecution
-) -> Result<Option<Constrained>, ()> {
+) -> Result<Option<Constrained<F>>, ()> {
     use super::execution_data::type_definitions::{SubComponentData, BusData};
 
     let full_symbol = if accessing_information.bus_access.is_some(){
@@ -1422,12 +1507,22 @@ fn perform_assign(
             *symbol_tags = TagInfo::new();
         } else {
 
-            let memory_result = AExpressionSlice::insert_values(
-                symbol_content,
-                &accessing_information.before_signal,
-                &r_slice,
-                false
-            );
+            let memory_result = if is_slice_access(&accessing_information.slice_lengths) {
+                AExpressionSlice::insert_values_ranges(
+                    symbol_content,
+                    &accessing_information.before_signal,
+                    &accessing_information.slice_lengths,
+                    &r_slice,
+                    false
+                )
+            } else {
+                AExpressionSlice::insert_values(
+                    symbol_content,
+                    &accessing_information.before_signal,
+                    &r_slice,
+                    false
+                )
+            };
             treat_result_with_memory_error_void(
                 memory_result,
                 meta,
@@ -1486,7 +1581,7 @@ fn perform_assign(
         let arithmetic_slice = r_folded.arithmetic_slice.unwrap();
         let value_aux = AExpressionSlice::unwrap_to_single(arithmetic_slice);
         let value = if let ArithmeticExpressionGen::Number { value } = value_aux {
-            value
+            value.to_bigint(runtime.constants.get_p())
         } else {
             treat_result_with_execution_error(
                 Result::Err(ExecutionError::NonValidTagAssignment),
@@ -1560,7 +1655,11 @@ fn perform_assign(
         reference_to_tags.is_init = true;
 
         // Perform the signal assignment
-        let signal_assignment_response = perform_signal_assignment(reference_to_signal_content, &accessing_information.before_signal, &r_slice.route(), &conditions_assignment);
+        let signal_assignment_response = if is_slice_access(&accessing_information.slice_lengths) {
+            perform_signal_slice_assignment(reference_to_signal_content, &accessing_information.before_signal, &accessing_information.slice_lengths, &r_slice.route(), &conditions_assignment)
+        } else {
+            perform_signal_assignment(reference_to_signal_content, &accessing_information.before_signal, &r_slice.route(), &conditions_assignment)
+        };
         
         treat_result_with_memory_error_void(
             signal_assignment_response,
@@ -1571,7 +1670,7 @@ fn perform_assign(
 
         // Get left arithmetic slice
         let mut l_signal_names = Vec::new();
-        unfold_signals(full_symbol, 0, r_slice.route(), &mut l_signal_names);
+        unfold_accessed_signals(symbol, accessing_information, r_slice.route(), &mut l_signal_names);
         let mut l_expressions = Vec::new();
         for signal_name in l_signal_names{
             l_expressions.push(AExpr::Signal { symbol: signal_name });
@@ -1644,7 +1743,7 @@ fn perform_assign(
                 let inputs_tags = component.inputs_tags.clone();
                 let result = execute_template_call_complete(
                     pretemplate_info.template_name(),
-                    pretemplate_info.parameter_instances().clone(),
+                    pretemplate_info.parameter_instances().iter().map(|value| convert_slice(value, runtime.constants.get_p())).collect(),
                     inputs_tags,
                     program_archive,
                     runtime,
@@ -1725,7 +1824,26 @@ fn perform_assign(
                         TagWire::default()
                     };
                         
-                    let memory_response = if remaining_access.field_access.is_none(){
+                    let memory_response = if is_slice_access(&remaining_access.slice_lengths) {
+                        // each element of the slice is assigned as a signal of the component
+                        check_slice_route(&remaining_access.slice_lengths, arithmetic_slice.route()).and_then(|element_route| {
+                            let element_accesses = SignalSlice::get_range_accesses(
+                                &remaining_access.array_access,
+                                &remaining_access.slice_lengths,
+                            );
+                            for element_access in element_accesses {
+                                ComponentRepresentation::assign_value_to_signal(
+                                    component,
+                                    &signal_accessed,
+                                    &element_access,
+                                    element_route,
+                                    &tags,
+                                    &conditions_assignment
+                                )?;
+                            }
+                            Ok(())
+                        })
+                    } else if remaining_access.field_access.is_none(){
                         ComponentRepresentation::assign_value_to_signal(
                             component,
                             &signal_accessed,
@@ -1754,7 +1872,7 @@ fn perform_assign(
 
                     // Get left arithmetic slice
                     let mut l_signal_names = Vec::new();
-                    unfold_signals(full_symbol, 0, arithmetic_slice.route(), &mut l_signal_names);
+                    unfold_accessed_bus_signals(symbol, accessing_information, arithmetic_slice.route(), &mut l_signal_names);
                     let mut l_expressions = Vec::new();
                     for signal_name in l_signal_names{
                         l_expressions.push(AExpr::Signal { symbol: signal_name });
@@ -1877,7 +1995,7 @@ fn perform_assign(
     
                     let folded_result = execute_template_call_complete(
                         pretemplate_info.template_name(),
-                        pretemplate_info.parameter_instances().clone(),
+                        pretemplate_info.parameter_instances().iter().map(|value| convert_slice(value, runtime.constants.get_p())).collect(),
                         inputs_tags,
                         program_archive,
                         runtime,
@@ -2168,7 +2286,7 @@ fn perform_assign(
                 let arithmetic_slice = r_folded.arithmetic_slice.unwrap();
                 let value_aux = AExpressionSlice::unwrap_to_single(arithmetic_slice);
                 let value = if let ArithmeticExpressionGen::Number { value } = value_aux {
-                    value
+                    value.to_bigint(runtime.constants.get_p())
                 } else {
                     treat_result_with_execution_error(
                         Result::Err(ExecutionError::NonValidTagAssignment),
@@ -2427,15 +2545,15 @@ fn perform_assign(
 
 
 // Evaluates the given condition and executes the corresponding statement. Returns a tuple (a,b) where a is the possible value returned and b is the value of the condition (in case the evaluation was successful)
-fn execute_conditional_statement(
+fn execute_conditional_statement<F: FieldElement>(
     condition: &Expression,
     true_case: &Statement,
     false_case: Option<&Statement>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut Option<ExecutedTemplate>,
     flags: FlagsExecution,
-) -> Result<(Option<FoldedValue>, bool, Option<bool>), ()> {
+) -> Result<ConditionalResult<F>, ()> {
     let f_cond = execute_expression(condition, program_archive, runtime, flags)?;
     let ae_cond = safe_unwrap_to_single_arithmetic_expression(f_cond, line!());
     let possible_cond_bool_value =
@@ -2502,14 +2620,14 @@ fn execute_conditional_statement(
     }
 }
 
-fn execute_sequence_of_statements(
+fn execute_sequence_of_statements<F: FieldElement>(
     stmts: &[Statement],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut Option<ExecutedTemplate>,
     flags: FlagsExecution,
     is_complete_template: bool
-) -> Result<(Option<FoldedValue>, bool), ()> {
+) -> Result<(Option<FoldedValue<F>>, bool), ()> {
     let mut can_be_simplified = true;
     for stmt in stmts.iter() {
         let (f_value, can_simplify) = execute_statement(stmt, program_archive, runtime, actual_node, flags)?;
@@ -2524,10 +2642,10 @@ fn execute_sequence_of_statements(
     Result::Ok((Option::None, can_be_simplified))
 }
 
-fn execute_sequence_of_bus_statements(
+fn execute_sequence_of_bus_statements<F: FieldElement>(
     stmts: &[Statement],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut ExecutedBus,
     flags: FlagsExecution,
 ) -> Result<(), ()> {
@@ -2537,9 +2655,9 @@ fn execute_sequence_of_bus_statements(
     Result::Ok(())
 }
 
-fn execute_delayed_declarations(
+fn execute_delayed_declarations<F: FieldElement>(
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     actual_node: &mut Option<ExecutedTemplate>,
     flags: FlagsExecution,
 )-> Result<(), ()> {
@@ -2557,7 +2675,7 @@ fn execute_delayed_declarations(
             &runtime.call_trace,
         )?;
         let usable_dimensions =
-            if let Option::Some(dimensions) = cast_indexing(&arithmetic_values) {
+            if let Option::Some(dimensions) = cast_indexing(&arithmetic_values, runtime.constants.get_p()) {
                 dimensions
             } else {
                 let err = Result::Err(ExecutionError::ArraySizeTooBig);
@@ -2634,14 +2752,14 @@ fn create_index_appendix(indexing: &[usize]) -> String {
 //     } 
 // }
 
-fn execute_variable(
+fn execute_variable<F: FieldElement>(
     meta: &Meta,
     symbol: &str,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     let access_information = treat_accessing(meta, access, program_archive, runtime, flags)?;
     if access_information.undefined {
         let arithmetic_slice = Option::Some(AExpressionSlice::new(&AExpr::NonQuadratic));
@@ -2657,7 +2775,11 @@ fn execute_variable(
         &mut runtime.runtime_errors,
         &runtime.call_trace,
     )?;
-    let memory_response = AExpressionSlice::access_values(&ae_slice, &indexing);
+    let memory_response = if is_slice_access(&access_information.slice_lengths) {
+        AExpressionSlice::access_values_ranges(&ae_slice, &indexing, &access_information.slice_lengths)
+    } else {
+        AExpressionSlice::access_values(&ae_slice, &indexing)
+    };
     let ae_slice = treat_result_with_memory_error(
         memory_response,
         meta,
@@ -2671,14 +2793,14 @@ fn execute_variable(
     Result::Ok(FoldedValue { arithmetic_slice: Option::Some(ae_slice), tags: Option::Some(tags), ..FoldedValue::default() })
 }
 
-fn execute_signal(
+fn execute_signal<F: FieldElement>(
     meta: &Meta,
     symbol: &str,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     let access_information = treat_accessing(meta, access, program_archive, runtime, flags)?;
     if access_information.undefined {
         let arithmetic_slice = Option::Some(AExpressionSlice::new(&AExpr::NonQuadratic));
@@ -2708,7 +2830,7 @@ fn execute_signal(
             if let Some(value_tag) = value_tag { // tag has value
                 // access only allowed when (1) it is value defined by user or (2) it is completely assigned
                 if state.value_defined || tag_data.remaining_inserts == 0{
-                    let a_value = AExpr::Number { value: value_tag.clone() };
+                    let a_value = AExpr::Number { value: F::from_bigint(value_tag, runtime.constants.get_p()) };
                     let ae_slice = AExpressionSlice::new(&a_value);
                     Result::Ok(FoldedValue { arithmetic_slice: Option::Some(ae_slice), ..FoldedValue::default() })
                 } else{
@@ -2736,15 +2858,20 @@ fn execute_signal(
              unreachable!() 
         }
     } else {
-        let memory_response = SignalSlice::access_values(signal_slice, indexing);
+        let memory_response = if is_slice_access(&access_information.slice_lengths) {
+            SignalSlice::access_values_ranges(signal_slice, indexing, &access_information.slice_lengths)
+        } else {
+            SignalSlice::access_values(signal_slice, indexing)
+        };
         let signal_slice = treat_result_with_memory_error(
             memory_response,
             meta,
             &mut runtime.runtime_errors,
             &runtime.call_trace,
         )?;
-        let full_symbol = create_symbol(symbol, &access_information);
-        let signal_access = signal_to_arith(full_symbol, signal_slice);
+        let mut symbols = vec![];
+        unfold_accessed_signals(symbol, &access_information, signal_slice.route(), &mut symbols);
+        let signal_access = signals_to_arith(symbols, signal_slice);
         let arith_slice = treat_result_with_memory_error(
             signal_access,
             meta,
@@ -2766,11 +2893,15 @@ fn execute_signal(
     }
 }
 
-fn signal_to_arith(symbol: String, slice: SignalSlice) -> Result<AExpressionSlice, MemoryError> {
+fn signal_to_arith<F: FieldElement>(symbol: String, slice: SignalSlice) -> Result<AExpressionSlice<F>, MemoryError> {
+    let mut symbols = vec![];
+    unfold_signals(symbol, 0, slice.route(), &mut symbols);
+    signals_to_arith(symbols, slice)
+}
+
+fn signals_to_arith<F: FieldElement>(symbols: Vec<String>, slice: SignalSlice) -> Result<AExpressionSlice<F>, MemoryError> {
     let mut expressions = vec![];
     let (route, values) = slice.destruct();
-    let mut symbols = vec![];
-    unfold_signals(symbol, 0, &route, &mut symbols);
     let mut index = 0;
     while index < symbols.len(){
         match values[index]{
@@ -2792,6 +2923,62 @@ fn signal_to_arith(symbol: String, slice: SignalSlice) -> Result<AExpressionSlic
     // TODO: in case inspect return warning in case might assigned?
 }
 
+fn is_slice_access(slice_lengths: &[Option<SliceCapacity>]) -> bool {
+    slice_lengths.iter().any(Option::is_some)
+}
+
+// Names of the signals accessed by symbol with access_information, where
+// route is the route of the accessed slice
+fn unfold_accessed_signals(
+    symbol: &str,
+    access_information: &AccessingInformation,
+    route: &[usize],
+    result: &mut Vec<String>,
+) {
+    if is_slice_access(&access_information.slice_lengths) {
+        let number_of_slices = access_information.slice_lengths.iter().flatten().count();
+        let mut element = access_information.clone();
+        let element_accesses = SignalSlice::get_range_accesses(
+            &access_information.before_signal,
+            &access_information.slice_lengths,
+        );
+        for element_access in element_accesses {
+            element.before_signal = element_access;
+            unfold_signals(create_symbol(symbol, &element), 0, &route[number_of_slices..], result);
+        }
+    } else {
+        unfold_signals(create_symbol(symbol, access_information), 0, route, result);
+    }
+}
+
+// Names of the signals accessed by symbol with the accesses of a component,
+// where route is the route of the accessed slice. Only the accesses after the
+// signal of the component can be slices
+fn unfold_accessed_bus_signals(
+    symbol: &str,
+    access_information: &AccessingInformationBus,
+    route: &[usize],
+    result: &mut Vec<String>,
+) {
+    let remaining_access = access_information.remaining_access.as_ref();
+    if let Option::Some(remaining_access) = remaining_access.filter(|access| is_slice_access(&access.slice_lengths)) {
+        let number_of_slices = remaining_access.slice_lengths.iter().flatten().count();
+        let mut signal_access = access_information.clone();
+        signal_access.remaining_access.as_mut().unwrap().array_access = Vec::new();
+        let signal_symbol = create_symbol_bus(symbol, &signal_access);
+        let element_accesses = SignalSlice::get_range_accesses(
+            &remaining_access.array_access,
+            &remaining_access.slice_lengths,
+        );
+        for element_access in element_accesses {
+            let element_symbol = format!("{}{}", signal_symbol, create_index_appendix(&element_access));
+            unfold_signals(element_symbol, 0, &route[number_of_slices..], result);
+        }
+    } else {
+        unfold_signals(create_symbol_bus(symbol, access_information), 0, route, result);
+    }
+}
+
 fn unfold_signals(current: String, dim: usize, lengths: &[usize], result: &mut Vec<String>) {
     if dim == lengths.len() {
         result.push(current);
@@ -2802,14 +2989,14 @@ fn unfold_signals(current: String, dim: usize, lengths: &[usize], result: &mut V
     }
 }
 
-fn execute_bus(
+fn execute_bus<F: FieldElement>(
     meta: &Meta,
     symbol: &str,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     let access_information = treat_accessing_bus(meta, access, program_archive, runtime, flags)?;
     
     let is_tag = match meta.get_type_knowledge().get_reduces_to(){
@@ -2888,7 +3075,7 @@ fn execute_bus(
         if let Some(value_tag) = value_tag { // tag has value
             // access only allowed when (1) it is value defined by user or (2) it is completely assigned
             if state.value_defined || is_complete{
-                let a_value = AExpr::Number { value: value_tag.clone() };
+                let a_value = AExpr::Number { value: F::from_bigint(value_tag, runtime.constants.get_p()) };
                 let ae_slice = AExpressionSlice::new(&a_value);
                 Result::Ok(FoldedValue { arithmetic_slice: Option::Some(ae_slice), ..FoldedValue::default() })
             } else{
@@ -2991,14 +3178,14 @@ fn execute_bus(
 
 }
 
-fn execute_component(
+fn execute_component<F: FieldElement>(
     meta: &Meta,
     symbol: &str,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
         
     let access_information = treat_accessing_bus(meta, access, program_archive, runtime, flags)?;
     if access_information.undefined {
@@ -3029,6 +3216,7 @@ fn execute_component(
     
     if let Option::Some(signal_name) = &access_information.field_access {
         let remaining_access = access_information.remaining_access.as_ref().unwrap();
+        let component_symbol = symbol;
         let symbol = create_symbol_bus(symbol, &access_information);
 
         if meta.get_type_knowledge().is_tag(){
@@ -3039,7 +3227,7 @@ fn execute_component(
                 &mut runtime.runtime_errors,
                 &runtime.call_trace,
             )?;
-            let a_value = AExpr::Number { value: result };
+            let a_value = AExpr::Number { value: F::from_bigint(&result, runtime.constants.get_p()) };
             let ae_slice = AExpressionSlice::new(&a_value);
             Result::Ok(FoldedValue { arithmetic_slice: Option::Some(ae_slice), ..FoldedValue::default() })
 
@@ -3054,7 +3242,9 @@ fn execute_component(
             
             match result{
                 FoldedResult::Signal(signals) =>{
-                    let result = signal_to_arith(symbol, signals)
+                    let mut symbols = vec![];
+                    unfold_accessed_bus_signals(&component_symbol, &access_information, signals.route(), &mut symbols);
+                    let result = signals_to_arith(symbols, signals)
                         .map(|s| FoldedValue { 
                             arithmetic_slice: Option::Some(s),
                             tags: Option::Some(tags),
@@ -3122,11 +3312,11 @@ fn execute_component(
     
 }
 
-fn prepare_environment_for_call(
+fn prepare_environment_for_call<F: FieldElement>(
     id: &str,
-    arg_values: &[AExpressionSlice],
+    arg_values: &[AExpressionSlice<F>],
     program_archive: &ProgramArchive,
-) -> ExecutionEnvironment {
+) -> ExecutionEnvironment<F> {
     let functions = program_archive.get_function_names();
     let templates = program_archive.get_template_names();
 
@@ -3147,12 +3337,12 @@ fn prepare_environment_for_call(
     environment
 }
 
-fn execute_function_call(
+fn execute_function_call<F: FieldElement>(
     id: &str,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<(FoldedValue, bool), ()> {
+) -> Result<(FoldedValue<F>, bool), ()> {
     use std::mem;
     let previous_block = runtime.block_type;
     let previous_conditions = mem::replace(&mut runtime.conditions_state, vec![]);
@@ -3169,14 +3359,14 @@ fn execute_function_call(
 
 
 
-fn execute_template_call(
+fn execute_template_call<F: FieldElement>(
     id: &str,
-    parameter_values: Vec<AExpressionSlice>,
+    parameter_values: Vec<AExpressionSlice<F>>,
     tag_values: HashMap<String, TagWire>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     debug_assert!(runtime.block_type == BlockType::Known);
     let is_main = std::mem::replace(&mut runtime.public_inputs, vec![]);
     let is_parallel = program_archive.get_template_data(id).is_parallel();
@@ -3190,7 +3380,7 @@ fn execute_template_call(
     for (name, value) in args_names.iter().zip(parameter_values) {
         instantiation_name.push_str(&format!("{},", value.to_string()));
         not_empty_name = true;
-        args_to_values.insert(name.clone(), value.clone());
+        args_to_values.insert(name.clone(), convert_slice(&value, runtime.constants.get_p()));
     }
     for (_input, input_tags) in &tag_values{
         // TODO: does not got inside bus
@@ -3268,18 +3458,19 @@ fn execute_template_call(
         
 
         let analysis = std::mem::replace(&mut runtime.analysis, analysis);
+        let analysis = analysis.into_big_int(runtime.constants.get_p());
         let node_pointer = runtime.exec_program.add_node_to_scheme(new_node, analysis);
         node_pointer
     };
     Result::Ok(FoldedValue { node_pointer: Option::Some(node_pointer), is_parallel: Option::Some(false), ..FoldedValue::default() })
 }
 
-fn preexecute_template_call(
+fn preexecute_template_call<F: FieldElement>(
     id: &str,
-    parameter_values: &[AExpressionSlice],
+    parameter_values: &[AExpressionSlice<F>],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
-) -> Result<FoldedValue, ()> {
+    runtime: &mut RuntimeInformation<F>,
+) -> Result<FoldedValue<F>, ()> {
     
     pub fn collect_tag_info(
         bus_data: &BusData, 
@@ -3361,7 +3552,7 @@ fn preexecute_template_call(
 
     let node_wrap = Option::Some(PreExecutedTemplate::new(
         id.to_string(),
-        parameter_values.to_vec(),
+        parameter_values.iter().map(|value| convert_slice(value, runtime.constants.get_p())).collect(),
         inputs_to_tags,
         outputs_to_tags,
     ));
@@ -3371,13 +3562,13 @@ fn preexecute_template_call(
     Result::Ok(FoldedValue { node_pointer: Option::Some(node_pointer), is_parallel: Option::Some(false), ..FoldedValue::default() })
 }
 
-fn execute_bus_call(
+fn execute_bus_call<F: FieldElement>(
     id: &str,
-    parameter_values: Vec<AExpressionSlice>,
+    parameter_values: Vec<AExpressionSlice<F>>,
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution,
-) -> Result<FoldedValue, ()> {
+) -> Result<FoldedValue<F>, ()> {
     debug_assert!(runtime.block_type == BlockType::Known);
    
     let args_names = program_archive.get_bus_data(id).get_name_of_params();
@@ -3390,7 +3581,7 @@ fn execute_bus_call(
     for (name, value) in args_names.iter().zip(parameter_values) {
         instantiation_name.push_str(&format!("{},", value.to_string()));
         not_empty_name = true;
-        args_to_values.insert(name.clone(), value.clone());
+        args_to_values.insert(name.clone(), convert_slice(&value, runtime.constants.get_p()));
     }
 
     if not_empty_name  {
@@ -3418,19 +3609,20 @@ fn execute_bus_call(
         )?;
 
         let analysis = std::mem::replace(&mut runtime.analysis, analysis);
+        let analysis = analysis.into_big_int(runtime.constants.get_p());
         let node_pointer = runtime.exec_program.add_bus_node_to_scheme(node, analysis);
         node_pointer
     };
     Result::Ok(FoldedValue { bus_node_pointer: Option::Some(node_pointer), ..FoldedValue::default() })
 }
 
-fn execute_infix_op(
+fn execute_infix_op<F: FieldElement>(
     meta: &Meta,
     infix: ExpressionInfixOpcode,
-    l_value: &AExpr,
-    r_value: &AExpr,
-    runtime: &mut RuntimeInformation,
-) -> Result<AExpr, ()> {
+    l_value: &AExpr<F>,
+    r_value: &AExpr<F>,
+    runtime: &mut RuntimeInformation<F>,
+) -> Result<AExpr<F>, ()> {
     use ExpressionInfixOpcode::*;
     let field = runtime.constants.get_p();
     let possible_result = match infix {
@@ -3463,11 +3655,11 @@ fn execute_infix_op(
     )
 }
 
-fn execute_prefix_op(
+fn execute_prefix_op<F: FieldElement>(
     prefix_op: ExpressionPrefixOpcode,
-    value: &AExpr,
-    runtime: &mut RuntimeInformation,
-) -> Result<AExpr, ()> {
+    value: &AExpr<F>,
+    runtime: &mut RuntimeInformation<F>,
+) -> Result<AExpr<F>, ()> {
     use ExpressionPrefixOpcode::*;
     let field = runtime.constants.get_p();
     let result = match prefix_op {
@@ -3486,13 +3678,13 @@ Returns (A,B,C) where:
     B = possible signal accessed
     C = index where the signal is accessed, C == access.len() if there is none
 */
-fn treat_indexing(
+fn treat_indexing<F: FieldElement>(
     start: usize,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
-) -> Result<(Vec<AExpr>, Option<String>, usize), ()> {
+) -> Result<IndexingResult<F>, ()> {
     let mut index_accesses = Vec::new();
     let mut signal_name = Option::None;
     let mut act = start;
@@ -3508,6 +3700,14 @@ fn treat_indexing(
                 index_accesses.push(index_arithmetic_expression);
                 act += 1;
             }
+            Access::ArraySlice(start, _) => {
+                // the length of the slice is treated in treat_accessing
+                let start_fold = execute_expression(start, program_archive, runtime, flags)?;
+                let start_arithmetic_expression =
+                    safe_unwrap_to_single_arithmetic_expression(start_fold, line!());
+                index_accesses.push(start_arithmetic_expression);
+                act += 1;
+            }
             Access::ComponentAccess(name) => {
                 signal_name = Option::Some(name.clone());
                 break;
@@ -3521,16 +3721,16 @@ fn treat_indexing(
     ae_indexes are a valid indexing when
     all Number values fit in usize
 */
-fn valid_indexing(ae_indexes: &[AExpr]) -> Result<(), MemoryError> {
+fn valid_indexing<F: FieldElement>(ae_indexes: &[AExpr<F>], field: &BigInt) -> Result<(), MemoryError> {
     for ae_index in ae_indexes {
-        if ae_index.is_number() && AExpr::get_usize(ae_index).is_none() {
+        if ae_index.is_number() && AExpr::get_usize(ae_index, field).is_none() {
             return Result::Err(MemoryError::OutOfBoundsError);
         }
     }
     Result::Ok(())
 }
 
-fn valid_array_declaration(ae_indexes: &[AExpr]) -> Result<(), MemoryError> {
+fn valid_array_declaration<F: FieldElement>(ae_indexes: &[AExpr<F>]) -> Result<(), MemoryError> {
     for ae_index in ae_indexes {
         if !ae_index.is_number() {
             return Result::Err(MemoryError::UnknownSizeDimension);
@@ -3545,13 +3745,13 @@ fn valid_array_declaration(ae_indexes: &[AExpr]) -> Result<(), MemoryError> {
     if valid_indexing does not return
     Result::Err(..)
 */
-fn cast_indexing(ae_indexes: &[AExpr]) -> Option<Vec<SliceCapacity>> {
+fn cast_indexing<F: FieldElement>(ae_indexes: &[AExpr<F>], field: &BigInt) -> Option<Vec<SliceCapacity>> {
     let mut sc_indexes = Vec::new();
     for ae_index in ae_indexes.iter() {
         if !ae_index.is_number() {
             return Option::None;
         }
-        match AExpr::get_usize(ae_index) {
+        match AExpr::get_usize(ae_index, field) {
             Some(index) => { sc_indexes.push(index); },
             None => { return Option::None; },
         }
@@ -3559,21 +3759,21 @@ fn cast_indexing(ae_indexes: &[AExpr]) -> Option<Vec<SliceCapacity>> {
     Option::Some(sc_indexes)
 }
 
-fn cast_index(ae_index: &AExpr) -> Option<SliceCapacity> {
+fn cast_index<F: FieldElement>(ae_index: &AExpr<F>, field: &BigInt) -> Option<SliceCapacity> {
     if !ae_index.is_number() {
         return Option::None;
     }
-    match AExpr::get_usize(ae_index) {
+    match AExpr::get_usize(ae_index, field) {
         Option::Some(index) => { Option::Some(index) },
         Option::None => {  Option::None },
     }
 }
 
-fn treat_accessing(
+fn treat_accessing<F: FieldElement>(
     meta: &Meta,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
 ) -> Result<AccessingInformation, ()> {
     let (ae_before_signal, signal_name, signal_index) =
@@ -3581,47 +3781,134 @@ fn treat_accessing(
     let (ae_after_signal, tag_name , _tag_index) =
         treat_indexing(signal_index + 1, access, program_archive, runtime, flags)?;
     treat_result_with_memory_error(
-        valid_indexing(&ae_before_signal),
+        valid_indexing(&ae_before_signal, runtime.constants.get_p()),
         meta,
         &mut runtime.runtime_errors,
         &runtime.call_trace,
     )?;
     treat_result_with_memory_error(
-        valid_indexing(&ae_after_signal),
+        valid_indexing(&ae_after_signal, runtime.constants.get_p()),
         meta,
         &mut runtime.runtime_errors,
         &runtime.call_trace,
     )?;
 
-    let possible_before_indexing = cast_indexing(&ae_before_signal);
-    let possible_after_indexing = cast_indexing(&ae_after_signal);
+    let slice_lengths = treat_slice_lengths(meta, access, program_archive, runtime, flags)?;
+
+    let possible_before_indexing = cast_indexing(&ae_before_signal, runtime.constants.get_p());
+    let possible_after_indexing = cast_indexing(&ae_after_signal, runtime.constants.get_p());
 
     let undefined = possible_before_indexing.is_none() || possible_after_indexing.is_none();
     let signal_access = signal_name;
     let tag_access = tag_name;
-    let (before_signal, after_signal) = if !undefined {
-        (possible_before_indexing.unwrap(), possible_after_indexing.unwrap())
+    let (before_signal, after_signal, slice_lengths) = if !undefined {
+        (possible_before_indexing.unwrap(), possible_after_indexing.unwrap(), slice_lengths)
     } else {
-        (Vec::new(), Vec::new())
+        (Vec::new(), Vec::new(), Vec::new())
     };
-    Result::Ok(AccessingInformation { undefined, before_signal, after_signal, signal_access, tag_access})
+    Result::Ok(AccessingInformation { undefined, before_signal, after_signal, signal_access, tag_access, slice_lengths})
 }
 
+// Length of each slice in the accesses before the first component access,
+// None for the indexes
+fn treat_slice_lengths<F: FieldElement>(
+    meta: &Meta,
+    access: &[Access],
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation<F>,
+    flags: FlagsExecution
+) -> Result<Vec<Option<SliceCapacity>>, ()> {
+    let mut slice_lengths = Vec::new();
+    for acc in access {
+        match acc {
+            Access::ArrayAccess(_) => slice_lengths.push(Option::None),
+            Access::ArraySlice(_, length) => {
+                let length = treat_slice_length(meta, length, program_archive, runtime, flags)?;
+                slice_lengths.push(Option::Some(length));
+            }
+            Access::ComponentAccess(_) => break,
+        }
+    }
+    Result::Ok(slice_lengths)
+}
 
-fn treat_accessing_bus(
+/*
+    The length of a slice must be known and the same in every execution of
+    the template, so that the slice can be copied as a single block
+*/
+fn treat_slice_length<F: FieldElement>(
+    meta: &Meta,
+    length: &Expression,
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation<F>,
+    flags: FlagsExecution
+) -> Result<SliceCapacity, ()> {
+    let length_fold = execute_expression(length, program_archive, runtime, flags)?;
+    let ae_length = safe_unwrap_to_single_arithmetic_expression(length_fold, line!());
+    let result = match cast_index(&ae_length, runtime.constants.get_p()) {
+        Option::Some(0) => Result::Err(ExecutionError::EmptySlice),
+        Option::Some(_) if !Analysis::is_computed(&runtime.analysis, length.get_meta().elem_id) => {
+            Result::Err(ExecutionError::VaryingSliceLength)
+        }
+        Option::Some(value) => Result::Ok(value),
+        Option::None if ae_length.is_number() => Result::Err(ExecutionError::EmptySlice),
+        Option::None => Result::Err(ExecutionError::UnknownSliceLength),
+    };
+    treat_result_with_execution_error(result, meta, &mut runtime.runtime_errors, &runtime.call_trace)
+}
+
+
+// Computes the lengths of the slices of an expression that is not executed
+fn treat_unexecuted_slice_lengths<F: FieldElement>(
+    expr: &Expression,
+    program_archive: &ProgramArchive,
+    runtime: &mut RuntimeInformation<F>,
+    flags: FlagsExecution
+) -> Result<(), ()> {
+    use Expression::*;
+    let subexpressions: Vec<&Expression> = match expr {
+        Variable { meta, access, .. } => {
+            let mut subexpressions = Vec::new();
+            for acc in access {
+                match acc {
+                    Access::ArrayAccess(index) => subexpressions.push(index),
+                    Access::ArraySlice(start, length) => {
+                        treat_slice_length(meta, length, program_archive, runtime, flags)?;
+                        subexpressions.push(start);
+                    }
+                    Access::ComponentAccess(_) => {}
+                }
+            }
+            subexpressions
+        }
+        InfixOp { lhe, rhe, .. } => vec![lhe, rhe],
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => vec![rhe],
+        InlineSwitchOp { cond, if_true, if_false, .. } => vec![cond, if_true, if_false],
+        Call { args, .. } | BusCall { args, .. } => args.iter().collect(),
+        ArrayInLine { values, .. } => values.iter().collect(),
+        UniformArray { value, dimension, .. } => vec![value, dimension],
+        _ => Vec::new(),
+    };
+    for subexpression in subexpressions {
+        treat_unexecuted_slice_lengths(subexpression, program_archive, runtime, flags)?;
+    }
+    Result::Ok(())
+}
+
+fn treat_accessing_bus<F: FieldElement>(
     meta: &Meta,
     access: &[Access],
     program_archive: &ProgramArchive,
-    runtime: &mut RuntimeInformation,
+    runtime: &mut RuntimeInformation<F>,
     flags: FlagsExecution
 ) -> Result<AccessingInformationBus, ()> {
 
-    fn treat_accessing_bus_index(
+    fn treat_accessing_bus_index<F: FieldElement>(
         index: usize,
         meta: &Meta,
         access: &[Access],
         program_archive: &ProgramArchive,
-        runtime: &mut RuntimeInformation,
+        runtime: &mut RuntimeInformation<F>,
         flags: FlagsExecution
     ) -> Result<AccessingInformationBus, ()>{
         
@@ -3629,12 +3916,14 @@ fn treat_accessing_bus(
             treat_indexing(index, access, program_archive, runtime, flags)?;
         
         treat_result_with_memory_error(
-            valid_indexing(&ae_before_signal),
+            valid_indexing(&ae_before_signal, runtime.constants.get_p()),
             meta,
             &mut runtime.runtime_errors,
             &runtime.call_trace,
         )?;
 
+        let slice_lengths = treat_slice_lengths(meta, &access[index..], program_archive, runtime, flags)?;
+
         let mut remaining_access = if signal_index < access.len(){
             Some(Box::new(
                 treat_accessing_bus_index(signal_index + 1, meta, access, program_archive, runtime, flags)?)
@@ -3643,22 +3932,22 @@ fn treat_accessing_bus(
             None
         };
 
-        let possible_before_indexing = cast_indexing(&ae_before_signal);
+        let possible_before_indexing = cast_indexing(&ae_before_signal, runtime.constants.get_p());
 
         let remaining_access_undefined = remaining_access.is_some() && remaining_access.as_ref().unwrap().undefined;
 
         let undefined = possible_before_indexing.is_none() || remaining_access_undefined;
 
-        let array_access = if undefined {
-            Vec::new()
+        let (array_access, slice_lengths) = if undefined {
+            (Vec::new(), Vec::new())
         } else {
-            possible_before_indexing.unwrap()
+            (possible_before_indexing.unwrap(), slice_lengths)
         };
         if undefined{
             remaining_access = None
         };
 
-    Result::Ok(AccessingInformationBus { undefined, array_access, remaining_access, field_access})
+    Result::Ok(AccessingInformationBus { undefined, array_access, slice_lengths, remaining_access, field_access})
 
     }
 
@@ -3677,23 +3966,30 @@ fn treat_accessing_bus(
 
 //************************************************* Safe transformations *************************************************
 
-fn safe_unwrap_to_single_arithmetic_expression(folded_value: FoldedValue, line: u32) -> AExpr {
+// Template and bus instances keep the values of their parameters as BigInt
+fn convert_slice<F: FieldElement, G: FieldElement>(slice: &AExpressionSlice<F>, field: &BigInt) -> AExpressionSlice<G> {
+    let (route, values) = slice.clone().destruct();
+    let values = values.iter().map(|value| value.convert_field(field)).collect();
+    AExpressionSlice::new_array(route, values)
+}
+
+fn safe_unwrap_to_single_arithmetic_expression<F: FieldElement>(folded_value: FoldedValue<F>, line: u32) -> AExpr<F> {
     let slice_result = safe_unwrap_to_arithmetic_slice(folded_value, line);
     safe_unwrap_to_single(slice_result, line)
 }
-fn safe_unwrap_to_arithmetic_slice(folded_value: FoldedValue, line: u32) -> AExpressionSlice {
+fn safe_unwrap_to_arithmetic_slice<F: FieldElement>(folded_value: FoldedValue<F>, line: u32) -> AExpressionSlice<F> {
     debug_assert!(FoldedValue::valid_arithmetic_slice(&folded_value), "Caused by call at {}", line);
     folded_value.arithmetic_slice.unwrap()
 }
-fn safe_unwrap_to_valid_node_pointer(folded_value: FoldedValue, line: u32) -> (NodePointer, bool) {
+fn safe_unwrap_to_valid_node_pointer<F: FieldElement>(folded_value: FoldedValue<F>, line: u32) -> (NodePointer, bool) {
     debug_assert!(FoldedValue::valid_node_pointer(&folded_value), "Caused by call at {}", line);
     (folded_value.node_pointer.unwrap(), folded_value.is_parallel.unwrap())
 }
-fn safe_unwrap_to_valid_bus_node_pointer(folded_value: FoldedValue, line: u32) -> NodePointer {
+fn safe_unwrap_to_valid_bus_node_pointer<F: FieldElement>(folded_value: FoldedValue<F>, line: u32) -> NodePointer {
     debug_assert!(FoldedValue::valid_bus_node_pointer(&folded_value), "Caused by call at {}", line);
     folded_value.bus_node_pointer.unwrap()
 }
-fn safe_unwrap_to_bus_slice(folded_value: FoldedValue, line: u32) -> (String, BusSlice) {
+fn safe_unwrap_to_bus_slice<F: FieldElement>(folded_value: FoldedValue<F>, line: u32) -> (String, BusSlice) {
     debug_assert!(FoldedValue::valid_arithmetic_slice(&folded_value), "Caused by call at {}", line);
     folded_value.bus_slice.unwrap()
 }
@@ -4059,13 +4355,28 @@ fn treat_result_with_execution_error<C>(
                     "Tags cannot be assigned to values that can be unknown during the constraint generation phase".to_string(),
                     ReportCode::RuntimeError,
                 ),
-                FalseAssert => {
+                FalseAssert(Option::None) => {
                     Report::error("False assert reached".to_string(), ReportCode::RuntimeError)
                 }
+                FalseAssert(Option::Some(message)) => {
+                    Report::error(format!("False assert reached: {}", message), ReportCode::RuntimeError)
+                }
                 ArraySizeTooBig => Report::error(
                     "The size of the array is expected to be a usize".to_string(),
                     ReportCode::RuntimeError,
                 ),
+                UnknownSliceLength => Report::error(
+                    "The bounds of a slice must define a length known during the constraint generation phase".to_string(),
+                    ReportCode::RuntimeError,
+                ),
+                VaryingSliceLength => Report::error(
+                    "The length of a slice must be the same every time the slice is executed in the template".to_string(),
+                    ReportCode::RuntimeError,
+                ),
+                EmptySlice => Report::error(
+                    "The end of a slice must be greater than its start".to_string(),
+                    ReportCode::RuntimeError,
+                ),
                 ConstraintInUnknown => Report::error(
                     "There are constraints depending on the value of a condition that can be unknown during the constraint generation phase".to_string(),
                     ReportCode::RuntimeError,
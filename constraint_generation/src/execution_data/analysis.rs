@@ -1,25 +1,27 @@
 use circom_algebra::algebra;
+use circom_algebra::field_element::FieldElement;
 use num_bigint::BigInt;
 
-type AExpr = algebra::ArithmeticExpression<String>;
+type AExpr<F> = algebra::ArithmeticExpression<String, F>;
 
-pub struct Analysis {
+// The values are kept as elements of the field used by the execution
+pub struct Analysis<F = BigInt> {
     reached: Vec<bool>,
-    computed_values: Vec<ValuePOS>,
+    computed_values: Vec<ValuePOS<F>>,
 }
-impl Analysis {
-    pub fn new(id_max: usize) -> Analysis {
+impl<F: FieldElement> Analysis<F> {
+    pub fn new(id_max: usize) -> Analysis<F> {
         Analysis { reached: vec![false; id_max], computed_values: vec![ValuePOS::Bottom; id_max] }
     }
 
-    pub fn reached(analysis: &mut Analysis, id: usize) {
+    pub fn reached(analysis: &mut Analysis<F>, id: usize) {
         analysis.reached[id] = true;
     }
-    pub fn is_reached(analysis: &Analysis, id: usize) -> bool {
+    pub fn is_reached(analysis: &Analysis<F>, id: usize) -> bool {
         analysis.reached[id]
     }
 
-    pub fn computed(analysis: &mut Analysis, id: usize, value: AExpr) {
+    pub fn computed(analysis: &mut Analysis<F>, id: usize, value: AExpr<F>) {
         if let AExpr::Number { value } = value {
             let new = ValuePOS::Val(value);
             let old = analysis.computed_values[id].clone();
@@ -29,6 +31,26 @@ impl Analysis {
         }
     }
 
+    pub fn is_computed(analysis: &Analysis<F>, id: usize) -> bool {
+        matches!(analysis.computed_values[id], ValuePOS::Val(_))
+    }
+
+    // The same analysis with the values as BigInt, as the filters use them
+    pub fn into_big_int(self, field: &BigInt) -> Analysis {
+        let computed_values = self
+            .computed_values
+            .into_iter()
+            .map(|value| match value {
+                ValuePOS::Bottom => ValuePOS::Bottom,
+                ValuePOS::Val(v) => ValuePOS::Val(v.to_bigint(field)),
+                ValuePOS::Top => ValuePOS::Top,
+            })
+            .collect();
+        Analysis { reached: self.reached, computed_values }
+    }
+}
+
+impl Analysis {
     pub fn read_computed(analysis: &Analysis, id: usize) -> Option<BigInt> {
         match &analysis.computed_values[id] {
             ValuePOS::Val(v) => Some(v.clone()),
@@ -38,14 +60,14 @@ impl Analysis {
 }
 
 #[derive(Clone)]
-enum ValuePOS {
+enum ValuePOS<F> {
     Bottom,
-    Val(BigInt),
+    Val(F),
     Top,
 }
 
-impl ValuePOS {
-    pub fn least_upper_bound(l: &ValuePOS, r: &ValuePOS) -> ValuePOS {
+impl<F: FieldElement> ValuePOS<F> {
+    pub fn least_upper_bound(l: &ValuePOS<F>, r: &ValuePOS<F>) -> ValuePOS<F> {
         use ValuePOS::*;
         match (l, r) {
             (v, Bottom) | (Bottom, v) => v.clone(),
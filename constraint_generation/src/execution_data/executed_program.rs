@@ -202,6 +202,8 @@ impl ExecutedProgram {
         if flags.inspect{
             let mut w = dag.constraint_analysis()?;
             warnings.append(&mut w);
+            let mut w = dag.range_analysis();
+            warnings.append(&mut w);
         }
 
         let dag_stats = produce_dags_stats(&dag);
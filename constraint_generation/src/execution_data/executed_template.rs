@@ -3,9 +3,9 @@ use super::type_definitions::*;
 use super::ExecutedBus;
 use circom_algebra::algebra::ArithmeticExpression;
 use compiler::hir::very_concrete_program::*;
-use dag::DAG;
+use dag::{ConstraintOrigin, DAG};
 use num_bigint::BigInt;
-use program_structure::ast::{SignalType, Statement};
+use program_structure::ast::{Meta, SignalType, Statement};
 use std::collections::{HashMap, HashSet};
 use crate::execution_data::AExpressionSlice;
 
@@ -70,6 +70,7 @@ pub struct ExecutedTemplate {
     pub intermediates: WireCollector,
     pub ordered_signals: WireCollector,
     pub constraints: Vec<Constraint>,
+    pub constraint_origins: Vec<Option<ConstraintOrigin>>,
     pub components: ComponentCollector,
     pub number_of_components: usize,
     pub public_inputs: HashSet<String>,
@@ -116,6 +117,7 @@ impl ExecutedTemplate {
             intermediates: WireCollector::new(),
             ordered_signals: WireCollector::new(),
             constraints: Vec::new(),
+            constraint_origins: Vec::new(),
             components: ComponentCollector::new(),
             number_of_components: 0,
             connexions: Vec::new(),
@@ -208,8 +210,12 @@ impl ExecutedTemplate {
         self.number_of_components += dimensions.iter().fold(1, |p, c| p * (*c));
     }
 
-    pub fn add_constraint(&mut self, constraint: Constraint) {
+    pub fn add_constraint(&mut self, constraint: Constraint, meta: &Meta) {
+        let origin = meta
+            .file_id
+            .map(|file_id| ConstraintOrigin { file_id, start: meta.get_start(), end: meta.get_end() });
         self.constraints.push(constraint);
+        self.constraint_origins.push(origin);
     }
 
     pub fn add_underscored_signal(&mut self, signal: &str) {
@@ -346,10 +352,10 @@ impl ExecutedTemplate {
     }
     fn build_constraints(&self, dag: &mut DAG) {
         
-        for c in &self.constraints {
+        for (c, origin) in self.constraints.iter().zip(&self.constraint_origins) {
             let correspondence = dag.get_main().unwrap().correspondence();
             let cc = Constraint::apply_correspondence(c, correspondence);
-            dag.add_constraint(cc);
+            dag.add_constraint(cc, *origin);
         }
         for s in &self.underscored_signals{
             let correspondence = dag.get_main().unwrap().correspondence();
@@ -419,7 +425,8 @@ impl ExecutedTemplate {
             arguments
         }
 
-        let header = format!("{}_{}", self.template_name, instances.len());
+        // Qualified names are not valid identifiers in the generated code
+        let header = format!("{}_{}", self.template_name.replace("::", "__"), instances.len());
         let clusters = build_clusters(&self, instances);
         let triggers = build_triggers(instances, self.connexions);
         let components = build_components(self.components);
@@ -96,9 +96,15 @@ pub fn apply_computed(stmt: &mut Statement, analysis: &Analysis) {
                 }
             }
         }
-        Assert { arg, .. } => {
+        Assert { arg, message, .. } => {
             *arg = computed_or_original(analysis, arg);
             apply_computed_expr(arg, analysis);
+            for arglog in message {
+                if let LogArgument::LogExp(arg) = arglog{
+                    *arg = computed_or_original(analysis, arg);
+                    apply_computed_expr(arg, analysis);
+                }
+            }
         }
         UnderscoreSubstitution {  rhe, .. } => {
             *rhe = computed_or_original(analysis, rhe);
@@ -123,6 +129,13 @@ fn apply_computed_to_access(accesses: &mut Vec<Access>, analysis: &Analysis) {
                 apply_computed_expr(&mut index, analysis);
                 accesses.push(Access::ArrayAccess(index));
             }
+            ArraySlice(mut start, mut length) => {
+                start = computed_or_original(analysis, &start);
+                apply_computed_expr(&mut start, analysis);
+                length = computed_or_original(analysis, &length);
+                apply_computed_expr(&mut length, analysis);
+                accesses.push(Access::ArraySlice(start, length));
+            }
             _ => {
                 accesses.push(acc);
             }
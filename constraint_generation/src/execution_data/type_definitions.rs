@@ -57,6 +57,7 @@ pub struct BusData {
     AccessingInformationBus {
         pub undefined: bool ===> true if one of the index values could not be transformed into a SliceCapacity during the process,
         pub array_access: Vec<SliceCapacity> 
+        pub slice_lengths: Vec<Option<SliceCapacity>> ==> length of the range of each value of array_access that starts a slice
         pub field_access: Option<String> // may not appear
         pub remaining_access: Option<AccessingInformation>, // may not appear
     }
@@ -65,6 +66,7 @@ pub struct BusData {
 pub struct AccessingInformationBus {
     pub undefined: bool,
     pub array_access: Vec<usize>,
+    pub slice_lengths: Vec<Option<usize>>,
     pub field_access: Option<String>,
     pub remaining_access: Option<Box<AccessingInformationBus>>,
 }
@@ -78,12 +80,15 @@ pub struct AccessingInformationBus {
         pub signal_access: Option<String> ==> may not appear,
         pub after_signal: Vec<SliceCapacity>
         pub tag_access: Option<String> ==> may not appear,
+        pub slice_lengths: Vec<Option<SliceCapacity>> ==> length of the range of each value of before_signal that starts a slice
     }
 */
+#[derive(Clone)]
 pub struct AccessingInformation {
     pub undefined: bool,
     pub before_signal: Vec<usize>,
     pub signal_access: Option<String>,
     pub after_signal: Vec<usize>,
-    pub tag_access: Option<String>
+    pub tag_access: Option<String>,
+    pub slice_lengths: Vec<Option<usize>>,
 }
@@ -9,10 +9,12 @@ mod assignment_utils;
 
 use ansi_term::Colour;
 use circom_algebra::algebra::{ArithmeticError, ArithmeticExpression};
+use circom_algebra::field_element::FieldElement;
+use circom_algebra::num_bigint::BigInt;
 use compiler::hir::very_concrete_program::VCP;
-use constraint_list::ConstraintList;
+use constraint_list::{Certificate, ComponentConstraints, ConstraintList};
 use constraint_writers::ConstraintExporter;
-use dag::DAG;
+use dag::{Hierarchy, HierarchyFormat, Profile, DAG};
 use execution_data::executed_program::ExportResult;
 use execution_data::ExecutedProgram;
 use program_structure::ast::{self};
@@ -33,6 +35,15 @@ pub struct BuildConfig {
     pub flag_old_heuristics: bool,
     pub inspect_constraints: bool,
     pub prime: String,
+    pub hierarchy_format: Option<HierarchyFormat>,
+    pub hierarchy_file: String,
+    pub flag_profile: bool,
+    pub profile_json: String,
+    pub profile_folded: String,
+    pub flag_plonk: bool,
+    pub plonk_file: String,
+    pub flag_certificate: bool,
+    pub certificate_file: String,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -59,8 +70,17 @@ pub fn build_circuit(program: ProgramArchive, config: BuildConfig) -> BuildRespo
     if config.inspect_constraints {
         Report::print_reports(&warnings, &files);
     }
+    let hierarchy = config.hierarchy_format.map(|_| Hierarchy::new(&dag));
+    let mut profile = if config.flag_profile { Some(Profile::new(&dag, &files)) } else { None };
     if config.flag_f {
         sync_dag_and_vcp(&mut vcp, &mut dag);
+        if let Some(hierarchy) = &hierarchy {
+            write_hierarchy(hierarchy, &config)?;
+        }
+        if let Some(profile) = &mut profile {
+            profile.count_constraints();
+            write_profile(profile, &config)?;
+        }
         if config.flag_json_sub { 
             use constraint_writers::json_writer::SubstitutionJSON;
             let substitution_log = SubstitutionJSON::new(&config.json_substitutions).unwrap();
@@ -70,12 +90,90 @@ pub fn build_circuit(program: ProgramArchive, config: BuildConfig) -> BuildRespo
 
         Result::Ok((Box::new(dag), vcp))
     } else {
-        let list = simplification_process(&mut vcp, dag, &config);
-        if config.flag_json_sub { 
-            println!("{} {}", Colour::Green.paint("Written successfully:"), config.json_substitutions);
-        };
-        Result::Ok((Box::new(list), vcp))
+        let writer = circom_algebra::with_field_element!(config.prime.as_str(), F => {
+            simplify_and_write::<F>(&mut vcp, dag, hierarchy, profile, &config)?
+        });
+        Result::Ok((writer, vcp))
+    }
+}
+
+// The constraints that remain after the simplification are kept with the
+// elements of F
+fn simplify_and_write<F: FieldElement + Sync + 'static>(
+    vcp: &mut VCP,
+    dag: DAG,
+    mut hierarchy: Option<Hierarchy>,
+    mut profile: Option<Profile>,
+    config: &BuildConfig,
+) -> Result<ConstraintWriter, ()> {
+    let components = if config.flag_certificate { Some(dag.original_constraints()) } else { None };
+    let list = simplification_process::<F>(vcp, dag, config);
+    if config.flag_json_sub { 
+        println!("{} {}", Colour::Green.paint("Written successfully:"), config.json_substitutions);
+    };
+    if let Some(hierarchy) = &mut hierarchy {
+        hierarchy.set_simplified_constraints(&list);
+        write_hierarchy(hierarchy, config)?;
+    }
+    if let Some(profile) = &mut profile {
+        profile.count_simplified_constraints(&list);
+        write_profile(profile, config)?;
+    }
+    if config.flag_plonk {
+        write_plonk(&list, config)?;
     }
+    if let Some(components) = components {
+        write_certificate(components, &list, config)?;
+    }
+    Result::Ok(Box::new(list))
+}
+
+pub struct ConstraintCount {
+    pub non_linear: usize,
+    pub linear: usize,
+}
+
+// Constraints of the circuit after the full simplification, without writing
+// any output or printing the warnings of the execution
+pub fn count_constraints(program: ProgramArchive, prime: &String) -> Result<ConstraintCount, ReportCollection> {
+    let (count, _) = instantiate(program, prime)?;
+    Result::Ok(count)
+}
+
+// Same as count_constraints, also returning the program of the circuit with
+// the witness of the simplified constraints, ready for the compiler
+pub fn instantiate(program: ProgramArchive, prime: &String) -> Result<(ConstraintCount, VCP), ReportCollection> {
+    use dag::SimplificationFlags;
+    let flags = FlagsExecution { verbose: false, inspect: false };
+    let (exe, _) = execute::constraint_execution(&program, flags, prime)?;
+    let (dag, mut vcp, _) = export(exe, program, flags)?;
+    let flags = SimplificationFlags {
+        no_rounds: usize::MAX,
+        flag_s: false,
+        parallel_flag: false,
+        port_substitution: false,
+        json_substitutions: String::new(),
+        flag_old_heuristics: false,
+        prime: prime.clone(),
+        keep_substitutions: false,
+    };
+    let list: ConstraintList = DAG::map_to_list(dag, flags);
+    VCP::add_witness_list(&mut vcp, Rc::new(list.get_witness_as_vec()));
+    let ids = list.constraints.get_ids();
+    let linear = ids.iter().filter(|c_id| list.constraints.is_linear(**c_id)).count();
+    Result::Ok((ConstraintCount { non_linear: ids.len() - linear, linear }, vcp))
+}
+
+// Value of the main expression of the program when it is a call to a
+// function: the dimensions of the result and the values of its elements
+pub fn evaluate_main_expression(
+    program: &ProgramArchive,
+    prime: &String,
+) -> Result<(Vec<usize>, Vec<BigInt>), ReportCollection> {
+    use environment_utils::environment::ExecutionEnvironment;
+    let flags = FlagsExecution { verbose: false, inspect: false };
+    let environment = ExecutionEnvironment::new();
+    execute::execute_constant_array_expression(program.get_main_expression(), program, environment, flags, prime)
 }
 
 type InstantiationResponse = Result<(ExecutedProgram, ReportCollection), ReportCollection>;
@@ -98,12 +196,87 @@ fn export(exe: ExecutedProgram, program: ProgramArchive, flags: FlagsExecution)
     exported
 }
 
+fn write_hierarchy(hierarchy: &Hierarchy, config: &BuildConfig) -> Result<(), ()> {
+    let format = config.hierarchy_format.unwrap();
+    if hierarchy.write(&config.hierarchy_file, format).is_ok() {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), config.hierarchy_file);
+        Result::Ok(())
+    } else {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        Result::Err(())
+    }
+}
+
+fn write_profile(profile: &Profile, config: &BuildConfig) -> Result<(), ()> {
+    for (result, file) in [
+        (profile.write_json(&config.profile_json), &config.profile_json),
+        (profile.write_folded(&config.profile_folded), &config.profile_folded),
+    ] {
+        if result.is_ok() {
+            println!("{} {}", Colour::Green.paint("Written successfully:"), file);
+        } else {
+            eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+            return Result::Err(());
+        }
+    }
+    Result::Ok(())
+}
+
+fn write_plonk<F: FieldElement>(list: &ConstraintList<F>, config: &BuildConfig) -> Result<(), ()> {
+    if let Result::Ok(stats) = list.plonk(&config.plonk_file) {
+        println!("{} {}", Colour::Green.paint("Written successfully:"), config.plonk_file);
+        println!("plonk gates: {}", stats.gates);
+        println!("  multiplication gates: {}", stats.multiplication_gates);
+        println!("  auxiliary wires: {}", stats.auxiliary_wires);
+        println!("  wires shared by several gates: {}", stats.shared_wires);
+        println!("  copy constraints: {}", stats.copy_constraints);
+        println!("  longest permutation cycle: {}", stats.longest_cycle);
+        Result::Ok(())
+    } else {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        Result::Err(())
+    }
+}
+
+// The certificate is checked right after it is written, an error means that
+// the simplification is wrong
+fn write_certificate<F: FieldElement>(components: Vec<ComponentConstraints>, list: &ConstraintList<F>, config: &BuildConfig) -> Result<(), ()> {
+    let constraints = list.constraints.get_ids().into_iter().map(|c_id| list.constraints.read_constraint(c_id).unwrap().convert_field(&list.field));
+    let certificate = Certificate {
+        field: list.field.clone(),
+        components,
+        substitutions: list.substitutions.clone(),
+        constraints: constraints.collect(),
+        witness: list.get_witness_as_vec(),
+    };
+    if certificate.write(&config.certificate_file).is_err() {
+        eprintln!("{}", Colour::Red.paint("Could not write the output in the given path"));
+        return Result::Err(());
+    }
+    println!("{} {}", Colour::Green.paint("Written successfully:"), config.certificate_file);
+    let check = certificate.check();
+    if check.is_valid() {
+        println!("simplification certificate checked: {} original constraints", check.checked);
+        Result::Ok(())
+    } else {
+        eprintln!(
+            "{}",
+            Colour::Red.paint(format!(
+                "The simplification certificate is not valid: {} invalid substitutions and {} original constraints not implied",
+                check.invalid_substitutions.len(),
+                check.failures.len()
+            ))
+        );
+        Result::Err(())
+    }
+}
+
 fn sync_dag_and_vcp(vcp: &mut VCP, dag: &mut DAG) {
     let witness = Rc::new(DAG::produce_witness(dag));
     VCP::add_witness_list(vcp, Rc::clone(&witness));
 }
 
-fn simplification_process(vcp: &mut VCP, dag: DAG, config: &BuildConfig) -> ConstraintList {
+fn simplification_process<F: FieldElement>(vcp: &mut VCP, dag: DAG, config: &BuildConfig) -> ConstraintList<F> {
     use dag::SimplificationFlags;
     let flags = SimplificationFlags {
         flag_s: config.flag_s,
@@ -113,8 +286,459 @@ fn simplification_process(vcp: &mut VCP, dag: DAG, config: &BuildConfig) -> Cons
         no_rounds: config.no_rounds,
         flag_old_heuristics: config.flag_old_heuristics,
         prime : config.prime.clone(),
+        keep_substitutions: config.flag_certificate,
     };
     let list = DAG::map_to_list(dag, flags);
     VCP::add_witness_list(vcp, Rc::new(list.get_witness_as_vec()));
+    if config.flag_verbose && !config.flag_s {
+        let stats = &list.non_linear_stats;
+        println!("constraints removed by the non-linear simplification: {}", stats.removed());
+        println!("  fixing a signal to a constant: {}", stats.constant_signals);
+        println!("  implied: {}", stats.implied);
+        println!("  duplicated: {}", stats.duplicated);
+        println!("  repeated boolean: {}", stats.repeated_boolean);
+        println!("  linearized: {}", stats.linearized);
+    }
     list
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_structure::constants::UsefulConstants;
+    use std::convert::TryInto;
+    use std::path::{Path, PathBuf};
+
+    #[derive(Debug, PartialEq)]
+    struct R1csSize {
+        bytes: usize,
+        constraints: u32,
+    }
+
+    fn parse(dir: &Path, name: &str, src: &str) -> ProgramArchive {
+        std::fs::create_dir_all(dir).unwrap();
+        let circuit = dir.join(format!("{}.circom", name));
+        std::fs::write(&circuit, src).unwrap();
+        let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let path = circuit.to_str().unwrap().to_string();
+        let (mut program, _) = parser::run_parser(path, "2.2.2", vec![], &prime, false, None)
+            .map_err(|_| ()).unwrap();
+        type_analysis::check_types::check_types(&mut program).map_err(|_| ()).unwrap();
+        program
+    }
+
+    fn config(dir: &Path, name: &str) -> BuildConfig {
+        // The build fails if the certificate of the simplification is not valid
+        let certificate = dir.join(format!("{}_certificate.json", name));
+        BuildConfig {
+            no_rounds: usize::MAX,
+            flag_json_sub: false,
+            json_substitutions: String::new(),
+            flag_s: false,
+            flag_f: false,
+            flag_p: false,
+            flag_verbose: false,
+            flag_old_heuristics: false,
+            inspect_constraints: false,
+            prime: "bn128".to_string(),
+            hierarchy_format: None,
+            hierarchy_file: String::new(),
+            flag_profile: false,
+            profile_json: String::new(),
+            profile_folded: String::new(),
+            flag_plonk: false,
+            plonk_file: String::new(),
+            flag_certificate: true,
+            certificate_file: certificate.to_str().unwrap().to_string(),
+        }
+    }
+
+    // Each circuit is compiled in its own folder, which is removed at the end
+    fn compile(name: &str, src: &str) -> R1csSize {
+        let dir = std::env::temp_dir().join(format!("circom_non_linear_{}_{}", name, std::process::id()));
+        let program = parse(&dir, name, src);
+        let (exporter, _) = build_circuit(program, config(&dir, name)).map_err(|_| ()).unwrap();
+        let r1cs = dir.join(format!("{}.r1cs", name));
+        exporter.r1cs(r1cs.to_str().unwrap(), false).unwrap();
+        let size = read_r1cs_size(&r1cs);
+        std::fs::remove_dir_all(&dir).unwrap();
+        size
+    }
+
+    // The header section contains the field size, the prime, the number of
+    // wires, public outputs, public inputs, private inputs, labels and constraints
+    fn read_r1cs_size(file: &PathBuf) -> R1csSize {
+        let bytes = std::fs::read(file).unwrap();
+        let u32_at = |position: usize| u32::from_le_bytes(bytes[position..position + 4].try_into().unwrap());
+        let u64_at = |position: usize| u64::from_le_bytes(bytes[position..position + 8].try_into().unwrap());
+        let mut position = 12;
+        while u32_at(position) != 1 {
+            position += 12 + u64_at(position + 4) as usize;
+        }
+        let header = position + 12;
+        let field_size = u32_at(header) as usize;
+        let constraints = u32_at(header + 4 + field_size + 24);
+        R1csSize { bytes: bytes.len(), constraints }
+    }
+
+    // With --O2 the redundant constraints of a circuit are removed, so it
+    // produces the same r1cs as the circuit without them
+    fn assert_same_r1cs(name: &str, redundant: &str, reference: &str) {
+        let redundant = compile(&format!("{}_redundant", name), redundant);
+        let reference = compile(&format!("{}_reference", name), reference);
+        assert_eq!(redundant, reference);
+    }
+
+    const NUM2BITS: &str = r#"
+        pragma circom 2.0.0;
+        template Num2Bits(n) {
+            signal input in;
+            signal output out[n];
+            var lc1 = 0;
+            var e2 = 1;
+            for (var i = 0; i < n; i++) {
+                out[i] <-- (in >> i) & 1;
+                out[i] * (out[i] - 1) === 0;
+                lc1 += out[i] * e2;
+                e2 = e2 + e2;
+            }
+            lc1 === in;
+        }
+        template AssertBit() {
+            signal input in;
+            (1 - in) * in === 0;
+        }
+    "#;
+
+    #[test]
+    fn repeated_boolean_constraints_are_removed() {
+        let redundant = format!("{}{}", NUM2BITS, r#"
+            template Main(n) {
+                signal input in;
+                signal output out[n];
+                component n2b = Num2Bits(n);
+                component check[n];
+                n2b.in <== in;
+                for (var i = 0; i < n; i++) {
+                    check[i] = AssertBit();
+                    check[i].in <== n2b.out[i];
+                    out[i] <== n2b.out[i];
+                }
+            }
+            component main = Main(8);
+        "#);
+        let reference = format!("{}{}", NUM2BITS, r#"
+            template Main(n) {
+                signal input in;
+                signal output out[n];
+                component n2b = Num2Bits(n);
+                n2b.in <== in;
+                for (var i = 0; i < n; i++) {
+                    out[i] <== n2b.out[i];
+                }
+            }
+            component main = Main(8);
+        "#);
+        assert_same_r1cs("boolean", &redundant, &reference);
+    }
+
+    const PAIRS: &str = r#"
+        pragma circom 2.2.2;
+        template Pairs() {
+            signal input in[4];
+            signal output out[3][2];
+            for (var i = 0; i < 3; i++) {
+                out[i][0] <== in[i] * in[i + 1];
+                out[i][1] <== in[i + 1] + 1;
+            }
+        }
+    "#;
+
+    #[test]
+    fn slices_access_the_same_signals_as_indexes() {
+        let sliced = format!("{}{}", PAIRS, r#"
+            template Main() {
+                signal input m[3][4];
+                signal output a[2][2];
+                signal output b[2];
+                signal output c[2][2];
+                component p = Pairs();
+                a <== m[0:2][1:3];
+                p.in[0:2] <== m[2][0:2];
+                p.in[2:4] <== m[1][2:4];
+                b <== p.out[1:3][1];
+                c[0:2][1] <== p.out[0:2][0];
+                c[0:2][0] <== [m[0][0] * m[0][1], m[1][0]];
+            }
+            component main = Main();
+        "#);
+        let indexed = format!("{}{}", PAIRS, r#"
+            template Main() {
+                signal input m[3][4];
+                signal output a[2][2];
+                signal output b[2];
+                signal output c[2][2];
+                component p = Pairs();
+                for (var i = 0; i < 2; i++) {
+                    a[i][0] <== m[i][1];
+                    a[i][1] <== m[i][2];
+                    p.in[i] <== m[2][i];
+                    p.in[i + 2] <== m[1][i + 2];
+                }
+                for (var i = 0; i < 2; i++) {
+                    b[i] <== p.out[i + 1][1];
+                    c[i][1] <== p.out[i][0];
+                }
+                c[0][0] <== m[0][0] * m[0][1];
+                c[1][0] <== m[1][0];
+            }
+            component main = Main();
+        "#);
+        assert_same_r1cs("slices", &sliced, &indexed);
+    }
+
+    const MUL: &str = r#"
+        pragma circom 2.0.0;
+        template Mul() {
+            signal input a;
+            signal input b;
+            signal output c;
+            c <== a * b;
+        }
+    "#;
+
+    #[test]
+    fn duplicated_constraints_are_removed() {
+        let redundant = format!("{}{}", MUL, r#"
+            template Main() {
+                signal input x;
+                signal input y;
+                signal output out;
+                component m1 = Mul();
+                component m2 = Mul();
+                m1.a <== x;
+                m1.b <== y;
+                m2.a <== 2 * y;
+                m2.b <== x;
+                2 * m1.c === m2.c;
+                out <== m1.c;
+            }
+            component main = Main();
+        "#);
+        let reference = format!("{}{}", MUL, r#"
+            template Main() {
+                signal input x;
+                signal input y;
+                signal output out;
+                component m1 = Mul();
+                m1.a <== x;
+                m1.b <== y;
+                out <== m1.c;
+            }
+            component main = Main();
+        "#);
+        assert_same_r1cs("duplicated", &redundant, &reference);
+    }
+
+    #[test]
+    fn squares_equal_to_zero_are_linearized() {
+        let redundant = r#"
+            pragma circom 2.0.0;
+            template Main() {
+                signal input a;
+                signal input b;
+                signal output o;
+                signal t;
+                t <-- b + 1;
+                (t - b - 1) * (t - b - 1) === 0;
+                o <== a * t;
+            }
+            component main = Main();
+        "#;
+        let reference = r#"
+            pragma circom 2.0.0;
+            template Main() {
+                signal input a;
+                signal input b;
+                signal output o;
+                signal t;
+                t <-- b + 1;
+                t - b - 1 === 0;
+                o <== a * t;
+            }
+            component main = Main();
+        "#;
+        assert_same_r1cs("linearized", redundant, reference);
+    }
+
+    #[test]
+    fn signals_fixed_to_constants_are_removed() {
+        let redundant = r#"
+            pragma circom 2.0.0;
+            template Main() {
+                signal input s;
+                signal input y;
+                signal output out;
+                signal t;
+                s * s === 0;
+                t <== s * y;
+                out <== y * y + t;
+            }
+            component main = Main();
+        "#;
+        let reference = r#"
+            pragma circom 2.0.0;
+            template Main() {
+                signal input s;
+                signal input y;
+                signal output out;
+                out <== y * y;
+            }
+            component main = Main();
+        "#;
+        assert_same_r1cs("constants", redundant, reference);
+    }
+
+    #[test]
+    fn hierarchy_counts_nested_components() {
+        let src = r#"
+            pragma circom 2.0.0;
+            template Leaf() {
+                signal input a;
+                signal output b;
+                b <== a * a;
+            }
+            template Middle(n) {
+                signal input in;
+                signal output out;
+                component leaves[n];
+                signal partial[n + 1];
+                partial[0] <== in;
+                for (var i = 0; i < n; i++) {
+                    leaves[i] = Leaf();
+                    leaves[i].a <== partial[i];
+                    partial[i + 1] <== leaves[i].b;
+                }
+                out <== partial[n];
+            }
+            template Main() {
+                signal input in;
+                signal output out;
+                component first = Middle(3);
+                component second[2];
+                first.in <== in;
+                second[0] = Middle(3);
+                second[0].in <== first.out;
+                second[1] = Middle(2);
+                second[1].in <== second[0].out;
+                out <== second[1].out;
+            }
+            component main = Main();
+        "#;
+        let dir = std::env::temp_dir().join(format!("circom_hierarchy_{}", std::process::id()));
+        let program = parse(&dir, "hierarchy", src);
+        let file = dir.join("hierarchy.json");
+        let mut config = config(&dir, "hierarchy");
+        config.hierarchy_format = Some(HierarchyFormat::Json);
+        config.hierarchy_file = file.to_str().unwrap().to_string();
+        build_circuit(program, config).map_err(|_| ()).unwrap();
+        let hierarchy = json::parse(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let nodes: Vec<&json::JsonValue> = hierarchy["nodes"].members().collect();
+        let node = |name: &str| *nodes.iter().find(|node| node["name"] == name).unwrap();
+        let leaf = node("Leaf()");
+        assert_eq!(nodes[hierarchy["main"].as_usize().unwrap()]["name"], "Main()");
+        // Leaf is instantiated 3 + 3 + 2 times, Middle(3) twice and Middle(2) once
+        assert_eq!(leaf["occurrences"], 8);
+        assert_eq!(leaf["total_constraints"], 8);
+        assert_eq!(node("Middle(3)")["occurrences"], 2);
+        assert_eq!(node("Middle(3)")["parameters"][0], "3");
+        assert_eq!(node("Middle(2)")["occurrences"], 1);
+        assert_eq!(node("Middle(3)")["signals"]["total"], 3 * 2 + 6);
+        assert_eq!(node("Main()")["signals"]["total"], 2 * 12 + 9 + 2);
+
+        let labels: Vec<String> = hierarchy["edges"]
+            .members()
+            .filter(|edge| edge["from"] == hierarchy["main"])
+            .map(|edge| edge["label"].to_string())
+            .collect();
+        assert_eq!(labels, vec!["first", "second[0]", "second[1]"]);
+        let leaves = hierarchy["edges"].members().filter(|edge| edge["to"] == leaf["id"]).count();
+        assert_eq!(leaves, 3 + 2);
+        // After the simplification only the multiplications of the leaves are left
+        let simplified: usize = nodes
+            .iter()
+            .map(|node| node["total_constraints_after_simplification"].as_usize().unwrap())
+            .sum();
+        assert_eq!(simplified, 8);
+    }
+
+    #[test]
+    fn profile_tracks_the_origin_of_every_constraint() {
+        let src = r#"
+            pragma circom 2.0.0;
+            template Square() {
+                signal input a;
+                signal output b;
+                b <== a * a;
+            }
+            template Sum() {
+                signal input x;
+                signal input y;
+                signal output s;
+                s <== x + y;
+            }
+            template Scale() {
+                signal input k;
+                signal input v;
+                signal output o;
+                o <== k * v;
+            }
+            template Main() {
+                signal input a;
+                signal input b;
+                signal input c;
+                signal output sum;
+                signal output square;
+                signal output scaled;
+                component adder = Sum();
+                adder.x <== a;
+                adder.y <== b;
+                sum <== adder.s;
+                component squarer = Square();
+                squarer.a <== c;
+                square <== squarer.b;
+                component scaler = Scale();
+                scaler.k <== 3;
+                scaler.v <== c;
+                scaled <== scaler.o;
+            }
+            component main {public [a, b, c]} = Main();
+        "#;
+        let dir = std::env::temp_dir().join(format!("circom_profile_{}", std::process::id()));
+        let program = parse(&dir, "profile", src);
+        let file = dir.join("profile.json");
+        let mut config = config(&dir, "profile");
+        config.flag_profile = true;
+        config.profile_json = file.to_str().unwrap().to_string();
+        config.profile_folded = dir.join("profile.folded").to_str().unwrap().to_string();
+        build_circuit(program, config).map_err(|_| ()).unwrap();
+        let profile = json::parse(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let template = |name: &str| {
+            let template = profile["templates"].members().find(|template| template["name"] == name).unwrap();
+            let lines: Vec<usize> = template["lines"].members().map(|line| line["line"].as_usize().unwrap()).collect();
+            (template["constraints"].as_usize().unwrap(), lines)
+        };
+        assert_eq!(profile["total_constraints"], 3);
+        // A non-linear constraint kept by the simplification
+        assert_eq!(template("Square()"), (1, vec![6]));
+        // A linear constraint over signals that can not be removed
+        assert_eq!(template("Sum()"), (1, vec![12]));
+        // A non-linear constraint that becomes linear once k is replaced by 3
+        assert_eq!(template("Scale()"), (1, vec![18]));
+        assert_eq!(template("Main()"), (0, vec![]));
+    }
+}
@@ -0,0 +1,405 @@
+use super::{A, C, S};
+use crate::json_porting::transform_constraint_to_json;
+use circom_algebra::modular_arithmetic;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::num_traits::Zero;
+use json::JsonValue;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use std::ops::Bound::{Excluded, Unbounded};
+
+type Monomial = (usize, usize);
+type Polynomial = BTreeMap<Monomial, BigInt>;
+
+// Constraints of a component before the simplification, in the numbering of
+// the signals used by the simplification
+pub struct ComponentConstraints {
+    pub template: String,
+    pub path: String,
+    pub constraints: Vec<C>,
+}
+
+// Everything needed to check the simplification without the compiler: the
+// substitutions in the order they were applied and the final constraints,
+// whose signals are placed in the witness as given by witness
+pub struct Certificate {
+    pub field: BigInt,
+    pub components: Vec<ComponentConstraints>,
+    pub substitutions: Vec<S>,
+    pub constraints: Vec<C>,
+    pub witness: Vec<usize>,
+}
+
+pub struct CheckFailure {
+    pub path: String,
+    pub index: usize,
+    pub constraint: C,
+}
+
+#[derive(Default)]
+pub struct CertificateCheck {
+    pub checked: usize,
+    // Signals substituted twice or whose substitutions depend on themselves
+    pub invalid_substitutions: Vec<usize>,
+    // Original constraints that do not follow from the final ones
+    pub failures: Vec<CheckFailure>,
+}
+
+impl CertificateCheck {
+    pub fn is_valid(&self) -> bool {
+        self.invalid_substitutions.is_empty() && self.failures.is_empty()
+    }
+}
+
+impl Certificate {
+    pub fn write(&self, file: &str) -> Result<(), ()> {
+        let file = File::create(file).map_err(|_| {})?;
+        let mut writer = BufWriter::new(file);
+        let mut text = format!("{{\n\"prime\": \"{}\",\n\"components\": [", self.field);
+        for (index, component) in self.components.iter().enumerate() {
+            let mut value = JsonValue::new_object();
+            value["template"] = component.template.as_str().into();
+            value["path"] = component.path.as_str().into();
+            value["constraints"] = constraints_as_json(&component.constraints);
+            text.push_str(if index == 0 { "\n" } else { ",\n" });
+            text.push_str(&value.dump());
+            writer.write_all(text.as_bytes()).map_err(|_| {})?;
+            text.clear();
+        }
+        text.push_str("\n],\n\"substitutions\": [");
+        for (index, substitution) in self.substitutions.iter().enumerate() {
+            let (from, to) = crate::json_porting::port_substitution(substitution);
+            text.push_str(if index == 0 { "\n" } else { ",\n" });
+            text.push_str(&format!("{{\"signal\":{},\"value\":{}}}", from, to));
+            writer.write_all(text.as_bytes()).map_err(|_| {})?;
+            text.clear();
+        }
+        text.push_str("\n],\n\"constraints\": ");
+        text.push_str(&constraints_as_json(&self.constraints).dump());
+        text.push_str(",\n\"witness\": ");
+        text.push_str(&JsonValue::from(self.witness.clone()).dump());
+        text.push_str("\n}\n");
+        writer.write_all(text.as_bytes()).map_err(|_| {})?;
+        writer.flush().map_err(|_| {})
+    }
+
+    pub fn read(file: &str) -> Result<Certificate, Error> {
+        let text = std::fs::read_to_string(file)?;
+        let value = json::parse(&text).map_err(|err| invalid(&err.to_string()))?;
+        let field = parse_number(&value["prime"])?;
+        let mut components = Vec::new();
+        for component in value["components"].members() {
+            components.push(ComponentConstraints {
+                template: component["template"].as_str().ok_or_else(|| invalid("missing template"))?.to_string(),
+                path: component["path"].as_str().ok_or_else(|| invalid("missing path"))?.to_string(),
+                constraints: parse_constraints(&component["constraints"])?,
+            });
+        }
+        let mut substitutions = Vec::new();
+        for substitution in value["substitutions"].members() {
+            let from = substitution["signal"].as_usize().ok_or_else(|| invalid("missing signal"))?;
+            let to = A::hashmap_into_arith(parse_linear_combination(&substitution["value"])?);
+            substitutions.push(S::new(from, to).ok_or_else(|| invalid("invalid substitution"))?);
+        }
+        let constraints = parse_constraints(&value["constraints"])?;
+        let witness: Option<Vec<usize>> = value["witness"].members().map(|signal| signal.as_usize()).collect();
+        let witness = witness.ok_or_else(|| invalid("invalid witness"))?;
+        Result::Ok(Certificate { field, components, substitutions, constraints, witness })
+    }
+
+    // Every original constraint, once its removed signals are replaced by
+    // their values, must be a linear combination of the final constraints
+    pub fn check(&self) -> CertificateCheck {
+        let mut check = CertificateCheck::default();
+        let substitutions = resolve_substitutions(&self.substitutions, &self.field, &mut check.invalid_substitutions);
+        let mut system = FinalSystem::new(&self.constraints, &self.field);
+        for component in &self.components {
+            for (index, constraint) in component.constraints.iter().enumerate() {
+                let mut constraint = constraint.clone();
+                for signal in constraint.take_cloned_signals() {
+                    if let Some(substitution) = substitutions.get(&signal) {
+                        C::apply_substitution(&mut constraint, substitution, &self.field);
+                    }
+                }
+                C::fix_constraint(&mut constraint, &self.field);
+                if !system.implies(&constraint) {
+                    check.failures.push(CheckFailure { path: component.path.clone(), index, constraint });
+                }
+                check.checked += 1;
+            }
+        }
+        check
+    }
+}
+
+// Replaces the removed signals in the values of the substitutions until they
+// only depend on the signals that remain. A substitution only uses signals
+// removed after it, except inside the group of substitutions of a step, so the
+// list is traversed backwards.
+fn resolve_substitutions(substitutions: &[S], field: &BigInt, invalid: &mut Vec<usize>) -> HashMap<usize, S> {
+    let mut resolved: HashMap<usize, S> = HashMap::new();
+    for substitution in substitutions {
+        if resolved.insert(*substitution.from(), substitution.clone()).is_some() {
+            invalid.push(*substitution.from());
+        }
+    }
+    let order: Vec<usize> = substitutions.iter().rev().map(|substitution| *substitution.from()).collect();
+    let mut changed = true;
+    let mut passes = 0;
+    while changed && passes <= substitutions.len() {
+        changed = false;
+        passes += 1;
+        for signal in &order {
+            let pending = match resolved.get(signal) {
+                Some(substitution) => substitution.take_cloned_signals(),
+                None => continue,
+            };
+            let pending: Vec<usize> = pending
+                .into_iter()
+                .filter(|other| resolved.contains_key(other))
+                .collect();
+            if pending.is_empty() {
+                continue;
+            }
+            if pending.contains(signal) {
+                invalid.push(*signal);
+                resolved.remove(signal);
+                continue;
+            }
+            let mut substitution = resolved.remove(signal).unwrap();
+            for other in pending {
+                S::apply_substitution(&mut substitution, &resolved[&other], field);
+            }
+            S::rmv_zero_coefficients(&mut substitution);
+            resolved.insert(*signal, substitution);
+            changed = true;
+        }
+    }
+    if changed {
+        // The substitutions depend on each other in a cycle
+        invalid.extend(resolved.keys().copied());
+    }
+    invalid.sort_unstable();
+    invalid.dedup();
+    resolved
+}
+
+// The final constraints as polynomials A*B - C. The ones divided by their
+// first coefficient answer most of the checks, the rest is done by gaussian
+// elimination over the monomials.
+struct FinalSystem<'a> {
+    field: &'a BigInt,
+    polynomials: Vec<Polynomial>,
+    normalized: HashSet<Vec<(Monomial, BigInt)>>,
+    // Rows indexed by their first monomial, whose coefficient is 1
+    basis: Option<BTreeMap<Monomial, Polynomial>>,
+}
+
+impl<'a> FinalSystem<'a> {
+    fn new(constraints: &[C], field: &'a BigInt) -> FinalSystem<'a> {
+        let polynomials: Vec<Polynomial> = constraints.iter().map(|constraint| expand(constraint, field)).collect();
+        let normalized = polynomials.iter().map(|polynomial| normalize(polynomial, field)).collect();
+        FinalSystem { field, polynomials, normalized, basis: None }
+    }
+
+    fn implies(&mut self, constraint: &C) -> bool {
+        let polynomial = expand(constraint, self.field);
+        if polynomial.is_empty() || self.normalized.contains(&normalize(&polynomial, self.field)) {
+            return true;
+        }
+        if self.in_span(polynomial) {
+            return true;
+        }
+        // As the field has no zero divisors, k*A*A = 0 holds if and only if A = 0
+        match square_root(constraint, self.field) {
+            Some(linear) => self.in_span(linear),
+            None => false,
+        }
+    }
+
+    fn in_span(&mut self, polynomial: Polynomial) -> bool {
+        if self.basis.is_none() {
+            let mut basis = BTreeMap::new();
+            for polynomial in &self.polynomials {
+                let row = reduce(&basis, polynomial.clone(), self.field);
+                if let Some((pivot, _)) = row.iter().next() {
+                    let pivot = *pivot;
+                    basis.insert(pivot, normalize_row(row, self.field));
+                }
+            }
+            self.basis = Some(basis);
+        }
+        reduce(self.basis.as_ref().unwrap(), polynomial, self.field).is_empty()
+    }
+}
+
+fn reduce(basis: &BTreeMap<Monomial, Polynomial>, mut polynomial: Polynomial, field: &BigInt) -> Polynomial {
+    let mut from = None;
+    loop {
+        let next = match from {
+            None => polynomial.keys().find(|monomial| basis.contains_key(monomial)).copied(),
+            Some(from) => polynomial
+                .range((Excluded(from), Unbounded))
+                .map(|(monomial, _)| *monomial)
+                .find(|monomial| basis.contains_key(monomial)),
+        };
+        let pivot = match next {
+            Some(pivot) => pivot,
+            None => return polynomial,
+        };
+        let factor = polynomial[&pivot].clone();
+        for (monomial, value) in &basis[&pivot] {
+            let product = modular_arithmetic::mul(&factor, value, field);
+            let entry = polynomial.entry(*monomial).or_insert_with(BigInt::zero);
+            *entry = modular_arithmetic::sub(entry, &product, field);
+            if entry.is_zero() {
+                polynomial.remove(monomial);
+            }
+        }
+        from = Some(pivot);
+    }
+}
+
+fn normalize_row(row: Polynomial, field: &BigInt) -> Polynomial {
+    let first = row.values().next().cloned().unwrap();
+    row.into_iter().map(|(monomial, value)| (monomial, divide(&value, &first, field))).collect()
+}
+
+fn normalize(polynomial: &Polynomial, field: &BigInt) -> Vec<(Monomial, BigInt)> {
+    normalize_row(polynomial.clone(), field).into_iter().collect()
+}
+
+// A*B - C, where the monomials (0, s) are the linear terms
+fn expand(constraint: &C, field: &BigInt) -> Polynomial {
+    let mut polynomial = Polynomial::new();
+    for (i, a) in constraint.a() {
+        for (j, b) in constraint.b() {
+            let monomial = if i <= j { (*i, *j) } else { (*j, *i) };
+            let product = modular_arithmetic::mul(a, b, field);
+            let entry = polynomial.entry(monomial).or_insert_with(BigInt::zero);
+            *entry = modular_arithmetic::add(entry, &product, field);
+        }
+    }
+    let constant = C::constant_coefficient();
+    for (signal, c) in constraint.c() {
+        let entry = polynomial.entry((constant, *signal)).or_insert_with(BigInt::zero);
+        *entry = modular_arithmetic::sub(entry, c, field);
+    }
+    polynomial.into_iter().filter(|(_, value)| !value.is_zero()).collect()
+}
+
+// A as a linear polynomial if the constraint is k*A*A = 0
+fn square_root(constraint: &C, field: &BigInt) -> Option<Polynomial> {
+    if C::is_linear(constraint) || constraint.c().values().any(|value| !value.is_zero()) {
+        return None;
+    }
+    let constant = C::constant_coefficient();
+    let linear = |expression: &HashMap<usize, BigInt>| -> Polynomial {
+        expression
+            .iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(signal, value)| ((constant, *signal), value.clone()))
+            .collect()
+    };
+    let a = linear(constraint.a());
+    if a.is_empty() || normalize(&a, field) != normalize(&linear(constraint.b()), field) {
+        return None;
+    }
+    Some(a)
+}
+
+fn divide(value: &BigInt, factor: &BigInt, field: &BigInt) -> BigInt {
+    modular_arithmetic::div(value, factor, field).unwrap_or_else(|_| value.clone())
+}
+
+fn constraints_as_json(constraints: &[C]) -> JsonValue {
+    JsonValue::Array(constraints.iter().map(transform_constraint_to_json).collect())
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("invalid certificate: {}", message))
+}
+
+fn parse_number(value: &JsonValue) -> Result<BigInt, Error> {
+    let text = value.as_str().ok_or_else(|| invalid("expected a number in a string"))?;
+    BigInt::parse_bytes(text.as_bytes(), 10).ok_or_else(|| invalid(&format!("invalid number {}", text)))
+}
+
+fn parse_linear_combination(value: &JsonValue) -> Result<HashMap<usize, BigInt>, Error> {
+    let mut linear_combination = HashMap::new();
+    for (signal, coefficient) in value.entries() {
+        let signal = signal.parse::<usize>().map_err(|_| invalid(&format!("invalid signal {}", signal)))?;
+        linear_combination.insert(signal, parse_number(coefficient)?);
+    }
+    Result::Ok(linear_combination)
+}
+
+fn parse_constraints(value: &JsonValue) -> Result<Vec<C>, Error> {
+    let mut constraints = Vec::new();
+    for constraint in value.members() {
+        constraints.push(C::new(
+            parse_linear_combination(&constraint[0])?,
+            parse_linear_combination(&constraint[1])?,
+            parse_linear_combination(&constraint[2])?,
+        ));
+    }
+    Result::Ok(constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_combination(terms: &[(usize, i64)]) -> HashMap<usize, BigInt> {
+        terms.iter().map(|(signal, value)| (*signal, BigInt::from(*value))).collect()
+    }
+
+    fn substitution(from: usize, terms: &[(usize, i64)]) -> S {
+        S::new(from, A::Linear { coefficients: linear_combination(terms) }).unwrap()
+    }
+
+    // out = x * y with x = in + 1 and y = x, where x and y are removed
+    fn certificate(substitutions: Vec<S>) -> Certificate {
+        let field = BigInt::from(101);
+        let original = vec![
+            C::new(HashMap::new(), HashMap::new(), linear_combination(&[(0, 1), (2, 1), (3, 100)])),
+            C::new(HashMap::new(), HashMap::new(), linear_combination(&[(3, 1), (4, 100)])),
+            C::new(linear_combination(&[(3, 1)]), linear_combination(&[(4, 1)]), linear_combination(&[(1, 1)])),
+        ];
+        let simplified = C::new(
+            linear_combination(&[(0, 1), (2, 1)]),
+            linear_combination(&[(0, 1), (2, 1)]),
+            linear_combination(&[(1, 1)]),
+        );
+        Certificate {
+            field,
+            components: vec![ComponentConstraints {
+                template: "Main".to_string(),
+                path: "main".to_string(),
+                constraints: original,
+            }],
+            substitutions,
+            constraints: vec![simplified],
+            witness: vec![0, 1, 2],
+        }
+    }
+
+    #[test]
+    fn removed_signals_are_replaced_by_their_values() {
+        let certificate = certificate(vec![substitution(4, &[(3, 1)]), substitution(3, &[(0, 1), (2, 1)])]);
+        let check = certificate.check();
+        assert_eq!(check.checked, 3);
+        assert!(check.is_valid());
+    }
+
+    #[test]
+    fn wrong_substitutions_are_reported() {
+        let certificate = certificate(vec![substitution(4, &[(3, 1)]), substitution(3, &[(0, 2), (2, 1)])]);
+        let check = certificate.check();
+        assert!(check.invalid_substitutions.is_empty());
+        // The definition of x and the product no longer hold
+        let failures: Vec<usize> = check.failures.iter().map(|failure| failure.index).collect();
+        assert_eq!(failures, vec![0, 2]);
+    }
+}
@@ -1,28 +1,39 @@
 // Uncomment lines 163, 165, 336 and 338 to print cluster information
-use super::{ConstraintStorage, EncodingIterator, SEncoded, Simplifier, A, C, S};
+use super::{ConstraintPosition, ConstraintStorage, EncodingIterator, NonLinearStats, SEncoded, Simplifier, TrackedConstraint, A, C, S};
 use crate::SignalMap;
 use circom_algebra::num_bigint::BigInt;
 use constraint_writers::json_writer::SubstitutionJSON;
 use std::collections::{HashMap, HashSet, LinkedList, BTreeSet};
 use std::sync::Arc;
 
-fn log_substitutions(substitutions: &LinkedList<S>, writer: &mut Option<SubstitutionJSON>) {
+// The substitutions go to the json file of --simplification_substitution and
+// are kept in order for the certificate of the simplification
+#[derive(Default)]
+struct SubstitutionLog {
+    json: Option<SubstitutionJSON>,
+    kept: Option<Vec<S>>,
+}
+
+fn log_substitutions(substitutions: &LinkedList<S>, log: &mut SubstitutionLog) {
     use super::json_porting::port_substitution;
-    if let Some(w) = writer {
+    if let Some(w) = &mut log.json {
         for s in substitutions {
             let (from, to) = port_substitution(s);
             w.write_substitution(&from, &to).unwrap();
         }
     }
+    if let Some(kept) = &mut log.kept {
+        kept.extend(substitutions.iter().cloned());
+    }
 }
 
 #[derive(Default, Clone)]
 struct Cluster {
-    constraints: LinkedList<C>,
+    constraints: LinkedList<TrackedConstraint>,
     num_signals: usize
 }
 impl Cluster {
-    pub fn new(constraint: C, num_signals: usize) -> Cluster {
+    pub fn new(constraint: TrackedConstraint, num_signals: usize) -> Cluster {
         let mut new = Cluster::default();
         LinkedList::push_back(&mut new.constraints, constraint);
         new.num_signals = num_signals;
@@ -42,7 +53,7 @@ impl Cluster {
     }
 }
 
-fn build_clusters(linear: LinkedList<C>, no_vars: usize) -> Vec<Cluster> {
+fn build_clusters(linear: LinkedList<TrackedConstraint>, no_vars: usize) -> Vec<Cluster> {
     type ClusterArena = Vec<Option<Cluster>>;
     type ClusterPath = Vec<usize>;
     fn shrink_jumps_and_find(c_to_c: &mut ClusterPath, org: usize) -> usize {
@@ -73,8 +84,8 @@ fn build_clusters(linear: LinkedList<C>, no_vars: usize) -> Vec<Cluster> {
     let mut cluster_to_current = ClusterPath::with_capacity(no_linear);
     let mut signal_to_cluster = vec![no_linear; no_vars];
     for constraint in linear {
-        if !constraint.is_empty(){
-            let signals = C::take_cloned_signals(&constraint);
+        if !constraint.0.is_empty(){
+            let signals = C::take_cloned_signals(&constraint.0);
             let dest = ClusterArena::len(&arena);
             ClusterArena::push(&mut arena, Some(Cluster::new(constraint, signals.len())));
             Vec::push(&mut cluster_to_current, dest);
@@ -127,12 +138,12 @@ fn eq_cluster_simplification(
     mut cluster: Cluster,
     forbidden: &HashSet<usize>,
     field: &BigInt,
-) -> (LinkedList<S>, LinkedList<C>) {
+) -> (LinkedList<S>, LinkedList<TrackedConstraint>) {
     if Cluster::size(&cluster) == 1 {
         let mut substitutions = LinkedList::new();
         let mut constraints = LinkedList::new();
         let constraint = LinkedList::pop_back(&mut cluster.constraints).unwrap();
-        let signals: Vec<_> = C::take_cloned_signals_ordered(&constraint).iter().cloned().collect();
+        let signals: Vec<_> = C::take_cloned_signals_ordered(&constraint.0).iter().cloned().collect();
         let s_0 = signals[0];
         let s_1 = signals[1];
         if HashSet::contains(forbidden, &s_0) && HashSet::contains(forbidden, &s_1) {
@@ -156,15 +167,18 @@ fn eq_cluster_simplification(
         let mut cons = LinkedList::new();
         let mut subs = LinkedList::new();
         let (mut remains, mut min_remains) = (BTreeSet::new(), None);
-        let (mut remove, mut min_remove) = (HashSet::new(), None);
-        for c in cluster.constraints {
+        let (mut remove, mut min_remove) = (BTreeSet::new(), None);
+        // The equality that joins each signal to the cluster
+        let mut positions = HashMap::new();
+        for (c, position) in cluster.constraints {
             for signal in C::take_cloned_signals_ordered(&c) {
+                positions.entry(signal).or_insert(position);
                 if HashSet::contains(&forbidden, &signal) {
                     BTreeSet::insert(&mut remains, signal);
                     min_remains = Some(min_remains.map_or(signal, |s| std::cmp::min(s, signal)));
                 } else {
                     min_remove = Some(min_remove.map_or(signal, |s| std::cmp::min(s, signal)));
-                    HashSet::insert(&mut remove, signal);
+                    BTreeSet::insert(&mut remove, signal);
                 }
             }
         }
@@ -174,7 +188,7 @@ fn eq_cluster_simplification(
             signal
         } else {
             let signal = min_remove.unwrap();
-            HashSet::remove(&mut remove, &signal);
+            BTreeSet::remove(&mut remove, &signal);
             signal
         };
 
@@ -183,7 +197,7 @@ fn eq_cluster_simplification(
             let r = A::Signal { symbol: rh_signal };
             let expr = A::sub(&l, &r, field);
             let c = A::transform_expression_to_constraint_form(expr, field).unwrap();
-            LinkedList::push_back(&mut cons, c);
+            LinkedList::push_back(&mut cons, (c, positions[&signal]));
         }
 
         for signal in remove {
@@ -196,12 +210,12 @@ fn eq_cluster_simplification(
 }
 
 fn eq_simplification(
-    equalities: LinkedList<C>,
+    equalities: LinkedList<TrackedConstraint>,
     forbidden: Arc<HashSet<usize>>,
     no_vars: usize,
     field: &BigInt,
-    substitution_log: &mut Option<SubstitutionJSON>,
-) -> (LinkedList<S>, LinkedList<C>) {
+    substitution_log: &mut SubstitutionLog,
+) -> (LinkedList<S>, LinkedList<TrackedConstraint>) {
     use std::sync::mpsc;
     use threadpool::ThreadPool;
     let field = Arc::new(field.clone());
@@ -214,12 +228,15 @@ fn eq_simplification(
     // println!("Clusters: {}", no_clusters);
     let mut single_clusters = 0;
     let mut id = 0;
+    // The results are kept in the order of the clusters, so the output does
+    // not depend on which thread finishes first
     let mut aux_constraints = vec![LinkedList::new(); clusters.len()];
+    let mut aux_substitutions = vec![LinkedList::new(); clusters.len()];
     for cluster in clusters {
         if Cluster::size(&cluster) == 1 {
-            let (mut subs, cons) = eq_cluster_simplification(cluster, &forbidden, &field);
+            let (subs, cons) = eq_cluster_simplification(cluster, &forbidden, &field);
             aux_constraints[id] = cons;
-            LinkedList::append(&mut substitutions, &mut subs);
+            aux_substitutions[id] = subs;
             single_clusters += 1;
         } else {
             let cluster_tx = cluster_tx.clone();
@@ -239,30 +256,31 @@ fn eq_simplification(
     // println!("{} clusters were of size 1", single_clusters);
     ThreadPool::join(&pool);
     for _ in 0..(no_clusters - single_clusters) {
-        let (id, (mut subs, cons)) = simplified_rx.recv().unwrap();
+        let (id, (subs, cons)) = simplified_rx.recv().unwrap();
         aux_constraints[id] = cons;
-        LinkedList::append(&mut substitutions, &mut subs);
+        aux_substitutions[id] = subs;
     }
     for id in 0..no_clusters {
         LinkedList::append(&mut constraints, &mut aux_constraints[id]);
+        LinkedList::append(&mut substitutions, &mut aux_substitutions[id]);
     }
     log_substitutions(&substitutions, substitution_log);
     (substitutions, constraints)
 }
 
 fn constant_eq_simplification(
-    c_eq: LinkedList<C>,
+    c_eq: LinkedList<TrackedConstraint>,
     forbidden: &HashSet<usize>,
     field: &BigInt,
-    substitution_log: &mut Option<SubstitutionJSON>,
-) -> (LinkedList<S>, LinkedList<C>) {
+    substitution_log: &mut SubstitutionLog,
+) -> (LinkedList<S>, LinkedList<TrackedConstraint>) {
     let mut cons = LinkedList::new();
     let mut subs = LinkedList::new();
-    for constraint in c_eq {
+    for (constraint, position) in c_eq {
         let mut signals: Vec<_> = C::take_cloned_signals_ordered(&constraint).iter().cloned().collect();
         let signal = signals.pop().unwrap();
         if HashSet::contains(&forbidden, &signal) {
-            LinkedList::push_back(&mut cons, constraint);
+            LinkedList::push_back(&mut cons, (constraint, position));
         } else {
             let sub = C::clear_signal_from_linear(constraint, &signal, field);
             LinkedList::push_back(&mut subs, sub);
@@ -273,13 +291,13 @@ fn constant_eq_simplification(
 }
 
 fn linear_simplification(
-    log: &mut Option<SubstitutionJSON>,
-    linear: LinkedList<C>,
+    log: &mut SubstitutionLog,
+    linear: LinkedList<TrackedConstraint>,
     forbidden: Arc<HashSet<usize>>,
     no_labels: usize,
     field: &BigInt,
     use_old_heuristics: bool,
-) -> (LinkedList<S>, LinkedList<C>) {
+) -> (LinkedList<S>, LinkedList<TrackedConstraint>) {
     use circom_algebra::simplification_utils::full_simplification;
     use circom_algebra::simplification_utils::Config;
     use std::sync::mpsc;
@@ -307,7 +325,7 @@ fn linear_simplification(
             // println!("cluster: {}", id);
             let result = full_simplification(config);
             // println!("End of cluster: {}", id);
-            cluster_tx.send(result).unwrap();
+            cluster_tx.send((id, result)).unwrap();
         };
         ThreadPool::execute(&pool, job);
         let _ = id;
@@ -315,8 +333,11 @@ fn linear_simplification(
     }
     ThreadPool::join(&pool);
 
-    for _ in 0..no_clusters {
-        let mut result = simplified_rx.recv().unwrap();
+    // The results are processed in the order of the clusters, so the output
+    // does not depend on which thread finishes first
+    let mut results: Vec<_> = (0..no_clusters).map(|_| simplified_rx.recv().unwrap()).collect();
+    results.sort_by_key(|(id, _)| *id);
+    for (_, mut result) in results {
         log_substitutions(&result.substitutions, log);
         LinkedList::append(&mut cons, &mut result.constraints);
         LinkedList::append(&mut substitutions, &mut result.substitutions);
@@ -342,12 +363,38 @@ fn build_non_linear_signal_map(non_linear: &ConstraintStorage) -> SignalToConstr
     map
 }
 
+// The signals of the constraint are only tracked when the unused signals
+// are removed
+fn store_constraint(
+    storage: &mut ConstraintStorage,
+    map: &mut SignalToConstraints,
+    constraint: C,
+    remove_unused: bool,
+) {
+    if remove_unused {
+        let signals = C::take_cloned_signals(&constraint);
+        let c_id = storage.add_constraint(constraint);
+        for signal in signals {
+            if let Some(list) = map.get_mut(&signal) {
+                list.push_back(c_id);
+            } else {
+                let mut new = LinkedList::new();
+                new.push_back(c_id);
+                map.insert(signal, new);
+            }
+        }
+    } else {
+        storage.add_constraint(constraint);
+    }
+}
+
 fn apply_substitution_to_map(
     storage: &mut ConstraintStorage,
+    origins: &[Option<ConstraintPosition>],
     map: &mut SignalToConstraints,
     substitutions: &LinkedList<S>,
     field: &BigInt,
-) -> LinkedList<C> {
+) -> LinkedList<TrackedConstraint> {
     fn constraint_processing(
         storage: &mut ConstraintStorage,
         map: &mut SignalToConstraints,
@@ -389,7 +436,7 @@ fn apply_substitution_to_map(
     let mut linear = LinkedList::new();
     for c_id in linear_id {
         let constraint = storage.read_constraint(c_id).unwrap();
-        linear.push_back(constraint);
+        linear.push_back((constraint, origins[c_id]));
         storage.replace(c_id, C::empty());
     }
     linear
@@ -438,19 +485,22 @@ fn remove_not_relevant(substitutions: &mut SEncoded, relevant: &HashSet<usize>)
 }
 
 
-// returns the constraints, the assignment of the witness and the number of inputs in the witness
-pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, usize) {
+// returns the constraints, the assignment of the witness, the number of inputs in the witness
+// and the substitutions when they are kept
+pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, usize, Vec<Option<ConstraintPosition>>, NonLinearStats, Vec<S>) {
     use super::non_linear_utils::obtain_and_simplify_non_linear;
     use circom_algebra::simplification_utils::build_encoded_fast_substitutions;
     use circom_algebra::simplification_utils::fast_encoded_constraint_substitution;
     use std::time::SystemTime;
 
-    let mut substitution_log =
-        if smp.port_substitution { 
-            Some(SubstitutionJSON::new(&smp.json_substitutions).unwrap()) 
+    let mut substitution_log = SubstitutionLog {
+        json: if smp.port_substitution {
+            Some(SubstitutionJSON::new(&smp.json_substitutions).unwrap())
         } else {
-             None 
-        };
+            None
+        },
+        kept: if smp.keep_substitutions { Some(Vec::new()) } else { None },
+    };
     let apply_linear = !smp.flag_s;
     let use_old_heuristics = smp.flag_old_heuristics;
     let field = smp.field.clone();
@@ -491,12 +541,12 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
 
         LinkedList::append(&mut lconst, &mut cons);
         let mut substitutions = build_encoded_fast_substitutions(subs);
-        for constraint in &mut linear {
+        for (constraint, _) in &mut linear {
             if fast_encoded_constraint_substitution(constraint, &substitutions, &field){
                 C::fix_constraint(constraint, &field);
             }
         }
-        for constraint in &mut cons_equalities {
+        for (constraint, _) in &mut cons_equalities {
             if fast_encoded_constraint_substitution(constraint, &substitutions, &field){
                 C::fix_constraint(constraint, &field);
             }
@@ -517,7 +567,7 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
             constant_eq_simplification(cons_equalities, &forbidden, &field, &mut substitution_log);
         LinkedList::append(&mut lconst, &mut cons);
         let substitutions = build_encoded_fast_substitutions(subs);
-        for constraint in &mut linear {
+        for (constraint, _) in &mut linear {
             if fast_encoded_constraint_substitution(constraint, &substitutions, &field){
                 C::fix_constraint(constraint, &field);
             }
@@ -566,7 +616,7 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
         let _dur = now.elapsed().unwrap().as_millis();
         // println!("End of cluster simplification: {} ms", dur);
         LinkedList::append(&mut lconst, &mut cons);
-        for constraint in &mut lconst {
+        for (constraint, _) in &mut lconst {
             if fast_encoded_constraint_substitution(constraint, &substitutions, &field){
                 C::fix_constraint(constraint, &field);
             }
@@ -577,6 +627,9 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
         HashMap::with_capacity(0)
     };
 
+    // Position of each constraint of the storage, the ids of the storage do
+    // not change until the empty constraints are removed
+    let mut origins = Vec::new();
     let (with_linear, mut constraint_storage) = {
         // println!("Building constraint storage");
         let now = SystemTime::now();
@@ -586,7 +639,7 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
         LinkedList::push_back(&mut frames, linear_substitutions);
         let iter = EncodingIterator::new(&smp.dag_encoding);
         let mut storage = ConstraintStorage::new();
-        let with_linear = obtain_and_simplify_non_linear(iter, &mut storage, &frames, &field);
+        let with_linear = obtain_and_simplify_non_linear(iter, &mut storage, &mut origins, &frames, &field);
         crate::state_utils::empty_encoding_constraints(&mut smp.dag_encoding);
         let _dur = now.elapsed().unwrap().as_millis();
         // println!("Storages built in {} ms", dur);
@@ -626,7 +679,7 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
             deleted.insert(*sub.from());
         }
         lconst.append(&mut constants);
-        for constraint in &mut lconst {
+        for (constraint, _) in &mut lconst {
             for substitution in &substitutions {
                 C::apply_substitution(constraint, substitution, &field);
             }
@@ -634,6 +687,7 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
         }
         linear = apply_substitution_to_map(
             &mut constraint_storage,
+            &origins,
             &mut non_linear_map,
             &substitutions,
             &field,
@@ -645,56 +699,82 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
         // println!("Iteration no {} took {} ms", round_id, dur);
     }
 
-    for constraint in linear {
-        if remove_unused {
-            let signals =  C::take_cloned_signals(&constraint);
-            let c_id = constraint_storage.add_constraint(constraint);
-            for signal in signals {
-                if let Some(list) = non_linear_map.get_mut(&signal) {
-                    list.push_back(c_id);
-                } else {
-                    let mut new = LinkedList::new();
-                    new.push_back(c_id);
-                    non_linear_map.insert(signal, new);
-                }
-            }
+    for (constraint, position) in linear {
+        origins.push(position);
+        store_constraint(&mut constraint_storage, &mut non_linear_map, constraint, remove_unused);
+    }
+    for (mut constraint, position) in lconst {
+        origins.push(position);
+        C::fix_constraint(&mut constraint, &field);
+        store_constraint(&mut constraint_storage, &mut non_linear_map, constraint, remove_unused);
+    }
+
+    let non_linear_stats = if apply_linear {
+        let (substitutions, linearized, mut stats) = crate::non_linear_simplification::simplify(
+            &mut constraint_storage,
+            &forbidden,
+            &field
+        );
+        log_substitutions(&substitutions, &mut substitution_log);
+        for substitution in &substitutions {
+            deleted.insert(*substitution.from());
         }
-        else{
-            constraint_storage.add_constraint(constraint);
+
+        // The constraints that became linear go back through the linear
+        // substitution, together with the ones that become linear in turn
+        let mut linear = LinkedList::new();
+        for c_id in linearized {
+            let constraint = constraint_storage.read_constraint(c_id).unwrap();
+            if !C::is_empty(&constraint) {
+                linear.push_back((constraint, origins[c_id]));
+                constraint_storage.replace(c_id, C::empty());
+            }
         }
-    }
-    for mut constraint in lconst {
-        if remove_unused{
-            C::fix_constraint(&mut constraint, &field);
-            let signals =  C::take_cloned_signals(&constraint);
-            let c_id = constraint_storage.add_constraint(constraint);
-            for signal in signals {
-                if let Some(list) = non_linear_map.get_mut(&signal) {
-                    list.push_back(c_id);
-                } else {
-                    let mut new = LinkedList::new();
-                    new.push_back(c_id);
-                    non_linear_map.insert(signal, new);
+        if !linear.is_empty() {
+            non_linear_map = build_non_linear_signal_map(&constraint_storage);
+        }
+        let mut taken = linear.len();
+        let mut lconst = LinkedList::new();
+        while !linear.is_empty() {
+            let (substitutions, mut constants) = linear_simplification(
+                &mut substitution_log,
+                linear,
+                Arc::clone(&forbidden),
+                no_labels,
+                &field,
+                use_old_heuristics,
+            );
+            for substitution in &substitutions {
+                deleted.insert(*substitution.from());
+            }
+            lconst.append(&mut constants);
+            for (constraint, _) in &mut lconst {
+                for substitution in &substitutions {
+                    C::apply_substitution(constraint, substitution, &field);
                 }
+                C::fix_constraint(constraint, &field);
             }
+            linear = apply_substitution_to_map(
+                &mut constraint_storage,
+                &origins,
+                &mut non_linear_map,
+                &substitutions,
+                &field,
+            );
+            taken += linear.len();
         }
-        else{
-            C::fix_constraint(&mut constraint, &field);
-            constraint_storage.add_constraint(constraint);
+        stats.linearized = taken - lconst.iter().filter(|(constraint, _)| !C::is_empty(constraint)).count();
+        for (constraint, position) in lconst {
+            origins.push(position);
+            store_constraint(&mut constraint_storage, &mut non_linear_map, constraint, remove_unused);
         }
-    }
-
-    let erased = crate::non_linear_simplification::simplify(
-        &mut constraint_storage,
-        &forbidden,
-        &field
-    );
-
-    for signal in erased {
-        deleted.insert(signal);
-    }
+        stats
+    } else {
+        NonLinearStats::default()
+    };
 
-    let _trash = constraint_storage.extract_with(&|c| C::is_empty(c));
+    let (_trash, kept) = constraint_storage.extract_with_kept_ids(&|c| C::is_empty(c));
+    let origins = kept.into_iter().map(|id| origins[id]).collect();
 
 
     let signal_map = {
@@ -722,11 +802,12 @@ pub fn simplification(smp: &mut Simplifier) -> (ConstraintStorage, SignalMap, us
     }
 
 
-    if let Some(w) = substitution_log {
+    if let Some(w) = substitution_log.json {
         w.end().unwrap();
     }
+    let substitutions = substitution_log.kept.unwrap_or_default();
     // println!("NO CONSTANTS: {}", constraint_storage.no_constants());
-    (constraint_storage, signal_map, smp.no_private_inputs - deleted_inputs)
+    (constraint_storage, signal_map, smp.no_private_inputs - deleted_inputs, origins, non_linear_stats, substitutions)
 }
 
 
@@ -1,5 +1,6 @@
 use super::{ConstraintStorage, C, S};
 use crate::SignalMap;
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_bigint::BigInt;
 use constraint_writers::debug_writer::DebugWriter;
 use json::JsonValue;
@@ -32,14 +33,15 @@ pub fn port_substitution(sub: &S) -> (String, String) {
     (from, to)
 }
 
-pub fn port_constraints(
-    storage: &ConstraintStorage,
+pub fn port_constraints<F: FieldElement>(
+    storage: &ConstraintStorage<F>,
+    field: &BigInt,
     map: &SignalMap,
     debug: &DebugWriter,
 ) -> Result<(), ()> {
     let mut writer = debug.build_constraints_file()?;
     for c_id in storage.get_ids() {
-        let constraint = storage.read_constraint(c_id).unwrap();
+        let constraint: C = storage.read_constraint(c_id).unwrap().convert_field(field);
         let constraint = C::apply_correspondence(&constraint, map);
         let json_value = transform_constraint_to_json(&constraint);
         writer.write_constraint(&json_value.to_string())?;
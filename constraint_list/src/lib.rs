@@ -1,10 +1,12 @@
 use std::collections::{HashMap, HashSet, LinkedList};
 
 use circom_algebra::constraint_storage::ConstraintStorage;
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_bigint::BigInt;
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
 
+mod certificate;
 mod constraint_simplification;
 mod json_porting;
 mod non_linear_utils;
@@ -12,6 +14,11 @@ mod r1cs_porting;
 mod state_utils;
 mod sym_porting;
 mod non_linear_simplification;
+mod plonk_porting;
+
+pub use certificate::{Certificate, CertificateCheck, CheckFailure, ComponentConstraints};
+pub use non_linear_simplification::NonLinearStats;
+pub use plonk_porting::PlonkStats;
 
 type C = circom_algebra::algebra::Constraint<usize>;
 type S = circom_algebra::algebra::Substitution<usize>;
@@ -19,6 +26,8 @@ type A = circom_algebra::algebra::ArithmeticExpression<usize>;
 type SignalMap = HashMap<usize, usize>;
 type SEncoded = HashMap<usize, A>;
 type SFrames = LinkedList<SEncoded>;
+// Constraint together with its position in the DAG, when it is known
+pub type TrackedConstraint = (C, Option<ConstraintPosition>);
 
 pub struct SignalInfo {
     pub name: String,
@@ -31,9 +40,20 @@ pub struct EncodingNode {
     pub signals: Vec<SignalInfo>,
     pub ordered_signals: Vec<usize>,
     pub non_linear: LinkedList<C>,
+    // Index among all the constraints of the instance of each non-linear one
+    pub non_linear_indexes: Vec<usize>,
     pub is_custom_gate: bool,
 }
 
+// Position of a constraint in the DAG: the template instance, the offset of
+// the component and the index among the constraints of the instance
+#[derive(Copy, Clone)]
+pub struct ConstraintPosition {
+    pub node_id: usize,
+    pub offset: usize,
+    pub index: usize,
+}
+
 pub struct EncodingEdge {
     pub goes_to: usize,
     pub path: String,
@@ -100,6 +120,16 @@ impl<'a> EncodingIterator<'a> {
         &iterator.encoding.adjacency[iterator.node_id]
     }
 
+    // Position in the DAG of the non-linear constraint with the given index
+    pub fn position(iterator: &EncodingIterator, index: usize) -> Option<ConstraintPosition> {
+        let indexes = &iterator.encoding.nodes[iterator.node_id].non_linear_indexes;
+        indexes.get(index).map(|index| ConstraintPosition {
+            node_id: iterator.node_id,
+            offset: iterator.offset,
+            index: *index,
+        })
+    }
+
     pub fn take(iter: &mut EncodingIterator) -> (Vec<SignalInfo>, LinkedList<C>) {
         let ret = (std::mem::take(&mut iter.signals), std::mem::take(&mut iter.non_linear));
         state_utils::clear_encoding_iterator(iter);
@@ -114,9 +144,9 @@ pub struct Simplifier {
     pub no_public_outputs: usize,
     pub no_private_inputs: usize,
     pub forbidden: HashSet<usize>,
-    pub cons_equalities: LinkedList<C>,
-    pub equalities: LinkedList<C>,
-    pub linear: LinkedList<C>,
+    pub cons_equalities: LinkedList<TrackedConstraint>,
+    pub equalities: LinkedList<TrackedConstraint>,
+    pub linear: LinkedList<TrackedConstraint>,
     //  Signals in [witness_len, Vec::len(&signal_map)) are the ones deleted
     pub max_signal: usize,
     // Flags
@@ -126,10 +156,15 @@ pub struct Simplifier {
     pub flag_old_heuristics: bool,
     pub port_substitution: bool,
     pub json_substitutions: String,
+    pub keep_substitutions: bool,
 }
 impl Simplifier {
-    pub fn simplify_constraints(mut self) -> ConstraintList {
-        let (portable, map, private_inputs_witness) = constraint_simplification::simplification(&mut self);
+    // The simplification works with BigInt, the constraints that remain are
+    // stored with the elements of F
+    pub fn simplify_constraints<F: FieldElement>(mut self) -> ConstraintList<F> {
+        let (portable, map, private_inputs_witness, origins, non_linear_stats, substitutions) =
+            constraint_simplification::simplification(&mut self);
+        let constraints = portable.convert_field(&self.field);
         ConstraintList {
             field: self.field,
             dag_encoding: self.dag_encoding,
@@ -138,8 +173,11 @@ impl Simplifier {
             no_private_inputs: self.no_private_inputs,
             no_private_inputs_witness: private_inputs_witness,
             no_labels: self.max_signal,
-            constraints: portable,
+            constraints,
             signal_map: map,
+            origins,
+            non_linear_stats,
+            substitutions,
         }
     }
 
@@ -152,34 +190,46 @@ impl Simplifier {
     }
 }
 
-pub struct ConstraintList {
+pub struct ConstraintList<F = BigInt> {
     pub field: BigInt,
     pub dag_encoding: DAGEncoding,
     pub no_public_inputs: usize,
     pub no_public_outputs: usize,
     pub no_private_inputs: usize,
     pub no_private_inputs_witness: usize,
-    pub constraints: ConstraintStorage,
+    pub constraints: ConstraintStorage<F>,
     pub no_labels: usize,
     //  Signals in [witness_len, Vec::len(&signal_map)) are the ones deleted
     pub signal_map: SignalMap,
+    // Position in the DAG of the constraint each one comes from. Constraints
+    // combined by the linear simplification keep the position of the one
+    // whose signal could not be removed.
+    pub origins: Vec<Option<ConstraintPosition>>,
+    pub non_linear_stats: NonLinearStats,
+    // Substitutions applied by the simplification in order, only if they
+    // were kept for the certificate
+    pub substitutions: Vec<S>,
 }
 
-impl ConstraintExporter for ConstraintList {
+impl<F: FieldElement + Sync> ConstraintExporter for ConstraintList<F> {
     fn r1cs(&self, out: &str, custom_gates: bool) -> Result<(), ()> {
         r1cs_porting::port_r1cs(self, out, custom_gates)
     }
 
     fn json_constraints(&self, writer: &DebugWriter) -> Result<(), ()> {
-        json_porting::port_constraints(&self.constraints, &self.signal_map, writer)
+        json_porting::port_constraints(&self.constraints, &self.field, &self.signal_map, writer)
     }
 
     fn sym(&self, out: &str) -> Result<(), ()> {
         sym_porting::port_sym(self, out)
     }
+
+    fn sym_index(&self, out: &str) -> Result<(), ()> {
+        sym_porting::port_sym_index(self, out)
+    }
 }
 
-impl ConstraintList {
+impl<F: FieldElement> ConstraintList<F> {
     pub fn get_witness(&self) -> &SignalMap {
         &self.signal_map
     }
@@ -199,4 +249,8 @@ impl ConstraintList {
     pub fn no_wires(&self) -> usize {
         self.signal_map.len()
     }
+
+    pub fn plonk(&self, out: &str) -> Result<PlonkStats, ()> {
+        plonk_porting::port_plonk(self, out)
+    }
 }
@@ -1,12 +1,251 @@
+use super::{A, C, S};
+use circom_algebra::constraint_storage::{ConstraintID, ConstraintStorage};
+use circom_algebra::modular_arithmetic;
 use circom_algebra::num_bigint::BigInt;
-use circom_algebra::constraint_storage::ConstraintStorage;
-use std::collections::{HashSet, LinkedList};
+use circom_algebra::num_traits::Zero;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList};
 
+// Above this number of products the constraints are compared by their
+// normalized A, B and C instead of by the polynomial A*B - C
+const MAX_EXPANSION: usize = 4096;
 
+type Coefficients = Vec<(usize, BigInt)>;
+
+// Number of constraints removed by each technique
+#[derive(Default, Clone, Copy)]
+pub struct NonLinearStats {
+    // Constraints fixing a signal to a constant, after replacing the signal
+    pub constant_signals: usize,
+    // Constraints that become 0 = 0 once the constants are known or expanded
+    pub implied: usize,
+    // Constraints equal to others up to a scalar or the order of A and B
+    pub duplicated: usize,
+    // Constraints x*(x-1) = 0 over a signal already constrained to be boolean
+    pub repeated_boolean: usize,
+    // Constraints A*A = 0 or A*B = C that become linear and are then removed
+    // by the linear substitution
+    pub linearized: usize,
+}
+
+impl NonLinearStats {
+    pub fn removed(&self) -> usize {
+        self.constant_signals + self.implied + self.duplicated + self.repeated_boolean + self.linearized
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Key {
+    Polynomial(Vec<((usize, usize), BigInt)>),
+    Factors(Coefficients, Coefficients, Coefficients),
+}
+
+// The ids of the constraints do not change: the removed constraints are
+// replaced by empty ones. Returns the substitutions of the signals that no
+// longer appear in the constraints because their value is a constant, and
+// the ids of the constraints that became linear.
 pub fn simplify(
-    _storage: &mut ConstraintStorage,
-    _forbidden: &HashSet<usize>,
-    _field: &BigInt
-) -> LinkedList<usize> {
-    LinkedList::new()
+    storage: &mut ConstraintStorage,
+    forbidden: &HashSet<usize>,
+    field: &BigInt,
+) -> (LinkedList<S>, BTreeSet<ConstraintID>, NonLinearStats) {
+    let mut stats = NonLinearStats::default();
+    let mut linearized = BTreeSet::new();
+    let substitutions = propagate_constants(storage, forbidden, field, &mut linearized, &mut stats);
+    remove_duplicates(storage, field, &mut stats);
+    (substitutions, linearized, stats)
+}
+
+fn propagate_constants(
+    storage: &mut ConstraintStorage,
+    forbidden: &HashSet<usize>,
+    field: &BigInt,
+    linearized: &mut BTreeSet<ConstraintID>,
+    stats: &mut NonLinearStats,
+) -> LinkedList<S> {
+    let mut uses: HashMap<usize, Vec<ConstraintID>> = HashMap::new();
+    let mut pending = LinkedList::new();
+    for c_id in storage.get_ids() {
+        let mut constraint = storage.read_constraint(c_id).unwrap();
+        if linearize(&mut constraint, field) {
+            linearized.insert(c_id);
+            storage.replace(c_id, constraint.clone());
+        }
+        for signal in constraint.take_cloned_signals() {
+            uses.entry(signal).or_default().push(c_id);
+        }
+        if constant_signal(&constraint, forbidden).is_some() {
+            pending.push_back(c_id);
+        }
+    }
+
+    let mut substitutions = LinkedList::new();
+    while let Some(c_id) = pending.pop_front() {
+        let constraint = storage.read_constraint(c_id).unwrap();
+        // The constraint may have changed since it was found
+        let signal = match constant_signal(&constraint, forbidden) {
+            Some(signal) => signal,
+            None => continue,
+        };
+        let substitution = C::clear_signal_from_linear(constraint, &signal, field);
+        storage.replace(c_id, C::empty());
+        stats.constant_signals += 1;
+        for other in uses.remove(&signal).unwrap_or_default() {
+            let mut constraint = storage.read_constraint(other).unwrap();
+            if other == c_id || constraint.is_empty() {
+                continue;
+            }
+            let was_linear = C::is_linear(&constraint);
+            C::apply_substitution(&mut constraint, &substitution, field);
+            C::fix_constraint(&mut constraint, field);
+            if linearize(&mut constraint, field) || (!was_linear && C::is_linear(&constraint)) {
+                linearized.insert(other);
+            }
+            if constraint.is_empty() {
+                stats.implied += 1;
+            } else if constant_signal(&constraint, forbidden).is_some() {
+                pending.push_back(other);
+            }
+            storage.replace(other, constraint);
+        }
+        substitutions.push_back(substitution);
+    }
+    substitutions
+}
+
+// Constraints c*x + d = 0 with a single signal that can be removed
+fn constant_signal(constraint: &C, forbidden: &HashSet<usize>) -> Option<usize> {
+    if !C::is_linear(constraint) {
+        return None;
+    }
+    let constant = C::constant_coefficient();
+    let mut signals = constraint.c().keys().filter(|signal| **signal != constant);
+    match (signals.next(), signals.next()) {
+        (Some(signal), None) if !forbidden.contains(signal) => Some(*signal),
+        _ => None,
+    }
+}
+
+// As the field has no zero divisors, k*A*A = 0 holds if and only if A = 0
+fn linearize(constraint: &mut C, field: &BigInt) -> bool {
+    if C::is_linear(constraint) || constraint.c().values().any(|value| !value.is_zero()) {
+        return false;
+    }
+    let a = normalized(constraint.a(), field);
+    let b = normalized(constraint.b(), field);
+    if a.1 != b.1 {
+        return false;
+    }
+    let mut coefficients = constraint.a().clone();
+    coefficients.entry(C::constant_coefficient()).or_insert_with(BigInt::zero);
+    let expression = A::Linear { coefficients };
+    *constraint = A::transform_expression_to_constraint_form(expression, field).unwrap();
+    C::fix_constraint(constraint, field);
+    true
+}
+
+fn remove_duplicates(storage: &mut ConstraintStorage, field: &BigInt, stats: &mut NonLinearStats) {
+    let mut seen = HashSet::new();
+    for c_id in storage.get_ids() {
+        let constraint = storage.read_constraint(c_id).unwrap();
+        if constraint.is_empty() {
+            continue;
+        }
+        let key = canonical_form(&constraint, field);
+        let removed = match &key {
+            Key::Polynomial(monomials) if monomials.is_empty() => {
+                stats.implied += 1;
+                true
+            }
+            _ if seen.contains(&key) => {
+                if is_boolean(&key, field) {
+                    stats.repeated_boolean += 1;
+                } else {
+                    stats.duplicated += 1;
+                }
+                true
+            }
+            _ => false,
+        };
+        if removed {
+            storage.replace(c_id, C::empty());
+        } else {
+            seen.insert(key);
+        }
+    }
+}
+
+// Two constraints with the same canonical form are satisfied by the same
+// assignments. The polynomial A*B - C is divided by its first coefficient.
+fn canonical_form(constraint: &C, field: &BigInt) -> Key {
+    if constraint.a().len() * constraint.b().len() > MAX_EXPANSION {
+        let (a_factor, a) = normalized(constraint.a(), field);
+        let (b_factor, b) = normalized(constraint.b(), field);
+        let factor = modular_arithmetic::mul(&a_factor, &b_factor, field);
+        let (_, c) = scaled(constraint.c(), &factor, field);
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        return Key::Factors(a, b, c);
+    }
+    let mut polynomial: BTreeMap<(usize, usize), BigInt> = BTreeMap::new();
+    for (i, a) in constraint.a() {
+        for (j, b) in constraint.b() {
+            let monomial = if i <= j { (*i, *j) } else { (*j, *i) };
+            let product = modular_arithmetic::mul(a, b, field);
+            let entry = polynomial.entry(monomial).or_insert_with(BigInt::zero);
+            *entry = modular_arithmetic::add(entry, &product, field);
+        }
+    }
+    let constant = C::constant_coefficient();
+    for (signal, c) in constraint.c() {
+        let entry = polynomial.entry((constant, *signal)).or_insert_with(BigInt::zero);
+        *entry = modular_arithmetic::sub(entry, c, field);
+    }
+    let mut monomials: Vec<_> = polynomial.into_iter().filter(|(_, value)| !value.is_zero()).collect();
+    if let Some((_, first)) = monomials.first() {
+        let factor = first.clone();
+        for (_, value) in &mut monomials {
+            *value = divide(value, &factor, field);
+        }
+    }
+    Key::Polynomial(monomials)
+}
+
+// -x*x + x = 0 once normalized
+fn is_boolean(key: &Key, field: &BigInt) -> bool {
+    let constant = C::constant_coefficient();
+    match key {
+        Key::Polynomial(monomials) if monomials.len() == 2 => {
+            let ((c0, x0), linear) = &monomials[0];
+            let ((x1, x2), square) = &monomials[1];
+            *c0 == constant
+                && x0 == x1
+                && x1 == x2
+                && *linear == BigInt::from(1)
+                && *square == modular_arithmetic::sub(field, &BigInt::from(1), field)
+        }
+        _ => false,
+    }
+}
+
+// Coefficients sorted by signal and divided by the first one, and that one
+fn normalized(expression: &HashMap<usize, BigInt>, field: &BigInt) -> (BigInt, Coefficients) {
+    let first = expression
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .min_by_key(|(signal, _)| **signal)
+        .map_or(BigInt::from(1), |(_, value)| value.clone());
+    scaled(expression, &first, field)
+}
+
+fn scaled(expression: &HashMap<usize, BigInt>, factor: &BigInt, field: &BigInt) -> (BigInt, Coefficients) {
+    let mut coefficients: Coefficients = expression
+        .iter()
+        .filter(|(_, value)| !value.is_zero())
+        .map(|(signal, value)| (*signal, divide(value, factor, field)))
+        .collect();
+    coefficients.sort();
+    (factor.clone(), coefficients)
+}
+
+fn divide(value: &BigInt, factor: &BigInt, field: &BigInt) -> BigInt {
+    modular_arithmetic::div(value, factor, field).unwrap_or_else(|_| value.clone())
 }
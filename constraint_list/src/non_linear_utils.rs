@@ -1,30 +1,34 @@
-use super::{ConstraintStorage, EncodingIterator, SFrames, C};
+use super::{ConstraintPosition, ConstraintStorage, EncodingIterator, SFrames, TrackedConstraint, C};
 use circom_algebra::num_bigint::BigInt;
 use circom_algebra::simplification_utils::fast_encoded_constraint_substitution;
 use std::collections::LinkedList;
 
+// The position of each constraint added to the storage is pushed to origins
 pub fn obtain_and_simplify_non_linear(
     mut iter: EncodingIterator,
     storage: &mut ConstraintStorage,
+    origins: &mut Vec<Option<ConstraintPosition>>,
     frames: &SFrames,
     field: &BigInt,
-) -> LinkedList<C> {
+) -> LinkedList<TrackedConstraint> {
     let mut linear = LinkedList::new();
     let (_, non_linear) = EncodingIterator::take(&mut iter);
-    for mut constraint in non_linear {
+    for (index, mut constraint) in non_linear.into_iter().enumerate() {
         for frame in frames {
             fast_encoded_constraint_substitution(&mut constraint, frame, &field);
         }
         C::fix_constraint(&mut constraint, &field);
+        let position = EncodingIterator::position(&iter, index);
         if C::is_linear(&constraint) {
-            linear.push_back(constraint);
+            linear.push_back((constraint, position));
         } else {
             storage.add_constraint(constraint);
+            origins.push(position);
         }
     }
     for edge in EncodingIterator::edges(&iter) {
         let next = EncodingIterator::next(&iter, edge);
-        let mut linear_in_next = obtain_and_simplify_non_linear(next, storage, frames, field);
+        let mut linear_in_next = obtain_and_simplify_non_linear(next, storage, origins, frames, field);
         linear.append(&mut linear_in_next);
     }
     linear
@@ -0,0 +1,283 @@
+use super::{ConstraintList, C};
+use circom_algebra::field_element;
+use circom_algebra::modular_arithmetic;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::num_traits::{One, Zero};
+use constraint_writers::plonk_writer::{self, PlonkData, PlonkGate};
+use std::collections::{HashMap, VecDeque};
+
+type Term = (usize, BigInt);
+
+#[derive(Default)]
+pub struct PlonkStats {
+    pub gates: usize,
+    pub multiplication_gates: usize,
+    pub auxiliary_wires: usize,
+    // Wires that appear in more than one position of the gate table
+    pub shared_wires: usize,
+    pub copy_constraints: usize,
+    pub longest_cycle: usize,
+}
+
+// Gates of the constraints in witness numbering. The auxiliary wires are
+// numbered after the witness and each one is the output c of the linear gate
+// that defines it.
+struct GateBuilder<'a> {
+    field: &'a BigInt,
+    next_wire: usize,
+    gates: Vec<PlonkGate>,
+    auxiliary_definitions: Vec<usize>,
+}
+
+impl<'a> GateBuilder<'a> {
+    fn new(field: &'a BigInt, witness_wires: usize) -> GateBuilder<'a> {
+        GateBuilder { field, next_wire: witness_wires, gates: Vec::new(), auxiliary_definitions: Vec::new() }
+    }
+
+    fn add_constraint(&mut self, constraint: &C) {
+        let (c_terms, c_constant) = self.split(constraint.c());
+        if C::is_linear(constraint) {
+            self.add_linear(c_terms.into(), c_constant);
+            return;
+        }
+        let (a_term, a_constant) = self.single_wire(constraint.a());
+        let (b_term, b_constant) = self.single_wire(constraint.b());
+        let (c_term, c_constant) = if c_terms.len() > 1 {
+            self.single_wire(constraint.c())
+        } else {
+            (c_terms.into_iter().next(), c_constant)
+        };
+        let q_c = self.sub(&self.mul(&a_constant, &b_constant), &c_constant);
+        let (c, q_o) = match c_term {
+            Some((wire, coefficient)) => (wire, self.neg(&coefficient)),
+            None => (0, BigInt::zero()),
+        };
+        match (a_term, b_term) {
+            // (ka·a + a0) · (kb·b + b0) - C
+            (Some((a, k_a)), Some((b, k_b))) => {
+                let gate = PlonkGate {
+                    a,
+                    b,
+                    c,
+                    q_l: self.mul(&k_a, &b_constant),
+                    q_r: self.mul(&a_constant, &k_b),
+                    q_o,
+                    q_m: self.mul(&k_a, &k_b),
+                    q_c,
+                };
+                self.gates.push(gate);
+            }
+            // One of the factors is a constant, so the constraint is linear
+            (a_term, b_term) => {
+                let mut terms = VecDeque::new();
+                if let Some((a, k_a)) = a_term {
+                    terms.push_back((a, self.mul(&k_a, &b_constant)));
+                }
+                if let Some((b, k_b)) = b_term {
+                    terms.push_back((b, self.mul(&a_constant, &k_b)));
+                }
+                if !q_o.is_zero() {
+                    terms.push_back((c, q_o));
+                }
+                self.add_linear(terms, q_c);
+            }
+        }
+    }
+
+    fn add_linear(&mut self, mut terms: VecDeque<Term>, constant: BigInt) {
+        terms.retain(|(_, coefficient)| !coefficient.is_zero());
+        self.chain(&mut terms, 3);
+        let mut slots = terms.into_iter();
+        let (a, q_l) = slots.next().unwrap_or((0, BigInt::zero()));
+        let (b, q_r) = slots.next().unwrap_or((0, BigInt::zero()));
+        let (c, q_o) = slots.next().unwrap_or((0, BigInt::zero()));
+        self.gates.push(PlonkGate { a, b, c, q_l, q_r, q_o, q_m: BigInt::zero(), q_c: constant });
+    }
+
+    // Adds the terms two by two into auxiliary wires until at most limit remain
+    fn chain(&mut self, terms: &mut VecDeque<Term>, limit: usize) {
+        while terms.len() > limit {
+            let (a, q_l) = terms.pop_front().unwrap();
+            let (b, q_r) = terms.pop_front().unwrap();
+            let c = self.new_auxiliary();
+            let q_o = self.neg(&BigInt::one());
+            self.gates.push(PlonkGate { a, b, c, q_l, q_r, q_o, q_m: BigInt::zero(), q_c: BigInt::zero() });
+            terms.push_front((c, BigInt::one()));
+        }
+    }
+
+    // Reduces a linear combination to k·wire + constant, defining an
+    // auxiliary wire as the sum of its terms when it has more than one
+    fn single_wire(&mut self, linear_combination: &HashMap<usize, BigInt>) -> (Option<Term>, BigInt) {
+        let (terms, constant) = self.split(linear_combination);
+        if terms.len() <= 1 {
+            return (terms.into_iter().next(), constant);
+        }
+        let mut terms: VecDeque<Term> = terms.into();
+        self.chain(&mut terms, 2);
+        let auxiliary = self.new_auxiliary();
+        terms.push_back((auxiliary, self.neg(&BigInt::one())));
+        self.add_linear(terms, BigInt::zero());
+        (Some((auxiliary, BigInt::one())), constant)
+    }
+
+    fn new_auxiliary(&mut self) -> usize {
+        let wire = self.next_wire;
+        self.next_wire += 1;
+        // Its definition is the next gate
+        self.auxiliary_definitions.push(self.gates.len());
+        wire
+    }
+
+    fn split(&self, linear_combination: &HashMap<usize, BigInt>) -> (Vec<Term>, BigInt) {
+        let constant = linear_combination.get(&0).cloned().unwrap_or_else(BigInt::zero);
+        let mut terms: Vec<Term> = linear_combination
+            .iter()
+            .filter(|(wire, coefficient)| **wire != 0 && !coefficient.is_zero())
+            .map(|(wire, coefficient)| (*wire, coefficient.clone()))
+            .collect();
+        terms.sort_by_key(|(wire, _)| *wire);
+        (terms, constant)
+    }
+
+    fn mul(&self, left: &BigInt, right: &BigInt) -> BigInt {
+        modular_arithmetic::mul(left, right, self.field)
+    }
+
+    fn sub(&self, left: &BigInt, right: &BigInt) -> BigInt {
+        modular_arithmetic::sub(left, right, self.field)
+    }
+
+    fn neg(&self, value: &BigInt) -> BigInt {
+        modular_arithmetic::prefix_sub(value, self.field)
+    }
+}
+
+// The positions of each wire form a cycle. Positions whose selector is zero
+// do not constrain their wire, so they are fixed points of the permutation.
+fn build_permutation(gates: &[PlonkGate], stats: &mut PlonkStats) -> Vec<usize> {
+    let rows = gates.len();
+    let mut permutation: Vec<usize> = (0..3 * rows).collect();
+    let mut positions: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (row, gate) in gates.iter().enumerate() {
+        let used_a = !gate.q_l.is_zero() || !gate.q_m.is_zero();
+        let used_b = !gate.q_r.is_zero() || !gate.q_m.is_zero();
+        let used_c = !gate.q_o.is_zero();
+        for (column, (wire, used)) in [(gate.a, used_a), (gate.b, used_b), (gate.c, used_c)].iter().enumerate() {
+            if *used {
+                positions.entry(*wire).or_default().push(column * rows + row);
+            }
+        }
+    }
+    for cycle in positions.values() {
+        if cycle.len() > 1 {
+            stats.shared_wires += 1;
+            stats.copy_constraints += cycle.len() - 1;
+        }
+        stats.longest_cycle = std::cmp::max(stats.longest_cycle, cycle.len());
+        for (index, position) in cycle.iter().enumerate() {
+            permutation[*position] = cycle[(index + 1) % cycle.len()];
+        }
+    }
+    permutation
+}
+
+pub fn build_plonk<F: field_element::FieldElement>(list: &ConstraintList<F>) -> (PlonkData, PlonkStats) {
+    let field_size = if list.field.bits().is_multiple_of(64) {
+        list.field.bits() / 8
+    } else {
+        (list.field.bits() / 64 + 1) * 8
+    };
+    let witness_wires = ConstraintList::no_wires(list);
+    let mut builder = GateBuilder::new(&list.field, witness_wires);
+    for c_id in list.constraints.get_ids() {
+        let c: C = list.constraints.read_constraint(c_id).unwrap().convert_field(&list.field);
+        let c = C::apply_correspondence(&c, &list.signal_map);
+        builder.add_constraint(&c);
+    }
+    let mut stats = PlonkStats {
+        gates: builder.gates.len(),
+        multiplication_gates: builder.gates.iter().filter(|gate| !gate.q_m.is_zero()).count(),
+        auxiliary_wires: builder.auxiliary_definitions.len(),
+        ..PlonkStats::default()
+    };
+    let permutation = build_permutation(&builder.gates, &mut stats);
+    let data = PlonkData {
+        field: list.field.clone(),
+        field_size,
+        witness_wires,
+        public_outputs: list.no_public_outputs,
+        public_inputs: list.no_public_inputs,
+        private_inputs: list.no_private_inputs_witness,
+        gates: builder.gates,
+        permutation,
+        auxiliary_definitions: builder.auxiliary_definitions,
+    };
+    (data, stats)
+}
+
+pub fn port_plonk<F: field_element::FieldElement>(list: &ConstraintList<F>, output: &str) -> Result<PlonkStats, ()> {
+    let (data, stats) = build_plonk(list);
+    plonk_writer::write_plonk(output, &data)?;
+    Result::Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_combination(terms: &[(usize, i64)]) -> HashMap<usize, BigInt> {
+        terms.iter().map(|(wire, value)| (*wire, BigInt::from(*value))).collect()
+    }
+
+    fn evaluate(gate: &PlonkGate, values: &[BigInt], field: &BigInt) -> BigInt {
+        let (a, b, c) = (&values[gate.a], &values[gate.b], &values[gate.c]);
+        let sum = &gate.q_l * a + &gate.q_r * b + &gate.q_o * c + &gate.q_m * a * b + &gate.q_c;
+        sum % field
+    }
+
+    // (in0 + in1 + 2) * (in2 - 1) = out + in3 + in4, with the witness
+    // [1, out, in0, in1, in2, in3, in4]
+    #[test]
+    fn gates_are_satisfied_by_the_extended_witness() {
+        let field = BigInt::from(101);
+        let constraint = C::new(
+            linear_combination(&[(2, 1), (3, 1), (0, 2)]),
+            linear_combination(&[(4, 1), (0, 100)]),
+            linear_combination(&[(1, 1), (5, 1), (6, 1)]),
+        );
+        let mut builder = GateBuilder::new(&field, 7);
+        builder.add_constraint(&constraint);
+        // in0 + in1 and out + in3 + in4 need auxiliary wires
+        assert_eq!(builder.auxiliary_definitions.len(), 3);
+        assert_eq!(builder.gates.iter().filter(|gate| !gate.q_m.is_zero()).count(), 1);
+
+        let (in0, in1, in2, in3) = (3, 4, 6, 10);
+        let out = (in0 + in1 + 2) * (in2 - 1) - in3 - 20;
+        let mut values: Vec<BigInt> = [1, out, in0, in1, in2, in3, 20].iter().map(|v| BigInt::from(*v)).collect();
+        for (index, gate) in builder.auxiliary_definitions.iter().enumerate() {
+            let gate = &builder.gates[*gate];
+            assert_eq!(gate.c, values.len());
+            assert!(gate.q_m.is_zero());
+            values.push(BigInt::zero());
+            // qO = -1, so the wire is the rest of the gate
+            assert_eq!(gate.q_o, &field - 1);
+            values[7 + index] = evaluate(gate, &values, &field);
+        }
+        for gate in &builder.gates {
+            assert!(evaluate(gate, &values, &field).is_zero());
+        }
+
+        let mut stats = PlonkStats::default();
+        let permutation = build_permutation(&builder.gates, &mut stats);
+        let rows = builder.gates.len();
+        for (position, next) in permutation.iter().enumerate() {
+            let wire = |p: usize| {
+                let gate = &builder.gates[p % rows];
+                [gate.a, gate.b, gate.c][p / rows]
+            };
+            assert_eq!(wire(position), wire(*next));
+        }
+        assert_eq!(stats.copy_constraints, 3);
+    }
+}
@@ -1,7 +1,8 @@
-use super::{ConstraintList, C, EncodingIterator, SignalMap};
-use constraint_writers::r1cs_writer::{ConstraintSection, CustomGatesAppliedData, HeaderData, R1CSWriter, SignalSection};
+use super::{ConstraintList, EncodingIterator, SignalMap};
+use circom_algebra::field_element::FieldElement;
+use constraint_writers::r1cs_writer::{CustomGatesAppliedData, HeaderData, R1CSWriter, SignalSection};
 
-pub fn port_r1cs(list: &ConstraintList, output: &str, custom_gates: bool) -> Result<(), ()> {
+pub fn port_r1cs<F: FieldElement + Sync>(list: &ConstraintList<F>, output: &str, custom_gates: bool) -> Result<(), ()> {
     use constraint_writers::log_writer::Log;
     let field_size = if list.field.bits() % 64 == 0 {
         list.field.bits() / 8
@@ -18,18 +19,15 @@ pub fn port_r1cs(list: &ConstraintList, output: &str, custom_gates: bool) -> Res
 
     let r1cs = R1CSWriter::new(output.to_string(), field_size, custom_gates)?;
     let mut constraint_section = R1CSWriter::start_constraints_section(r1cs)?;
-    let mut written = 0;
-
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    constraint_section.write_constraint_storage(&list.constraints, &list.field, &list.signal_map, threads)?;
+    let written = constraint_section.constraints_written();
     for c_id in list.constraints.get_ids() {
-        let c = list.constraints.read_constraint(c_id).unwrap();
-        let c = C::apply_correspondence(&c, &list.signal_map);
-        ConstraintSection::write_constraint_usize(&mut constraint_section, c.a(), c.b(), c.c())?;
-        if C::is_linear(&c) {
+        if list.constraints.is_linear(c_id) {
             log.no_linear += 1;
         } else {
             log.no_non_linear += 1;
         }
-        written += 1;
     }
 
     let r1cs = constraint_section.end_section()?;
@@ -1,8 +1,10 @@
 use super::{ConstraintList, EncodingIterator, IteratorSignal, SignalMap};
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_traits::AsPrimitive;
+use constraint_writers::sym_index::SymIndexWriter;
 use constraint_writers::sym_writer::*;
 
-pub fn port_sym(list: &ConstraintList, file_name: &str) -> Result<(), ()> {
+pub fn port_sym<F: FieldElement>(list: &ConstraintList<F>, file_name: &str) -> Result<(), ()> {
     let iter = EncodingIterator::new(&list.dag_encoding);
     let mut dot_sym = SymFile::new(file_name)?;
     signal_iteration(iter, &list.signal_map, &mut dot_sym)?;
@@ -20,13 +22,7 @@ pub fn signal_iteration(
 
     for signal in signals {
         let signal = IteratorSignal::new(signal, map);
-        let sym_elem = SymElem {
-            original: signal.original.as_(),
-            witness: if signal.witness == map.len() { -1 } else { signal.witness.as_() },
-            node_id: iter.node_id.as_(),
-            symbol: signal.name.clone(),
-        };
-        SymFile::write_sym_elem(dot_sym, sym_elem)?;
+        SymFile::write_sym_elem(dot_sym, sym_elem(&signal, iter.node_id, map))?;
     }
 
     for edge in EncodingIterator::edges(&iter) {
@@ -35,3 +31,35 @@ pub fn signal_iteration(
     }
     Ok(())
 }
+
+fn sym_elem(signal: &IteratorSignal, node_id: usize, map: &SignalMap) -> SymElem {
+    SymElem {
+        original: signal.original.as_(),
+        witness: if signal.witness == map.len() { -1 } else { signal.witness.as_() },
+        node_id: node_id.as_(),
+        symbol: signal.name.clone(),
+    }
+}
+
+pub fn port_sym_index<F: FieldElement>(list: &ConstraintList<F>, file_name: &str) -> Result<(), ()> {
+    let iter = EncodingIterator::new(&list.dag_encoding);
+    let mut index = SymIndexWriter::new();
+    index_iteration(iter, &list.signal_map, &mut index);
+    index.write(file_name)
+}
+
+fn index_iteration(mut iter: EncodingIterator, map: &SignalMap, index: &mut SymIndexWriter) {
+    let (signals, _) = EncodingIterator::take(&mut iter);
+    let first = signals.iter().map(|signal| signal.id).min().unwrap_or(0);
+    let end = signals.iter().map(|signal| signal.id).max().map_or(first, |last| last + 1);
+    for signal in signals {
+        let signal = IteratorSignal::new(signal, map);
+        index.add_signal(sym_elem(&signal, iter.node_id, map));
+    }
+    index.add_component(iter.path.clone(), first..end);
+
+    for edge in EncodingIterator::edges(&iter) {
+        let next = EncodingIterator::next(&iter, edge);
+        index_iteration(next, map, index);
+    }
+}
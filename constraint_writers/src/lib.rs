@@ -1,12 +1,17 @@
 pub mod debug_writer;
 pub mod json_writer;
 pub mod log_writer;
+pub mod plonk_writer;
 pub mod r1cs_writer;
 pub mod sym_writer;
+pub mod sym_index;
 pub mod r1cs_reader;
+pub mod sym_reader;
+pub mod wtns;
 
 pub trait ConstraintExporter {
     fn r1cs(&self, out: &str, custom_gates: bool) -> Result<(), ()>;
     fn json_constraints(&self, writer: &debug_writer::DebugWriter) -> Result<(), ()>;
     fn sym(&self, out: &str) -> Result<(), ()>;
+    fn sym_index(&self, out: &str) -> Result<(), ()>;
 }
@@ -0,0 +1,78 @@
+use circom_algebra::num_bigint::BigInt;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const MAGIC: &[u8] = b"plnk";
+const VERSION: u32 = 1;
+
+// qL·a + qR·b + qO·c + qM·a·b + qC = 0
+pub struct PlonkGate {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub q_l: BigInt,
+    pub q_r: BigInt,
+    pub q_o: BigInt,
+    pub q_m: BigInt,
+    pub q_c: BigInt,
+}
+
+pub struct PlonkData {
+    pub field: BigInt,
+    pub field_size: usize,
+    // Including the wire 0, which holds the constant 1
+    pub witness_wires: usize,
+    pub public_outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+    pub gates: Vec<PlonkGate>,
+    // Position column * gates + row -> next position of the same wire
+    pub permutation: Vec<usize>,
+    // Auxiliary wire witness_wires + i -> gate that defines it
+    pub auxiliary_definitions: Vec<usize>,
+}
+
+fn write_u32(writer: &mut BufWriter<File>, value: usize) -> Result<(), ()> {
+    let value = u32::try_from(value).map_err(|_| {})?;
+    writer.write_all(&value.to_le_bytes()).map_err(|_| {})
+}
+
+fn write_field_element(writer: &mut BufWriter<File>, value: &BigInt, field_size: usize) -> Result<(), ()> {
+    let (_, mut bytes) = value.to_bytes_le();
+    if bytes.len() > field_size {
+        return Result::Err(());
+    }
+    bytes.resize(field_size, 0);
+    writer.write_all(&bytes).map_err(|_| {})
+}
+
+pub fn write_plonk(output: &str, data: &PlonkData) -> Result<(), ()> {
+    let file = File::create(output).map_err(|_| {})?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(MAGIC).map_err(|_| {})?;
+    writer.write_all(&VERSION.to_le_bytes()).map_err(|_| {})?;
+    write_u32(&mut writer, data.field_size)?;
+    write_field_element(&mut writer, &data.field, data.field_size)?;
+    write_u32(&mut writer, data.witness_wires)?;
+    write_u32(&mut writer, data.public_outputs)?;
+    write_u32(&mut writer, data.public_inputs)?;
+    write_u32(&mut writer, data.private_inputs)?;
+    write_u32(&mut writer, data.auxiliary_definitions.len())?;
+    write_u32(&mut writer, data.gates.len())?;
+    for gate in &data.gates {
+        write_u32(&mut writer, gate.a)?;
+        write_u32(&mut writer, gate.b)?;
+        write_u32(&mut writer, gate.c)?;
+        for selector in [&gate.q_l, &gate.q_r, &gate.q_o, &gate.q_m, &gate.q_c] {
+            write_field_element(&mut writer, selector, data.field_size)?;
+        }
+    }
+    for position in &data.permutation {
+        write_u32(&mut writer, *position)?;
+    }
+    for gate in &data.auxiliary_definitions {
+        write_u32(&mut writer, *gate)?;
+    }
+    writer.flush().map_err(|_| {})
+}
@@ -126,9 +126,9 @@ pub struct HeaderData {
 }
 
 
-type Constraint = HashMap<usize, BigInt>;
-type ConstraintList = Vec<(Constraint, Constraint, Constraint)>;
-type SignalList = Vec<usize>;
+pub type Constraint = HashMap<usize, BigInt>;
+pub type ConstraintList = Vec<(Constraint, Constraint, Constraint)>;
+pub type SignalList = Vec<usize>;
 pub struct ConstraintSection {
     reader: BufReader<File>,
     number_of_constraints: usize,
@@ -419,12 +419,13 @@ impl CustomGatesAppliedSection {
 
 //This struct contained all the sections
 pub struct R1CSData {
-    header_data: HeaderData,
-    constraints: ConstraintList,
-    signals: SignalList,
-    custom_gates: bool,
-    custom_gates_used_data: Option<CustomGatesUsedData>,
-    custom_gates_applied_data: Option<CustomGatesAppliedData>,
+    pub header_data: HeaderData,
+    pub constraints: ConstraintList,
+    // Label of each wire
+    pub signals: SignalList,
+    pub custom_gates: bool,
+    pub custom_gates_used_data: Option<CustomGatesUsedData>,
+    pub custom_gates_applied_data: Option<CustomGatesAppliedData>,
 }
 
 impl R1CSData {
@@ -455,10 +456,31 @@ pub fn read_r1cs(input: &str) -> Result<R1CSData, std::io::Error> {
     let mut r1cs = R1CSReader::new(input.to_string())?;
 
     let buffer = read_initialization(&mut r1cs)?;
-
-    //compute the beginning of each section
     let n_sections = buffer[0] as usize;
     info.custom_gates = n_sections == 5;
+    let section_starts = read_section_starts(&mut r1cs, n_sections)?;
+
+    read_sections(&mut info, r1cs, section_starts)?;
+    Ok(info)
+}
+
+// Only reads the header section, without the constraints
+pub fn read_r1cs_header(input: &str) -> Result<HeaderData, std::io::Error> {
+    let mut r1cs = R1CSReader::new(input.to_string())?;
+    let buffer = read_initialization(&mut r1cs)?;
+    let section_starts = read_section_starts(&mut r1cs, buffer[0] as usize)?;
+    if let Some(&start) = section_starts.get(&1) {
+        r1cs.reader.seek(SeekFrom::Start(start))?;
+        let mut header_section = R1CSReader::start_header_section(r1cs)?;
+        header_section.read_section()
+    } else {
+        Err(std::io::Error::new(std::io::ErrorKind::Other,
+            R1CSParsingError::SectionNotPresent("Header".to_string()).to_string()))
+    }
+}
+
+//compute the beginning of each section
+fn read_section_starts(r1cs: &mut R1CSReader, n_sections: usize) -> Result<HashMap<u8, u64>, std::io::Error> {
     let mut current_offset = 0;
     let mut section_starts = HashMap::new();
     for _ in 0..n_sections{
@@ -474,9 +496,7 @@ pub fn read_r1cs(input: &str) -> Result<R1CSData, std::io::Error> {
          }
         current_offset = (section_size.to_u64().unwrap()) as i64;
     }
-
-    read_sections(&mut info, r1cs, section_starts)?;
-    Ok(info)
+    Ok(section_starts)
 }
 
 fn read_initialization(r1cs: &mut R1CSReader) -> Result<Vec<u8>, std::io::Error> {
@@ -1,7 +1,9 @@
+use circom_algebra::constraint_storage::{ConstraintID, ConstraintStorage};
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_bigint::BigInt;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
 
 const SECTIONS: u8 = 5;
 const MAGIC: &[u8] = b"r1cs";
@@ -12,6 +14,10 @@ const WIRE2LABEL_TYPE: &[u8] = &[3, 0, 0, 0];
 const CUSTOM_GATES_USED_TYPE: &[u8] = &[4, 0, 0, 0];
 const CUSTOM_GATES_APPLIED_TYPE: &[u8] = &[5, 0, 0, 0];
 const PLACE_HOLDER: &[u8] = &[3, 3, 3, 3, 3, 3, 3, 3];
+// Constraints encoded at once by each thread
+const CHUNK_SIZE: usize = 1 << 14;
+// Name in the file of each signal of the stored constraints
+pub type Rename<'a> = dyn Fn(usize) -> usize + Sync + 'a;
 
 fn into_format(number: &[u8], with_bytes: usize) -> (Vec<u8>, usize) {
     let mut value = number.to_vec();
@@ -27,7 +33,7 @@ fn bigint_as_bytes(number: &BigInt, with_bytes: usize) -> (Vec<u8>, usize) {
     into_format(&value, with_bytes)
 }
 
-fn initialize_section(writer: &mut BufWriter<File>, header: &[u8]) -> Result<u64, ()> {
+fn initialize_section<W: Write + Seek>(writer: &mut W, header: &[u8]) -> Result<u64, ()> {
     writer.write_all(header).map_err(|_err| {})?;
     //writer.flush().map_err(|_err| {})?;
     let go_back = writer.seek(SeekFrom::Current(0)).map_err(|_err| {})?;
@@ -36,7 +42,7 @@ fn initialize_section(writer: &mut BufWriter<File>, header: &[u8]) -> Result<u64
     Result::Ok(go_back)
 }
 
-fn end_section(writer: &mut BufWriter<File>, go_back: u64, size: usize) -> Result<(), ()> {
+fn end_section<W: Write + Seek>(writer: &mut W, go_back: u64, size: usize) -> Result<(), ()> {
     let go_back_1 = writer.seek(SeekFrom::Current(0)).map_err(|_err| {})?;
     writer.seek(SeekFrom::Start(go_back)).map_err(|_err| {})?;
     let (stream, _) = bigint_as_bytes(&BigInt::from(size), 8);
@@ -46,51 +52,52 @@ fn end_section(writer: &mut BufWriter<File>, go_back: u64, size: usize) -> Resul
     Result::Ok(())
 }
 
-fn obtain_linear_combination_block<T>(
-    linear_combination: &HashMap<T, BigInt>,
-    field_size: usize,
-) -> (Vec<u8>, usize) where T: AsRef<[u8]> + std::cmp::Ord + std::hash::Hash {
-    let mut block = Vec::new();
-    let non_zero_factors = BigInt::from(linear_combination.len());
-    let mut size = 0;
-    let (stream, bytes) = bigint_as_bytes(&non_zero_factors, 4);
-    size += bytes;
-    block.extend_from_slice(&stream);
-    let mut order: Vec<&T> = linear_combination.keys().collect();
-    order.sort();
-    for i in order {
-        let (id, factor) = linear_combination.get_key_value(i).unwrap();
-        let (stream, bytes) = into_format(id.as_ref(), 4);
-        size += bytes;
-        block.extend_from_slice(&stream);
+// The little-endian bytes of a signal id, as BigInt::to_bytes_le gives them
+fn id_as_bytes(id: usize) -> ([u8; 8], usize) {
+    let length = std::cmp::max(1, (64 - id.leading_zeros() as usize).div_ceil(8));
+    (id.to_le_bytes(), length)
+}
 
-        let (stream, bytes) = bigint_as_bytes(factor, field_size);
-        size += bytes;
+// Appends the block of a linear combination: the number of factors and then
+// each signal with its factor, sorted by the little-endian bytes of the signals
+fn encode_linear_combination(block: &mut Vec<u8>, mut factors: Vec<(usize, &[u8])>) {
+    factors.sort_by(|(left, _), (right, _)| {
+        let (left, left_length) = id_as_bytes(*left);
+        let (right, right_length) = id_as_bytes(*right);
+        left[..left_length].cmp(&right[..right_length])
+    });
+    block.extend_from_slice(&(factors.len() as u32).to_le_bytes());
+    for (id, factor) in factors {
+        let (bytes, length) = id_as_bytes(id);
+        let (stream, _) = into_format(&bytes[..length], 4);
         block.extend_from_slice(&stream);
+        block.extend_from_slice(factor);
     }
-    (block, size)
 }
 
-fn write_constraint<T>(
-    file: &mut BufWriter<File>,
-    a: &HashMap<T, BigInt>,
-    b: &HashMap<T, BigInt>,
-    c: &HashMap<T, BigInt>,
-    field_size: usize,
-) -> Result<usize, ()> where T: AsRef<[u8]> + std::cmp::Ord + std::hash::Hash {
-    let (block_a, size_a) = obtain_linear_combination_block(a, field_size);
-    let (block_b, size_b) = obtain_linear_combination_block(b, field_size);
-    let (block_c, size_c) = obtain_linear_combination_block(c, field_size);
-    file.write_all(&block_a).map_err(|_err| {})?;
-    //file.flush().map_err(|_err| {})?;
-    file.write_all(&block_b).map_err(|_err| {})?;
-    //file.flush().map_err(|_err| {})?;
-    file.write_all(&block_c).map_err(|_err| {})?;
-    //file.flush().map_err(|_err| {})?;
-    Result::Ok(size_a + size_b + size_c)
+// Encodes the constraints of the storage with the given ids. The coefficients
+// are taken from the table of encoded constants of the storage
+fn encode_stored_constraints<F: FieldElement>(
+    storage: &ConstraintStorage<F>,
+    ids: &[ConstraintID],
+    constants: &[Vec<u8>],
+    rename: &Rename,
+) -> Vec<u8> {
+    let mut block = Vec::new();
+    for id in ids {
+        let (a, b, c) = storage.read_compressed(*id).unwrap();
+        for expression in [a, b, c] {
+            let factors = expression
+                .iter()
+                .map(|(constant, signal)| (rename(*signal), constants[*constant].as_slice()))
+                .collect();
+            encode_linear_combination(&mut block, factors);
+        }
+    }
+    block
 }
 
-fn initialize_file(writer: &mut BufWriter<File>, num_sections: u8) -> Result<(), ()> {
+fn initialize_file<W: Write>(writer: &mut W, num_sections: u8) -> Result<(), ()> {
     writer.write_all(MAGIC).map_err(|_err| {})?;
     //writer.flush().map_err(|_err| {})?;
     writer.write_all(VERSION).map_err(|_err| {})?;
@@ -100,14 +107,14 @@ fn initialize_file(writer: &mut BufWriter<File>, num_sections: u8) -> Result<(),
     Result::Ok(())
 }
 
-pub struct R1CSWriter {
+pub struct R1CSWriter<W = BufWriter<File>> {
     field_size: usize,
-    writer: BufWriter<File>,
+    writer: W,
     sections: [bool; SECTIONS as usize]
 }
 
-pub struct HeaderSection {
-    writer: BufWriter<File>,
+pub struct HeaderSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -115,8 +122,8 @@ pub struct HeaderSection {
     sections: [bool; SECTIONS as usize]
 }
 
-pub struct ConstraintSection {
-    writer: BufWriter<File>,
+pub struct ConstraintSection<W = BufWriter<File>> {
+    writer: W,
     number_of_constraints: usize,
     go_back: u64,
     size: usize,
@@ -125,8 +132,8 @@ pub struct ConstraintSection {
     sections: [bool; SECTIONS as usize]
 }
 
-pub struct SignalSection {
-    writer: BufWriter<File>,
+pub struct SignalSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -134,8 +141,8 @@ pub struct SignalSection {
     sections: [bool; SECTIONS as usize]
 }
 
-pub struct CustomGatesUsedSection {
-    writer: BufWriter<File>,
+pub struct CustomGatesUsedSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -143,8 +150,8 @@ pub struct CustomGatesUsedSection {
     sections: [bool; SECTIONS as usize]
 }
 
-pub struct CustomGatesAppliedSection {
-    writer: BufWriter<File>,
+pub struct CustomGatesAppliedSection<W = BufWriter<File>> {
+    writer: W,
     go_back: u64,
     size: usize,
     index: usize,
@@ -158,15 +165,26 @@ impl R1CSWriter {
         field_size: usize,
         custom_gates: bool
     ) -> Result<R1CSWriter, ()> {
+        let writer =
+            File::create(&output_file).map_err(|_err| {}).map(|f| BufWriter::new(f))?;
+        R1CSWriter::from_writer(writer, field_size, custom_gates)
+    }
+}
+
+impl<W: Write + Seek> R1CSWriter<W> {
+    // Writes the file to any seekable target, for example an in-memory buffer
+    pub fn from_writer(
+        mut writer: W,
+        field_size: usize,
+        custom_gates: bool
+    ) -> Result<R1CSWriter<W>, ()> {
         let sections = [false; SECTIONS as usize];
         let num_sections: u8 = if custom_gates { 5 } else { 3 };
-        let mut writer =
-            File::create(&output_file).map_err(|_err| {}).map(|f| BufWriter::new(f))?;
         initialize_file(&mut writer, num_sections)?;
         Result::Ok(R1CSWriter { writer, sections, field_size })
     }
 
-    pub fn start_header_section(mut r1cs: R1CSWriter) -> Result<HeaderSection, ()> {
+    pub fn start_header_section(mut r1cs: R1CSWriter<W>) -> Result<HeaderSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, HEADER_TYPE)?;
         Result::Ok(HeaderSection {
             writer: r1cs.writer,
@@ -178,7 +196,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_constraints_section(mut r1cs: R1CSWriter) -> Result<ConstraintSection, ()> {
+    pub fn start_constraints_section(mut r1cs: R1CSWriter<W>) -> Result<ConstraintSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CONSTRAINT_TYPE)?;
         Result::Ok(ConstraintSection {
             number_of_constraints: 0,
@@ -191,7 +209,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_signal_section(mut r1cs: R1CSWriter) -> Result<SignalSection, ()> {
+    pub fn start_signal_section(mut r1cs: R1CSWriter<W>) -> Result<SignalSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, WIRE2LABEL_TYPE)?;
         Result::Ok(SignalSection {
             writer: r1cs.writer,
@@ -203,7 +221,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_custom_gates_used_section(mut r1cs: R1CSWriter) -> Result<CustomGatesUsedSection, ()> {
+    pub fn start_custom_gates_used_section(mut r1cs: R1CSWriter<W>) -> Result<CustomGatesUsedSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CUSTOM_GATES_USED_TYPE)?;
         Result::Ok(CustomGatesUsedSection {
             writer: r1cs.writer,
@@ -215,7 +233,7 @@ impl R1CSWriter {
         })
     }
 
-    pub fn start_custom_gates_applied_section(mut r1cs: R1CSWriter) -> Result<CustomGatesAppliedSection, ()> {
+    pub fn start_custom_gates_applied_section(mut r1cs: R1CSWriter<W>) -> Result<CustomGatesAppliedSection<W>, ()> {
         let start = initialize_section(&mut r1cs.writer, CUSTOM_GATES_APPLIED_TYPE)?;
         Result::Ok(CustomGatesAppliedSection {
             writer: r1cs.writer,
@@ -227,9 +245,27 @@ impl R1CSWriter {
         })
     }
 
-    pub fn finish_writing(mut r1cs: R1CSWriter) -> Result<(), ()> {
+    pub fn finish_writing(mut r1cs: R1CSWriter<W>) -> Result<(), ()> {
 	r1cs.writer.flush().map_err(|_err| {})
     }
+
+    // Finishes the file and gives back the target it was written to
+    pub fn into_inner(mut r1cs: R1CSWriter<W>) -> Result<W, ()> {
+        r1cs.writer.flush().map_err(|_err| {})?;
+        Result::Ok(r1cs.writer)
+    }
+
+    // Finishes the file and copies it to a target that can not seek, like a
+    // pipe, when it was written to a temporary file
+    pub fn copy_to<T: Write>(r1cs: R1CSWriter<W>, target: &mut T) -> Result<(), ()>
+    where
+        W: Read,
+    {
+        let mut writer = R1CSWriter::into_inner(r1cs)?;
+        writer.seek(SeekFrom::Start(0)).map_err(|_err| {})?;
+        std::io::copy(&mut writer, target).map_err(|_err| {})?;
+        target.flush().map_err(|_err| {})
+    }
 }
 
 pub struct HeaderData {
@@ -242,7 +278,7 @@ pub struct HeaderData {
     pub number_of_constraints: usize,
 }
 
-impl HeaderSection {
+impl<W: Write + Seek> HeaderSection<W> {
     pub fn write_section(&mut self, data: HeaderData) -> Result<(), ()> {
         let (field_stream, bytes_field) = bigint_as_bytes(&data.field, self.field_size);
         let (length_stream, bytes_size) = bigint_as_bytes(&BigInt::from(self.field_size), 4);
@@ -268,7 +304,7 @@ impl HeaderSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -278,36 +314,91 @@ impl HeaderSection {
 }
 
 type Constraint = HashMap<usize, BigInt>;
-impl ConstraintSection {
+impl<W: Write + Seek> ConstraintSection<W> {
     pub fn write_constraint_usize(
         &mut self,
         a: &Constraint,
         b: &Constraint,
         c: &Constraint,
     ) -> Result<(), ()> {
-        let field_size = self.field_size;
-        let mut r1cs_a = HashMap::new();
-        for (k, v) in a {
-            let (_, bytes) = BigInt::from(*k).to_bytes_le();
-            r1cs_a.insert(bytes, v.clone());
-        }
-        let mut r1cs_b = HashMap::new();
-        for (k, v) in b {
-            let (_, bytes) = BigInt::from(*k).to_bytes_le();
-            r1cs_b.insert(bytes, v.clone());
-        }
-        let mut r1cs_c = HashMap::new();
-        for (k, v) in c {
-            let (_, bytes) = BigInt::from(*k).to_bytes_le();
-            r1cs_c.insert(bytes, v.clone());
+        let mut block = Vec::new();
+        for expression in [a, b, c] {
+            let factors: Vec<_> =
+                expression.iter().map(|(k, v)| (*k, bigint_as_bytes(v, self.field_size).0)).collect();
+            let factors = factors.iter().map(|(k, v)| (*k, v.as_slice())).collect();
+            encode_linear_combination(&mut block, factors);
         }
-        let size = write_constraint(&mut self.writer, &r1cs_a, &r1cs_b, &r1cs_c, field_size)?;
-        self.size += size;
+        self.writer.write_all(&block).map_err(|_err| {})?;
+        self.size += block.len();
         self.number_of_constraints += 1;
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    // Writes the constraints of the storage without decoding them: every
+    // coefficient is encoded once and the signals are renamed with the given
+    // correspondence
+    pub fn write_constraint_storage<F: FieldElement + Sync>(
+        &mut self,
+        storage: &ConstraintStorage<F>,
+        field: &BigInt,
+        correspondence: &HashMap<usize, usize>,
+        threads: usize,
+    ) -> Result<(), ()> {
+        let constants = self.encode_constants(storage, field);
+        let rename = |signal: usize| {
+            if signal == 0 {
+                0
+            } else {
+                *correspondence.get(&signal).unwrap_or_else(|| panic!("Unknown signal: {}", signal))
+            }
+        };
+        self.write_stored_constraints(storage, &storage.get_ids(), &constants, &rename, threads)
+    }
+
+    // Coefficients of the storage as they are written in the file, by their id
+    pub fn encode_constants<F: FieldElement>(&self, storage: &ConstraintStorage<F>, field: &BigInt) -> Vec<Vec<u8>> {
+        (0..storage.no_constants())
+            .map(|id| {
+                let value = F::from_raw(storage.raw_constant(id).unwrap()).to_bigint(field);
+                bigint_as_bytes(&value, self.field_size).0
+            })
+            .collect()
+    }
+
+    // Writes the constraints of the storage with the given ids, the constants
+    // are the ones given by encode_constants. With more than one thread,
+    // chunks of constraints are encoded in parallel and written in order.
+    pub fn write_stored_constraints<F: FieldElement + Sync>(
+        &mut self,
+        storage: &ConstraintStorage<F>,
+        ids: &[ConstraintID],
+        constants: &[Vec<u8>],
+        rename: &Rename,
+        threads: usize,
+    ) -> Result<(), ()> {
+        let threads = std::cmp::max(threads, 1);
+        for round in ids.chunks(CHUNK_SIZE * threads) {
+            let blocks: Vec<Vec<u8>> = if threads == 1 || round.len() <= CHUNK_SIZE {
+                vec![encode_stored_constraints(storage, round, constants, rename)]
+            } else {
+                std::thread::scope(|scope| {
+                    let workers: Vec<_> = round
+                        .chunks(CHUNK_SIZE)
+                        .map(|chunk| scope.spawn(move || encode_stored_constraints(storage, chunk, constants, rename)))
+                        .collect();
+                    workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+                })
+            };
+            for block in blocks {
+                self.writer.write_all(&block).map_err(|_err| {})?;
+                self.size += block.len();
+            }
+            self.number_of_constraints += round.len();
+        }
+        Result::Ok(())
+    }
+
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -324,7 +415,7 @@ impl ConstraintSection {
     }
 }
 
-impl SignalSection {
+impl<W: Write + Seek> SignalSection<W> {
     pub fn write_signal<T>(
         &mut self,
         bytes: &T
@@ -340,7 +431,7 @@ impl SignalSection {
         SignalSection::write_signal(self, &as_bytes)
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -354,7 +445,7 @@ impl SignalSection {
 }
 
 pub type CustomGatesUsedData = Vec<(String, Vec<BigInt>)>;
-impl CustomGatesUsedSection {
+impl<W: Write + Seek> CustomGatesUsedSection<W> {
     pub fn write_custom_gates_usages(&mut self, data: CustomGatesUsedData) -> Result<(), ()> {
         let no_custom_gates = data.len();
         let (no_custom_gates_stream, no_custom_gates_size) =
@@ -390,7 +481,7 @@ impl CustomGatesUsedSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -404,7 +495,7 @@ impl CustomGatesUsedSection {
 }
 
 pub type CustomGatesAppliedData = Vec<(usize, Vec<usize>)>;
-impl CustomGatesAppliedSection {
+impl<W: Write + Seek> CustomGatesAppliedSection<W> {
     pub fn write_custom_gates_applications(&mut self, data: CustomGatesAppliedData) -> Result<(), ()> {
         let no_custom_gate_applications = data.len();
         let (no_custom_gate_applications_stream, no_custom_gate_applications_size) =
@@ -440,7 +531,7 @@ impl CustomGatesAppliedSection {
         Result::Ok(())
     }
 
-    pub fn end_section(mut self) -> Result<R1CSWriter, ()> {
+    pub fn end_section(mut self) -> Result<R1CSWriter<W>, ()> {
         end_section(&mut self.writer, self.go_back, self.size)?;
         let mut sections = self.sections;
         let index = self.index;
@@ -452,3 +543,52 @@ impl CustomGatesAppliedSection {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use circom_algebra::algebra::Constraint as StoredConstraint;
+    use std::io::Cursor;
+
+    fn write_constraints(constraints: &[StoredConstraint<usize>], threads: Option<usize>) -> Vec<u8> {
+        let field = BigInt::from(7919);
+        let correspondence: HashMap<usize, usize> = (1..400).map(|signal| (signal, 400 - signal)).collect();
+        let r1cs = R1CSWriter::from_writer(Cursor::new(Vec::new()), 8, false).unwrap();
+        let mut section = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        if let Some(threads) = threads {
+            let mut storage = ConstraintStorage::new();
+            for constraint in constraints {
+                storage.add_constraint(constraint.clone());
+            }
+            section.write_constraint_storage(&storage, &field, &correspondence, threads).unwrap();
+        } else {
+            for constraint in constraints {
+                let constraint = StoredConstraint::apply_correspondence(constraint, &correspondence);
+                section.write_constraint_usize(constraint.a(), constraint.b(), constraint.c()).unwrap();
+            }
+        }
+        assert_eq!(section.constraints_written(), constraints.len());
+        let r1cs = section.end_section().unwrap();
+        R1CSWriter::into_inner(r1cs).unwrap().into_inner()
+    }
+
+    #[test]
+    fn stored_constraints_are_written_as_decoded_ones() {
+        let mut constraints = Vec::new();
+        // more than two chunks, so that the threads write some of them
+        for i in 1..(2 * CHUNK_SIZE / 100 + 2) {
+            let signal = i % 399 + 1;
+            let a = HashMap::from([(signal, BigInt::from(i)), (0, BigInt::from(3))]);
+            let b = HashMap::from([(399 - signal + 1, BigInt::from(1))]);
+            let c = HashMap::from([(signal, BigInt::from(7918)), (250, BigInt::from(i * 31 % 7919))]);
+            for _ in 0..100 {
+                constraints.push(StoredConstraint::new(a.clone(), b.clone(), c.clone()));
+            }
+        }
+        let decoded = write_constraints(&constraints, None);
+        assert_eq!(decoded, write_constraints(&constraints, Some(1)));
+        assert_eq!(decoded, write_constraints(&constraints, Some(4)));
+        let size = decoded.len() - MAGIC.len() - VERSION.len() - 4 - CONSTRAINT_TYPE.len() - PLACE_HOLDER.len();
+        assert_eq!(decoded[16..24], (size as u64).to_le_bytes());
+    }
+}
@@ -0,0 +1,437 @@
+use super::sym_writer::SymElem;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Range;
+
+// Layout of a symidx file, all numbers in little endian:
+//   header: magic, version and the sizes of the sections as u64
+//   signals: one record per signal sorted by name
+//     (name offset, name length, original, witness, node id)
+//   components: one record per component sorted by path
+//     (path offset, path length, first signal, end of the signals)
+//   wires: the position in signals of the signal of each wire
+//   originals: the position in signals of each signal
+//   strings: the names and the paths
+const MAGIC: &[u8] = b"sidx";
+const VERSION: u32 = 1;
+const HEADER_SIZE: usize = 48;
+const SIGNAL_SIZE: usize = 40;
+const COMPONENT_SIZE: usize = 32;
+const ENTRY_SIZE: usize = 8;
+const NO_SIGNAL: u64 = u64::MAX;
+
+pub struct SymIndexWriter {
+    signals: Vec<SymElem>,
+    components: Vec<(String, Range<usize>)>,
+}
+
+impl SymIndexWriter {
+    pub fn new() -> SymIndexWriter {
+        SymIndexWriter { signals: Vec::new(), components: Vec::new() }
+    }
+
+    pub fn add_signal(&mut self, elem: SymElem) {
+        self.signals.push(elem);
+    }
+
+    // The signals of the component itself, without the ones of its subcomponents
+    pub fn add_component(&mut self, path: String, signals: Range<usize>) {
+        self.components.push((path, signals));
+    }
+
+    pub fn write(self, file: &str) -> Result<(), ()> {
+        let file = File::create(file).map_err(|_err| {})?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&self.encode()).map_err(|_err| {})?;
+        writer.flush().map_err(|_err| {})
+    }
+
+    pub fn encode(mut self) -> Vec<u8> {
+        self.signals.sort_by(|left, right| left.symbol.cmp(&right.symbol));
+        self.components.sort_by(|left, right| left.0.cmp(&right.0));
+        let position = |number: i64| if number < 0 { None } else { Some(number as usize) };
+        let no_wires = self.signals.iter().filter_map(|s| position(s.witness)).max().map_or(0, |w| w + 1);
+        let no_originals = self.signals.iter().filter_map(|s| position(s.original)).max().map_or(0, |o| o + 1);
+        let mut wires = vec![NO_SIGNAL; no_wires];
+        let mut originals = vec![NO_SIGNAL; no_originals];
+        let mut strings = Vec::new();
+        let mut signals = Vec::with_capacity(self.signals.len() * SIGNAL_SIZE);
+        for (index, signal) in self.signals.iter().enumerate() {
+            if let Some(wire) = position(signal.witness) {
+                wires[wire] = index as u64;
+            }
+            if let Some(original) = position(signal.original) {
+                originals[original] = index as u64;
+            }
+            push_u64(&mut signals, strings.len() as u64);
+            push_u64(&mut signals, signal.symbol.len() as u64);
+            push_u64(&mut signals, signal.original as u64);
+            push_u64(&mut signals, signal.witness as u64);
+            push_u64(&mut signals, signal.node_id as u64);
+            strings.extend_from_slice(signal.symbol.as_bytes());
+        }
+        let mut components = Vec::with_capacity(self.components.len() * COMPONENT_SIZE);
+        for (path, range) in &self.components {
+            push_u64(&mut components, strings.len() as u64);
+            push_u64(&mut components, path.len() as u64);
+            push_u64(&mut components, range.start as u64);
+            push_u64(&mut components, range.end as u64);
+            strings.extend_from_slice(path.as_bytes());
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        for size in [self.signals.len(), self.components.len(), no_wires, no_originals, strings.len()] {
+            push_u64(&mut bytes, size as u64);
+        }
+        bytes.extend_from_slice(&signals);
+        bytes.extend_from_slice(&components);
+        for entry in wires.iter().chain(originals.iter()) {
+            push_u64(&mut bytes, *entry);
+        }
+        bytes.extend_from_slice(&strings);
+        bytes
+    }
+}
+
+impl Default for SymIndexWriter {
+    fn default() -> Self {
+        SymIndexWriter::new()
+    }
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn invalid_index(reason: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid symidx file: {}", reason))
+}
+
+pub struct IndexedSignal<'a> {
+    pub name: &'a str,
+    pub original: i64,
+    pub witness: i64,
+    pub node_id: i64,
+}
+
+impl IndexedSignal<'_> {
+    pub fn wire(&self) -> Option<usize> {
+        if self.witness < 0 {
+            None
+        } else {
+            Some(self.witness as usize)
+        }
+    }
+
+    pub fn to_sym_elem(&self) -> SymElem {
+        SymElem {
+            original: self.original,
+            witness: self.witness,
+            node_id: self.node_id,
+            symbol: self.name.to_string(),
+        }
+    }
+}
+
+// The file is kept as it was read and every lookup is a binary search on it
+pub struct SymIndex {
+    bytes: Vec<u8>,
+    no_signals: usize,
+    no_components: usize,
+    no_wires: usize,
+    no_originals: usize,
+    strings: usize,
+}
+
+impl SymIndex {
+    pub fn read(input: &str) -> Result<SymIndex, std::io::Error> {
+        let mut bytes = Vec::new();
+        File::open(input)?.read_to_end(&mut bytes)?;
+        SymIndex::from_bytes(bytes)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<SymIndex, std::io::Error> {
+        if bytes.len() < HEADER_SIZE || &bytes[0..4] != MAGIC {
+            return Err(invalid_index("wrong magic number"));
+        }
+        if bytes[4..8] != VERSION.to_le_bytes() {
+            return Err(invalid_index("unknown version"));
+        }
+        let size = |index: usize| read_u64(&bytes, 8 + 8 * index) as usize;
+        let mut index = SymIndex {
+            no_signals: size(0),
+            no_components: size(1),
+            no_wires: size(2),
+            no_originals: size(3),
+            strings: 0,
+            bytes: Vec::new(),
+        };
+        let sections = [
+            (index.no_signals, SIGNAL_SIZE),
+            (index.no_components, COMPONENT_SIZE),
+            (index.no_wires, ENTRY_SIZE),
+            (index.no_originals, ENTRY_SIZE),
+            (size(4), 1),
+        ];
+        let mut total = Some(HEADER_SIZE);
+        for (length, record_size) in sections {
+            total = total.and_then(|total| length.checked_mul(record_size)?.checked_add(total));
+        }
+        if total != Some(bytes.len()) {
+            return Err(invalid_index("wrong size"));
+        }
+        index.strings = bytes.len() - size(4);
+        index.bytes = bytes;
+        index.check()?;
+        Ok(index)
+    }
+
+    // Checks once that the names are in the strings and that the tables point
+    // to signals, so that the lookups can not fail
+    fn check(&self) -> Result<(), std::io::Error> {
+        let strings = &self.bytes[self.strings..];
+        let valid_string = |offset: usize, length: usize| {
+            offset.checked_add(length).is_some_and(|end| end <= strings.len())
+                && std::str::from_utf8(&strings[offset..offset + length]).is_ok()
+        };
+        for signal in 0..self.no_signals {
+            let record = self.signal_record(signal);
+            if !valid_string(self.u64_at(record) as usize, self.u64_at(record + 8) as usize) {
+                return Err(invalid_index("wrong signal name"));
+            }
+        }
+        for component in 0..self.no_components {
+            let record = self.component_record(component);
+            if !valid_string(self.u64_at(record) as usize, self.u64_at(record + 8) as usize) {
+                return Err(invalid_index("wrong component path"));
+            }
+        }
+        for entry in 0..(self.no_wires + self.no_originals) {
+            let signal = self.u64_at(self.wires_start() + entry * ENTRY_SIZE);
+            if signal != NO_SIGNAL && signal as usize >= self.no_signals {
+                return Err(invalid_index("wrong signal position"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.no_signals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.no_signals == 0
+    }
+
+    // The signals sorted by name
+    pub fn signals(&self) -> impl Iterator<Item = IndexedSignal<'_>> {
+        (0..self.no_signals).map(move |position| self.signal_at(position))
+    }
+
+    pub fn signal(&self, name: &str) -> Option<IndexedSignal<'_>> {
+        let position = self.partition_point(|other| other < name);
+        if position < self.no_signals && self.name_at(position) == name {
+            Some(self.signal_at(position))
+        } else {
+            None
+        }
+    }
+
+    pub fn wire(&self, name: &str) -> Option<usize> {
+        self.signal(name).and_then(|signal| signal.wire())
+    }
+
+    pub fn signal_of_wire(&self, wire: usize) -> Option<IndexedSignal<'_>> {
+        self.table_entry(self.wires_start(), self.no_wires, wire).map(|position| self.signal_at(position))
+    }
+
+    pub fn name_of_wire(&self, wire: usize) -> Option<&str> {
+        self.signal_of_wire(wire).map(|signal| signal.name)
+    }
+
+    pub fn signal_of_original(&self, original: usize) -> Option<IndexedSignal<'_>> {
+        let start = self.wires_start() + self.no_wires * ENTRY_SIZE;
+        self.table_entry(start, self.no_originals, original).map(|position| self.signal_at(position))
+    }
+
+    // The signal numbers of the own signals of the component, which are
+    // consecutive. They are not wires: the wires are given by signal_of_original
+    // or wires_of_component. The signals of its subcomponents are found with
+    // query("path.*")
+    pub fn component(&self, path: &str) -> Option<Range<usize>> {
+        let (mut low, mut high) = (0, self.no_components);
+        while low < high {
+            let middle = (low + high) / 2;
+            let record = self.component_record(middle);
+            if self.string(record) < path {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        if low < self.no_components && self.string(self.component_record(low)) == path {
+            let record = self.component_record(low);
+            Some(self.u64_at(record + 16) as usize..self.u64_at(record + 24) as usize)
+        } else {
+            None
+        }
+    }
+
+    // The wires of the signals of the component and its subcomponents
+    pub fn wires_of_component(&self, path: &str) -> Vec<usize> {
+        self.query(&format!("{}.*", path)).iter().filter_map(|signal| signal.wire()).collect()
+    }
+
+    // A pattern ending in * gives the signals whose names start with the rest
+    // of it, like main.hasher[3].* or main.in[*. Otherwise it gives the signal
+    // with that name or, for a bus, an array or a component, all the signals
+    // that it contains, with their fields expanded.
+    pub fn query(&self, pattern: &str) -> Vec<IndexedSignal<'_>> {
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            return self.prefix_range(prefix).map(|position| self.signal_at(position)).collect();
+        }
+        if let Some(signal) = self.signal(pattern) {
+            return vec![signal];
+        }
+        let fields = self.prefix_range(&format!("{}.", pattern));
+        let positions = self.prefix_range(&format!("{}[", pattern));
+        fields.chain(positions).map(|position| self.signal_at(position)).collect()
+    }
+
+    fn prefix_range(&self, prefix: &str) -> Range<usize> {
+        let start = self.partition_point(|name| name < prefix);
+        let end = self.partition_point(|name| name < prefix || name.starts_with(prefix));
+        start..end
+    }
+
+    // The first position whose name does not satisfy the predicate, which
+    // must hold for a prefix of the sorted names
+    fn partition_point(&self, predicate: impl Fn(&str) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.no_signals);
+        while low < high {
+            let middle = (low + high) / 2;
+            if predicate(self.name_at(middle)) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    fn table_entry(&self, start: usize, length: usize, index: usize) -> Option<usize> {
+        if index < length {
+            let entry = self.u64_at(start + index * ENTRY_SIZE);
+            if entry != NO_SIGNAL {
+                return Some(entry as usize);
+            }
+        }
+        None
+    }
+
+    fn signal_at(&self, position: usize) -> IndexedSignal<'_> {
+        let record = self.signal_record(position);
+        IndexedSignal {
+            name: self.string(record),
+            original: self.u64_at(record + 16) as i64,
+            witness: self.u64_at(record + 24) as i64,
+            node_id: self.u64_at(record + 32) as i64,
+        }
+    }
+
+    fn name_at(&self, position: usize) -> &str {
+        self.string(self.signal_record(position))
+    }
+
+    // The string given by the offset and the length at the start of a record
+    fn string(&self, record: usize) -> &str {
+        let start = self.strings + self.u64_at(record) as usize;
+        let end = start + self.u64_at(record + 8) as usize;
+        std::str::from_utf8(&self.bytes[start..end]).unwrap()
+    }
+
+    fn signal_record(&self, position: usize) -> usize {
+        HEADER_SIZE + position * SIGNAL_SIZE
+    }
+
+    fn component_record(&self, position: usize) -> usize {
+        HEADER_SIZE + self.no_signals * SIGNAL_SIZE + position * COMPONENT_SIZE
+    }
+
+    fn wires_start(&self) -> usize {
+        HEADER_SIZE + self.no_signals * SIGNAL_SIZE + self.no_components * COMPONENT_SIZE
+    }
+
+    fn u64_at(&self, offset: usize) -> u64 {
+        read_u64(&self.bytes, offset)
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut value = [0; 8];
+    value.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> SymIndex {
+        let signals = [
+            (1, 1, 1, "main.out.x"),
+            (2, 2, 1, "main.out.y"),
+            (3, 3, 1, "main.in.p[0].x"),
+            (4, -1, 1, "main.in.p[1].x"),
+            (5, 4, 1, "main.in.tag"),
+            (6, 5, 0, "main.hasher[1].out"),
+            (7, -1, 0, "main.hasher[1].in"),
+            (8, 6, 0, "main.hasher[10].out"),
+            (9, -1, 0, "main.hasher[10].in"),
+        ];
+        let mut writer = SymIndexWriter::new();
+        for (original, witness, node_id, symbol) in signals {
+            writer.add_signal(SymElem { original, witness, node_id, symbol: symbol.to_string() });
+        }
+        writer.add_component("main".to_string(), 1..6);
+        writer.add_component("main.hasher[1]".to_string(), 6..8);
+        writer.add_component("main.hasher[10]".to_string(), 8..10);
+        SymIndex::from_bytes(writer.encode()).unwrap()
+    }
+
+    fn names(signals: Vec<IndexedSignal<'_>>) -> Vec<&str> {
+        signals.iter().map(|signal| signal.name).collect()
+    }
+
+    #[test]
+    fn lookups_by_name_wire_and_component() {
+        let index = example();
+        assert_eq!(index.len(), 9);
+        assert_eq!(index.wire("main.in.tag"), Some(4));
+        assert_eq!(index.wire("main.in.p[1].x"), None);
+        assert!(index.signal("main.in").is_none());
+        assert_eq!(index.name_of_wire(5), Some("main.hasher[1].out"));
+        assert_eq!(index.name_of_wire(0), None);
+        assert_eq!(index.signal_of_original(9).unwrap().name, "main.hasher[10].in");
+        assert_eq!(index.component("main.hasher[10]"), Some(8..10));
+        assert_eq!(index.component("main.hasher"), None);
+        assert_eq!(names(index.query("main.hasher[1].*")), ["main.hasher[1].in", "main.hasher[1].out"]);
+        assert_eq!(names(index.query("main.in")), ["main.in.p[0].x", "main.in.p[1].x", "main.in.tag"]);
+        assert_eq!(names(index.query("main.in.p")), ["main.in.p[0].x", "main.in.p[1].x"]);
+        assert_eq!(index.query("main.hasher").len(), 4);
+        assert_eq!(index.wires_of_component("main.hasher[10]"), [6]);
+    }
+
+    #[test]
+    fn corrupted_files_are_rejected() {
+        let mut bytes = SymIndexWriter::new().encode();
+        assert!(SymIndex::from_bytes(bytes.clone()).unwrap().is_empty());
+        bytes[0] = b'x';
+        assert!(SymIndex::from_bytes(bytes).is_err());
+        let mut bytes = example().bytes;
+        bytes.pop();
+        assert!(SymIndex::from_bytes(bytes).is_err());
+    }
+}
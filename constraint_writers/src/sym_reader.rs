@@ -0,0 +1,33 @@
+use super::sym_writer::SymElem;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+fn invalid_line(number: usize) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid sym line {}", number))
+}
+
+// Each line is original,witness,node_id,symbol and the symbol may not
+// contain commas
+fn parse_sym_elem(line: &str, number: usize) -> Result<SymElem, std::io::Error> {
+    let mut fields = line.splitn(4, ',');
+    let mut next_number = || {
+        fields.next().and_then(|field| field.trim().parse::<i64>().ok()).ok_or_else(|| invalid_line(number))
+    };
+    let original = next_number()?;
+    let witness = next_number()?;
+    let node_id = next_number()?;
+    let symbol = fields.next().ok_or_else(|| invalid_line(number))?.to_string();
+    Ok(SymElem { original, witness, node_id, symbol })
+}
+
+pub fn read_sym(input: &str) -> Result<Vec<SymElem>, std::io::Error> {
+    let reader = BufReader::new(File::open(input)?);
+    let mut elems = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            elems.push(parse_sym_elem(&line, index + 1)?);
+        }
+    }
+    Ok(elems)
+}
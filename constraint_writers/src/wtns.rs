@@ -0,0 +1,400 @@
+// Reader and writer of the binary witness files (.wtns) of iden3, the format
+// written by the C++ and WebAssembly witness generators
+use crate::r1cs_reader::{read_r1cs_header, HeaderData};
+use circom_algebra::num_bigint::{BigInt, Sign};
+use json::JsonValue;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, Read, Seek, SeekFrom, Write};
+
+const MAGIC: &[u8] = b"wtns";
+const VERSION: u32 = 2;
+const HEADER_TYPE: u32 = 1;
+const WITNESS_TYPE: u32 = 2;
+
+pub enum WtnsParsingError {
+    InvalidMagicNumber,
+    InvalidVersion(u32),
+    InvalidFieldSize(usize),
+    InvalidSectionSize(String),
+    SectionNotPresent(String),
+    ValueNotInField(usize),
+    WrongNumberOfValues { expected: usize, found: usize },
+    DifferentPrime,
+    DifferentNumberOfWires { r1cs: usize, witness: usize },
+    InvalidJsonValue(usize),
+}
+
+impl fmt::Display for WtnsParsingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WtnsParsingError::*;
+        match self {
+            InvalidMagicNumber => write!(f, "Invalid magic number"),
+            InvalidVersion(version) => write!(f, "Invalid version {}, only version {} is supported", version, VERSION),
+            InvalidFieldSize(size) => write!(f, "Invalid field size {}", size),
+            InvalidSectionSize(section) => write!(f, "Invalid size of the section \"{}\"", section),
+            SectionNotPresent(section) => write!(f, "Section \"{}\" not present", section),
+            ValueNotInField(index) => write!(f, "The value {} of the witness is not an element of the field", index),
+            WrongNumberOfValues { expected, found } => {
+                write!(f, "The witness has {} values instead of {}", found, expected)
+            }
+            DifferentPrime => write!(f, "The prime of the witness is not the prime of the r1cs file"),
+            DifferentNumberOfWires { r1cs, witness } => {
+                write!(f, "The witness has {} values but the r1cs file has {} wires", witness, r1cs)
+            }
+            InvalidJsonValue(index) => write!(f, "The value {} of the JSON witness is not a non-negative integer", index),
+        }
+    }
+}
+
+fn error(kind: WtnsParsingError) -> Error {
+    Error::other(kind.to_string())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut buffer = [0; 4];
+    reader.read_exact(&mut buffer)?;
+    Ok(u32::from_le_bytes(buffer))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_element<R: Read>(reader: &mut R, field_size: usize) -> Result<BigInt, Error> {
+    let mut buffer = vec![0; field_size];
+    reader.read_exact(&mut buffer)?;
+    Ok(BigInt::from_bytes_le(Sign::Plus, &buffer))
+}
+
+pub struct WtnsHeader {
+    pub field: BigInt,
+    pub field_size: usize,
+    pub witness_size: usize,
+}
+
+impl WtnsHeader {
+    // The elements take the bytes of the 64-bit words needed by the prime, as
+    // in the r1cs files
+    pub fn new(field: &BigInt, witness_size: usize) -> WtnsHeader {
+        let field_size = if field.bits().is_multiple_of(64) { field.bits() / 8 } else { (field.bits() / 64 + 1) * 8 };
+        WtnsHeader { field: field.clone(), field_size, witness_size }
+    }
+
+    // A witness can only be used with the constraints of its circuit
+    pub fn check_r1cs(&self, r1cs: &HeaderData) -> Result<(), Error> {
+        if self.field != r1cs.field {
+            return Err(error(WtnsParsingError::DifferentPrime));
+        }
+        if self.witness_size != r1cs.total_wires {
+            return Err(error(WtnsParsingError::DifferentNumberOfWires {
+                r1cs: r1cs.total_wires,
+                witness: self.witness_size,
+            }));
+        }
+        Ok(())
+    }
+
+    pub fn check_r1cs_file(&self, r1cs_file: &str) -> Result<(), Error> {
+        self.check_r1cs(&read_r1cs_header(r1cs_file)?)
+    }
+
+    fn section_size(&self) -> u64 {
+        (4 + self.field_size + 4) as u64
+    }
+
+    fn witness_section_size(&self) -> u64 {
+        self.field_size as u64 * self.witness_size as u64
+    }
+}
+
+// Reads the values of the witness one by one, after the header
+pub struct WtnsReader<R = BufReader<File>> {
+    reader: R,
+    header: WtnsHeader,
+    read: usize,
+}
+
+impl WtnsReader {
+    pub fn open(file: &str) -> Result<WtnsReader, Error> {
+        WtnsReader::new(BufReader::new(File::open(file)?))
+    }
+}
+
+impl<R: Read + Seek> WtnsReader<R> {
+    pub fn new(mut reader: R) -> Result<WtnsReader<R>, Error> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(error(WtnsParsingError::InvalidMagicNumber));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(error(WtnsParsingError::InvalidVersion(version)));
+        }
+        // the sections may come in any order
+        let n_sections = read_u32(&mut reader)?;
+        let mut header_section = None;
+        let mut witness_section = None;
+        for _ in 0..n_sections {
+            let section_type = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            let start = reader.stream_position()?;
+            match section_type {
+                HEADER_TYPE => header_section = Some((start, size)),
+                WITNESS_TYPE => witness_section = Some((start, size)),
+                _ => {}
+            }
+            reader.seek(SeekFrom::Start(start + size))?;
+        }
+        let length = reader.seek(SeekFrom::End(0))?;
+
+        let (start, size) = header_section.ok_or_else(|| error(WtnsParsingError::SectionNotPresent("Header".to_string())))?;
+        reader.seek(SeekFrom::Start(start))?;
+        let field_size = read_u32(&mut reader)? as usize;
+        if field_size == 0 || !field_size.is_multiple_of(8) {
+            return Err(error(WtnsParsingError::InvalidFieldSize(field_size)));
+        }
+        let field = read_element(&mut reader, field_size)?;
+        let witness_size = read_u32(&mut reader)? as usize;
+        let header = WtnsHeader { field, field_size, witness_size };
+        if size != header.section_size() || start + size > length {
+            return Err(error(WtnsParsingError::InvalidSectionSize("Header".to_string())));
+        }
+
+        let (start, size) = witness_section.ok_or_else(|| error(WtnsParsingError::SectionNotPresent("Witness".to_string())))?;
+        if size != header.witness_section_size() || start + size > length {
+            return Err(error(WtnsParsingError::InvalidSectionSize("Witness".to_string())));
+        }
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(WtnsReader { reader, header, read: 0 })
+    }
+
+    pub fn header(&self) -> &WtnsHeader {
+        &self.header
+    }
+
+    // None once all the values have been read
+    pub fn read_value(&mut self) -> Result<Option<BigInt>, Error> {
+        if self.read == self.header.witness_size {
+            return Ok(None);
+        }
+        let value = read_element(&mut self.reader, self.header.field_size)?;
+        if value >= self.header.field {
+            return Err(error(WtnsParsingError::ValueNotInField(self.read)));
+        }
+        self.read += 1;
+        Ok(Some(value))
+    }
+
+    pub fn read_all(mut self) -> Result<Vec<BigInt>, Error> {
+        let mut values = Vec::with_capacity(self.header.witness_size - self.read);
+        while let Some(value) = self.read_value()? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+impl<R: Read + Seek> Iterator for WtnsReader<R> {
+    type Item = Result<BigInt, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_value().transpose()
+    }
+}
+
+// Writes the values of the witness one by one. The header goes first, so the
+// number of values must be known when the writer is created
+pub struct WtnsWriter<W = BufWriter<File>> {
+    writer: W,
+    header: WtnsHeader,
+    written: usize,
+}
+
+impl WtnsWriter {
+    pub fn create(file: &str, field: &BigInt, witness_size: usize) -> Result<WtnsWriter, Error> {
+        WtnsWriter::new(BufWriter::new(File::create(file)?), field, witness_size)
+    }
+}
+
+impl<W: Write> WtnsWriter<W> {
+    pub fn new(mut writer: W, field: &BigInt, witness_size: usize) -> Result<WtnsWriter<W>, Error> {
+        let header = WtnsHeader::new(field, witness_size);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&HEADER_TYPE.to_le_bytes())?;
+        writer.write_all(&header.section_size().to_le_bytes())?;
+        writer.write_all(&(header.field_size as u32).to_le_bytes())?;
+        writer.write_all(&element_bytes(field, header.field_size))?;
+        writer.write_all(&(witness_size as u32).to_le_bytes())?;
+        writer.write_all(&WITNESS_TYPE.to_le_bytes())?;
+        writer.write_all(&header.witness_section_size().to_le_bytes())?;
+        Ok(WtnsWriter { writer, header, written: 0 })
+    }
+
+    pub fn header(&self) -> &WtnsHeader {
+        &self.header
+    }
+
+    pub fn write_value(&mut self, value: &BigInt) -> Result<(), Error> {
+        if self.written == self.header.witness_size {
+            return Err(error(WtnsParsingError::WrongNumberOfValues {
+                expected: self.header.witness_size,
+                found: self.written + 1,
+            }));
+        }
+        if value.sign() == Sign::Minus || *value >= self.header.field {
+            return Err(error(WtnsParsingError::ValueNotInField(self.written)));
+        }
+        self.writer.write_all(&element_bytes(value, self.header.field_size))?;
+        self.written += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<W, Error> {
+        if self.written != self.header.witness_size {
+            return Err(error(WtnsParsingError::WrongNumberOfValues {
+                expected: self.header.witness_size,
+                found: self.written,
+            }));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn element_bytes(value: &BigInt, field_size: usize) -> Vec<u8> {
+    let (_, mut bytes) = value.to_bytes_le();
+    bytes.resize(field_size, 0);
+    bytes
+}
+
+// The JSON form of a witness is the one of `snarkjs wtns export json`: an
+// array with its values as decimal strings
+pub fn wtns_to_json<R: Read + Seek, W: Write>(reader: WtnsReader<R>, mut out: W) -> Result<W, Error> {
+    out.write_all(b"[")?;
+    for (index, value) in reader.enumerate() {
+        let separator = if index == 0 { "\n" } else { ",\n" };
+        write!(out, "{} \"{}\"", separator, value?)?;
+    }
+    out.write_all(b"\n]\n")?;
+    out.flush()?;
+    Ok(out)
+}
+
+// Also accepts numbers and 0x-hexadecimal strings
+pub fn json_to_wtns<W: Write>(json: &JsonValue, writer: W, field: &BigInt) -> Result<W, Error> {
+    let values = json_values(json)?;
+    let mut wtns = WtnsWriter::new(writer, field, values.len())?;
+    for value in &values {
+        wtns.write_value(value)?;
+    }
+    wtns.finish()
+}
+
+fn json_values(json: &JsonValue) -> Result<Vec<BigInt>, Error> {
+    if !json.is_array() {
+        return Err(error(WtnsParsingError::InvalidJsonValue(0)));
+    }
+    let parse = |index: usize, value: &JsonValue| {
+        let parsed = if let Some(text) = value.as_str() {
+            match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                Some(digits) => BigInt::parse_bytes(digits.as_bytes(), 16),
+                None => BigInt::parse_bytes(text.as_bytes(), 10),
+            }
+        } else {
+            value.as_u64().map(BigInt::from)
+        };
+        parsed
+            .filter(|value| value.sign() != Sign::Minus)
+            .ok_or_else(|| error(WtnsParsingError::InvalidJsonValue(index)))
+    };
+    json.members().enumerate().map(|(index, value)| parse(index, value)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn bn128() -> BigInt {
+        BigInt::parse_bytes(b"21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap()
+    }
+
+    fn witness() -> Vec<BigInt> {
+        vec![BigInt::from(1), BigInt::from(37), bn128() - 1, BigInt::from(u64::MAX) * 3]
+    }
+
+    fn encode(values: &[BigInt]) -> Vec<u8> {
+        let mut writer = WtnsWriter::new(Vec::new(), &bn128(), values.len()).unwrap();
+        for value in values {
+            writer.write_value(value).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn written_witnesses_are_read_back() {
+        let bytes = encode(&witness());
+        // magic, version, sections, both section headers, n8, prime, size and values
+        assert_eq!(bytes.len(), 4 + 4 + 4 + 12 + 4 + 32 + 4 + 12 + 32 * 4);
+        let reader = WtnsReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.header().field, bn128());
+        assert_eq!(reader.header().field_size, 32);
+        assert_eq!(reader.header().witness_size, 4);
+        assert_eq!(reader.read_all().unwrap(), witness());
+    }
+
+    #[test]
+    fn json_witnesses_round_trip() {
+        let reader = WtnsReader::new(Cursor::new(encode(&witness()))).unwrap();
+        let text = String::from_utf8(wtns_to_json(reader, Vec::new()).unwrap()).unwrap();
+        let json = json::parse(&text).unwrap();
+        assert_eq!(json[1], "37");
+        assert_eq!(json_to_wtns(&json, Vec::new(), &bn128()).unwrap(), encode(&witness()));
+        let mixed = json::parse(r#"["1", 37, "0x2A"]"#).unwrap();
+        let values = WtnsReader::new(Cursor::new(json_to_wtns(&mixed, Vec::new(), &bn128()).unwrap())).unwrap();
+        assert_eq!(values.read_all().unwrap(), vec![BigInt::from(1), BigInt::from(37), BigInt::from(42)]);
+        assert!(json_to_wtns(&json::parse(r#"["-1"]"#).unwrap(), Vec::new(), &bn128()).is_err());
+    }
+
+    #[test]
+    fn invalid_witnesses_are_rejected() {
+        let mut writer = WtnsWriter::new(Vec::new(), &bn128(), 2).unwrap();
+        assert!(writer.write_value(&bn128()).is_err());
+        writer.write_value(&BigInt::from(1)).unwrap();
+        assert!(writer.finish().is_err());
+
+        let mut bytes = encode(&witness());
+        bytes.truncate(bytes.len() - 1);
+        assert!(WtnsReader::new(Cursor::new(bytes)).is_err());
+        let mut bytes = encode(&witness());
+        bytes[0] = b'x';
+        assert!(WtnsReader::new(Cursor::new(bytes)).is_err());
+    }
+
+    #[test]
+    fn witnesses_are_checked_against_the_r1cs_header() {
+        let header = WtnsHeader::new(&bn128(), 4);
+        let mut r1cs = HeaderData {
+            field: bn128(),
+            field_size: 32,
+            total_wires: 4,
+            public_outputs: 1,
+            public_inputs: 0,
+            private_inputs: 2,
+            number_of_labels: 5,
+            number_of_constraints: 1,
+        };
+        assert!(header.check_r1cs(&r1cs).is_ok());
+        r1cs.total_wires = 5;
+        assert!(header.check_r1cs(&r1cs).is_err());
+        r1cs.total_wires = 4;
+        r1cs.field = BigInt::from(18446744069414584321u64);
+        assert!(header.check_r1cs(&r1cs).is_err());
+    }
+}
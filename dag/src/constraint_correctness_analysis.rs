@@ -147,10 +147,12 @@ pub fn clean_constraints(nodes: &mut [Node]){
     for node in nodes{
         let length_bound = Vec::len(&node.constraints);
         let work = std::mem::replace(&mut node.constraints, Vec::with_capacity(length_bound));
-        for mut constraint in work {
+        let origins = std::mem::replace(&mut node.constraint_origins, Vec::with_capacity(length_bound));
+        for (mut constraint, origin) in work.into_iter().zip(origins) {
             C::remove_zero_value_coefficients(&mut constraint);
             if !C::is_empty(&constraint) {
                 Vec::push(&mut node.constraints, constraint);
+                Vec::push(&mut node.constraint_origins, origin);
             }
         }
     }
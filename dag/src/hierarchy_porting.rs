@@ -0,0 +1,204 @@
+use super::DAG;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::field_element::FieldElement;
+use constraint_list::ConstraintList;
+use json::JsonValue;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum HierarchyFormat {
+    Json,
+    Dot,
+}
+
+struct HierarchyNode {
+    // Name of the template followed by the values of its parameters
+    template_name: String,
+    parameters: Vec<BigInt>,
+    inputs: usize,
+    public_inputs: usize,
+    outputs: usize,
+    intermediates: usize,
+    // Signals of the instance including the ones of its subcomponents
+    signals: usize,
+    constraints: usize,
+    // Number of components of this instance in the whole circuit
+    occurrences: usize,
+    // Over all the occurrences, None if the constraints were not simplified
+    simplified_constraints: Option<usize>,
+}
+
+struct HierarchyEdge {
+    from: usize,
+    to: usize,
+    label: String,
+    in_number: usize,
+    out_number: usize,
+}
+
+// Snapshot of the template instances and the components that connect them,
+// taken before the DAG is consumed by the simplification
+pub struct Hierarchy {
+    main: usize,
+    nodes: Vec<HierarchyNode>,
+    edges: Vec<Vec<HierarchyEdge>>,
+}
+
+impl Hierarchy {
+    pub fn new(dag: &DAG) -> Hierarchy {
+        let mut nodes = Vec::with_capacity(dag.number_of_nodes());
+        let mut edges: Vec<Vec<HierarchyEdge>> = Vec::with_capacity(dag.number_of_nodes());
+        for (id, node) in dag.nodes.iter().enumerate() {
+            nodes.push(HierarchyNode {
+                template_name: node.template_name.clone(),
+                parameters: node.parameters().clone(),
+                inputs: node.number_of_inputs(),
+                public_inputs: node.number_of_public_inputs(),
+                outputs: node.number_of_outputs(),
+                intermediates: node.number_of_intermediates(),
+                signals: node.number_of_signals(),
+                constraints: node.constraints().iter().filter(|c| !c.is_empty()).count(),
+                occurrences: 0,
+                simplified_constraints: None,
+            });
+            let node_edges = dag.adjacency[id].iter().map(|edge| HierarchyEdge {
+                from: id,
+                to: edge.goes_to,
+                label: edge.label.clone(),
+                in_number: edge.in_number,
+                out_number: edge.out_number,
+            });
+            edges.push(node_edges.collect());
+        }
+        let main = dag.main_id();
+        // Subcomponents are always created before the components using them,
+        // so the occurrences can be propagated from the main to the leaves
+        nodes[main].occurrences = 1;
+        for id in (0..nodes.len()).rev() {
+            let occurrences = nodes[id].occurrences;
+            for edge in &edges[id] {
+                nodes[edge.to].occurrences += occurrences;
+            }
+        }
+        Hierarchy { main, nodes, edges }
+    }
+
+    // Each constraint left after the simplification is assigned to the
+    // innermost component that contains all its signals
+    pub fn set_simplified_constraints<F: FieldElement>(&mut self, list: &ConstraintList<F>) {
+        let mut counters = vec![0; self.nodes.len()];
+        for c_id in list.constraints.get_ids() {
+            let constraint = list.constraints.read_constraint(c_id).unwrap();
+            let signals = constraint.take_cloned_signals_ordered();
+            let mut signals = signals.into_iter().filter(|signal| *signal != 0);
+            let first = signals.next();
+            let last = signals.next_back().or(first);
+            let node = match (first, last) {
+                (Some(first), Some(last)) => self.innermost_component(first, last),
+                _ => self.main,
+            };
+            counters[node] += 1;
+        }
+        for (node, counter) in self.nodes.iter_mut().zip(counters) {
+            node.simplified_constraints = Some(counter);
+        }
+    }
+
+    fn innermost_component(&self, first: usize, last: usize) -> usize {
+        let mut node = self.main;
+        let mut offset = 0;
+        loop {
+            // The signals of the subcomponent reached by an edge are the ones
+            // in (offset + in_number, offset + out_number]
+            let edges = &self.edges[node];
+            let position = edges.partition_point(|edge| offset + edge.in_number < first);
+            match position.checked_sub(1).map(|index| &edges[index]) {
+                Some(edge) if last <= offset + edge.out_number => {
+                    offset += edge.in_number;
+                    node = edge.to;
+                }
+                _ => return node,
+            }
+        }
+    }
+
+    pub fn write(&self, file: &str, format: HierarchyFormat) -> Result<(), ()> {
+        let file = File::create(file).map_err(|_err| {})?;
+        let mut writer = BufWriter::new(file);
+        match format {
+            HierarchyFormat::Json => self.write_json(&mut writer),
+            HierarchyFormat::Dot => self.write_dot(&mut writer),
+        }
+        .map_err(|_err| {})?;
+        writer.flush().map_err(|_err| {})
+    }
+
+    fn write_json<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut nodes = JsonValue::new_array();
+        for (id, node) in self.nodes.iter().enumerate() {
+            let parameters: Vec<JsonValue> =
+                node.parameters.iter().map(|p| p.to_str_radix(10).into()).collect();
+            let mut value = json::object! {
+                "id": id,
+                "name": node.template_name.as_str(),
+                "parameters": JsonValue::Array(parameters),
+                "occurrences": node.occurrences,
+                "signals": json::object! {
+                    "inputs": node.inputs,
+                    "public_inputs": node.public_inputs,
+                    "outputs": node.outputs,
+                    "intermediates": node.intermediates,
+                    "total": node.signals,
+                },
+                "constraints": node.constraints,
+                "total_constraints": node.constraints * node.occurrences,
+            };
+            if let Some(simplified) = node.simplified_constraints {
+                value["total_constraints_after_simplification"] = simplified.into();
+            }
+            nodes.push(value).unwrap();
+        }
+        let mut edges = JsonValue::new_array();
+        for edge in self.edges.iter().flatten() {
+            edges.push(json::object! {
+                "from": edge.from,
+                "to": edge.to,
+                "label": edge.label.as_str(),
+            }).unwrap();
+        }
+        let hierarchy = json::object! { "main": self.main, "nodes": nodes, "edges": edges };
+        hierarchy.write_pretty(writer, 2)
+    }
+
+    fn write_dot<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "digraph hierarchy {{")?;
+        writeln!(writer, "    node [shape=box];")?;
+        for (id, node) in self.nodes.iter().enumerate() {
+            let mut label = format!(
+                "{}\\nsignals: {} in, {} out, {} intermediate\\nconstraints: {}",
+                node.template_name,
+                node.inputs,
+                node.outputs,
+                node.intermediates,
+                node.constraints,
+            );
+            if let Some(simplified) = node.simplified_constraints {
+                label.push_str(&format!(
+                    "\\ntotal constraints: {} -> {}",
+                    node.constraints * node.occurrences,
+                    simplified
+                ));
+            }
+            writeln!(writer, "    n{} [label=\"{}\"];", id, escape(&label))?;
+        }
+        for edge in self.edges.iter().flatten() {
+            writeln!(writer, "    n{} -> n{} [label=\"{}\"];", edge.from, edge.to, escape(&edge.label))?;
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
@@ -1,16 +1,22 @@
 mod constraint_correctness_analysis;
+mod hierarchy_porting;
 mod json_porting;
 mod map_to_constraint_list;
+mod profile_porting;
 mod r1cs_porting;
+mod range_analysis;
 mod sym_porting;
 mod witness_producer;
+use circom_algebra::field_element::FieldElement;
 use circom_algebra::num_bigint::BigInt;
-use constraint_list::ConstraintList;
+use constraint_list::{ComponentConstraints, ConstraintList};
 use constraint_writers::debug_writer::DebugWriter;
 use constraint_writers::ConstraintExporter;
 use program_structure::constants::UsefulConstants;
 use program_structure::error_definition::ReportCollection;
 use std::collections::{HashMap, HashSet};
+pub use hierarchy_porting::{Hierarchy, HierarchyFormat};
+pub use profile_porting::{ConstraintOrigin, Profile};
 type Signal = usize;
 type Constraint = circom_algebra::algebra::Constraint<usize>;
 type Substitution = circom_algebra::algebra::Substitution<usize>;
@@ -28,6 +34,8 @@ pub struct Tree<'a> {
     pub forbidden: HashSet<usize>,
     pub id_to_name: HashMap<usize, String>,
     pub constraints: Vec<Constraint>,
+    // Index in the instance of each constraint
+    pub indexes: Vec<usize>,
 }
 
 impl<'a> Tree<'a> {
@@ -39,6 +47,7 @@ impl<'a> Tree<'a> {
         let offset = dag.get_entry().unwrap().in_number;
         let path = dag.get_entry().unwrap().label.clone();
         let constraints = root.constraints.clone();
+        let indexes = (0..constraints.len()).collect();
         let mut id_to_name = HashMap::new();
         let mut signals: Vec<_> = Vec::new();
         let forbidden: HashSet<_> =
@@ -50,7 +59,7 @@ impl<'a> Tree<'a> {
             }
         }
         signals.sort();
-        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints }
+        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints, indexes }
     }
 
     pub fn go_to_subtree(current: &'a Tree, edge: &Edge) -> Tree<'a> {
@@ -70,13 +79,14 @@ impl<'a> Tree<'a> {
             }
         }
         signals.sort();
-        let constraints: Vec<_> = node
+        let (indexes, constraints) = node
             .constraints
             .iter()
-            .filter(|c| !c.is_empty())
-            .map(|c| Constraint::apply_offset(c, offset))
-            .collect();
-        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints }
+            .enumerate()
+            .filter(|(_, c)| !c.is_empty())
+            .map(|(index, c)| (index, Constraint::apply_offset(c, offset)))
+            .unzip();
+        Tree { field, dag, path, offset, node_id, signals, forbidden, id_to_name, constraints, indexes }
     }
 
     pub fn get_edges(tree: &'a Tree) -> &'a Vec<Edge> {
@@ -149,6 +159,8 @@ pub struct Node {
     forbidden_if_main: HashSet<usize>,
     io_signals: Vec<usize>,
     constraints: Vec<Constraint>,
+    // Where each constraint was generated, in the same order as the constraints
+    constraint_origins: Vec<Option<ConstraintOrigin>>,
     underscored_signals: Vec<usize>,
     is_parallel: bool,
     has_parallel_sub_cmp: bool,
@@ -217,8 +229,9 @@ impl Node {
         self.ordered_signals.push(name);
     }
 
-    fn add_constraint(&mut self, constraint: Constraint) {
-        self.constraints.push(constraint)
+    fn add_constraint(&mut self, constraint: Constraint, origin: Option<ConstraintOrigin>) {
+        self.constraints.push(constraint);
+        self.constraint_origins.push(origin);
     }
 
     fn add_underscored_signal(&mut self, signal: usize) {
@@ -253,6 +266,10 @@ impl Node {
         &self.constraints
     }
 
+    pub fn constraint_origins(&self) -> &[Option<ConstraintOrigin>] {
+        &self.constraint_origins
+    }
+
     pub fn io_signals(&self) -> &Vec<usize> {
         &self.io_signals
     }
@@ -313,6 +330,10 @@ impl ConstraintExporter for DAG {
     fn sym(&self, out: &str) -> Result<(), ()> {
         DAG::generate_sym_output(self, out)
     }
+
+    fn sym_index(&self, out: &str) -> Result<(), ()> {
+        sym_porting::write_index(self, out)
+    }
 }
 
 impl DAG {
@@ -409,9 +430,9 @@ impl DAG {
         }
     }
 
-    pub fn add_constraint(&mut self, constraint: Constraint) {
+    pub fn add_constraint(&mut self, constraint: Constraint, origin: Option<ConstraintOrigin>) {
         if let Option::Some(node) = self.get_mut_main() {
-            node.add_constraint(constraint);
+            node.add_constraint(constraint, origin);
         }
     }
 
@@ -474,6 +495,11 @@ impl DAG {
         }
     }
 
+    pub fn range_analysis(&self) -> ReportCollection {
+        let field = UsefulConstants::new(&self.prime).get_p().clone();
+        range_analysis::analyse(&self.nodes, &self.adjacency, &field)
+    }
+
     pub fn clean_constraints(&mut self) {
         constraint_correctness_analysis::clean_constraints(&mut self.nodes);
     }
@@ -538,9 +564,13 @@ impl DAG {
         }
     }
 
-    pub fn map_to_list(self, flags: SimplificationFlags) -> ConstraintList {
+    pub fn map_to_list<F: FieldElement>(self, flags: SimplificationFlags) -> ConstraintList<F> {
         map_to_constraint_list::map(self, flags)
     }
+
+    pub fn original_constraints(&self) -> Vec<ComponentConstraints> {
+        map_to_constraint_list::original_constraints(self)
+    }
 }
 
 pub struct SimplificationFlags {
@@ -551,4 +581,5 @@ pub struct SimplificationFlags {
     pub json_substitutions: String,
     pub flag_old_heuristics: bool,
     pub prime : String,
+    pub keep_substitutions: bool,
 }
@@ -1,12 +1,13 @@
 use super::{Constraint, Edge, Node, SimplificationFlags, Tree, DAG};
-use constraint_list::{ConstraintList, DAGEncoding, EncodingEdge, EncodingNode, SignalInfo, Simplifier};
+use circom_algebra::field_element::FieldElement;
+use constraint_list::{ComponentConstraints, ConstraintList, ConstraintPosition, DAGEncoding, EncodingEdge, EncodingNode, SignalInfo, Simplifier, TrackedConstraint};
 use program_structure::utils::constants::UsefulConstants;
 use std::collections::{HashSet, LinkedList};
 #[derive(Default)]
 struct CHolder {
-    linear: LinkedList<Constraint>,
-    equalities: LinkedList<Constraint>,
-    constant_equalities: LinkedList<Constraint>,
+    linear: LinkedList<TrackedConstraint>,
+    equalities: LinkedList<TrackedConstraint>,
+    constant_equalities: LinkedList<TrackedConstraint>,
 }
 
 fn map_tree(
@@ -24,13 +25,15 @@ fn map_tree(
         }
     }
 
-    for constraint in &tree.constraints {
+    for (constraint, index) in tree.constraints.iter().zip(&tree.indexes) {
+        let position = ConstraintPosition { node_id: tree.node_id, offset: tree.offset, index: *index };
+        let tracked = (constraint.clone(), Some(position));
         if Constraint::is_constant_equality(constraint) {
-            LinkedList::push_back(&mut c_holder.constant_equalities, constraint.clone());
+            LinkedList::push_back(&mut c_holder.constant_equalities, tracked);
         } else if Constraint::is_equality(constraint, &tree.field) {
-            LinkedList::push_back(&mut c_holder.equalities, constraint.clone());
+            LinkedList::push_back(&mut c_holder.equalities, tracked);
         } else if Constraint::is_linear(constraint) {
-            LinkedList::push_back(&mut c_holder.linear, constraint.clone());
+            LinkedList::push_back(&mut c_holder.linear, tracked);
         } else {
             no_constraints += 1;
         }
@@ -43,6 +46,24 @@ fn map_tree(
     no_constraints
 }
 
+// The constraints of every component with the numbering of the signals used
+// by the simplification
+pub fn original_constraints(dag: &DAG) -> Vec<ComponentConstraints> {
+    fn collect(tree: &Tree, components: &mut Vec<ComponentConstraints>) {
+        components.push(ComponentConstraints {
+            template: tree.dag.nodes[tree.node_id].template_name.clone(),
+            path: tree.path.clone(),
+            constraints: tree.constraints.iter().filter(|c| !c.is_empty()).cloned().collect(),
+        });
+        for edge in Tree::get_edges(tree) {
+            collect(&Tree::go_to_subtree(tree, edge), components);
+        }
+    }
+    let mut components = Vec::new();
+    collect(&Tree::new(dag), &mut components);
+    components
+}
+
 fn produce_encoding(
     no_constraints: usize,
     init: usize,
@@ -73,9 +94,11 @@ fn map_node_to_encoding(id: usize, node: Node) -> EncodingNode {
     let mut ordered_signals = Vec::new();
     let locals = node.locals;
     let mut non_linear = LinkedList::new();
-    for c in node.constraints {
+    let mut non_linear_indexes = Vec::new();
+    for (index, c) in node.constraints.into_iter().enumerate() {
         if !Constraint::is_linear(&c) {
             LinkedList::push_back(&mut non_linear, c);
+            non_linear_indexes.push(index);
         }
     }
 
@@ -99,6 +122,7 @@ fn map_node_to_encoding(id: usize, node: Node) -> EncodingNode {
         signals,
         ordered_signals,
         non_linear,
+        non_linear_indexes,
         is_custom_gate: node.is_custom_gate,
     }
 }
@@ -107,7 +131,7 @@ fn map_edge_to_encoding(edge: Edge) -> EncodingEdge {
     EncodingEdge { goes_to: edge.goes_to, path: edge.label, offset: edge.in_number }
 }
 
-pub fn map(dag: DAG, flags: SimplificationFlags) -> ConstraintList {
+pub fn map<F: FieldElement>(dag: DAG, flags: SimplificationFlags) -> ConstraintList<F> {
     use std::time::SystemTime;
     // println!("Start of dag to list mapping");
     let now = SystemTime::now();
@@ -142,6 +166,7 @@ pub fn map(dag: DAG, flags: SimplificationFlags) -> ConstraintList {
         flag_old_heuristics: flags.flag_old_heuristics,
         port_substitution: flags.port_substitution,
         json_substitutions: flags.json_substitutions,
+        keep_substitutions: flags.keep_substitutions,
     }
     .simplify_constraints()
 }
@@ -0,0 +1,265 @@
+use super::DAG;
+use circom_algebra::field_element::FieldElement;
+use constraint_list::{ConstraintList, ConstraintPosition};
+use json::JsonValue;
+use program_structure::file_definition::{FileID, FileLibrary};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Statement of the template that generated a constraint
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ConstraintOrigin {
+    pub file_id: FileID,
+    pub start: usize,
+    pub end: usize,
+}
+
+struct SourceLine {
+    file: String,
+    line: usize,
+}
+
+struct ProfileNode {
+    template_name: String,
+    // Number of components of this instance in the whole circuit
+    occurrences: usize,
+    // Source line of each constraint of the instance
+    constraint_sources: Vec<usize>,
+}
+
+struct ProfileEdge {
+    to: usize,
+    in_number: usize,
+    out_number: usize,
+}
+
+// Attribution of the final constraints to the template instances and the
+// source lines that produced them. As the DAG, the instances are shared by
+// all their components, but the counters add up every component.
+pub struct Profile {
+    main: usize,
+    simplified: bool,
+    nodes: Vec<ProfileNode>,
+    edges: Vec<Vec<ProfileEdge>>,
+    sources: Vec<SourceLine>,
+    // Final constraints of each instance, without and with its subcomponents
+    own: Vec<usize>,
+    cumulative: Vec<usize>,
+    // (instance, source line) -> final constraints
+    lines: HashMap<(usize, usize), usize>,
+    // (instances from the main to the component, source line) -> final constraints
+    stacks: HashMap<(Vec<usize>, usize), usize>,
+}
+
+impl Profile {
+    pub fn new(dag: &DAG, files: &FileLibrary) -> Profile {
+        let mut sources = Vec::new();
+        let mut source_ids: HashMap<Option<(FileID, usize)>, usize> = HashMap::new();
+        let mut nodes = Vec::with_capacity(dag.number_of_nodes());
+        let mut edges = Vec::with_capacity(dag.number_of_nodes());
+        for (id, node) in dag.nodes.iter().enumerate() {
+            let mut constraint_sources = Vec::with_capacity(node.constraints().len());
+            for origin in node.constraint_origins() {
+                let key = origin.and_then(|origin| {
+                    files.get_line(origin.start, origin.file_id).map(|line| (origin.file_id, line))
+                });
+                let source = *source_ids.entry(key).or_insert_with(|| {
+                    sources.push(match key {
+                        // The names of the files are stored quoted
+                        Some((file_id, line)) => SourceLine {
+                            file: files.get_name(file_id).unwrap_or("unknown").trim_matches('"').to_string(),
+                            line,
+                        },
+                        None => SourceLine { file: "unknown".to_string(), line: 0 },
+                    });
+                    sources.len() - 1
+                });
+                constraint_sources.push(source);
+            }
+            nodes.push(ProfileNode {
+                template_name: node.template_name.clone(),
+                occurrences: 0,
+                constraint_sources,
+            });
+            let node_edges = dag.adjacency[id].iter().map(|edge| ProfileEdge {
+                to: edge.goes_to,
+                in_number: edge.in_number,
+                out_number: edge.out_number,
+            });
+            edges.push(node_edges.collect::<Vec<_>>());
+        }
+        let main = dag.main_id();
+        nodes[main].occurrences = 1;
+        for id in (0..nodes.len()).rev() {
+            let occurrences = nodes[id].occurrences;
+            for edge in &edges[id] {
+                nodes[edge.to].occurrences += occurrences;
+            }
+        }
+        let number_of_nodes = nodes.len();
+        Profile {
+            main,
+            simplified: false,
+            nodes,
+            edges,
+            sources,
+            own: vec![0; number_of_nodes],
+            cumulative: vec![0; number_of_nodes],
+            lines: HashMap::new(),
+            stacks: HashMap::new(),
+        }
+    }
+
+    // Without simplification every constraint of the DAG is a final
+    // constraint and its origin is known exactly
+    pub fn count_constraints(&mut self) {
+        let mut path = Vec::new();
+        self.count_component(self.main, &mut path);
+    }
+
+    fn count_component(&mut self, node: usize, path: &mut Vec<usize>) {
+        path.push(node);
+        for index in 0..self.nodes[node].constraint_sources.len() {
+            let source = self.nodes[node].constraint_sources[index];
+            self.add(path, source);
+        }
+        for index in 0..self.edges[node].len() {
+            let to = self.edges[node][index].to;
+            self.count_component(to, path);
+        }
+        path.pop();
+    }
+
+    // Every constraint left keeps the position of the constraint of the DAG
+    // it comes from, the ones combined by the linear simplification take the
+    // position of one of the constraints combined
+    pub fn count_simplified_constraints<F: FieldElement>(&mut self, list: &ConstraintList<F>) {
+        self.simplified = true;
+        let unknown = self.sources.len();
+        for c_id in list.constraints.get_ids() {
+            let (path, source) = match list.origins.get(c_id).copied().flatten() {
+                Some(origin) => self.tracked_origin(origin),
+                None => (vec![self.main], None),
+            };
+            self.add(&path, source.unwrap_or(unknown));
+        }
+        if self.lines.keys().any(|(_, source)| *source == unknown) {
+            self.sources.push(SourceLine { file: "unknown".to_string(), line: 0 });
+        }
+    }
+
+    fn tracked_origin(&self, origin: ConstraintPosition) -> (Vec<usize>, Option<usize>) {
+        let mut path = vec![self.main];
+        let mut offset = 0;
+        while *path.last().unwrap() != origin.node_id || offset != origin.offset {
+            let edges = &self.edges[*path.last().unwrap()];
+            let position = edges.partition_point(|edge| offset + edge.in_number <= origin.offset);
+            match position.checked_sub(1).map(|index| &edges[index]) {
+                Some(edge) if origin.offset <= offset + edge.out_number => {
+                    offset += edge.in_number;
+                    path.push(edge.to);
+                }
+                _ => break,
+            }
+        }
+        let source = self.nodes[origin.node_id].constraint_sources.get(origin.index).copied();
+        (path, source)
+    }
+
+    fn add(&mut self, path: &[usize], source: usize) {
+        // An instance can not be a subcomponent of itself, so every instance
+        // of the path is counted once
+        for node in path {
+            self.cumulative[*node] += 1;
+        }
+        let node = *path.last().unwrap();
+        self.own[node] += 1;
+        *self.lines.entry((node, source)).or_insert(0) += 1;
+        *self.stacks.entry((path.to_vec(), source)).or_insert(0) += 1;
+    }
+
+    pub fn write_json(&self, file: &str) -> Result<(), ()> {
+        let mut lines_by_node: Vec<Vec<(usize, usize)>> = vec![Vec::new(); self.nodes.len()];
+        for ((node, source), count) in &self.lines {
+            lines_by_node[*node].push((*source, *count));
+        }
+        let mut templates = JsonValue::new_array();
+        for id in self.by_cumulative_constraints() {
+            let node = &self.nodes[id];
+            let mut node_lines = std::mem::take(&mut lines_by_node[id]);
+            node_lines.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let mut lines = JsonValue::new_array();
+            for (source, count) in node_lines {
+                let source = &self.sources[source];
+                lines.push(json::object! {
+                    "file": source.file.as_str(),
+                    "line": source.line,
+                    "constraints": count,
+                }).unwrap();
+            }
+            templates.push(json::object! {
+                "id": id,
+                "name": node.template_name.as_str(),
+                "occurrences": node.occurrences,
+                "constraints": self.own[id],
+                "cumulative_constraints": self.cumulative[id],
+                "lines": lines,
+            }).unwrap();
+        }
+        let mut all_lines: Vec<_> = self.lines.iter().collect();
+        all_lines.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let mut lines = JsonValue::new_array();
+        for ((node, source), count) in all_lines {
+            let source = &self.sources[*source];
+            lines.push(json::object! {
+                "file": source.file.as_str(),
+                "line": source.line,
+                "template": self.nodes[*node].template_name.as_str(),
+                "constraints": *count,
+            }).unwrap();
+        }
+        let profile = json::object! {
+            "simplified": self.simplified,
+            "total_constraints": self.cumulative[self.main],
+            "templates": templates,
+            "lines": lines,
+        };
+        let file = File::create(file).map_err(|_err| {})?;
+        let mut writer = BufWriter::new(file);
+        profile.write_pretty(&mut writer, 2).map_err(|_err| {})?;
+        writer.flush().map_err(|_err| {})
+    }
+
+    // One line per stack of templates ending in a source line, in the
+    // collapsed format read by flame graph tools
+    pub fn write_folded(&self, file: &str) -> Result<(), ()> {
+        let mut stacks: BTreeMap<String, usize> = BTreeMap::new();
+        for ((path, source), count) in &self.stacks {
+            let mut frames: Vec<String> =
+                path.iter().map(|node| frame(&self.nodes[*node].template_name)).collect();
+            let source = &self.sources[*source];
+            let file_name = Path::new(&source.file).file_name().and_then(|name| name.to_str());
+            frames.push(frame(&format!("{}:{}", file_name.unwrap_or(&source.file), source.line)));
+            *stacks.entry(frames.join(";")).or_insert(0) += count;
+        }
+        let file = File::create(file).map_err(|_err| {})?;
+        let mut writer = BufWriter::new(file);
+        for (stack, count) in stacks {
+            writeln!(writer, "{} {}", stack, count).map_err(|_err| {})?;
+        }
+        writer.flush().map_err(|_err| {})
+    }
+
+    fn by_cumulative_constraints(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = (0..self.nodes.len()).collect();
+        ids.sort_by(|a, b| self.cumulative[*b].cmp(&self.cumulative[*a]).then(a.cmp(b)));
+        ids
+    }
+}
+
+// The frames of a stack are separated by ; and the count follows the last space
+fn frame(name: &str) -> String {
+    name.replace(';', ",").replace(' ', "_")
+}
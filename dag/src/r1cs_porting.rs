@@ -1,4 +1,6 @@
-use super::{Constraint, Tree, DAG};
+use super::{Tree, DAG};
+use circom_algebra::constraint_storage::{ConstraintID, ConstraintStorage};
+use circom_algebra::num_bigint::BigInt;
 use constraint_writers::log_writer::Log;
 use constraint_writers::r1cs_writer::{ConstraintSection, CustomGatesAppliedData, HeaderData, R1CSWriter};
 
@@ -13,7 +15,7 @@ pub fn write(dag: &DAG, output: &str, custom_gates: bool) -> Result<(), ()> {
     let r1cs = R1CSWriter::new(output.to_string(), field_size, custom_gates)?;
 
     let mut constraint_section = R1CSWriter::start_constraints_section(r1cs)?;
-    let wires = write_constraint_section(&mut constraint_section, &mut log, &tree)? + 1; // adding 1 to include the signal used to represent value 1 in the field (signal one)
+    let wires = write_constraint_section(&mut constraint_section, &mut log, dag, &tree.field)? + 1; // adding 1 to include the signal used to represent value 1 in the field (signal one)
     let labels = wires;
     let constraint_counter = constraint_section.constraints_written();
     let r1cs = constraint_section.end_section()?;
@@ -112,24 +114,125 @@ pub fn write(dag: &DAG, output: &str, custom_gates: bool) -> Result<(), ()> {
     Result::Ok(())
 }
 
+// The constraints of each node are stored once and written for each of its
+// occurrences, with the signals moved by the offset of the occurrence. As in
+// Tree, the empty constraints are only written for the main component.
+struct StoredNodes {
+    storage: ConstraintStorage,
+    constraints: Vec<Vec<ConstraintID>>,
+    signals: Vec<usize>,
+}
+
+impl StoredNodes {
+    fn new(dag: &DAG) -> StoredNodes {
+        let mut storage = ConstraintStorage::new();
+        let mut constraints = Vec::with_capacity(dag.nodes.len());
+        let mut signals = Vec::with_capacity(dag.nodes.len());
+        for (node_id, node) in dag.nodes.iter().enumerate() {
+            let ids = node
+                .constraints
+                .iter()
+                .filter(|c| node_id == dag.main_id() || !c.is_empty())
+                .map(|c| storage.add_constraint(c.clone()))
+                .collect();
+            constraints.push(ids);
+            signals.push(node.correspondence().values().filter(|id| node.is_local_signal(**id)).count());
+        }
+        StoredNodes { storage, constraints, signals }
+    }
+}
+
 fn write_constraint_section(
     constraint_section: &mut ConstraintSection,
     log: &mut Log,
-    tree: &Tree,
+    dag: &DAG,
+    field: &BigInt,
 ) -> Result<usize, ()> {
-    let mut no_signals = tree.signals.len();
-    for c in &tree.constraints {
-        if Constraint::is_linear(c) {
-            log.no_linear += 1;
-        } else {
-            log.no_non_linear += 1;
+    let nodes = StoredNodes::new(dag);
+    let constants = constraint_section.encode_constants(&nodes.storage, field);
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let mut pending = vec![(dag.main_id(), None)];
+    let mut no_signals = 0;
+    while let Some((node_id, offset)) = pending.pop() {
+        let ids = &nodes.constraints[node_id];
+        no_signals += nodes.signals[node_id];
+        for c_id in ids {
+            if nodes.storage.is_linear(*c_id) {
+                log.no_linear += 1;
+            } else {
+                log.no_non_linear += 1;
+            }
+        }
+        // The constraints of the main component keep their signals
+        let rename = |signal: usize| match offset {
+            Some(offset) if signal != 0 => signal + offset,
+            _ => signal,
+        };
+        constraint_section.write_stored_constraints(&nodes.storage, ids, &constants, &rename, threads)?;
+        let offset = offset.unwrap_or(dag.get_entry().unwrap().in_number);
+        for edge in dag.adjacency[node_id].iter().rev() {
+            pending.push((edge.goes_to, Some(offset + edge.in_number)));
         }
-        ConstraintSection::write_constraint_usize(constraint_section, c.a(), c.b(), c.c())?;
-    }
-    for edge in Tree::get_edges(tree) {
-        let subtree = Tree::go_to_subtree(tree, edge);
-        let subtree_signals = write_constraint_section(constraint_section, log, &subtree)?;
-        no_signals += subtree_signals;
     }
     Result::Ok(no_signals)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Constraint;
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    fn terms(terms: &[(usize, i64)]) -> HashMap<usize, BigInt> {
+        terms.iter().map(|(signal, value)| (*signal, BigInt::from(*value))).collect()
+    }
+
+    // Main with two Square subcomponents, in * in = out, that has an empty
+    // constraint left by the simplification of the node
+    fn build_dag() -> DAG {
+        let mut dag = DAG::new(&"bn128".to_string());
+        dag.add_node("Square()".to_string(), vec![], false, false);
+        dag.add_output("out".to_string());
+        dag.add_input("in".to_string(), false);
+        dag.add_constraint(Constraint::new(terms(&[(2, 1)]), terms(&[(2, 1)]), terms(&[(1, 1)])), None);
+        dag.add_constraint(Constraint::empty(), None);
+        dag.add_node("Main()".to_string(), vec![], false, false);
+        dag.add_output("y".to_string());
+        dag.add_input("x".to_string(), false);
+        dag.add_edge(0, "first", false);
+        dag.add_edge(0, "second", false);
+        dag.add_constraint(Constraint::new(terms(&[]), terms(&[]), terms(&[(2, 1), (4, -1)])), None);
+        dag.add_constraint(Constraint::new(terms(&[]), terms(&[]), terms(&[(3, 1), (6, -1)])), None);
+        dag.add_constraint(Constraint::new(terms(&[]), terms(&[]), terms(&[(5, 1), (1, -1), (0, 3)])), None);
+        dag
+    }
+
+    // Constraints decoded in each occurrence of the nodes
+    fn write_tree(tree: &Tree, section: &mut ConstraintSection<Cursor<Vec<u8>>>) {
+        for c in &tree.constraints {
+            section.write_constraint_usize(c.a(), c.b(), c.c()).unwrap();
+        }
+        for edge in Tree::get_edges(tree) {
+            write_tree(&Tree::go_to_subtree(tree, edge), section);
+        }
+    }
+
+    #[test]
+    fn stored_constraints_are_written_as_the_ones_of_the_tree() {
+        let dag = build_dag();
+        let r1cs = R1CSWriter::from_writer(Cursor::new(Vec::new()), 32, false).unwrap();
+        let mut section = R1CSWriter::start_constraints_section(r1cs).unwrap();
+        write_tree(&Tree::new(&dag), &mut section);
+        assert_eq!(section.constraints_written(), 5);
+        let r1cs = section.end_section().unwrap();
+        let expected = R1CSWriter::into_inner(r1cs).unwrap().into_inner();
+
+        let file = std::env::temp_dir().join(format!("circom_dag_r1cs_{}.r1cs", std::process::id()));
+        write(&dag, file.to_str().unwrap(), false).unwrap();
+        let written = std::fs::read(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        // The constraint section is the first one of the file
+        assert_eq!(written[..expected.len()], expected[..]);
+    }
+}
@@ -0,0 +1,503 @@
+use super::{Constraint, Edge, Node};
+use circom_algebra::modular_arithmetic;
+use circom_algebra::num_bigint::BigInt;
+use circom_algebra::num_traits::{One, ToPrimitive, Zero};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use std::collections::{BTreeMap, HashMap};
+
+/*
+    Value-range analysis of the signals of every instance. A signal with
+    bound b in a node is proven by the constraints of the node (and by the
+    bounds of the inputs and outputs of its subcomponents) to be an integer
+    in [0, b]. Bounds come from the boolean-like constraints x * (x - k) === 0
+    and from solving a constraint for one of its signals when the rest of
+    the expression has a known integer range that does not wrap the field.
+*/
+type Bounds = HashMap<usize, BigInt>;
+type Linear = HashMap<usize, BigInt>;
+
+const MAX_PASSES: usize = 32;
+const POSSIBLE_FIELD_OVERFLOW_CODE: ReportCode = ReportCode::PossibleFieldOverflow;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Checked {
+    Comparison,
+    Decomposition,
+    Shift,
+}
+
+// Name of the template without its parameters and namespaces
+fn base_name(template_name: &str) -> &str {
+    let base = template_name.split('(').next().unwrap();
+    base.rsplit("::").next().unwrap()
+}
+
+fn checked_template(template_name: &str) -> Option<Checked> {
+    match base_name(template_name) {
+        "LessThan" | "LessEqThan" | "GreaterThan" | "GreaterEqThan" => Some(Checked::Comparison),
+        "Num2Bits" => Some(Checked::Decomposition),
+        "ShiftRight" | "ShiftLeft" => Some(Checked::Shift),
+        _ => None,
+    }
+}
+
+// Field elements greater than p/2 are read as negative integers
+fn signed(value: &BigInt, field: &BigInt) -> BigInt {
+    let value = modular_arithmetic::add(value, &BigInt::zero(), field);
+    if &value + &value > *field {
+        value - field
+    } else {
+        value
+    }
+}
+
+fn product(x: &(BigInt, BigInt), y: &(BigInt, BigInt)) -> (BigInt, BigInt) {
+    let candidates = [&x.0 * &y.0, &x.0 * &y.1, &x.1 * &y.0, &x.1 * &y.1];
+    let lo = candidates.iter().min().unwrap().clone();
+    let hi = candidates.iter().max().unwrap().clone();
+    (lo, hi)
+}
+
+// Integer range of a linear expression, ignoring the signal skip
+fn linear_range(
+    expression: &Linear,
+    skip: Option<usize>,
+    bounds: &Bounds,
+    field: &BigInt,
+) -> Option<(BigInt, BigInt)> {
+    let mut lo = BigInt::zero();
+    let mut hi = BigInt::zero();
+    for (signal, coefficient) in expression {
+        if Some(*signal) == skip {
+            continue;
+        }
+        let coefficient = signed(coefficient, field);
+        if *signal == Constraint::constant_coefficient() {
+            lo += &coefficient;
+            hi += &coefficient;
+        } else {
+            let bound = bounds.get(signal)?;
+            if coefficient < BigInt::zero() {
+                lo += coefficient * bound;
+            } else {
+                hi += coefficient * bound;
+            }
+        }
+    }
+    Some((lo, hi))
+}
+
+fn single_signal(expression: &Linear) -> Option<(usize, BigInt, BigInt)> {
+    let constant = Constraint::constant_coefficient();
+    let mut signals = expression.iter().filter(|(s, _)| **s != constant);
+    let (signal, coefficient) = signals.next()?;
+    if signals.next().is_some() {
+        return None;
+    }
+    let offset = expression.get(&constant).cloned().unwrap_or_else(BigInt::zero);
+    Some((*signal, coefficient.clone(), offset))
+}
+
+// x * (x - k) === 0 forces x to be either 0 or k
+fn root_bound(constraint: &Constraint, field: &BigInt) -> Option<(usize, BigInt)> {
+    if !constraint.c().is_empty() {
+        return None;
+    }
+    let (xa, ka, oa) = single_signal(constraint.a())?;
+    let (xb, kb, ob) = single_signal(constraint.b())?;
+    if xa != xb {
+        return None;
+    }
+    let root_a = modular_arithmetic::div(&modular_arithmetic::prefix_sub(&oa, field), &ka, field).ok()?;
+    let root_b = modular_arithmetic::div(&modular_arithmetic::prefix_sub(&ob, field), &kb, field).ok()?;
+    Some((xa, std::cmp::max(root_a, root_b)))
+}
+
+// Solves A * B - C = 0 for a signal that only appears in C
+fn solved_bound(constraint: &Constraint, signal: usize, bounds: &Bounds, field: &BigInt) -> Option<BigInt> {
+    if constraint.a().contains_key(&signal) || constraint.b().contains_key(&signal) {
+        return None;
+    }
+    let coefficient = constraint.c().get(&signal)?;
+    let quadratic = if constraint.a().is_empty() || constraint.b().is_empty() {
+        (BigInt::zero(), BigInt::zero())
+    } else {
+        let a = linear_range(constraint.a(), None, bounds, field)?;
+        let b = linear_range(constraint.b(), None, bounds, field)?;
+        product(&a, &b)
+    };
+    let rest = linear_range(constraint.c(), Some(signal), bounds, field)?;
+    let numerator = (&quadratic.0 - &rest.1, &quadratic.1 - &rest.0);
+    let inverse = modular_arithmetic::div(&BigInt::one(), coefficient, field).ok()?;
+    let inverse = signed(&inverse, field);
+    let (lo, hi) = product(&numerator, &(inverse.clone(), inverse));
+    if lo >= BigInt::zero() && hi < *field {
+        Some(hi)
+    } else {
+        None
+    }
+}
+
+fn tighten(bounds: &mut Bounds, signal: usize, bound: BigInt) -> bool {
+    match bounds.get(&signal) {
+        Some(previous) if *previous <= bound => false,
+        _ => {
+            bounds.insert(signal, bound);
+            true
+        }
+    }
+}
+
+fn propagate(constraints: &[Constraint], mut bounds: Bounds, field: &BigInt) -> Bounds {
+    let constant = Constraint::constant_coefficient();
+    for _ in 0..MAX_PASSES {
+        let mut changed = false;
+        for constraint in constraints {
+            if let Some((signal, bound)) = root_bound(constraint, field) {
+                changed |= tighten(&mut bounds, signal, bound);
+            }
+            let signals: Vec<usize> = constraint.c().keys().filter(|s| **s != constant).cloned().collect();
+            for signal in signals {
+                if let Some(bound) = solved_bound(constraint, signal, &bounds, field) {
+                    changed |= tighten(&mut bounds, signal, bound);
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    bounds
+}
+
+fn node_bounds(nodes: &[Node], edges: &[Edge], summaries: &[Bounds], node: &Node, field: &BigInt) -> Bounds {
+    let mut seeds = Bounds::new();
+    for edge in edges {
+        if nodes[edge.goes_to].is_custom_gate() {
+            continue;
+        }
+        for (signal, bound) in &summaries[edge.goes_to] {
+            seeds.insert(edge.in_number + signal, bound.clone());
+        }
+    }
+    propagate(&node.constraints, seeds, field)
+}
+
+fn summary(node: &Node, bounds: &Bounds) -> Bounds {
+    let io = node.number_of_inputs() + node.number_of_outputs();
+    bounds.iter().filter(|(s, _)| **s >= 1 && **s <= io).map(|(s, b)| (*s, b.clone())).collect()
+}
+
+// Statement of the constraint in the given position
+fn origin_of(node: &Node, position: usize) -> Option<(FileID, FileLocation)> {
+    let origin = node.constraint_origins.get(position)?.as_ref()?;
+    Some((origin.file_id, origin.start..origin.end))
+}
+
+// Statement of the first constraint where the signal appears
+fn location_of(node: &Node, signal: usize) -> Option<(FileID, FileLocation)> {
+    let position = node.constraints.iter().position(|c| c.take_cloned_signals().contains(&signal))?;
+    origin_of(node, position)
+}
+
+fn without_indexes(label: &str) -> String {
+    label.split('[').next().unwrap().to_string()
+}
+
+struct Finding {
+    message: String,
+    examples: Vec<String>,
+    location: Option<(FileID, FileLocation)>,
+}
+
+struct Findings {
+    // template, subcomponent array and input of the finding
+    found: BTreeMap<(String, String, String), Finding>,
+}
+
+impl Findings {
+    fn add(
+        &mut self,
+        key: (String, String, String),
+        message: String,
+        example: String,
+        location: Option<(FileID, FileLocation)>,
+    ) {
+        let finding = self.found.entry(key).or_insert(Finding { message, examples: vec![], location });
+        finding.examples.push(example);
+    }
+
+    fn into_reports(self) -> ReportCollection {
+        let mut reports = vec![];
+        for (_, finding) in self.found {
+            let mut report = Report::warning(finding.message, POSSIBLE_FIELD_OVERFLOW_CODE);
+            if let Some((file_id, location)) = finding.location {
+                report.add_primary(location, file_id, "possible field overflow".to_string());
+            }
+            if finding.examples.len() > 1 {
+                report.add_note(format!(
+                    "It happens in {} subcomponents, for example: {}, {}.",
+                    finding.examples.len(),
+                    finding.examples[0],
+                    finding.examples[1]
+                ));
+            }
+            reports.push(report);
+        }
+        reports
+    }
+}
+
+// Comparisons of n bits compute in[0] + 2^n - in[1], which needs n + 2 bits
+fn check_comparison(node: &Node, edge: &Edge, child: &Node, bounds: &Bounds, field: &BigInt, findings: &mut Findings) {
+    let bits = match child.parameters().first().and_then(|n| n.to_usize()) {
+        Some(bits) => bits,
+        None => return,
+    };
+    let max_bits = field.bits() - 2;
+    if bits > max_bits {
+        let message = format!(
+            "In template \"{}\": {} compares values of {} bits, but only comparisons of at most {} bits are sound",
+            node.template_name, child.template_name, bits, max_bits
+        );
+        let key = (node.template_name.clone(), without_indexes(&edge.label), String::new());
+        let first_input = child.correspondence().get("in[0]").map(|signal| edge.in_number + signal);
+        let location = first_input.and_then(|signal| location_of(node, signal));
+        findings.add(key, message, edge.label.clone(), location);
+        return;
+    }
+    let limit = (BigInt::one() << bits) - 1;
+    for input in ["in[0]", "in[1]"] {
+        let signal = match child.correspondence().get(input) {
+            Some(signal) => edge.in_number + signal,
+            None => continue,
+        };
+        let bounded = bounds.get(&signal).is_some_and(|bound| *bound <= limit);
+        if !bounded {
+            let message = format!(
+                "In template \"{}\": Input {}.{} of {} is not proven to be smaller than 2^{}",
+                node.template_name,
+                without_indexes(&edge.label),
+                input,
+                child.template_name,
+                bits
+            );
+            let key = (node.template_name.clone(), without_indexes(&edge.label), input.to_string());
+            findings.add(key, message, format!("{}.{}", edge.label, input), location_of(node, signal));
+        }
+    }
+}
+
+fn check_decomposition(node: &Node, edge: &Edge, child: &Node, bounds: &Bounds, field: &BigInt, findings: &mut Findings) {
+    let bits = match child.parameters().first().and_then(|n| n.to_usize()) {
+        Some(bits) => bits,
+        None => return,
+    };
+    let signal = match child.correspondence().get("in") {
+        Some(signal) => edge.in_number + signal,
+        None => return,
+    };
+    let key = (node.template_name.clone(), without_indexes(&edge.label), "in".to_string());
+    if bits >= field.bits() {
+        let message = format!(
+            "In template \"{}\": The decomposition of {} is not unique, as {} bits can represent values over the field size",
+            node.template_name, child.template_name, bits
+        );
+        findings.add(key, message, edge.label.clone(), location_of(node, signal));
+        return;
+    }
+    let limit = (BigInt::one() << bits) - 1;
+    let constant = Constraint::constant_coefficient();
+    for (position, constraint) in node.constraints.iter().enumerate() {
+        let is_sum = Constraint::is_linear(constraint)
+            && constraint.c().contains_key(&signal)
+            && constraint.c().keys().filter(|s| **s != constant && **s != signal).count() >= 2;
+        if !is_sum {
+            continue;
+        }
+        let bounded = solved_bound(constraint, signal, bounds, field).is_some_and(|bound| bound <= limit);
+        if !bounded {
+            let message = format!(
+                "In template \"{}\": Input {}.in of {} is a sum that is not proven to be smaller than 2^{}",
+                node.template_name,
+                without_indexes(&edge.label),
+                child.template_name,
+                bits
+            );
+            findings.add(key, message, format!("{}.in", edge.label), origin_of(node, position));
+            return;
+        }
+    }
+}
+
+// Shifts of n bits decompose their input in n bits, and ShiftLeft(n, k) needs n + k bits
+fn check_shift(node: &Node, edge: &Edge, child: &Node, bounds: &Bounds, field: &BigInt, findings: &mut Findings) {
+    let bits = match child.parameters().first().and_then(|n| n.to_usize()) {
+        Some(bits) => bits,
+        None => return,
+    };
+    let signal = match child.correspondence().get("in") {
+        Some(signal) => edge.in_number + signal,
+        None => return,
+    };
+    let key = (node.template_name.clone(), without_indexes(&edge.label), "in".to_string());
+    let shift = match base_name(&child.template_name) {
+        "ShiftLeft" => child.parameters().get(1).and_then(|k| k.to_usize()),
+        _ => Some(0),
+    };
+    let result_bits = shift.map_or(usize::MAX, |shift| bits.saturating_add(shift));
+    if result_bits >= field.bits() {
+        let message = format!(
+            "In template \"{}\": {} shifts values of {} bits into values that can exceed the field size",
+            node.template_name, child.template_name, bits
+        );
+        findings.add(key, message, edge.label.clone(), location_of(node, signal));
+        return;
+    }
+    let limit = (BigInt::one() << bits) - 1;
+    let bounded = bounds.get(&signal).is_some_and(|bound| *bound <= limit);
+    if !bounded {
+        let message = format!(
+            "In template \"{}\": Input {}.in of {} is not proven to be smaller than 2^{}",
+            node.template_name,
+            without_indexes(&edge.label),
+            child.template_name,
+            bits
+        );
+        findings.add(key, message, format!("{}.in", edge.label), location_of(node, signal));
+    }
+}
+
+pub fn analyse(nodes: &[Node], adjacency: &[Vec<Edge>], field: &BigInt) -> ReportCollection {
+    let mut summaries: Vec<Bounds> = Vec::with_capacity(nodes.len());
+    let mut findings = Findings { found: BTreeMap::new() };
+    for (id, node) in nodes.iter().enumerate() {
+        if node.is_custom_gate() {
+            summaries.push(Bounds::new());
+            continue;
+        }
+        let bounds = node_bounds(nodes, &adjacency[id], &summaries, node, field);
+        if checked_template(&node.template_name).is_none() {
+            for edge in &adjacency[id] {
+                let child = &nodes[edge.goes_to];
+                match checked_template(&child.template_name) {
+                    Some(Checked::Comparison) => {
+                        check_comparison(node, edge, child, &bounds, field, &mut findings)
+                    }
+                    Some(Checked::Decomposition) => {
+                        check_decomposition(node, edge, child, &bounds, field, &mut findings)
+                    }
+                    Some(Checked::Shift) => check_shift(node, edge, child, &bounds, field, &mut findings),
+                    None => {}
+                }
+            }
+        }
+        summaries.push(summary(node, &bounds));
+    }
+    findings.into_reports()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstraintOrigin, DAG};
+    use program_structure::constants::UsefulConstants;
+
+    fn linear(terms: &[(usize, i64)]) -> Linear {
+        terms.iter().map(|(s, v)| (*s, BigInt::from(*v))).collect()
+    }
+
+    #[test]
+    fn range_analysis_bounds_bits_and_their_sum() {
+        let field = BigInt::from(101);
+        let minus_one = BigInt::from(100);
+        // b1 * (b1 - 1) === 0, b2 * (b2 - 1) === 0, s === b1 + 2 * b2
+        let mut constraints = vec![];
+        for bit in [1, 2] {
+            let a = linear(&[(bit, 1)]);
+            let mut b = linear(&[(bit, 1)]);
+            b.insert(0, minus_one.clone());
+            constraints.push(Constraint::new(a, b, Linear::new()));
+        }
+        constraints.push(Constraint::new(Linear::new(), Linear::new(), linear(&[(1, 1), (2, 2), (3, 100)])));
+        let bounds = propagate(&constraints, Bounds::new(), &field);
+        assert_eq!(bounds.get(&1), Some(&BigInt::from(1)));
+        assert_eq!(bounds.get(&2), Some(&BigInt::from(1)));
+        assert_eq!(bounds.get(&3), Some(&BigInt::from(3)));
+    }
+
+    // main has a bit b, its double d === 2 * b, and a free signal x, and shifts d and x.
+    // The statement free.in <== x is at 40..55.
+    fn shifted(template_name: &str, bits: i64, shift: i64) -> ReportCollection {
+        let mut dag = DAG::new(&"bn128".to_string());
+        let name = format!("{}({},{})", template_name, bits, shift);
+        dag.add_node(name, vec![BigInt::from(bits), BigInt::from(shift)], false, false);
+        dag.add_input("in".to_string(), false);
+        dag.add_output("out".to_string());
+        dag.add_node("Main()".to_string(), vec![], false, false);
+        for signal in ["b", "d", "x"] {
+            dag.add_intermediate(signal.to_string());
+        }
+        dag.add_edge(0, "bounded", false);
+        dag.add_edge(0, "free", false);
+        let field = UsefulConstants::new(&dag.prime).get_p().clone();
+        let minus = |value: i64| &field - BigInt::from(value);
+        let correspondence = dag.get_main().unwrap().correspondence().clone();
+        let (b, d, x) = (correspondence["b"], correspondence["d"], correspondence["x"]);
+        let (bounded, free) = (correspondence["bounded.in"], correspondence["free.in"]);
+        let mut b_minus_one = linear(&[(b, 1)]);
+        b_minus_one.insert(0, minus(1));
+        dag.add_constraint(Constraint::new(linear(&[(b, 1)]), b_minus_one, Linear::new()), None);
+        let mut double = linear(&[(d, 1)]);
+        double.insert(b, minus(2));
+        dag.add_constraint(Constraint::new(Linear::new(), Linear::new(), double), None);
+        let mut bounded_input = linear(&[(bounded, 1)]);
+        bounded_input.insert(d, minus(1));
+        dag.add_constraint(Constraint::new(Linear::new(), Linear::new(), bounded_input), None);
+        let mut free_input = linear(&[(free, 1)]);
+        free_input.insert(x, minus(1));
+        let origin = ConstraintOrigin { file_id: 0, start: 40, end: 55 };
+        dag.add_constraint(Constraint::new(Linear::new(), Linear::new(), free_input), Some(origin));
+        dag.range_analysis()
+    }
+
+    fn messages(reports: &ReportCollection) -> Vec<&String> {
+        reports.iter().map(|report| report.get_message()).collect()
+    }
+
+    #[test]
+    fn range_analysis_checks_the_inputs_of_shifts() {
+        let reports = shifted("ShiftRight", 2, 1);
+        assert_eq!(
+            messages(&reports),
+            vec!["In template \"Main()\": Input free.in of ShiftRight(2,1) is not proven to be smaller than 2^2"]
+        );
+        let label = &reports[0].get_primary()[0];
+        assert_eq!((label.file_id, label.range.clone()), (0, 40..55));
+        let reports = shifted("ShiftLeft", 2, 3);
+        assert_eq!(
+            messages(&reports),
+            vec!["In template \"Main()\": Input free.in of ShiftLeft(2,3) is not proven to be smaller than 2^2"]
+        );
+    }
+
+    #[test]
+    fn range_analysis_checks_the_width_of_shifts() {
+        // both subcomponents are reported, whatever the bounds of their inputs
+        let reports = shifted("ShiftLeft", 250, 10);
+        assert_eq!(reports.len(), 2);
+        for report in &reports {
+            assert_eq!(
+                report.get_message(),
+                "In template \"Main()\": ShiftLeft(250,10) shifts values of 250 bits into values that can exceed the field size"
+            );
+        }
+        let reports = shifted("ShiftRight", 254, 0);
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].get_message().contains("ShiftRight(254,0) shifts values of 254 bits"));
+        let reports = shifted("Shift", 2, 1);
+        assert!(reports.is_empty());
+    }
+}
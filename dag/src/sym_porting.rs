@@ -1,5 +1,6 @@
 use super::{Tree, DAG};
 use circom_algebra::num_traits::AsPrimitive;
+use constraint_writers::sym_index::SymIndexWriter;
 use constraint_writers::sym_writer::*;
 use std::collections::HashMap;
 
@@ -14,13 +15,7 @@ pub fn write(dag: &DAG, file_name: &str) -> Result<(), ()> {
 
 fn visit_tree(tree: &Tree, dot_sym: &mut SymFile) -> Result<(), ()> {
     for signal in &tree.signals {
-        let name = HashMap::get(&tree.id_to_name, signal).unwrap();
-        let symbol = format!("{}.{}", tree.path, name);
-        let original = signal.as_();
-        let witness = original;
-        let node_id = tree.node_id.as_();
-        let sym_elem = SymElem { original, witness, node_id, symbol };
-        SymFile::write_sym_elem(dot_sym, sym_elem)?;
+        SymFile::write_sym_elem(dot_sym, sym_elem(tree, *signal))?;
     }
     for edge in Tree::get_edges(tree) {
         let subtree = Tree::go_to_subtree(tree, edge);
@@ -28,3 +23,32 @@ fn visit_tree(tree: &Tree, dot_sym: &mut SymFile) -> Result<(), ()> {
     }
     Ok(())
 }
+
+fn sym_elem(tree: &Tree, signal: usize) -> SymElem {
+    let name = HashMap::get(&tree.id_to_name, &signal).unwrap();
+    let symbol = format!("{}.{}", tree.path, name);
+    let original = signal.as_();
+    let witness = original;
+    let node_id = tree.node_id.as_();
+    SymElem { original, witness, node_id, symbol }
+}
+
+pub fn write_index(dag: &DAG, file_name: &str) -> Result<(), ()> {
+    let tree = Tree::new(dag);
+    let mut index = SymIndexWriter::new();
+    index_tree(&tree, &mut index);
+    index.write(file_name)
+}
+
+fn index_tree(tree: &Tree, index: &mut SymIndexWriter) {
+    for signal in &tree.signals {
+        index.add_signal(sym_elem(tree, *signal));
+    }
+    let first = tree.signals.iter().min().cloned().unwrap_or(0);
+    let end = tree.signals.iter().max().map_or(first, |last| last + 1);
+    index.add_component(tree.path.clone(), first..end);
+    for edge in Tree::get_edges(tree) {
+        let subtree = Tree::go_to_subtree(tree, edge);
+        index_tree(&subtree, index);
+    }
+}
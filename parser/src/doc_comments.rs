@@ -0,0 +1,80 @@
+// Doc comments of a definition: the /// lines or the /** */ block placed
+// right before the line of its header. Pragmas like //#[allow(rule)] may
+// appear between the doc comment and the header.
+pub fn doc_comment(source: &str, header_location: usize) -> Option<String> {
+    let header_location = header_location.min(source.len());
+    let header_start = source[..header_location].rfind('\n').map_or(0, |pos| pos + 1);
+    let mut before = &source[..header_start];
+    loop {
+        let trimmed = before.trim_end();
+        let line_start = trimmed.rfind('\n').map_or(0, |pos| pos + 1);
+        let line = trimmed[line_start..].trim_start();
+        if line.starts_with("//#") {
+            before = &trimmed[..line_start];
+        } else {
+            before = trimmed;
+            break;
+        }
+    }
+    if before.ends_with("*/") {
+        let start = before.rfind("/*")?;
+        let block = &before[start..];
+        let line_start = before[..start].rfind('\n').map_or(0, |pos| pos + 1);
+        if !block.starts_with("/**") || block == "/**/" || !before[line_start..start].trim().is_empty() {
+            return None;
+        }
+        let text = &block[3..block.len() - 2];
+        let lines = text.lines().map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        });
+        return non_empty(lines.collect());
+    }
+    let mut lines = Vec::new();
+    for line in before.lines().rev() {
+        let line = line.trim_start();
+        if line.starts_with("//#") {
+            continue;
+        }
+        match line.strip_prefix("///") {
+            Some(text) if !text.starts_with('/') => lines.push(text.strip_prefix(' ').unwrap_or(text)),
+            _ => break,
+        }
+    }
+    lines.reverse();
+    non_empty(lines)
+}
+
+// Removes the empty lines at the beginning and the end of the comment
+fn non_empty(lines: Vec<&str>) -> Option<String> {
+    let first = lines.iter().position(|line| !line.trim().is_empty())?;
+    let last = lines.iter().rposition(|line| !line.trim().is_empty())?;
+    Some(lines[first..=last].iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::doc_comment;
+
+    fn doc_of(source: &str, name: &str) -> Option<String> {
+        doc_comment(source, source.find(name).unwrap())
+    }
+
+    #[test]
+    fn doc_comments_before_the_header() {
+        let source = "/// Decomposes in into n bits.\n///\n///   out[0] is the least significant\n\
+                      //#[allow(unused-output)]\ntemplate Num2Bits(n) {}\n\n\
+                      /**\n * Sum of\n * two values\n */\nfunction sum(a, b) { return a + b; }\n\n\
+                      // Not a doc comment\ntemplate A() {}\n\n//// Neither\nbus B() {}\n\n\
+                      /* Nor this one */\ntemplate C() {}\n";
+        assert_eq!(
+            doc_of(source, "Num2Bits"),
+            Some("Decomposes in into n bits.\n\n  out[0] is the least significant".to_string())
+        );
+        assert_eq!(doc_of(source, "sum"), Some("Sum of\ntwo values".to_string()));
+        assert_eq!(doc_of(source, "A()"), None);
+        assert_eq!(doc_of(source, "B()"), None);
+        assert_eq!(doc_of(source, "C()"), None);
+    }
+}
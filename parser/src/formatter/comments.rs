@@ -0,0 +1,90 @@
+// Comments are removed from the source before parsing (see
+// parser_logic::preprocess), so they are collected here with their
+// locations to be placed back in the formatted code.
+
+#[derive(Clone)]
+pub struct Comment {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+pub struct CommentQueue<'a> {
+    src: &'a str,
+    comments: Vec<Comment>,
+    next: usize,
+}
+
+impl<'a> CommentQueue<'a> {
+    pub fn new(src: &'a str) -> CommentQueue<'a> {
+        CommentQueue { src, comments: collect_comments(src), next: 0 }
+    }
+
+    // Takes all the comments that start before the given location
+    pub fn take_before(&mut self, location: usize) -> Vec<Comment> {
+        let first = self.next;
+        while self.next < self.comments.len() && self.comments[self.next].start < location {
+            self.next += 1;
+        }
+        self.comments[first..self.next].to_vec()
+    }
+
+    // Takes the next comment if it is placed in the same line after the given
+    // location, with only semicolons between them
+    pub fn take_trailing(&mut self, location: usize) -> Option<Comment> {
+        let comment = self.comments.get(self.next)?;
+        if comment.start < location || comment.text.contains('\n') {
+            return None;
+        }
+        let gap = &self.src[location..comment.start];
+        if gap.chars().all(|c| c == ' ' || c == '\t' || c == ';') {
+            self.next += 1;
+            Some(comment.clone())
+        } else {
+            None
+        }
+    }
+
+    pub fn take_remaining(&mut self) -> Vec<Comment> {
+        self.take_before(usize::MAX)
+    }
+
+    // Checks if the original code had at least one empty line between both locations
+    pub fn has_empty_line(&self, from: usize, to: usize) -> bool {
+        if from >= to || to > self.src.len() {
+            return false;
+        }
+        let lines: Vec<&str> = self.src[from..to].split('\n').collect();
+        lines.len() > 2 && lines[1..lines.len() - 1].iter().any(|line| line.trim().is_empty())
+    }
+
+    // Column of the given location in the original code
+    pub fn column(&self, location: usize) -> usize {
+        let line_start = self.src[..location].rfind('\n').map_or(0, |pos| pos + 1);
+        self.src[line_start..location].chars().count()
+    }
+}
+
+// Follows the same rules as parser_logic::preprocess, so every character
+// blanked by the preprocessing is part of exactly one comment.
+fn collect_comments(src: &str) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'/' && i + 1 < bytes.len() && bytes[i + 1] == b'/' {
+            let end = src[i..].find('\n').map_or(src.len(), |pos| i + pos);
+            let text = src[i..end].trim_end().to_string();
+            comments.push(Comment { start: i, end, text });
+            i = end;
+        } else if bytes[i] == b'/' && i + 1 < bytes.len() && bytes[i + 1] == b'*' {
+            let end = src[i + 2..].find("*/").map_or(src.len(), |pos| i + 2 + pos + 2);
+            let text = src[i..end].to_string();
+            comments.push(Comment { start: i, end, text });
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    comments
+}
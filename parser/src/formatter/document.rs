@@ -0,0 +1,118 @@
+// Minimal implementation of Wadler's "prettier printer" document algebra.
+// Groups are printed on a single line when they fit in the remaining width,
+// otherwise every Line inside them is turned into a line break.
+
+#[derive(Clone)]
+pub enum Doc {
+    Nil,
+    Text(String),
+    // A space when the enclosing group is flat, a line break otherwise
+    Line,
+    // Nothing when the enclosing group is flat, a line break otherwise
+    SoftLine,
+    // Always a line break
+    HardLine,
+    Nest(usize, Box<Doc>),
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+pub fn text<T: Into<String>>(value: T) -> Doc {
+    Doc::Text(value.into())
+}
+
+pub fn nest(indent: usize, doc: Doc) -> Doc {
+    Doc::Nest(indent, Box::new(doc))
+}
+
+pub fn group(doc: Doc) -> Doc {
+    Doc::Group(Box::new(doc))
+}
+
+pub fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub fn render(doc: &Doc, width: usize) -> String {
+    let mut output = String::new();
+    let mut column = 0;
+    let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Nil => {}
+            Doc::Text(value) => {
+                output.push_str(value);
+                column += value.chars().count();
+            }
+            Doc::Line | Doc::SoftLine if mode == Mode::Flat => {
+                if let Doc::Line = doc {
+                    output.push(' ');
+                    column += 1;
+                }
+            }
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                // Trailing spaces are never left at the end of a line
+                while output.ends_with(' ') {
+                    output.pop();
+                }
+                output.push('\n');
+                output.push_str(&" ".repeat(indent));
+                column = indent;
+            }
+            Doc::Nest(extra, inner) => stack.push((indent + extra, mode, inner)),
+            Doc::Group(inner) => {
+                let flat_fits = width >= column && fits(width - column, inner, &stack);
+                let mode = if flat_fits { Mode::Flat } else { Mode::Break };
+                stack.push((indent, mode, inner));
+            }
+            Doc::Concat(docs) => {
+                for inner in docs.iter().rev() {
+                    stack.push((indent, mode, inner));
+                }
+            }
+        }
+    }
+    output
+}
+
+// Checks whether the given document printed in flat mode, followed by the
+// rest of the pending documents up to the next line break, fits in the
+// remaining width.
+fn fits(remaining: usize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut remaining = remaining as isize;
+    let mut pending: Vec<(Mode, &Doc)> = vec![(Mode::Flat, doc)];
+    let mut rest_index = rest.len();
+    loop {
+        let (mode, doc) = match pending.pop() {
+            Some(next) => next,
+            None if rest_index > 0 => {
+                rest_index -= 1;
+                (rest[rest_index].1, rest[rest_index].2)
+            }
+            None => return true,
+        };
+        match doc {
+            Doc::Nil => {}
+            Doc::Text(value) => remaining -= value.chars().count() as isize,
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::Nest(_, inner) => pending.push((mode, inner)),
+            Doc::Group(inner) => pending.push((Mode::Flat, inner)),
+            Doc::Concat(docs) => {
+                for inner in docs.iter().rev() {
+                    pending.push((mode, inner));
+                }
+            }
+        }
+        if remaining < 0 {
+            return false;
+        }
+    }
+}
@@ -0,0 +1,1005 @@
+// Canonical pretty-printer for circom source files.
+//
+// The printer works over the AST returned by the parser before removing the
+// syntactic sugar. Some constructions (for loops, compound assignments,
+// multiple declarations...) are already expanded by the grammar actions, so
+// they are recognized again from the locations of the nodes that were
+// generated for them. Comments and number literals are recovered from the
+// source code.
+
+mod comments;
+mod document;
+
+use crate::parser_logic::{parse_file, preprocess};
+use comments::{Comment, CommentQueue};
+use document::{concat, group, nest, render, text, Doc};
+use program_structure::ast::*;
+use program_structure::constants::UsefulConstants;
+use program_structure::error_definition::ReportCollection;
+use program_structure::file_definition::FileID;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TrailingComma {
+    // Lists never end with a comma
+    Never,
+    // Lists end with a comma when they do in the source code
+    Preserve,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct FormatConfig {
+    pub indent: usize,
+    pub max_width: usize,
+    pub trailing_comma: TrailingComma,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig { indent: 4, max_width: 100, trailing_comma: TrailingComma::Never }
+    }
+}
+
+// Length of the chunks in which build_log_call splits the log strings
+const LOG_STRING_CHUNK: usize = 230;
+
+pub fn format_source(
+    src: &str,
+    file_id: FileID,
+    config: &FormatConfig,
+) -> Result<String, ReportCollection> {
+    // The value of the field is irrelevant here: literals are printed as written
+    let field = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+    let ast = parse_file(src, file_id, &field, true)?;
+    let code = preprocess(src, file_id)?;
+    let mut printer = Printer { config, code: &code, comments: CommentQueue::new(src) };
+    let doc = printer.program(&ast);
+    let mut formatted = render(&doc, config.max_width);
+    formatted.push('\n');
+    Ok(formatted)
+}
+
+struct Printer<'a> {
+    config: &'a FormatConfig,
+    // Source code without comments
+    code: &'a str,
+    comments: CommentQueue<'a>,
+}
+
+impl<'a> Printer<'a> {
+    fn program(&mut self, ast: &AST) -> Doc {
+        let mut lines = Vec::new();
+        let mut last = None;
+        let definitions_start = match (ast.definitions.first(), &ast.main_component) {
+            (Some(definition), _) => definition_meta(definition).start,
+            (None, Some((_, call))) => self.main_start(call),
+            (None, None) => self.code.len(),
+        };
+        let mut includes = ast.includes.iter();
+        for (start, end) in self.header_items(definitions_start) {
+            self.push_comments(&mut lines, &mut last, start, false);
+            let item = &self.code[start..end];
+            let doc = if item.starts_with("include") {
+                let include = includes.next().unwrap();
+                match &include.alias {
+                    Some(alias) => text(format!("include \"{}\" as {};", include.path, alias)),
+                    None => text(format!("include \"{}\";", include.path)),
+                }
+            } else if item.contains("custom_templates") {
+                text("pragma custom_templates;")
+            } else if let Some((major, minor, patch)) = ast.compiler_version {
+                text(format!("pragma circom {}.{}.{};", major, minor, patch))
+            } else {
+                text(item.split_whitespace().collect::<Vec<_>>().join(" "))
+            };
+            self.push_item(&mut lines, &mut last, start, end, doc, false);
+        }
+        for definition in &ast.definitions {
+            let meta = definition_meta(definition);
+            let separate = self.push_comments(&mut lines, &mut last, meta.start, true);
+            let doc = self.definition(definition);
+            self.push_item(&mut lines, &mut last, meta.start, meta.end, doc, !separate);
+        }
+        if let Some((public, call)) = &ast.main_component {
+            let start = self.main_start(call);
+            let end = call.get_meta().end;
+            let separate = self.push_comments(&mut lines, &mut last, start, true);
+            let mut doc = vec![text("component main ")];
+            if !public.is_empty() {
+                doc.push(text(format!("{{public [{}]}} ", public.join(", "))));
+            }
+            doc.push(text("= "));
+            doc.push(self.expression(call, TOP_LEVEL));
+            doc.push(text(";"));
+            self.push_item(&mut lines, &mut last, start, end, concat(doc), !separate);
+        }
+        for comment in self.comments.take_remaining() {
+            self.push_comment(&mut lines, &mut last, comment, false);
+        }
+        join_lines(lines)
+    }
+
+    // Locations of the pragma and include directives, placed before any definition
+    fn header_items(&self, limit: usize) -> Vec<(usize, usize)> {
+        let mut items = Vec::new();
+        let mut start = None;
+        for (i, c) in self.code[..limit].char_indices() {
+            if start.is_none() && !c.is_whitespace() {
+                start = Some(i);
+            }
+            if c == ';' {
+                if let Some(s) = start.take() {
+                    items.push((s, i + 1));
+                }
+            }
+        }
+        items
+    }
+
+    fn main_start(&self, call: &Expression) -> usize {
+        self.code[..call.get_meta().start].rfind("component").unwrap_or(call.get_meta().start)
+    }
+
+    // Pushes the comments placed before the given location. When separate is
+    // set, an empty line is added before the comments attached to the code at
+    // that location; returns true if that empty line was added.
+    fn push_comments(
+        &mut self,
+        lines: &mut Vec<Doc>,
+        last: &mut Option<usize>,
+        location: usize,
+        separate: bool,
+    ) -> bool {
+        let comments = self.comments.take_before(location);
+        let mut attached = comments.len();
+        let mut next_start = location;
+        while attached > 0 && !self.comments.has_empty_line(comments[attached - 1].end, next_start) {
+            attached -= 1;
+            next_start = comments[attached].start;
+        }
+        let separated = separate && attached < comments.len() && last.is_some();
+        for (i, comment) in comments.into_iter().enumerate() {
+            self.push_comment(lines, last, comment, separate && i == attached);
+        }
+        separated
+    }
+
+    fn push_comment(
+        &mut self,
+        lines: &mut Vec<Doc>,
+        last: &mut Option<usize>,
+        comment: Comment,
+        separate: bool,
+    ) {
+        if let Some(previous) = *last {
+            if separate || self.comments.has_empty_line(previous, comment.start) {
+                lines.push(Doc::Nil);
+            }
+        }
+        let column = self.comments.column(comment.start);
+        let mut doc = Vec::new();
+        for (i, line) in comment.text.lines().enumerate() {
+            if i == 0 {
+                doc.push(text(line));
+            } else {
+                let indentation = line.len() - line.trim_start().len();
+                doc.push(Doc::HardLine);
+                doc.push(text(line[indentation.min(column)..].trim_end()));
+            }
+        }
+        lines.push(concat(doc));
+        *last = Some(comment.end);
+    }
+
+    fn push_item(
+        &mut self,
+        lines: &mut Vec<Doc>,
+        last: &mut Option<usize>,
+        start: usize,
+        end: usize,
+        doc: Doc,
+        separate: bool,
+    ) {
+        if let Some(previous) = *last {
+            if separate || self.comments.has_empty_line(previous, start) {
+                lines.push(Doc::Nil);
+            }
+        }
+        if let Some(comment) = self.comments.take_trailing(end) {
+            lines.push(concat(vec![doc, text(" "), text(comment.text)]));
+            *last = Some(comment.end);
+        } else {
+            lines.push(doc);
+            *last = Some(end);
+        }
+    }
+
+    fn definition(&mut self, definition: &Definition) -> Doc {
+        match definition {
+            Definition::Template { name, args, arg_location, body, parallel, is_custom_gate, .. } => {
+                let mut header = String::from("template ");
+                if *is_custom_gate {
+                    header.push_str("custom ");
+                }
+                if *parallel {
+                    header.push_str("parallel ");
+                }
+                header.push_str(name);
+                self.definition_with_body(header, args, arg_location.end, body, true)
+            }
+            Definition::Function { name, args, arg_location, body, .. } => {
+                self.definition_with_body(format!("function {}", name), args, arg_location.end, body, true)
+            }
+            Definition::Bus { name, args, arg_location, body, .. } => {
+                // The parameters of a bus are optional, the parenthesis are kept if written
+                let parenthesis = self.code.get(arg_location.clone()).is_some_and(|a| a.contains('('));
+                self.definition_with_body(format!("bus {}", name), args, arg_location.end, body, parenthesis)
+            }
+        }
+    }
+
+    fn definition_with_body(
+        &mut self,
+        header: String,
+        args: &[String],
+        args_end: usize,
+        body: &Statement,
+        parenthesis: bool,
+    ) -> Doc {
+        let mut doc = vec![text(header)];
+        if parenthesis {
+            let args = args.iter().map(|arg| text(arg.clone())).collect();
+            doc.push(self.list("(", args, ")", Some(args_end)));
+        }
+        doc.push(text(" "));
+        doc.push(self.statement(body));
+        concat(doc)
+    }
+
+    // Statements
+
+    fn sequence(&mut self, stmts: &[Statement], end: usize) -> Vec<Doc> {
+        let mut lines = Vec::new();
+        let mut last = None;
+        for stmt in stmts {
+            let meta = stmt.get_meta();
+            let comments_until = if is_compound(stmt) { meta.start } else { meta.end };
+            self.push_comments(&mut lines, &mut last, comments_until, false);
+            let doc = self.statement(stmt);
+            self.push_item(&mut lines, &mut last, meta.start, meta.end, doc, false);
+        }
+        self.push_comments(&mut lines, &mut last, end, false);
+        lines
+    }
+
+    fn block(&mut self, stmts: &[Statement], end: usize) -> Doc {
+        let lines = self.sequence(stmts, end);
+        if lines.is_empty() {
+            text("{}")
+        } else {
+            concat(vec![
+                text("{"),
+                nest(self.config.indent, concat(vec![Doc::HardLine, join_lines(lines)])),
+                Doc::HardLine,
+                text("}"),
+            ])
+        }
+    }
+
+    // Body of a conditional or a loop
+    fn body(&mut self, stmt: &Statement) -> Doc {
+        match stmt {
+            Statement::Block { meta, stmts } if for_loop(stmt).is_none() => {
+                concat(vec![text(" "), self.block(stmts, meta.end)])
+            }
+            _ => {
+                let lines = self.sequence(std::slice::from_ref(stmt), stmt.get_meta().end);
+                nest(self.config.indent, concat(vec![Doc::HardLine, join_lines(lines)]))
+            }
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> Doc {
+        use Statement::*;
+        match stmt {
+            Block { meta, stmts } => {
+                if let Some((init, cond, step, body)) = for_loop(stmt) {
+                    concat(vec![
+                        text("for ("),
+                        self.simple_statement(init),
+                        text("; "),
+                        self.expression(cond, TOP_LEVEL),
+                        text("; "),
+                        self.simple_statement(step),
+                        text(")"),
+                        self.body(body),
+                    ])
+                } else {
+                    self.block(stmts, meta.end)
+                }
+            }
+            IfThenElse { cond, if_case, else_case, .. } => {
+                let mut doc = vec![
+                    text("if ("),
+                    self.expression(cond, TOP_LEVEL),
+                    text(")"),
+                    self.body(if_case),
+                ];
+                if let Some(else_case) = else_case {
+                    doc.push(if is_plain_block(if_case) { text(" else") } else {
+                        concat(vec![Doc::HardLine, text("else")])
+                    });
+                    if let IfThenElse { .. } = else_case.as_ref() {
+                        doc.push(text(" "));
+                        doc.push(self.statement(else_case));
+                    } else {
+                        doc.push(self.body(else_case));
+                    }
+                }
+                concat(doc)
+            }
+            While { cond, stmt, .. } => concat(vec![
+                text("while ("),
+                self.expression(cond, TOP_LEVEL),
+                text(")"),
+                self.body(stmt),
+            ]),
+            _ => concat(vec![self.simple_statement(stmt), text(";")]),
+        }
+    }
+
+    // Statements that are not compound, printed without the final semicolon
+    fn simple_statement(&mut self, stmt: &Statement) -> Doc {
+        use Statement::*;
+        match stmt {
+            InitializationBlock { xtype, initializations, .. } => {
+                self.declaration(xtype, initializations)
+            }
+            Declaration { xtype, name, dimensions, .. } => concat(vec![
+                self.declaration_header(xtype, None),
+                text(" "),
+                self.symbol(name, dimensions),
+            ]),
+            Substitution { meta, var, access, op, rhe } => {
+                let target = self.variable(var, access);
+                if let Some((infix_op, value)) = compound_assignment(meta, rhe) {
+                    if let Expression::Number(number_meta, _) = value {
+                        if same_location(meta, number_meta) {
+                            let suffix = if infix_op == ExpressionInfixOpcode::Add { "++" } else { "--" };
+                            return concat(vec![target, text(suffix)]);
+                        }
+                    }
+                    let operator = format!(" {}= ", infix_opcode(infix_op));
+                    concat(vec![target, text(operator), self.expression(value, TOP_LEVEL)])
+                } else {
+                    self.assignment(meta, target, *op, rhe)
+                }
+            }
+            MultSubstitution { meta, lhe, op, rhe } => {
+                if let Expression::Tuple { meta: tuple_meta, values } = lhe {
+                    if values.is_empty() && same_location(meta, tuple_meta) {
+                        // Anonymous component used as a statement
+                        return self.expression(rhe, TOP_LEVEL);
+                    }
+                }
+                let target = self.expression(lhe, TOP_LEVEL);
+                self.assignment(meta, target, *op, rhe)
+            }
+            UnderscoreSubstitution { op, rhe, .. } => concat(vec![
+                text(format!("_ {} ", assign_opcode(*op))),
+                self.expression(rhe, TOP_LEVEL),
+            ]),
+            ConstraintEquality { lhe, rhe, .. } => concat(vec![
+                self.expression(lhe, TOP_LEVEL),
+                text(" === "),
+                self.expression(rhe, TOP_LEVEL),
+            ]),
+            LogCall { meta, args } => {
+                let mut docs: Vec<Doc> = Vec::new();
+                let mut pending_string: Option<String> = None;
+                for arg in args {
+                    match arg {
+                        LogArgument::LogStr(value) => {
+                            // Long strings are split by the parser, they are joined back
+                            match pending_string.as_mut() {
+                                Some(previous) if previous.len() % LOG_STRING_CHUNK == 0 => {
+                                    previous.push_str(value)
+                                }
+                                _ => {
+                                    if let Some(previous) = pending_string.take() {
+                                        docs.push(text(format!("\"{}\"", previous)));
+                                    }
+                                    pending_string = Some(value.clone());
+                                }
+                            }
+                        }
+                        LogArgument::LogExp(expression) => {
+                            if let Some(previous) = pending_string.take() {
+                                docs.push(text(format!("\"{}\"", previous)));
+                            }
+                            docs.push(self.expression(expression, TOP_LEVEL));
+                        }
+                    }
+                }
+                if let Some(previous) = pending_string.take() {
+                    docs.push(text(format!("\"{}\"", previous)));
+                }
+                concat(vec![text("log"), self.list("(", docs, ")", Some(meta.end))])
+            }
+            Assert { arg, message, .. } if message.is_empty() => {
+                concat(vec![text("assert("), self.expression(arg, TOP_LEVEL), text(")")])
+            }
+            Assert { meta, arg, message } => {
+                // The values of the message go back to their {} placeholders
+                let mut format = String::new();
+                let mut values = Vec::new();
+                for arg in message {
+                    match arg {
+                        LogArgument::LogStr(value) => format.push_str(value),
+                        LogArgument::LogExp(expression) => {
+                            format.push_str("{}");
+                            values.push(self.expression(expression, TOP_LEVEL));
+                        }
+                    }
+                }
+                let mut docs = vec![self.expression(arg, TOP_LEVEL), text(format!("\"{}\"", format))];
+                docs.append(&mut values);
+                concat(vec![text("assert"), self.list("(", docs, ")", Some(meta.end))])
+            }
+            Return { value, .. } => {
+                concat(vec![text("return "), self.expression(value, TOP_LEVEL)])
+            }
+            Block { .. } | IfThenElse { .. } | While { .. } => self.statement(stmt),
+        }
+    }
+
+    fn assignment(&self, meta: &Meta, target: Doc, op: AssignOp, rhe: &Expression) -> Doc {
+        let value = self.expression(rhe, TOP_LEVEL);
+        if op != AssignOp::AssignVar && rhe.get_meta().start == meta.start {
+            // Written with the arrow pointing to the right: rhe ==> target
+            let operator = if op == AssignOp::AssignSignal { " --> " } else { " ==> " };
+            concat(vec![value, text(operator), target])
+        } else {
+            concat(vec![target, text(format!(" {} ", assign_opcode(op))), value])
+        }
+    }
+
+    // Declarations are split by the grammar in an initialization block with
+    // a declaration followed by the substitutions of every symbol
+    fn declaration(&mut self, xtype: &VariableType, initializations: &[Statement]) -> Doc {
+        let is_bus = matches!(xtype, VariableType::Bus(..));
+        let mut symbols: Vec<(Doc, Option<(AssignOp, &Expression)>)> = Vec::new();
+        let mut bus_type: Option<&Expression> = None;
+        let mut tuple_init: Option<(AssignOp, &Expression)> = None;
+        let mut pending_bus = false;
+        for stmt in initializations {
+            match stmt {
+                Statement::Declaration { name, dimensions, .. } => {
+                    symbols.push((self.symbol(name, dimensions), None));
+                    pending_bus = is_bus;
+                }
+                Statement::Substitution { rhe, .. } if pending_bus => {
+                    // Instantiation of the bus (as an array when the symbol has dimensions)
+                    let mut bus_call = rhe;
+                    while let Expression::UniformArray { value, .. } = bus_call {
+                        bus_call = value;
+                    }
+                    bus_type = bus_type.or(Some(bus_call));
+                    pending_bus = false;
+                }
+                Statement::Substitution { op, rhe, .. } => {
+                    if let Some(symbol) = symbols.last_mut() {
+                        symbol.1 = Some((*op, rhe));
+                    }
+                }
+                Statement::MultSubstitution { op, rhe, .. } => tuple_init = Some((*op, rhe)),
+                _ => {}
+            }
+        }
+        let mut doc = vec![self.declaration_header(xtype, bus_type), text(" ")];
+        if let Some((op, rhe)) = tuple_init {
+            let names = symbols.into_iter().map(|(name, _)| name).collect();
+            doc.push(self.list("(", names, ")", None));
+            doc.push(text(format!(" {} ", assign_opcode(op))));
+            doc.push(self.expression(rhe, TOP_LEVEL));
+        } else {
+            let mut declared = Vec::new();
+            for (name, init) in symbols {
+                if let Some((op, rhe)) = init {
+                    declared.push(concat(vec![
+                        name,
+                        text(format!(" {} ", assign_opcode(op))),
+                        self.expression(rhe, TOP_LEVEL),
+                    ]));
+                } else {
+                    declared.push(name);
+                }
+            }
+            doc.push(join(declared, text(", ")));
+        }
+        concat(doc)
+    }
+
+    fn declaration_header(&self, xtype: &VariableType, bus_type: Option<&Expression>) -> Doc {
+        match xtype {
+            VariableType::Var => text("var"),
+            VariableType::Component | VariableType::AnonymousComponent => text("component"),
+            VariableType::Signal(signal_type, tags) => {
+                text(format!("signal{}{}", signal_type_suffix(*signal_type), tags_suffix(tags)))
+            }
+            VariableType::Bus(name, signal_type, tags) => {
+                let bus = match bus_type {
+                    Some(Expression::BusCall { meta, id, args }) => {
+                        if self.code[meta.start..meta.end].contains('(') {
+                            let args = args.iter().map(|arg| self.expression(arg, TOP_LEVEL)).collect();
+                            concat(vec![text(id.clone()), self.list("(", args, ")", Some(meta.end))])
+                        } else {
+                            text(id.clone())
+                        }
+                    }
+                    _ => text(name.clone()),
+                };
+                concat(vec![
+                    bus,
+                    text(format!("{}{}", signal_type_suffix(*signal_type), tags_suffix(tags))),
+                ])
+            }
+        }
+    }
+
+    fn symbol(&self, name: &str, dimensions: &[Expression]) -> Doc {
+        let mut doc = vec![text(name)];
+        for dimension in dimensions {
+            doc.push(text("["));
+            doc.push(self.expression(dimension, TOP_LEVEL));
+            doc.push(text("]"));
+        }
+        concat(doc)
+    }
+
+    // Expressions
+
+    fn expression(&self, expression: &Expression, max_level: usize) -> Doc {
+        let doc = self.unparenthesized_expression(expression);
+        if precedence(expression) > max_level {
+            concat(vec![text("("), doc, text(")")])
+        } else {
+            doc
+        }
+    }
+
+    fn unparenthesized_expression(&self, expression: &Expression) -> Doc {
+        use Expression::*;
+        match expression {
+            InfixOp { infix_op, .. } => {
+                let level = infix_precedence(*infix_op);
+                let mut operands = Vec::new();
+                let mut first = expression;
+                while let InfixOp { lhe, infix_op, rhe, .. } = first {
+                    if infix_precedence(*infix_op) != level {
+                        break;
+                    }
+                    operands.push((*infix_op, rhe.as_ref()));
+                    first = lhe;
+                }
+                let mut rest = Vec::new();
+                for (op, operand) in operands.into_iter().rev() {
+                    rest.push(Doc::Line);
+                    rest.push(text(format!("{} ", infix_opcode(op))));
+                    rest.push(self.expression(operand, level - 1));
+                }
+                group(concat(vec![
+                    self.expression(first, level),
+                    nest(self.config.indent, concat(rest)),
+                ]))
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                concat(vec![text(prefix_opcode(*prefix_op)), self.expression(rhe, ATOM_LEVEL)])
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => group(concat(vec![
+                self.expression(cond, SWITCH_LEVEL - 1),
+                nest(
+                    self.config.indent,
+                    concat(vec![
+                        Doc::Line,
+                        text("? "),
+                        self.expression(if_true, SWITCH_LEVEL - 1),
+                        Doc::Line,
+                        text(": "),
+                        self.expression(if_false, SWITCH_LEVEL - 1),
+                    ]),
+                ),
+            ])),
+            ParallelOp { rhe, .. } => {
+                concat(vec![text("parallel "), self.expression(rhe, SWITCH_LEVEL)])
+            }
+            Variable { name, access, .. } => self.variable(name, access),
+            Number(meta, value) => {
+                let literal = self.code.get(meta.start..meta.end).map(str::trim).unwrap_or("");
+                if !literal.is_empty() && literal.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    text(literal)
+                } else {
+                    text(value.to_string())
+                }
+            }
+            Call { meta, id, args } | BusCall { meta, id, args } => {
+                let args = args.iter().map(|arg| self.expression(arg, TOP_LEVEL)).collect();
+                concat(vec![text(id.clone()), self.list("(", args, ")", Some(meta.end))])
+            }
+            AnonymousComp { meta, id, is_parallel, params, signals, names } => {
+                let params = params.iter().map(|param| self.expression(param, TOP_LEVEL)).collect();
+                let signals = signals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, signal)| {
+                        let value = self.expression(signal, TOP_LEVEL);
+                        match names.as_ref().and_then(|names| names.get(i)) {
+                            Some((op, name)) => concat(vec![
+                                text(format!("{} {} ", name, assign_opcode(*op))),
+                                value,
+                            ]),
+                            None => value,
+                        }
+                    })
+                    .collect();
+                let prefix = if *is_parallel { "parallel " } else { "" };
+                concat(vec![
+                    text(format!("{}{}", prefix, id)),
+                    self.list("(", params, ")", None),
+                    self.list("(", signals, ")", Some(meta.end)),
+                ])
+            }
+            ArrayInLine { meta, values } => {
+                let values = values.iter().map(|value| self.expression(value, TOP_LEVEL)).collect();
+                self.list("[", values, "]", Some(meta.end))
+            }
+            Tuple { meta, values } => {
+                let values = values.iter().map(|value| self.expression(value, TOP_LEVEL)).collect();
+                self.list("(", values, ")", Some(meta.end))
+            }
+            // Only generated by the parser inside bus declarations
+            UniformArray { value, .. } => self.unparenthesized_expression(value),
+        }
+    }
+
+    fn variable(&self, name: &str, access: &[Access]) -> Doc {
+        let mut doc = vec![text(name)];
+        for acc in access {
+            match acc {
+                Access::ArrayAccess(index) => {
+                    doc.push(text("["));
+                    doc.push(self.expression(index, TOP_LEVEL));
+                    doc.push(text("]"));
+                }
+                Access::ArraySlice(start, length) => {
+                    // The parser stores the length of a[start:end] as end - start
+                    let end = match length {
+                        Expression::InfixOp { lhe, .. } => lhe.as_ref(),
+                        _ => length,
+                    };
+                    doc.push(text("["));
+                    doc.push(self.expression(start, TOP_LEVEL));
+                    doc.push(text(":"));
+                    doc.push(self.expression(end, TOP_LEVEL));
+                    doc.push(text("]"));
+                }
+                Access::ComponentAccess(field) => doc.push(text(format!(".{}", field))),
+            }
+        }
+        concat(doc)
+    }
+
+    // Comma separated list that is split in one element per line when it does not fit.
+    // The list closes right before `end` in the source code, when it is known.
+    fn list(&self, open: &str, items: Vec<Doc>, close: &str, end: Option<usize>) -> Doc {
+        if items.is_empty() {
+            return text(format!("{}{}", open, close));
+        }
+        let trailing = match self.config.trailing_comma {
+            TrailingComma::Preserve if end.is_some_and(|end| self.ends_with_comma(end, close)) => text(","),
+            _ => Doc::Nil,
+        };
+        group(concat(vec![
+            text(open),
+            nest(
+                self.config.indent,
+                concat(vec![Doc::SoftLine, join(items, concat(vec![text(","), Doc::Line])), trailing]),
+            ),
+            Doc::SoftLine,
+            text(close),
+        ]))
+    }
+
+    // Skips the semicolon of the statement and the closing bracket of the list
+    fn ends_with_comma(&self, end: usize, close: &str) -> bool {
+        let before = self.code.get(..end).unwrap_or("").trim_end();
+        let before = before.strip_suffix(';').unwrap_or(before).trim_end();
+        let before = before.strip_suffix(close).unwrap_or(before).trim_end();
+        before.ends_with(',')
+    }
+}
+
+// Precedence levels, following the expression tiers of the grammar
+const ATOM_LEVEL: usize = 1;
+const PREFIX_LEVEL: usize = 2;
+const SWITCH_LEVEL: usize = 13;
+const TOP_LEVEL: usize = 14;
+
+fn precedence(expression: &Expression) -> usize {
+    use Expression::*;
+    match expression {
+        ParallelOp { .. } => TOP_LEVEL,
+        InlineSwitchOp { .. } => SWITCH_LEVEL,
+        InfixOp { infix_op, .. } => infix_precedence(*infix_op),
+        PrefixOp { .. } => PREFIX_LEVEL,
+        _ => ATOM_LEVEL,
+    }
+}
+
+fn infix_precedence(op: ExpressionInfixOpcode) -> usize {
+    use ExpressionInfixOpcode::*;
+    match op {
+        BoolOr => 12,
+        BoolAnd => 11,
+        Eq | NotEq | Lesser | Greater | LesserEq | GreaterEq => 10,
+        BitOr => 9,
+        BitXor => 8,
+        BitAnd => 7,
+        ShiftL | ShiftR => 6,
+        Add | Sub => 5,
+        Mul | Div | IntDiv | Mod => 4,
+        Pow => 3,
+    }
+}
+
+fn infix_opcode(op: ExpressionInfixOpcode) -> &'static str {
+    use ExpressionInfixOpcode::*;
+    match op {
+        Mul => "*",
+        Div => "/",
+        Add => "+",
+        Sub => "-",
+        Pow => "**",
+        IntDiv => "\\",
+        Mod => "%",
+        ShiftL => "<<",
+        ShiftR => ">>",
+        LesserEq => "<=",
+        GreaterEq => ">=",
+        Lesser => "<",
+        Greater => ">",
+        Eq => "==",
+        NotEq => "!=",
+        BoolOr => "||",
+        BoolAnd => "&&",
+        BitOr => "|",
+        BitAnd => "&",
+        BitXor => "^",
+    }
+}
+
+fn prefix_opcode(op: ExpressionPrefixOpcode) -> &'static str {
+    match op {
+        ExpressionPrefixOpcode::Sub => "-",
+        ExpressionPrefixOpcode::BoolNot => "!",
+        ExpressionPrefixOpcode::Complement => "~",
+    }
+}
+
+fn assign_opcode(op: AssignOp) -> &'static str {
+    match op {
+        AssignOp::AssignVar => "=",
+        AssignOp::AssignSignal => "<--",
+        AssignOp::AssignConstraintSignal => "<==",
+    }
+}
+
+fn signal_type_suffix(signal_type: SignalType) -> &'static str {
+    match signal_type {
+        SignalType::Input => " input",
+        SignalType::Output => " output",
+        SignalType::Intermediate => "",
+    }
+}
+
+fn tags_suffix(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" {{{}}}", tags.join(", "))
+    }
+}
+
+fn definition_meta(definition: &Definition) -> &Meta {
+    match definition {
+        Definition::Template { meta, .. }
+        | Definition::Function { meta, .. }
+        | Definition::Bus { meta, .. } => meta,
+    }
+}
+
+fn same_location(a: &Meta, b: &Meta) -> bool {
+    a.start == b.start && a.end == b.end
+}
+
+fn is_compound(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::Block { .. } | Statement::IfThenElse { .. } | Statement::While { .. })
+}
+
+fn is_plain_block(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::Block { .. }) && for_loop(stmt).is_none()
+}
+
+// Recognizes the blocks generated by ast_shortcuts::for_into_while:
+// { init; while (cond) { body; step } } where the while loop has the
+// same location as the block
+fn for_loop(stmt: &Statement) -> Option<(&Statement, &Expression, &Statement, &Statement)> {
+    if let Statement::Block { meta, stmts } = stmt {
+        if let [init, Statement::While { meta: while_meta, cond, stmt: while_body }] = stmts.as_slice() {
+            if let Statement::Block { stmts: body, .. } = while_body.as_ref() {
+                if let [body, step] = body.as_slice() {
+                    if same_location(meta, while_meta) {
+                        return Some((init, cond, step, body));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// Recognizes the substitutions generated by ast_shortcuts::assign_with_op_shortcut,
+// where the variable and the operation have the same location as the statement
+fn compound_assignment<'b>(
+    meta: &Meta,
+    rhe: &'b Expression,
+) -> Option<(ExpressionInfixOpcode, &'b Expression)> {
+    if let Expression::InfixOp { meta: infix_meta, lhe, infix_op, rhe } = rhe {
+        if same_location(meta, infix_meta) && same_location(meta, lhe.get_meta()) {
+            return Some((*infix_op, rhe));
+        }
+    }
+    None
+}
+
+fn join(docs: Vec<Doc>, separator: Doc) -> Doc {
+    let mut joined = Vec::new();
+    for (i, doc) in docs.into_iter().enumerate() {
+        if i > 0 {
+            joined.push(separator.clone());
+        }
+        joined.push(doc);
+    }
+    concat(joined)
+}
+
+fn join_lines(lines: Vec<Doc>) -> Doc {
+    join(lines, Doc::HardLine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(src: &str, config: &FormatConfig) -> String {
+        let formatted = format_source(src, 0, config).ok().expect("source should be formatted");
+        let reformatted = format_source(&formatted, 0, config).ok().expect("output should parse");
+        assert_eq!(formatted, reformatted, "formatting is not idempotent");
+        formatted
+    }
+
+    #[test]
+    fn formatter_keeps_syntactic_sugar() {
+        let src = "template A(n){signal input a[n];var x=0x1F,y;for(var i=0;i<n;i++){x+=a[i];}\n\
+                   a[0]==>y; signal output b<==T()(a[0]); if(x) x--; else {x=x+1;}}";
+        let expected = "template A(n) {
+    signal input a[n];
+    var x = 0x1F, y;
+    for (var i = 0; i < n; i++) {
+        x += a[i];
+    }
+    a[0] ==> y;
+    signal output b <== T()(a[0]);
+    if (x)
+        x--;
+    else {
+        x = x + 1;
+    }
+}
+";
+        assert_eq!(format(src, &FormatConfig::default()), expected);
+    }
+
+    #[test]
+    fn formatter_keeps_comments() {
+        let src = "pragma circom 2.1.0;\n// header\n\n/* doc\n   comment */\ntemplate A() {\n\
+                   // first\n  signal a; // trailing\n\n\n  a <== 1;\n  // last\n}\n// end\n";
+        let expected = "pragma circom 2.1.0;
+// header
+
+/* doc
+   comment */
+template A() {
+    // first
+    signal a; // trailing
+
+    a <== 1;
+    // last
+}
+// end
+";
+        assert_eq!(format(src, &FormatConfig::default()), expected);
+    }
+
+    #[test]
+    fn formatter_splits_long_lists() {
+        let config = FormatConfig { indent: 2, max_width: 30, ..FormatConfig::default() };
+        let src = "function f(a, b) { return g(a + b, [1, 2], 123456789, 987654321); }";
+        let expected = "function f(a, b) {
+  return g(
+    a + b,
+    [1, 2],
+    123456789,
+    987654321
+  );
+}
+";
+        assert_eq!(format(src, &config), expected);
+    }
+
+    #[test]
+    fn formatter_preserves_trailing_commas() {
+        let config = FormatConfig { trailing_comma: TrailingComma::Preserve, ..FormatConfig::default() };
+        let src = "template A(n){signal input a[2];log(a[0],n);var v[2]=[f(n,1),2];}";
+        assert_eq!(format(src, &config), format(src, &FormatConfig::default()));
+        // The grammar does not accept trailing commas yet, so they are looked up directly
+        let code = "f(a, [1, 2 ,\n], b,) ; log(a,); g(c)";
+        let printer = Printer { config: &config, code, comments: CommentQueue::new("") };
+        assert!(printer.ends_with_comma(14, "]"));
+        assert!(printer.ends_with_comma(19, ")"));
+        assert!(printer.ends_with_comma(30, ")"));
+        assert!(!printer.ends_with_comma(35, ")"));
+    }
+
+    #[test]
+    fn formatter_keeps_assert_messages() {
+        let src = "template A(n){signal input a;assert(n>0);assert(a!=n,\"a = {} is not {}\",a,n);}";
+        let expected = "template A(n) {
+    signal input a;
+    assert(n > 0);
+    assert(a != n, \"a = {} is not {}\", a, n);
+}
+";
+        assert_eq!(format(src, &FormatConfig::default()), expected);
+    }
+
+    #[test]
+    fn formatter_keeps_qualified_names() {
+        let src = "include \"bits.circom\"   as  b;\ninclude \"c.circom\";\ntemplate A(){signal input in;component n = b::Num2Bits(8);n.in<==in;b::Pair() p;signal o<==b::sq(in);}";
+        let expected = "include \"bits.circom\" as b;
+include \"c.circom\";
+
+template A() {
+    signal input in;
+    component n = b::Num2Bits(8);
+    n.in <== in;
+    b::Pair() p;
+    signal o <== b::sq(in);
+}
+";
+        assert_eq!(format(src, &FormatConfig::default()), expected);
+    }
+
+    #[test]
+    fn formatter_keeps_array_slices() {
+        let src = "template A(){signal input in[4];signal output out[2];out<==in[1:1+2];var v[3];v[0:2]=[1,2];}";
+        let expected = "template A() {
+    signal input in[4];
+    signal output out[2];
+    out <== in[1:1 + 2];
+    var v[3];
+    v[0:2] = [1, 2];
+}
+";
+        assert_eq!(format(src, &FormatConfig::default()), expected);
+    }
+}
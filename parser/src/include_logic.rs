@@ -4,22 +4,32 @@ use program_structure::error_definition::Report;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+// Files are parsed once per namespace where they are included: a file
+// included with an alias has its definitions in a different namespace
+// than the same file included without it
 pub struct FileStack {
     current_location: PathBuf,
-    black_paths: HashSet<PathBuf>,
-    stack: Vec<PathBuf>,
+    current_namespace: String,
+    black_paths: HashSet<(PathBuf, String)>,
+    stack: Vec<(PathBuf, String)>,
 }
 
 impl FileStack {
     pub fn new(src: PathBuf) -> FileStack {
         let mut location = src.clone();
         location.pop();
-        FileStack { current_location: location, black_paths: HashSet::new(), stack: vec![src] }
+        FileStack {
+            current_location: location,
+            current_namespace: String::new(),
+            black_paths: HashSet::new(),
+            stack: vec![(src, String::new())],
+        }
     }
 
     pub fn add_include(
         f_stack: &mut FileStack,
         name: String,
+        namespace: String,
         libraries: &Vec<PathBuf>,
     ) -> Result<String, Report> {
         let mut libraries2 = Vec::new();
@@ -34,8 +44,9 @@ impl FileStack {
                 Err(_) => {}
                 Ok(path) => {
                     if path.is_file() {
-                        if !f_stack.black_paths.contains(&path) {
-                            f_stack.stack.push(path.clone());
+                        let entry = (path.clone(), namespace);
+                        if !f_stack.black_paths.contains(&entry) {
+                            f_stack.stack.push(entry);
                         }
                         return Result::Ok(path.to_str().unwrap().to_string());
                     }
@@ -45,22 +56,32 @@ impl FileStack {
         Result::Err(produce_report_with_message(ReportCode::IncludeNotFound, name))
     }
 
-    pub fn take_next(f_stack: &mut FileStack) -> Option<PathBuf> {
+    pub fn take_next(f_stack: &mut FileStack) -> Option<(PathBuf, String)> {
         loop {
             match f_stack.stack.pop() {
                 None => {
                     break None;
                 }
-                Some(file) if !f_stack.black_paths.contains(&file) => {
-                    f_stack.current_location = file.clone();
+                Some(entry) if !f_stack.black_paths.contains(&entry) => {
+                    f_stack.current_location = entry.0.clone();
                     f_stack.current_location.pop();
-                    f_stack.black_paths.insert(file.clone());
-                    break Some(file);
+                    f_stack.current_namespace = entry.1.clone();
+                    f_stack.black_paths.insert(entry.clone());
+                    break Some(entry);
                 }
                 _ => {}
             }
         }
     }
+
+    // Namespace of the definitions of a file included from the current
+    // one, given the alias of the include
+    pub fn include_namespace(f_stack: &FileStack, alias: &Option<String>) -> String {
+        match alias {
+            Some(alias) => format!("{}{}::", f_stack.current_namespace, alias),
+            None => f_stack.current_namespace.clone(),
+        }
+    }
 }
 
 pub struct IncludesNode {
@@ -97,6 +118,14 @@ impl IncludesGraph {
         Ok(())
     }
 
+    // Every file of the graph, without repetitions
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.nodes.iter().map(|node| node.path.clone()).collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
     pub fn get_problematic_paths(&self) -> Vec<Vec<PathBuf>> {
         let mut problematic_paths = Vec::new();
         for from in &self.custom_gates_nodes {
@@ -7,25 +7,52 @@ extern crate lalrpop_util;
 
 lalrpop_mod!(pub lang);
 
+mod doc_comments;
+mod formatter;
 mod include_logic;
+mod namespaces;
 mod parser_logic;
 mod syntax_sugar_remover;
 
 use include_logic::{FileStack, IncludesGraph};
+use namespaces::Namespaces;
 use num_bigint::BigInt;
 use program_structure::ast::{produce_compiler_version_report, produce_report, produce_report_with_message, produce_version_warning_report, Expression};
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::ReportCollection;
 use program_structure::error_definition::Report;
-use program_structure::file_definition::{FileLibrary};
+use program_structure::ast::{Definition, MainComponent, Meta};
+use program_structure::expression_builders::build_number_without_field;
+use program_structure::file_definition::{FileID, FileLibrary};
 use program_structure::program_archive::ProgramArchive;
+use std::collections::HashSet;
 use std::path::{PathBuf, Path};
-use syntax_sugar_remover::{apply_syntactic_sugar};
+use syntax_sugar_remover::{apply_syntactic_sugar, remove_syntactic_sugar};
+
+pub use doc_comments::doc_comment;
+pub use formatter::{format_source, FormatConfig, TrailingComma};
 
 use std::str::FromStr;
 
 pub type Version = (usize, usize, usize);
 
+// Main component given from outside the source files: a call to the main
+// template and the list of its public inputs
+pub struct MainComponentSpec {
+    pub call: String,
+    pub public: Vec<String>,
+}
+
+impl MainComponentSpec {
+    fn to_source(&self) -> String {
+        if self.public.is_empty() {
+            format!("component main = {};", self.call)
+        } else {
+            format!("component main {{public [{}]}} = {};", self.public.join(", "), self.call)
+        }
+    }
+}
+
 pub fn find_file(
     crr_file: PathBuf,
     ext_link_libraries: Vec<PathBuf>,
@@ -57,40 +84,150 @@ pub fn find_file(
     (found, path, src, crr_str_file, reports)
 }
 
+// Files read when parsing the given file: the file itself and every file
+// it includes, transitively, looked up in the link libraries. Files with
+// syntax errors are in the list, but not the files they include.
+pub fn find_included_files(file: String, link_libraries: Vec<PathBuf>, field: &BigInt) -> Vec<PathBuf> {
+    let mut file_stack = FileStack::new(PathBuf::from(file));
+    let mut includes_graph = IncludesGraph::new();
+    let mut ext_link_libraries = vec![Path::new("").to_path_buf()];
+    ext_link_libraries.append(&mut link_libraries.clone());
+    while let Some((crr_file, _)) = FileStack::take_next(&mut file_stack) {
+        let (found, _, src, crr_str_file, _) = find_file(crr_file, ext_link_libraries.clone());
+        if !found {
+            continue;
+        }
+        let crr_str_file = std::fs::canonicalize(&crr_str_file).unwrap_or(crr_str_file);
+        match parser_logic::parse_file(&src, 0, field, false) {
+            Ok(program) => {
+                includes_graph.add_node(crr_str_file, program.custom_gates, program.custom_gates_declared);
+                for include in program.includes {
+                    // Namespaces do not change the files that are read
+                    if let Ok(path_include) =
+                        FileStack::add_include(&mut file_stack, include.path, String::new(), &link_libraries)
+                    {
+                        let _ = includes_graph.add_edge(path_include);
+                    }
+                }
+            }
+            Err(_) => includes_graph.add_node(crr_str_file, false, false),
+        }
+    }
+    includes_graph.files()
+}
+
 pub fn run_parser(
     file: String,
     version: &str,
     link_libraries: Vec<PathBuf>,
     field: &BigInt,     
-    flag_no_init: bool
+    flag_no_init: bool,
+    main_spec: Option<&MainComponentSpec>,
+) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
+    parse_program(file, version, link_libraries, field, flag_no_init, main_spec, false)
+}
+
+// Parses a library, whose files do not need a main component. Without one,
+// the main expression of the archive is a placeholder that is not a call,
+// and ProgramArchive::set_main_component must be used before the analysis
+pub fn run_library_parser(
+    file: String,
+    version: &str,
+    link_libraries: Vec<PathBuf>,
+    field: &BigInt,
+) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
+    parse_program(file, version, link_libraries, field, false, None, true)
+}
+
+// Parses a main component given as text, like the one of --main, to
+// instantiate a template of a program that is already parsed
+pub fn parse_main_call(
+    spec: &MainComponentSpec,
+    file_library: &mut FileLibrary,
+    field: &BigInt,
+) -> Result<(FileID, MainComponent), ReportCollection> {
+    let src = spec.to_source();
+    let file_id = file_library.add_file(format!("{:?}", spec.call), src.clone());
+    let main = parser_logic::parse_main_component(&src, file_id, field, false)?;
+    Ok((file_id, main))
+}
+
+// Parses definitions given as text, like the ones written in circom repl,
+// and adds them to a program that is already parsed. The names are used as
+// written, as in the initial file of the program. Gives the id of the file
+// of the definitions
+pub fn parse_definitions(
+    name: String,
+    src: &str,
+    program_archive: &mut ProgramArchive,
+    field: &BigInt,
+) -> Result<FileID, ReportCollection> {
+    let file_id = program_archive.file_library.add_file(name, src.to_string());
+    let program = parser_logic::parse_file(src, file_id, field, false)?;
+    if !program.includes.is_empty() || program.main_component.is_some() {
+        let report = Report::error(
+            "Only templates, functions and buses can be added to a parsed program".to_string(),
+            ReportCode::IllegalExpression,
+        );
+        return Err(vec![report]);
+    }
+    let mut templates = HashSet::new();
+    let mut functions = HashSet::new();
+    for definition in &program.definitions {
+        match definition {
+            Definition::Template { name, .. } => templates.insert(name.clone()),
+            Definition::Function { name, .. } => functions.insert(name.clone()),
+            Definition::Bus { .. } => false,
+        };
+    }
+    program_archive.add_definitions(file_id, program.definitions)?;
+    remove_syntactic_sugar(program_archive, &templates, &functions).map_err(|report| vec![report])?;
+    Ok(file_id)
+}
+
+fn parse_program(
+    file: String,
+    version: &str,
+    link_libraries: Vec<PathBuf>,
+    field: &BigInt,
+    flag_no_init: bool,
+    main_spec: Option<&MainComponentSpec>,
+    library: bool,
 ) -> Result<(ProgramArchive, ReportCollection), (FileLibrary, ReportCollection)> {
     let mut file_library = FileLibrary::new();
     let mut definitions = Vec::new();
     let mut main_components = Vec::new();
     let mut file_stack = FileStack::new(PathBuf::from(file));
     let mut includes_graph = IncludesGraph::new();
+    let mut namespaces = Namespaces::new();
     let mut warnings = Vec::new();
+    let mut initial_custom_gates = None;
     let mut link_libraries2 = link_libraries.clone();
     let mut ext_link_libraries = vec![Path::new("").to_path_buf()];
     ext_link_libraries.append(&mut link_libraries2);
-    while let Some(crr_file) = FileStack::take_next(&mut file_stack) {
+    while let Some((crr_file, namespace)) = FileStack::take_next(&mut file_stack) {
         let (found, path, src, crr_str_file, reports) =
             find_file(crr_file, ext_link_libraries.clone());
         if !found {
             return Result::Err((file_library.clone(), reports));
         }
         let file_id = file_library.add_file(path.clone(), src.clone());
+        namespaces.add_file(file_id, namespace);
         let program =
             parser_logic::parse_file(&src, file_id, field, flag_no_init).map_err(|e| (file_library.clone(), e))?;
         if let Some(main) = program.main_component {
             main_components.push((file_id, main, program.custom_gates));
         }
+        if initial_custom_gates.is_none() {
+            initial_custom_gates = Some(program.custom_gates);
+        }
         includes_graph.add_node(crr_str_file, program.custom_gates, program.custom_gates_declared);
         let includes = program.includes;
         definitions.push((file_id, program.definitions));
         for include in includes {
+            let namespace = FileStack::include_namespace(&file_stack, &include.alias);
             let path_include =
-                FileStack::add_include(&mut file_stack, include.clone(), &link_libraries.clone())
+                FileStack::add_include(&mut file_stack, include.path, namespace, &link_libraries.clone())
                     .map_err(|e| (file_library.clone(), vec![e]))?;
             includes_graph.add_edge(path_include).map_err(|e| (file_library.clone(), vec![e]))?;
         }
@@ -112,6 +249,23 @@ pub fn run_parser(
         }
     }
 
+    if let Some(main_spec) = main_spec {
+        // The main component given in the command line is stored as a new
+        // file, using the custom templates pragma of the initial file
+        let src = main_spec.to_source();
+        let file_id = file_library.add_file(format!("{:?}", "--main"), src.clone());
+        let main = parser_logic::parse_main_component(&src, file_id, field, flag_no_init)
+            .map_err(|e| (file_library.clone(), e))?;
+        // Names in the command line are resolved as in the initial file
+        namespaces.add_file(file_id, String::new());
+        main_components.push((file_id, main, initial_custom_gates.unwrap_or(false)));
+    }
+
+    if main_components.is_empty() && library {
+        let placeholder = build_number_without_field(Meta::new(0, 0), BigInt::from(0));
+        main_components.push((0, (Vec::new(), placeholder), initial_custom_gates.unwrap_or(false)));
+    }
+
     if main_components.len() == 0 {
         let report = produce_report(ReportCode::NoMainFoundInProject,0..0, 0);
         warnings.push(report);
@@ -135,7 +289,11 @@ pub fn run_parser(
             warnings.append(& mut errors);
             Err((file_library, warnings))
         } else {
-            let (main_id, main_component, custom_gates) = main_components.pop().unwrap();
+            let (main_id, mut main_component, custom_gates) = main_components.pop().unwrap();
+            if let Err(mut errors) = namespaces.apply(&mut definitions, main_id, &mut main_component.1) {
+                warnings.append(&mut errors);
+                return Err((file_library, warnings));
+            }
             let result_program_archive = ProgramArchive::new(
                 file_library,
                 main_id,
@@ -263,4 +421,59 @@ fn check_custom_gates_version(
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_structure::constants::UsefulConstants;
+
+    const MULTIPLIER: &str = r#"
+        pragma circom 2.0.0;
+        template Multiplier(n) {
+            signal input a;
+            signal input b;
+            signal output c;
+            c <== a * b * n;
+        }
+    "#;
+
+    fn parse(name: &str, src: &str, main_spec: &MainComponentSpec) -> Result<ProgramArchive, ReportCollection> {
+        let dir = std::env::temp_dir().join(format!("circom_main_spec_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let circuit = dir.join("circuit.circom");
+        std::fs::write(&circuit, src).unwrap();
+        let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let path = circuit.to_str().unwrap().to_string();
+        let result = run_parser(path, "2.2.2", vec![], &prime, false, Some(main_spec));
+        std::fs::remove_dir_all(&dir).unwrap();
+        result.map(|(program, _)| program).map_err(|(_, reports)| reports)
+    }
+
+    #[test]
+    fn main_component_is_taken_from_the_spec() {
+        let spec = MainComponentSpec { call: "Multiplier(3)".to_string(), public: vec!["b".to_string()] };
+        let program = parse("call", MULTIPLIER, &spec).map_err(|_| ()).unwrap();
+        match program.get_main_expression() {
+            Expression::Call { id, args, .. } => {
+                assert_eq!(id, "Multiplier");
+                assert_eq!(args.len(), 1);
+            }
+            _ => panic!("the main expression is not a call"),
+        }
+        assert_eq!(program.get_public_inputs_main_component(), &vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn main_component_spec_requires_a_file_without_main() {
+        let src = format!("{}component main = Multiplier(2);", MULTIPLIER);
+        let spec = MainComponentSpec { call: "Multiplier(3)".to_string(), public: Vec::new() };
+        let reports = parse("duplicated", &src, &spec).err().unwrap();
+        assert!(reports.iter().any(|report| matches!(report.get_code(), ReportCode::MultipleMain)));
+    }
+
+    #[test]
+    fn main_component_spec_is_parsed() {
+        let spec = MainComponentSpec { call: "Multiplier(".to_string(), public: Vec::new() };
+        assert!(parse("invalid", MULTIPLIER, &spec).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+use program_structure::ast::{Access, Definition, Expression, LogArgument, Meta, Statement, VariableType};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::FileID;
+use std::collections::{HashMap, HashSet};
+
+// Namespaces of the parsed files. The root namespace is the empty string,
+// and the namespace of a file included with alias a from namespace n is
+// n + "a::". Files included without an alias share the namespace of the
+// file that includes them.
+#[derive(Default)]
+pub struct Namespaces {
+    of_file: HashMap<FileID, String>,
+    declared: HashSet<String>,
+}
+
+impl Namespaces {
+    pub fn new() -> Namespaces {
+        Namespaces::default()
+    }
+
+    pub fn add_file(&mut self, file_id: FileID, namespace: String) {
+        self.declared.insert(namespace.clone());
+        self.of_file.insert(file_id, namespace);
+    }
+
+    fn namespace_of(&self, file_id: FileID) -> &str {
+        self.of_file.get(&file_id).map_or("", |namespace| namespace.as_str())
+    }
+
+    // Prefixes the names of the definitions with the namespace of their
+    // files and resolves the names of the templates, functions and buses
+    // used in their bodies and in the main component
+    pub fn apply(
+        &self,
+        definitions: &mut Vec<(FileID, Vec<Definition>)>,
+        main_file: FileID,
+        main_component: &mut Expression,
+    ) -> Result<(), ReportCollection> {
+        let mut defined = HashSet::new();
+        for (file_id, file_definitions) in definitions.iter_mut() {
+            let namespace = self.namespace_of(*file_id).to_string();
+            for definition in file_definitions {
+                let name = match definition {
+                    Definition::Template { name, .. }
+                    | Definition::Function { name, .. }
+                    | Definition::Bus { name, .. } => name,
+                };
+                *name = format!("{}{}", namespace, name);
+                defined.insert(name.clone());
+            }
+        }
+        let mut resolver = Resolver { namespaces: self, defined: &defined, file_id: main_file, reports: Vec::new() };
+        for (file_id, file_definitions) in definitions.iter_mut() {
+            resolver.file_id = *file_id;
+            for definition in file_definitions {
+                let body = match definition {
+                    Definition::Template { body, .. }
+                    | Definition::Function { body, .. }
+                    | Definition::Bus { body, .. } => body,
+                };
+                resolver.statement(body);
+            }
+        }
+        resolver.file_id = main_file;
+        resolver.expression(main_component);
+        if resolver.reports.is_empty() {
+            Ok(())
+        } else {
+            Err(resolver.reports)
+        }
+    }
+}
+
+fn parent_namespace(namespace: &str) -> &str {
+    let inner = &namespace[..namespace.len() - 2];
+    match inner.rfind("::") {
+        Some(pos) => &namespace[..pos + 2],
+        None => "",
+    }
+}
+
+struct Resolver<'a> {
+    namespaces: &'a Namespaces,
+    defined: &'a HashSet<String>,
+    file_id: FileID,
+    reports: ReportCollection,
+}
+
+impl Resolver<'_> {
+    // A qualified name alias::rest is looked up in the namespace of the
+    // alias, searched from the namespace of the current file outwards. An
+    // unqualified name refers to the closest definition with that name.
+    fn resolve(&mut self, meta: &Meta, name: &mut String) {
+        let mut namespace = self.namespaces.namespace_of(self.file_id);
+        if let Some((alias, rest)) = name.split_once("::") {
+            loop {
+                let candidate = format!("{}{}::", namespace, alias);
+                if self.namespaces.declared.contains(&candidate) {
+                    *name = format!("{}{}", candidate, rest);
+                    return;
+                }
+                if namespace.is_empty() {
+                    break;
+                }
+                namespace = parent_namespace(namespace);
+            }
+            let mut report = Report::error(
+                format!("Unknown namespace {}", alias),
+                ReportCode::UnknownNamespace,
+            );
+            report.add_primary(
+                meta.location.clone(),
+                self.file_id,
+                format!("{} is not the alias of an include", alias),
+            );
+            self.reports.push(report);
+        } else {
+            loop {
+                let candidate = format!("{}{}", namespace, name);
+                if self.defined.contains(&candidate) {
+                    *name = candidate;
+                    return;
+                }
+                if namespace.is_empty() {
+                    return;
+                }
+                namespace = parent_namespace(namespace);
+            }
+        }
+    }
+
+    fn statement(&mut self, stmt: &mut Statement) {
+        use Statement::*;
+        match stmt {
+            IfThenElse { cond, if_case, else_case, .. } => {
+                self.expression(cond);
+                self.statement(if_case);
+                if let Some(else_case) = else_case {
+                    self.statement(else_case);
+                }
+            }
+            While { cond, stmt, .. } => {
+                self.expression(cond);
+                self.statement(stmt);
+            }
+            Return { value, .. } => self.expression(value),
+            InitializationBlock { meta, xtype, initializations } => {
+                self.variable_type(meta, xtype);
+                for init in initializations {
+                    self.statement(init);
+                }
+            }
+            Declaration { meta, xtype, dimensions, .. } => {
+                self.variable_type(meta, xtype);
+                for dimension in dimensions {
+                    self.expression(dimension);
+                }
+            }
+            Substitution { access, rhe, .. } => {
+                self.accesses(access);
+                self.expression(rhe);
+            }
+            MultSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+                self.expression(lhe);
+                self.expression(rhe);
+            }
+            UnderscoreSubstitution { rhe, .. } => self.expression(rhe),
+            LogCall { args, .. } => self.log_arguments(args),
+            Block { stmts, .. } => {
+                for stmt in stmts {
+                    self.statement(stmt);
+                }
+            }
+            Assert { arg, message, .. } => {
+                self.expression(arg);
+                self.log_arguments(message);
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &mut Expression) {
+        use Expression::*;
+        match expr {
+            InfixOp { lhe, rhe, .. } => {
+                self.expression(lhe);
+                self.expression(rhe);
+            }
+            PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => self.expression(rhe),
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                self.expression(cond);
+                self.expression(if_true);
+                self.expression(if_false);
+            }
+            Variable { access, .. } => self.accesses(access),
+            Number(..) => {}
+            Call { meta, id, args } | BusCall { meta, id, args } => {
+                self.resolve(meta, id);
+                self.expressions(args);
+            }
+            AnonymousComp { meta, id, params, signals, .. } => {
+                self.resolve(meta, id);
+                self.expressions(params);
+                self.expressions(signals);
+            }
+            ArrayInLine { values, .. } | Tuple { values, .. } => self.expressions(values),
+            UniformArray { value, dimension, .. } => {
+                self.expression(value);
+                self.expression(dimension);
+            }
+        }
+    }
+
+    fn expressions(&mut self, exprs: &mut Vec<Expression>) {
+        for expr in exprs {
+            self.expression(expr);
+        }
+    }
+
+    fn accesses(&mut self, access: &mut Vec<Access>) {
+        for acc in access {
+            match acc {
+                Access::ArrayAccess(index) => self.expression(index),
+                Access::ArraySlice(start, length) => {
+                    self.expression(start);
+                    self.expression(length);
+                }
+                Access::ComponentAccess(_) => {}
+            }
+        }
+    }
+
+    fn log_arguments(&mut self, args: &mut Vec<LogArgument>) {
+        for arg in args {
+            if let LogArgument::LogExp(expr) = arg {
+                self.expression(expr);
+            }
+        }
+    }
+
+    fn variable_type(&mut self, meta: &Meta, xtype: &mut VariableType) {
+        if let VariableType::Bus(name, ..) = xtype {
+            self.resolve(meta, name);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::lang;
 use num_bigint::BigInt;
-use program_structure::ast::{AST};
+use program_structure::ast::{MainComponent, AST};
 use program_structure::ast::produce_report;
 use program_structure::error_code::ReportCode;
 use program_structure::error_definition::{ReportCollection, Report};
@@ -85,35 +85,13 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, ReportCollectio
 }
 
 pub fn parse_file(src: &str, file_id: FileID, field: &BigInt, flag_no_init: bool) -> Result<AST, ReportCollection> {
-    use lalrpop_util::ParseError::*;
-
     let mut errors = Vec::new();
     let preprocess = preprocess(src, file_id)?;
 
     let ast = lang::ParseAstParser::new()
         .parse(file_id, &mut errors, field, flag_no_init, &preprocess)
         // TODO: is this always fatal?
-        .map_err(|parse_error| match parse_error {
-            InvalidToken { location } => 
-                produce_generic_report(
-                format!("{:?}", parse_error),
-                 location..location, file_id
-                ),
-            UnrecognizedToken { ref token, .. } => 
-            produce_generic_report(
-                format!("{:?}", parse_error),
-                 token.0..token.2, file_id
-                ),
-            ExtraToken { ref token } => produce_generic_report(
-                format!("{:?}", parse_error),
-                 token.0..token.2, file_id
-                ),
-            _ => produce_generic_report(
-                format!("{:?}", parse_error),
-                 0..0, file_id
-                )
-        })
-        .map_err(|e| vec![e])?;
+        .map_err(|parse_error| vec![parse_error_report(parse_error, file_id)])?;
 
     if !errors.is_empty() {
         return Err(errors.into_iter().collect());
@@ -122,6 +100,49 @@ pub fn parse_file(src: &str, file_id: FileID, field: &BigInt, flag_no_init: bool
     Ok(ast)
 }
 
+// Parses a main component declaration that is not part of any source file
+pub fn parse_main_component(src: &str, file_id: FileID, field: &BigInt, flag_no_init: bool) -> Result<MainComponent, ReportCollection> {
+    let mut errors = Vec::new();
+    let preprocess = preprocess(src, file_id)?;
+
+    let main = lang::ParseMainComponentParser::new()
+        .parse(file_id, &mut errors, field, flag_no_init, &preprocess)
+        .map_err(|parse_error| vec![parse_error_report(parse_error, file_id)])?;
+
+    if !errors.is_empty() {
+        return Err(errors.into_iter().collect());
+    }
+
+    Ok(main)
+}
+
+fn parse_error_report<T: std::fmt::Debug, E: std::fmt::Debug>(
+    parse_error: lalrpop_util::ParseError<usize, T, E>,
+    file_id: FileID,
+) -> Report {
+    use lalrpop_util::ParseError::*;
+    match parse_error {
+        InvalidToken { location } => 
+            produce_generic_report(
+            format!("{:?}", parse_error),
+             location..location, file_id
+            ),
+        UnrecognizedToken { ref token, .. } => 
+        produce_generic_report(
+            format!("{:?}", parse_error),
+             token.0..token.2, file_id
+            ),
+        ExtraToken { ref token } => produce_generic_report(
+            format!("{:?}", parse_error),
+             token.0..token.2, file_id
+            ),
+        _ => produce_generic_report(
+            format!("{:?}", parse_error),
+             0..0, file_id
+            )
+    }
+}
+
 fn produce_generic_report(format: String, token: std::ops::Range<usize>, file_id: usize) -> Report {
     let mut report = Report::error(format, ReportCode::IllegalExpression);
     report.add_primary(token, file_id, "here".to_string());
@@ -6,7 +6,7 @@ use program_structure::file_definition::FileLibrary;
 use program_structure::program_archive::ProgramArchive;
 use program_structure::statement_builders::{build_declaration, build_log_call, build_initialization_block};
 use program_structure::template_data::TemplateData;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, BTreeMap};
 use num_bigint::BigInt;
 
 
@@ -16,9 +16,21 @@ pub fn apply_syntactic_sugar(program_archive : &mut  ProgramArchive) -> Result<(
         return Result::Err(anonymous_general_error(program_archive.get_main_expression().get_meta().clone(),"The main component cannot contain an anonymous call  ".to_string()));
      
     }
+    let templates = program_archive.get_template_names().clone();
+    let functions = program_archive.get_function_names().clone();
+    remove_syntactic_sugar(program_archive, &templates, &functions)
+}
+
+// Removes the syntactic sugar of the given templates and functions only, as
+// the definitions added to a program after parsing it
+pub fn remove_syntactic_sugar(
+    program_archive: &mut ProgramArchive,
+    templates: &HashSet<String>,
+    functions: &HashSet<String>,
+) -> Result<(), Report> {
     let old_templates = program_archive.templates.clone();
 
-    for (_name, t) in &mut program_archive.templates {
+    for (_name, t) in program_archive.templates.iter_mut().filter(|(name, _)| templates.contains(*name)) {
         let old_body = t.get_body().clone();
         check_anonymous_components_statement(&old_body)?;
         let (new_body, component_decs, variable_decs, mut substitutions) = remove_anonymous_from_statement(&old_templates, &program_archive.file_library, old_body, &None)?;
@@ -38,7 +50,7 @@ pub fn apply_syntactic_sugar(program_archive : &mut  ProgramArchive) -> Result<(
     }
 
 
-    for (_, t) in &mut program_archive.functions {
+    for (_, t) in program_archive.functions.iter_mut().filter(|(name, _)| functions.contains(*name)) {
         let old_body = t.get_body().clone();
         if old_body.contains_anonymous_comp(){
             return Result::Err(anonymous_general_error(old_body.get_meta().clone(),"Functions cannot contain calls to anonymous templates".to_string()));
@@ -100,8 +112,11 @@ fn check_anonymous_components_statement(
             }
             Result::Ok(())
         }  
-        Statement::Assert { meta, arg}   => {
-            if arg.contains_anonymous_comp() {
+        Statement::Assert { meta, arg, message }   => {
+            let in_message = message.iter().any(|arg| {
+                matches!(arg, LogArgument::LogExp(exp) if exp.contains_anonymous_comp())
+            });
+            if arg.contains_anonymous_comp() || in_message {
                 Result::Err(anonymous_general_error(meta.clone(), "An anonymous component cannot be used inside an assert".to_string()))
             } else{
                 Result::Ok(())
@@ -146,7 +161,7 @@ fn check_anonymous_components_statement(
         }
         Statement::Substitution { meta, rhe, access, op, ..} => {
             use program_structure::ast::Access::ComponentAccess;
-            use program_structure::ast::Access::ArrayAccess;
+            use program_structure::ast::Access::{ArrayAccess, ArraySlice};
             for acc in access{
                 match acc{
                     ArrayAccess(exp) =>{
@@ -154,6 +169,11 @@ fn check_anonymous_components_statement(
                             return Result::Err(anonymous_general_error(meta.clone(),"An anonymous component cannot be used to define a dimension of an array".to_string()));
                         }
                     },
+                    ArraySlice(start, length) =>{
+                        if start.contains_anonymous_comp() || length.contains_anonymous_comp(){
+                            return Result::Err(anonymous_general_error(meta.clone(),"An anonymous component cannot be used to define a dimension of an array".to_string()));
+                        }
+                    },
                     ComponentAccess(_)=>{},
                 }
             }
@@ -194,7 +214,7 @@ pub fn check_anonymous_components_expression(
         },
         Variable { meta, access, .. } => {
             use program_structure::ast::Access::ComponentAccess;
-            use program_structure::ast::Access::ArrayAccess;
+            use program_structure::ast::Access::{ArrayAccess, ArraySlice};
             for acc in access{
                 match acc{
                     ArrayAccess(exp) =>{
@@ -202,6 +222,11 @@ pub fn check_anonymous_components_expression(
                             return Result::Err(anonymous_general_error(meta.clone(),"An anonymous component cannot be used to define a dimension of an array".to_string()));
                         }
                     },
+                    ArraySlice(start, length) =>{
+                        if start.contains_anonymous_comp() || length.contains_anonymous_comp(){
+                            return Result::Err(anonymous_general_error(meta.clone(),"An anonymous component cannot be used to define a dimension of an array".to_string()));
+                        }
+                    },
                     ComponentAccess(_)=>{},
                 }
             }
@@ -639,10 +664,16 @@ fn check_tuples_statement(stm: &Statement)-> Result<(), Report>{
             }
             Result::Ok(())
         }  
-        Statement::Assert { meta, arg}   => { 
+        Statement::Assert { meta, arg, message }   => { 
+            let in_message = message.iter().any(|arg| {
+                matches!(arg, LogArgument::LogExp(exp) if exp.contains_tuple())
+            });
             if arg.contains_tuple(){
                 Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used in a return ".to_string()))       
             }
+            else if in_message {
+                Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used in the message of an assert ".to_string()))
+            }
             else{ 
                 Result::Ok(())
             }
@@ -688,7 +719,7 @@ fn check_tuples_statement(stm: &Statement)-> Result<(), Report>{
         }
         Statement::Substitution { rhe, access, meta,  ..} => {
             use program_structure::ast::Access::ComponentAccess;
-            use program_structure::ast::Access::ArrayAccess;
+            use program_structure::ast::Access::{ArrayAccess, ArraySlice};
             for acc in access{
                 match acc{
                     ArrayAccess(exp) =>{
@@ -696,6 +727,11 @@ fn check_tuples_statement(stm: &Statement)-> Result<(), Report>{
                             return Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used to define a dimension of an array".to_string()));
                         }
                     },
+                    ArraySlice(start, length) =>{
+                        if start.contains_tuple() || length.contains_tuple(){
+                            return Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used to define a dimension of an array".to_string()));
+                        }
+                    },
                     ComponentAccess(_)=>{},
                 }
             }
@@ -735,6 +771,11 @@ pub fn check_tuples_expression(exp: &Expression) -> Result<(), Report>{
                             return Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used to define a dimension of an array".to_string()));
                         }
                     },
+                    ArraySlice(start, length) =>{
+                        if start.contains_tuple() || length.contains_tuple(){
+                            return Result::Err(tuple_general_error(meta.clone(),"A tuple cannot be used to define a dimension of an array".to_string()));
+                        }
+                    },
                     ComponentAccess(_)=>{},
                 }
             }
@@ -87,7 +87,7 @@ pub struct AST {
     pub compiler_version: Option<Version>,
     pub custom_gates: bool,
     pub custom_gates_declared: bool,
-    pub includes: Vec<String>,
+    pub includes: Vec<Include>,
     pub definitions: Vec<Definition>,
     pub main_component: Option<MainComponent>,
 }
@@ -96,7 +96,7 @@ impl AST {
     pub fn new(
         meta: Meta,
         pragmas: Vec<Pragma>,
-        includes: Vec<String>,
+        includes: Vec<Include>,
         definitions: Vec<Definition>,
         main_component: Option<MainComponent>,
     ) -> (AST,Vec<Report>) {
@@ -137,6 +137,18 @@ impl AST {
     }
 }
 
+// An include directive. The definitions of a file included with an alias
+// live in the namespace of the alias and are referenced as alias::name
+#[derive(Clone)]
+pub struct Include {
+    pub meta: Meta,
+    pub path: String,
+    pub alias: Option<String>,
+}
+pub fn build_include(meta: Meta, path: String, alias: Option<String>) -> Include {
+    Include { meta, path, alias }
+}
+
 #[derive(Clone)]
 pub enum Definition {
     Template {
@@ -259,6 +271,7 @@ pub enum Statement {
     Assert {
         meta: Meta,
         arg: Expression,
+        message: Vec<LogArgument>,
     },
 }
 
@@ -347,6 +360,9 @@ pub enum Expression {
 pub enum Access {
     ComponentAccess(String),
     ArrayAccess(Expression),
+    // a[start:end] is stored as the start and the length (end - start) of
+    // the range, so that the length can be computed like any other expression
+    ArraySlice(Expression, Expression),
 }
 pub fn build_component_access(acc: String) -> Access {
     Access::ComponentAccess(acc)
@@ -354,6 +370,15 @@ pub fn build_component_access(acc: String) -> Access {
 pub fn build_array_access(expr: Expression) -> Access {
     Access::ArrayAccess(expr)
 }
+pub fn build_array_slice(meta: Meta, start: Expression, end: Expression) -> Access {
+    let length = Expression::InfixOp {
+        meta,
+        lhe: Box::new(end),
+        infix_op: ExpressionInfixOpcode::Sub,
+        rhe: Box::new(start.clone()),
+    };
+    Access::ArraySlice(start, length)
+}
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum AssignOp {
@@ -569,6 +594,12 @@ impl MemoryKnowledge {
             report.add_primary(location, file_id, "This should be an identifier".to_string());
             report
             },
+            InvalidAssertMessage => {
+                let mut report =
+                Report::error("The number of values does not match the {} placeholders of the assert message".to_string(), ReportCode::InvalidAssertMessage);
+            report.add_primary(location, file_id, "here".to_string());
+            report
+            },
             _ => unreachable!(),    
     };
     report
@@ -185,6 +185,7 @@ impl Expression {
                     match ac {
                         Access::ComponentAccess(_) => {},
                         Access::ArrayAccess( exp ) => if exp.contains_anonymous_comp() {return true;},
+                        Access::ArraySlice( start, length ) => if start.contains_anonymous_comp() || length.contains_anonymous_comp() {return true;},
                     }
                 }
                 false
@@ -226,6 +227,7 @@ impl Expression {
                     match ac {
                         Access::ComponentAccess(_) => {},
                         Access::ArrayAccess( exp ) => if exp.contains_tuple() {return true;},
+                        Access::ArraySlice( start, length ) => if start.contains_tuple() || length.contains_tuple() {return true;},
                     }
                 }
                 false
@@ -274,8 +276,13 @@ fn fill_number(meta: &mut Meta, file_id: usize, _element_id: &mut usize) {
 fn fill_variable(meta: &mut Meta, access: &mut [Access], file_id: usize, element_id: &mut usize) {
     meta.set_file_id(file_id);
     for acc in access {
-        if let Access::ArrayAccess(e) = acc {
-            e.fill(file_id, element_id)
+        match acc {
+            Access::ArrayAccess(e) => e.fill(file_id, element_id),
+            Access::ArraySlice(start, length) => {
+                start.fill(file_id, element_id);
+                length.fill(file_id, element_id);
+            }
+            Access::ComponentAccess(_) => {}
         }
     }
 }
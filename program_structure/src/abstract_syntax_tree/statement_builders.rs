@@ -78,7 +78,29 @@ fn split_string(str: String) -> Vec<LogArgument> {
 }
 
 pub fn build_assert(meta: Meta, arg: Expression) -> Statement {
-    Assert { meta, arg }
+    Assert { meta, arg, message: Vec::new() }
+}
+
+pub fn build_assert_with_message(meta: Meta, arg: Expression, message: Vec<LogArgument>) -> Statement {
+    Assert { meta, arg, message }
+}
+
+// Replaces each {} of the format by the next value, None if the number of
+// placeholders and values differ
+pub fn build_assert_message(format: String, values: Vec<Expression>) -> Option<Vec<LogArgument>> {
+    let chunks: Vec<&str> = format.split("{}").collect();
+    if chunks.len() != values.len() + 1 {
+        return None;
+    }
+    let mut message = Vec::new();
+    let mut values = values.into_iter();
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        if i > 0 {
+            message.push(LogArgument::LogExp(values.next().unwrap()));
+        }
+        message.append(&mut split_string(chunk.to_string()));
+    }
+    Some(message)
 }
 
 pub fn build_mult_substitution(meta: Meta, lhe: Expression, op : AssignOp, rhe: Expression) -> Statement {
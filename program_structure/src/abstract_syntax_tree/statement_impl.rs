@@ -163,6 +163,7 @@ impl Statement {
                     match acc {
                         Access::ComponentAccess(_) => {},
                         Access::ArrayAccess( exp ) => if exp.contains_anonymous_comp() {return true;},
+                        Access::ArraySlice( start, length ) => if start.contains_anonymous_comp() || length.contains_anonymous_comp() {return true;},
                     }
                 }
                 rhe.contains_anonymous_comp()
@@ -192,8 +193,9 @@ impl Statement {
                 }
                 false
             }
-            Assert {  arg, .. } => {
-                arg.contains_anonymous_comp() 
+            Assert {  arg, message, .. } => {
+                arg.contains_anonymous_comp()
+                    || message.iter().any(|arg| matches!(arg, LogArgument::LogExp(exp) if exp.contains_anonymous_comp()))
             }
             UnderscoreSubstitution { rhe, .. } => {
                 rhe.contains_anonymous_comp() 
@@ -232,7 +234,7 @@ impl FillMeta for Statement {
             }
             LogCall { meta, args, .. } => fill_log_call(meta, args, file_id, element_id),
             Block { meta, stmts, .. } => fill_block(meta, stmts, file_id, element_id),
-            Assert { meta, arg, .. } => fill_assert(meta, arg, file_id, element_id),
+            Assert { meta, arg, message } => fill_assert(meta, arg, message, file_id, element_id),
             UnderscoreSubstitution { meta, rhe, .. } => {
                 fill_underscore_substitution(meta, rhe, file_id, element_id);
             },
@@ -309,8 +311,13 @@ fn fill_substitution(
     meta.set_file_id(file_id);
     rhe.fill(file_id, element_id);
     for a in access {
-        if let Access::ArrayAccess(e) = a {
-            e.fill(file_id, element_id);
+        match a {
+            Access::ArrayAccess(e) => e.fill(file_id, element_id),
+            Access::ArraySlice(start, length) => {
+                start.fill(file_id, element_id);
+                length.fill(file_id, element_id);
+            }
+            Access::ComponentAccess(_) => {}
         }
     }
 }
@@ -355,9 +362,14 @@ fn fill_block(meta: &mut Meta, stmts: &mut [Statement], file_id: usize, element_
     }
 }
 
-fn fill_assert(meta: &mut Meta, arg: &mut Expression, file_id: usize, element_id: &mut usize) {
+fn fill_assert(meta: &mut Meta, arg: &mut Expression, message: &mut Vec<LogArgument>, file_id: usize, element_id: &mut usize) {
     meta.set_file_id(file_id);
     arg.fill(file_id, element_id);
+    for arg in message {
+        if let LogArgument::LogExp(e) = arg {
+            e.fill(file_id, element_id);
+        }
+    }
 }
 
 fn fill_underscore_substitution(meta: &mut Meta, rhe: &mut Expression, file_id: usize, element_id: &mut usize) {
@@ -13,6 +13,8 @@ pub enum ReportCode {
     UnrecognizedVersion,
     UnrecognizedPragma,
     ExpectedIdentifier,
+    InvalidAssertMessage,
+    UnknownNamespace,
     IncludeNotFound,
     IllegalExpression,
     MultiplePragma,
@@ -70,6 +72,7 @@ pub enum ReportCode {
     InconsistentReturnTypesInBlock,
     InconsistentStaticInformation,
     InvalidArrayAccess(usize, usize),
+    InvalidArraySlice,
     InvalidSignalAccess,
     InvalidTagAccess,
     InvalidTagAccessAfterArray,
@@ -108,6 +111,15 @@ pub enum ReportCode {
     UnconstrainedIOSignal,
     UnusedInput,
     UnusedOutput,
+    PossibleFieldOverflow,
+    // Lint codes
+    UnknownLintRule,
+    LintUnconstrainedAssignment,
+    LintShadowedVariable,
+    LintUnusedDefinition,
+    LintUnusedParameter,
+    LintUnusedComponentOutput,
+    LintConstantConstraint,
 
     ErrorWat2Wasm,
     CustomGateIntermediateSignalWarning,
@@ -144,6 +156,8 @@ impl fmt::Display for ReportCode {
             MultiplePragma => "P1013",
             IncludeNotFound => "P1014",
             ExpectedIdentifier => "P1015",
+            InvalidAssertMessage => "P1016",
+            UnknownNamespace => "P1017",
             UndefinedFunction => "T2001",
             UndefinedTemplate => "T2002",
             UninitializedSymbolInExpression => "T2003",
@@ -218,6 +232,7 @@ impl fmt::Display for ReportCode {
             WrongTypesInAssignOperationDims(..) => "T2061",
             NonValidTagAssignment => "T2062",
             IllegalMainExpression => "T2063",
+            InvalidArraySlice => "T2064",
             RuntimeError => "T3001",
             RuntimeWarning => "T3002",
             UnknownDimension => "T20460",
@@ -234,6 +249,14 @@ impl fmt::Display for ReportCode {
             UnconstrainedIOSignal => "CA02",
             UnusedInput => "CA03",
             UnusedOutput => "CA04",
+            PossibleFieldOverflow => "CA05",
+            UnknownLintRule => "L00",
+            LintUnconstrainedAssignment => "L01",
+            LintShadowedVariable => "L02",
+            LintUnusedDefinition => "L03",
+            LintUnusedParameter => "L04",
+            LintUnusedComponentOutput => "L05",
+            LintConstantConstraint => "L06",
             ErrorWat2Wasm => "W01",
             CustomGateIntermediateSignalWarning => "CG01",
             CustomGateConstraintError => "CG02",
@@ -2,6 +2,8 @@ use super::error_code::ReportCode;
 use super::file_definition::{FileID, FileLibrary, FileLocation};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term;
+use std::cell::RefCell;
+use std::collections::HashSet;
 
 pub type ReportCollection = Vec<Report>;
 pub type DiagnosticCode = String;
@@ -30,6 +32,12 @@ impl MessageCategory {
     }
 }
 
+thread_local! {
+    // While recording, the keys of the reports that are not printed again
+    // and every report given to be printed
+    static RECORDING: RefCell<Option<(HashSet<String>, Vec<Report>)>> = const { RefCell::new(None) };
+}
+
 #[derive(Clone)]
 pub struct Report {
     category: MessageCategory,
@@ -57,7 +65,9 @@ impl Report {
         let mut diagnostics = Vec::new();
         let files = file_library.to_storage();
         for report in reports.iter() {
-            diagnostics.push(report.to_diagnostic());
+            if Report::record(report) {
+                diagnostics.push(report.to_diagnostic());
+            }
         }
         for diagnostic in diagnostics.iter() {
             let print_result = term::emit(&mut writer.lock(), &config, files, &diagnostic);
@@ -66,6 +76,37 @@ impl Report {
             }
         }
     }
+    // Reports with a key in known are not printed until the recording stops,
+    // and the reports to print are recorded
+    pub fn start_recording(known: HashSet<String>) {
+        RECORDING.with(|recording| *recording.borrow_mut() = Some((known, Vec::new())));
+    }
+    pub fn stop_recording() -> Vec<Report> {
+        RECORDING.with(|recording| recording.borrow_mut().take().map_or(Vec::new(), |(_, printed)| printed))
+    }
+    fn record(report: &Report) -> bool {
+        RECORDING.with(|recording| match recording.borrow_mut().as_mut() {
+            Some((known, printed)) => {
+                printed.push(report.clone());
+                !known.contains(&report.key())
+            }
+            None => true,
+        })
+    }
+    // One line description of the report, without its locations
+    pub fn summary(&self) -> String {
+        let category = if self.is_error() { "error" } else { "warning" };
+        format!("{}[{}]: {}", category, Report::error_code_to_diagnostic_code(&self.error_code), self.error_message)
+    }
+    // Identifies the report by its summary and the file and span of its
+    // primary labels, so equal messages at different places are different
+    pub fn key(&self) -> String {
+        let mut key = self.summary();
+        for label in &self.primary {
+            key.push_str(&format!(" @{}:{}..{}", label.file_id, label.range.start, label.range.end));
+        }
+        key
+    }
     pub fn error_code_to_diagnostic_code(error_code: &ReportCode) -> DiagnosticCode {
         error_code.to_string()
     }
@@ -102,6 +143,21 @@ impl Report {
         self.get_mut_notes().push(note);
         self
     }
+    // Moves every label to the file and location given for its current ones
+    pub fn relocate_labels(&mut self, relocate: impl Fn(FileID, FileLocation) -> (FileID, FileLocation)) -> &mut Self {
+        for label in self.primary.iter_mut().chain(self.secondary.iter_mut()) {
+            let (file_id, range) = relocate(label.file_id, label.range.clone());
+            label.file_id = file_id;
+            label.range = range;
+        }
+        self
+    }
+    pub fn map_notes(&mut self, map: impl Fn(&str) -> String) -> &mut Self {
+        for note in self.get_mut_notes() {
+            *note = map(note);
+        }
+        self
+    }
 
     pub fn to_diagnostic(&self) -> Diagnostic<FileID> {
         let mut labels = self.get_primary().clone();
@@ -149,3 +205,44 @@ impl Report {
         &mut self.notes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_code::ReportCode;
+
+    fn warning_at(file_id: FileID, location: FileLocation) -> Report {
+        let mut report = Report::warning("Unused parameter n".to_string(), ReportCode::LintUnusedParameter);
+        report.add_primary(location, file_id, "unused".to_string());
+        report
+    }
+
+    #[test]
+    fn summary_has_the_category_code_and_message() {
+        let report = Report::error("Missing semicolon".to_string(), ReportCode::MissingSemicolon);
+        assert_eq!(report.summary(), format!("error[{}]: Missing semicolon", ReportCode::MissingSemicolon));
+        let summary = warning_at(0, 10..11).summary();
+        assert!(summary.starts_with("warning["));
+        assert_eq!(summary, warning_at(1, 20..21).summary());
+    }
+
+    #[test]
+    fn key_tells_equal_messages_at_different_places_apart() {
+        let report = warning_at(0, 10..11);
+        assert_eq!(report.key(), format!("{} @0:10..11", report.summary()));
+        assert_eq!(report.key(), warning_at(0, 10..11).key());
+        assert_ne!(report.key(), warning_at(1, 10..11).key());
+        assert_ne!(report.key(), warning_at(0, 12..13).key());
+        assert_ne!(report.key(), warning_at(0, 10..12).key());
+    }
+
+    #[test]
+    fn recording_holds_back_the_known_reports() {
+        let report = warning_at(0, 10..11);
+        Report::start_recording(std::iter::once(report.key()).collect());
+        assert!(!Report::record(&report));
+        assert!(Report::record(&warning_at(0, 12..13)));
+        assert_eq!(Report::stop_recording().len(), 2);
+        assert!(Report::record(&report));
+    }
+}
@@ -1,7 +1,7 @@
 use super::ast::{Definition, Expression, MainComponent};
 use super::file_definition::{FileID, FileLibrary};
 use super::function_data::{FunctionData, FunctionInfo};
-use super::program_merger::Merger;
+use super::program_merger::{duplicated_symbol_report, Merger};
 use super::template_data::{TemplateData, TemplateInfo};
 use super::bus_data::{BusData, BusInfo};
 use crate::abstract_syntax_tree::ast::FillMeta;
@@ -166,6 +166,49 @@ impl ProgramArchive {
     pub fn get_main_expression(&self) -> &Expression {
         &self.initial_template_call
     }
+    // Replaces the main component, to instantiate other templates of the program
+    pub fn set_main_component(&mut self, file_id_main: FileID, main_component: MainComponent) {
+        let (public_inputs, mut initial_template_call) = main_component;
+        initial_template_call.fill(file_id_main, &mut self.id_max);
+        self.file_id_main = file_id_main;
+        self.public_inputs = public_inputs;
+        self.initial_template_call = initial_template_call;
+    }
+    // Adds the definitions of a file parsed after the archive, like the ones
+    // written in circom repl
+    pub fn add_definitions(&mut self, file_id: FileID, definitions: Vec<Definition>) -> Result<(), Vec<Report>> {
+        let mut reports = vec![];
+        for definition in &definitions {
+            let (name, meta) = match definition {
+                Definition::Template { name, meta, .. }
+                | Definition::Function { name, meta, .. }
+                | Definition::Bus { name, meta, .. } => (name, meta),
+            };
+            if self.contains_template(name) || self.contains_function(name) || self.contains_bus(name) {
+                reports.push(duplicated_symbol_report(name, meta, file_id));
+            }
+        }
+        if !reports.is_empty() {
+            return Err(reports);
+        }
+        let mut merger = Merger::starting_at(self.id_max);
+        merger.add_definitions(file_id, definitions)?;
+        let (fresh_id, functions, templates, buses) = merger.decompose();
+        self.id_max = fresh_id;
+        for (name, data) in functions {
+            self.function_keys.insert(name.clone());
+            self.functions.insert(name, data);
+        }
+        for (name, data) in templates {
+            self.template_keys.insert(name.clone());
+            self.templates.insert(name, data);
+        }
+        for (name, data) in buses {
+            self.bus_keys.insert(name.clone());
+            self.buses.insert(name, data);
+        }
+        Ok(())
+    }
     // FileLibrary functions
     pub fn get_file_library(&self) -> &FileLibrary {
         &self.file_library
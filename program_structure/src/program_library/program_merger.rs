@@ -1,4 +1,4 @@
-use super::ast::Definition;
+use super::ast::{Definition, Meta};
 use super::error_code::ReportCode;
 use super::error_definition::Report;
 use super::file_definition::FileID;
@@ -28,6 +28,11 @@ impl Merger {
         Merger::default()
     }
 
+    // Merger for definitions added to a program whose ids go up to fresh_id
+    pub fn starting_at(fresh_id: usize) -> Merger {
+        Merger { fresh_id, ..Merger::default() }
+    }
+
     pub fn add_definitions(&mut self, file_id: FileID, definitions: Vec<Definition>)  -> Result<(), Vec<Report>> {
         let mut reports = vec![];
         for definition in definitions {
@@ -87,16 +92,7 @@ impl Merger {
                 }
             };
             if let Option::Some(definition_name) = name {
-                let mut report = Report::error(
-                    String::from("Duplicated callable symbol"),
-                    ReportCode::SameSymbolDeclaredTwice,
-                );
-                report.add_primary(
-                    meta.file_location(),
-                    file_id,
-                    format!("{} is already in use", definition_name),
-                );
-                reports.push(report);
+                reports.push(duplicated_symbol_report(&definition_name, &meta, file_id));
             }
         }
         if reports.is_empty() { Ok(()) } else { Err(reports) }
@@ -135,4 +131,13 @@ impl Merger {
     pub fn decompose(self) -> (usize, FunctionInfo, TemplateInfo, BusInfo) {
         (self.fresh_id, self.function_info, self.template_info, self.bus_info)
     }
+}
+
+pub fn duplicated_symbol_report(name: &str, meta: &Meta, file_id: FileID) -> Report {
+    let mut report = Report::error(
+        String::from("Duplicated callable symbol"),
+        ReportCode::SameSymbolDeclaredTwice,
+    );
+    report.add_primary(meta.file_location(), file_id, format!("{} is already in use", name));
+    report
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use num_bigint::BigInt;
+use num_traits::{One, Zero};
 
 const P_BN128: &str =
     "21888242871839275222246405745257275088548364400416034343698204186575808495617";
@@ -26,20 +27,119 @@ impl Clone for UsefulConstants {
 
 impl UsefulConstants {
     pub fn new(possible_prime: &String) -> UsefulConstants {
-        let prime_to_use = if possible_prime.eq("bn128") {P_BN128} 
-          else if possible_prime.eq("bls12381") { P_BLS12381} 
-          else if possible_prime.eq("goldilocks") { P_GOLDILOCKS} 
-          else if possible_prime.eq("grumpkin") { P_GRUMPKIN} 
-          else if possible_prime.eq("pallas") { P_PALLAS} 
-          else if possible_prime.eq("vesta") { P_VESTA} 
-          else if possible_prime.eq("secq256r1") { P_SECQ256R1}
-          else if possible_prime.eq("bls12377") { P_BLS12377}
-          else {unreachable!()};
-
-        UsefulConstants { p: BigInt::parse_bytes(prime_to_use.as_bytes(), 10).expect("can not parse p") }
+        let p = match named_prime(possible_prime) {
+            Some(prime_to_use) => BigInt::parse_bytes(prime_to_use.as_bytes(), 10),
+            // Custom primes are validated by the caller
+            None => parse_prime(possible_prime),
+        };
+        UsefulConstants { p: p.expect("can not parse p") }
     }
     
     pub fn get_p(&self) -> &BigInt {
         &self.p
     }
 }
+
+fn named_prime(name: &str) -> Option<&'static str> {
+    match name {
+        "bn128" => Some(P_BN128),
+        "bls12381" => Some(P_BLS12381),
+        "goldilocks" => Some(P_GOLDILOCKS),
+        "grumpkin" => Some(P_GRUMPKIN),
+        "pallas" => Some(P_PALLAS),
+        "vesta" => Some(P_VESTA),
+        "secq256r1" => Some(P_SECQ256R1),
+        "bls12377" => Some(P_BLS12377),
+        _ => None,
+    }
+}
+
+pub const PRIME_NAMES: [&str; 8] =
+    ["bn128", "bls12377", "bls12381", "goldilocks", "grumpkin", "pallas", "secq256r1", "vesta"];
+
+pub fn is_named_prime(name: &str) -> bool {
+    named_prime(name).is_some()
+}
+
+// The name of the curve whose scalar field has the prime p, if any
+pub fn name_of_prime(p: &BigInt) -> Option<&'static str> {
+    PRIME_NAMES.iter().copied().find(|name| UsefulConstants::new(&name.to_string()).get_p() == p)
+}
+
+// Decimal or 0x-prefixed hexadecimal
+pub fn parse_prime(value: &str) -> Option<BigInt> {
+    let (digits, radix) = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (value, 10),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix)
+}
+
+// Miller-Rabin with the first prime numbers as bases, which is deterministic
+// below 3.3 * 10^24 and has a negligible error above
+pub fn is_prime(n: &BigInt) -> bool {
+    const BASES: [u32; 20] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+    let one = BigInt::one();
+    let two = BigInt::from(2);
+    if *n < two {
+        return false;
+    }
+    for base in BASES.iter() {
+        let base = BigInt::from(*base);
+        if *n == base {
+            return true;
+        }
+        if (n % &base).is_zero() {
+            return false;
+        }
+    }
+    let n_minus_one = n - &one;
+    let mut d = n_minus_one.clone();
+    let mut s = 0;
+    while (&d % &two).is_zero() {
+        d /= &two;
+        s += 1;
+    }
+    'bases: for base in BASES.iter() {
+        let mut x = BigInt::from(*base).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_primes_pass_the_primality_test() {
+        for name in PRIME_NAMES.iter() {
+            let p = UsefulConstants::new(&name.to_string()).get_p().clone();
+            assert!(is_prime(&p));
+            assert_eq!(name_of_prime(&p), Some(*name));
+        }
+        // 2^255 - 19 and its hexadecimal form
+        let p = parse_prime("57896044618658097711785492504343953926634992332820282019728792003956564819949").unwrap();
+        assert_eq!(parse_prime("0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"), Some(p.clone()));
+        assert!(is_prime(&p));
+        assert_eq!(name_of_prime(&p), None);
+        // A Carmichael number and the square of a prime
+        assert!(!is_prime(&BigInt::from(561)));
+        assert!(!is_prime(&(&p * &p)));
+        assert_eq!(parse_prime("0x"), None);
+        assert_eq!(parse_prime("-7"), None);
+        assert_eq!(parse_prime("bn254"), None);
+    }
+}
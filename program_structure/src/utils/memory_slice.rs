@@ -203,6 +203,42 @@ impl<C: Clone> MemorySlice<C> {
         Result::Ok(MemorySlice { route: size, values })
     }
 
+    // Returns the route, the initial cells and the number of cells of the
+    // blocks accessed by access, where the values with a length are the start
+    // of a range of that length of their dimension
+    fn generate_ranges_from_access(
+        memory_slice: &MemorySlice<C>,
+        access: &[SliceCapacity],
+        lengths: &[Option<SliceCapacity>],
+    ) -> Result<(Vec<SliceCapacity>, Vec<SliceCapacity>, SliceCapacity), MemoryError> {
+        if access.len() > memory_slice.route.len() {
+            return Result::Err(MemoryError::OutOfBoundsError);
+        }
+        let mut route = Vec::new();
+        for (dim, index) in access.iter().enumerate() {
+            match lengths.get(dim).copied().flatten() {
+                Option::Some(length) => {
+                    if length == 0 || index + length > memory_slice.route[dim] {
+                        return Result::Err(MemoryError::OutOfBoundsError);
+                    }
+                    route.push(length);
+                }
+                Option::None if *index >= memory_slice.route[dim] => {
+                    return Result::Err(MemoryError::OutOfBoundsError);
+                }
+                Option::None => {}
+            }
+        }
+        let (inner_route, cells_per_block) =
+            MemorySlice::generate_new_route_from_access(memory_slice, access)?;
+        route.extend(inner_route);
+        let mut initial_cells = Vec::new();
+        for element in MemorySlice::<C>::get_range_accesses(access, lengths) {
+            initial_cells.push(MemorySlice::get_initial_cell(memory_slice, &element)?);
+        }
+        Result::Ok((route, initial_cells, cells_per_block))
+    }
+
     fn generate_references_from_access<'a>(
         memory_slice: &'a MemorySlice<C>,
         access: &[SliceCapacity],
@@ -353,6 +389,67 @@ impl<C: Clone> MemorySlice<C> {
         MemorySlice::generate_slice_from_access(memory_slice, access)
     }
 
+    // Accesses the values selected by access, where the values with a length
+    // are the start of the range [start, start + length) of their dimension
+    pub fn access_values_ranges(
+        memory_slice: &MemorySlice<C>,
+        access: &[SliceCapacity],
+        lengths: &[Option<SliceCapacity>],
+    ) -> Result<MemorySlice<C>, MemoryError> {
+        let (route, initial_cells, cells_per_block) =
+            MemorySlice::generate_ranges_from_access(memory_slice, access, lengths)?;
+        let mut values = Vec::with_capacity(initial_cells.len() * cells_per_block);
+        for cell in initial_cells {
+            values.extend_from_slice(&memory_slice.values[cell..cell + cells_per_block]);
+        }
+        Result::Ok(MemorySlice { route, values })
+    }
+
+    pub fn insert_values_ranges(
+        memory_slice: &mut MemorySlice<C>,
+        access: &[SliceCapacity],
+        lengths: &[Option<SliceCapacity>],
+        new_values: &MemorySlice<C>,
+        is_strict: bool,
+    ) -> Result<(), MemoryError> {
+        let (route, initial_cells, cells_per_block) =
+            MemorySlice::generate_ranges_from_access(memory_slice, access, lengths)?;
+        let range = MemorySlice { route, values: Vec::new() };
+        let result = MemorySlice::check_correct_dims(&range, &[], new_values, is_strict);
+        match result {
+            Result::Ok(_) | Result::Err(MemoryError::MismatchedDimensionsWeak(..)) => {
+                let cells = initial_cells.iter().flat_map(|cell| *cell..*cell + cells_per_block);
+                for (cell, value) in cells.zip(new_values.values.iter()) {
+                    memory_slice.values[cell] = value.clone();
+                }
+                result
+            }
+            Result::Err(error) => Result::Err(error),
+        }
+    }
+
+    // Accesses to each element of the ranges, where the values of access with
+    // a length are the start of a range of that length
+    pub fn get_range_accesses(
+        access: &[SliceCapacity],
+        lengths: &[Option<SliceCapacity>],
+    ) -> Vec<Vec<SliceCapacity>> {
+        let mut elements = vec![Vec::new()];
+        for (dim, index) in access.iter().enumerate() {
+            let length = lengths.get(dim).copied().flatten().unwrap_or(1);
+            let mut new_elements = Vec::with_capacity(elements.len() * length);
+            for element in &elements {
+                for value in *index..*index + length {
+                    let mut new_element = element.clone();
+                    new_element.push(value);
+                    new_elements.push(new_element);
+                }
+            }
+            elements = new_elements;
+        }
+        elements
+    }
+
     pub fn access_values_by_reference<'a>(
         memory_slice: &'a MemorySlice<C>,
         access: &[SliceCapacity],
@@ -503,6 +600,36 @@ mod tests {
         }
     }
     #[test]
+    fn memory_slice_range_access_and_insertion() {
+        let route = vec![3, 4];
+        let values = (0..12).collect();
+        let mut slice = U32Slice::new_array(route, values);
+
+        let range = U32Slice::access_values_ranges(&slice, &[1, 1], &[None, Some(2)]).ok().unwrap();
+        assert_eq!(range.route(), &[2]);
+        assert_eq!(range.values, vec![5, 6]);
+        let rows = U32Slice::access_values_ranges(&slice, &[1], &[Some(2)]).ok().unwrap();
+        assert_eq!(rows.route(), &[2, 4]);
+        assert_eq!(rows.values, (4..12).collect::<Vec<u32>>());
+        let block = U32Slice::access_values_ranges(&slice, &[1, 1], &[Some(2), Some(3)]).ok().unwrap();
+        assert_eq!(block.route(), &[2, 3]);
+        assert_eq!(block.values, vec![5, 6, 7, 9, 10, 11]);
+        assert!(U32Slice::access_values_ranges(&slice, &[1, 3], &[None, Some(2)]).is_err());
+        assert!(U32Slice::access_values_ranges(&slice, &[2, 0], &[Some(2), Some(1)]).is_err());
+
+        let new_values = U32Slice::new_with_route(&[3], &20);
+        assert!(U32Slice::insert_values_ranges(&mut slice, &[2, 1], &[None, Some(3)], &new_values, true).is_ok());
+        assert_eq!(slice.values[8..12], [8, 20, 20, 20]);
+        let too_short = U32Slice::new_with_route(&[2], &30);
+        assert!(U32Slice::insert_values_ranges(&mut slice, &[0, 0], &[None, Some(3)], &too_short, true).is_err());
+        let column = U32Slice::new_array(vec![2, 1], vec![40, 41]);
+        assert!(U32Slice::insert_values_ranges(&mut slice, &[0, 3], &[Some(2), Some(1)], &column, true).is_ok());
+        assert_eq!((slice.values[3], slice.values[7]), (40, 41));
+
+        let accesses = U32Slice::get_range_accesses(&[1, 0, 2], &[Some(2), None, Some(2)]);
+        assert_eq!(accesses, vec![vec![1, 0, 2], vec![1, 0, 3], vec![2, 0, 2], vec![2, 0, 3]]);
+    }
+    #[test]
     fn memory_slice_multiple_insertion() {
         let route = vec![3, 4];
         let mut slice = U32Slice::new_with_route(&route, &0);
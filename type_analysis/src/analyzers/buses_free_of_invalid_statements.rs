@@ -158,6 +158,9 @@ fn analyse_access(
     for acc in access.iter() {
         if let Access::ArrayAccess(index) = acc {
             analyse_expression(index, function_names, reports);
+        } else if let Access::ArraySlice(start, length) = acc {
+            analyse_expression(start, function_names, reports);
+            analyse_expression(length, function_names, reports);
         } else {
             report_undefined_bus_error(meta, "Bus uses name-access operators", file_id, Some("Template operator found"), reports)
         }
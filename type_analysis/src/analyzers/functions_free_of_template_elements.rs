@@ -109,8 +109,13 @@ fn analyse_statement(
                 }
             }
         }
-        Assert { arg, .. } => {
+        Assert { arg, message, .. } => {
             analyse_expression(arg, function_names, reports);
+            for logarg in message {
+                if let LogArgument::LogExp(arg) = logarg {
+                    analyse_expression(arg, function_names, reports);
+                }
+            }
         }
         Return { value, .. } => {
             analyse_expression(value, function_names, reports);
@@ -141,6 +146,15 @@ fn analyse_access(
     for acc in access.iter() {
         if let Access::ArrayAccess(index) = acc {
             analyse_expression(index, function_names, reports);
+        } else if let Access::ArraySlice(..) = acc {
+            let mut report = Report::error(
+                format!("Function uses array slices"),
+                ReportCode::InvalidArraySlice,
+            );
+            let location =
+                file_definition::generate_file_location(meta.get_start(), meta.get_end());
+            report.add_primary(location, file_id.clone(), format!("Array slice found"));
+            reports.push(report);
         } else {
             let mut report = Report::error(
                 format!("Function uses component operators"),
@@ -269,8 +269,13 @@ fn analyze_statement(
                 }
             }
         }
-        Statement::Assert { arg, .. } => {
-            analyze_expression(arg, file_id, function_info, template_info, bus_info, reports, environment)
+        Statement::Assert { arg, message, .. } => {
+            analyze_expression(arg, file_id, function_info, template_info, bus_info, reports, environment);
+            for logarg in message {
+                if let LogArgument::LogExp(arg) = logarg {
+                    analyze_expression(arg, file_id, function_info, template_info, bus_info, reports, environment);
+                }
+            }
         }
         Statement::Block { stmts, .. } => {
             environment.push(Block::new());
@@ -334,6 +339,10 @@ fn treat_variable(
             Access::ArrayAccess(index) => {
                 analyze_expression(index, file_id, function_info, template_info, bus_info, reports, environment);
             }
+            Access::ArraySlice(start, length) => {
+                analyze_expression(start, file_id, function_info, template_info, bus_info, reports, environment);
+                analyze_expression(length, file_id, function_info, template_info, bus_info, reports, environment);
+            }
             Access::ComponentAccess(_) => {}
         }
     }
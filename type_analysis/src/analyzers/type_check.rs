@@ -78,7 +78,10 @@ pub struct OutInfo {
     pub reached: HashSet<String>,
 }
 
-pub fn type_check(program_archive: &ProgramArchive) -> Result<OutInfo, ReportCollection> {
+pub fn type_check(
+    program_archive: &ProgramArchive,
+    function_main: bool,
+) -> Result<OutInfo, ReportCollection> {
     let mut analysis_information = AnalysisInformation {
         reached: HashSet::new(),
         file_id: *program_archive.get_file_id_main(),
@@ -94,7 +97,13 @@ pub fn type_check(program_archive: &ProgramArchive) -> Result<OutInfo, ReportCol
         return Result::Err(analysis_information.reports);
     }
 
-    check_main_has_tags(initial_expression, program_archive, &mut analysis_information.reports);
+    let is_function_call = matches!(
+        initial_expression,
+        Call { id, .. } if program_archive.contains_function(id)
+    );
+    if !(function_main && is_function_call) {
+        check_main_has_tags(initial_expression, program_archive, &mut analysis_information.reports);
+    }
 
 
     if analysis_information.reports.is_empty() {
@@ -261,6 +270,10 @@ fn type_statement(
                 &mut analysis_information.reports,
                 program_archive,
             );
+            let symbol_type_result = symbol_type_result.and_then(|s_type| {
+                let is_component = analysis_information.environment.has_component(var);
+                apply_slice_to_symbol(access, is_component, s_type, meta, &mut analysis_information.reports)
+            });
             let symbol_information = if let Result::Ok(s_type) = symbol_type_result {
                 s_type
             } else {
@@ -481,37 +494,10 @@ fn type_statement(
             }
         }
         LogCall { args, meta } => {
-            for arglog in args {
-                if let LogArgument::LogExp(arg) = arglog{
-                    let arg_response = type_expression(&arg, program_archive, analysis_information);
-                    let arg_type = if let Result::Ok(t) = arg_response {
-                        t
-                    } else {
-                        return;
-                    };
-                    if arg_type.is_template() {
-                        add_report(
-                            ReportCode::MustBeSingleArithmeticT,
-                            meta,
-                            &mut analysis_information.reports,
-                        )
-                    } else if arg_type.is_bus() {
-                        add_report(
-                            ReportCode::MustBeSingleArithmeticB,
-                            meta,
-                            &mut analysis_information.reports,
-                        )
-                    } else if arg_type.dim() > 0 {
-                        add_report(
-                            ReportCode::MustBeSingleArithmetic(arg_type.dim()),
-                            meta,
-                            &mut analysis_information.reports,
-                        )
-                    }
-                }
-            }
+            type_log_arguments(args, meta, program_archive, analysis_information);
         }
-        Assert { arg, meta } => {
+        Assert { arg, meta, message } => {
+            type_log_arguments(message, meta, program_archive, analysis_information);
             let arg_response = type_expression(arg, program_archive, analysis_information);
             let arg_type = if let Result::Ok(t) = arg_response {
                 t
@@ -641,6 +627,44 @@ fn type_statement(
     }
 }
 
+// The values printed by log and by the message of assert are single field elements
+fn type_log_arguments(
+    args: &[LogArgument],
+    meta: &Meta,
+    program_archive: &ProgramArchive,
+    analysis_information: &mut AnalysisInformation,
+) {
+    for arglog in args {
+        if let LogArgument::LogExp(arg) = arglog {
+            let arg_response = type_expression(arg, program_archive, analysis_information);
+            let arg_type = if let Result::Ok(t) = arg_response {
+                t
+            } else {
+                return;
+            };
+            if arg_type.is_template() {
+                add_report(
+                    ReportCode::MustBeSingleArithmeticT,
+                    meta,
+                    &mut analysis_information.reports,
+                )
+            } else if arg_type.is_bus() {
+                add_report(
+                    ReportCode::MustBeSingleArithmeticB,
+                    meta,
+                    &mut analysis_information.reports,
+                )
+            } else if arg_type.dim() > 0 {
+                add_report(
+                    ReportCode::MustBeSingleArithmetic(arg_type.dim()),
+                    meta,
+                    &mut analysis_information.reports,
+                )
+            }
+        }
+    }
+}
+
 fn type_expression(
     expression: &Expression,
     program_archive: &ProgramArchive,
@@ -820,11 +844,14 @@ fn type_expression(
             let symbol_information = apply_access_to_symbol(
                 name,
                 meta,
-                access_information,
+                access_information.clone(),
                 environment,
                 reports,
                 program_archive,
             )?;
+            let is_component = environment.has_component(name);
+            let symbol_information =
+                apply_slice_to_symbol(access, is_component, symbol_information, meta, reports)?;
             match symbol_information {
                 SymbolInformation::Component(possible_template) if possible_template.is_some() => {
                     Result::Ok(FoldedType::template(&possible_template.unwrap()))
@@ -1020,6 +1047,26 @@ fn treat_access(
                         }
                     }
             }
+            ArraySlice(start, length) => {
+                if signal_info.len() > 0 {
+                    let mut info = signal_info.get(signal_info.len()-1).unwrap().clone();
+                    info.1 = info.1 + 1;
+                    signal_info.remove(signal_info.len()-1);
+                    signal_info.push(info);
+                } else {
+                    access_info.0 += 1;
+                }
+                for bound in [start, length] {
+                    let bound_type = type_expression(bound, program_archive, analysis_information)?;
+                    if bound_type.is_template() || bound_type.is_bus() || bound_type.dim() > 0 {
+                        add_report(
+                            ReportCode::InvalidArraySlice,
+                            bound.get_meta(),
+                            &mut analysis_information.reports,
+                        );
+                    }
+                }
+            }
             ComponentAccess(name) => {
 
                 if signal_info.len() > 0 {
@@ -1158,6 +1205,43 @@ fn check_if_it_is_a_tag(
     return Ok(false);
 }
 
+// A slice a[i:j] keeps the accessed dimension, so the result has one more
+// dimension for each slice than the same access with indexes. Slices are
+// allowed in the accesses of a local signal or variable, and after the
+// signal of a subcomponent.
+fn apply_slice_to_symbol(
+    accesses: &[Access],
+    is_component: bool,
+    symbol_information: SymbolInformation,
+    meta: &Meta,
+    reports: &mut ReportCollection,
+) -> Result<SymbolInformation, ()> {
+    let number_of_slices = accesses.iter().filter(|access| matches!(access, Access::ArraySlice(..))).count();
+    if number_of_slices == 0 {
+        return Result::Ok(symbol_information);
+    }
+    let first_slice = accesses.iter().position(|access| matches!(access, Access::ArraySlice(..))).unwrap();
+    let component_accesses: Vec<usize> = accesses
+        .iter()
+        .enumerate()
+        .filter(|(_, access)| matches!(access, Access::ComponentAccess(_)))
+        .map(|(position, _)| position)
+        .collect();
+    let valid_position = match component_accesses.as_slice() {
+        [] => true,
+        [signal_position] => is_component && *signal_position < first_slice,
+        _ => false,
+    };
+    if !valid_position {
+        return add_report_and_end(ReportCode::InvalidArraySlice, meta, reports);
+    }
+    match symbol_information {
+        SymbolInformation::Var(dim) => Result::Ok(SymbolInformation::Var(dim + number_of_slices)),
+        SymbolInformation::Signal(dim) => Result::Ok(SymbolInformation::Signal(dim + number_of_slices)),
+        _ => add_report_and_end(ReportCode::InvalidArraySlice, meta, reports),
+    }
+}
+
 fn apply_access_to_symbol(
     symbol: &str,
     meta: &Meta,
@@ -1467,6 +1551,9 @@ fn add_report(error_code: ReportCode, meta: &Meta, reports: &mut ReportCollectio
                 expected, given
             )
         }
+        InvalidArraySlice => {
+            "Array slices must access a signal or variable, or the signal of a subcomponent, and their bounds must be single arithmetic expressions".to_string()
+        }
         InvalidSignalAccess => "Signal not found in component: only accesses to input/output signals are allowed".to_string(),
         InvalidSignalTagAccess => "Invalid tag access: could not find the tag".to_string(),
         InvalidTagAccess => "Tag not found in signal: only accesses to tags that appear in the definition of the signal are allowed".to_string(),
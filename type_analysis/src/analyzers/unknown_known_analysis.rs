@@ -158,6 +158,12 @@ fn analyze(stmt: &Statement, entry_information: EntryInformation) -> ExitInforma
                     Access::ArrayAccess(exp) => {
                         access_tag = tag(exp, &environment);
                     }
+                    Access::ArraySlice(start, length) => {
+                        access_tag = tag(start, &environment);
+                        if access_tag == Known {
+                            access_tag = tag(length, &environment);
+                        }
+                    }
                     _ => {}
                 }
                 if access_tag == Unknown {
@@ -497,6 +503,9 @@ fn unknown_index(exp: &Expression, environment: &Environment) -> bool {
             for acc in access {
                 if let Access::ArrayAccess(ex) = acc {
                     has_unknown_index = Unknown == tag(ex, environment);
+                } else if let Access::ArraySlice(start, length) = acc {
+                    has_unknown_index = Unknown == tag(start, environment)
+                        || Unknown == tag(length, environment);
                 }
                 if has_unknown_index {
                     break;
@@ -1,10 +1,35 @@
 use super::analyzers::*;
 use super::decorators::*;
+use super::lints::{run_lints, LintConfig};
 use program_structure::error_definition::ReportCollection;
 use program_structure::program_archive::ProgramArchive;
 
 pub fn check_types(
     program_archive: &mut ProgramArchive,
+) -> Result<ReportCollection, ReportCollection> {
+    analyse(program_archive, Option::None, false)
+}
+
+// Same as check_types, but the main expression can also be a call to a
+// function, whose value is computed instead of instantiating a template
+pub fn check_expression_types(
+    program_archive: &mut ProgramArchive,
+) -> Result<ReportCollection, ReportCollection> {
+    analyse(program_archive, Option::None, true)
+}
+
+// Same as check_types, also running the lint rules with the given configuration
+pub fn check_types_with_lints(
+    program_archive: &mut ProgramArchive,
+    lint_config: &LintConfig,
+) -> Result<ReportCollection, ReportCollection> {
+    analyse(program_archive, Option::Some(lint_config), false)
+}
+
+fn analyse(
+    program_archive: &mut ProgramArchive,
+    lint_config: Option<&LintConfig>,
+    function_main: bool,
 ) -> Result<ReportCollection, ReportCollection> {
     let mut errors = ReportCollection::new();
     let mut warnings = ReportCollection::new();
@@ -47,13 +72,16 @@ pub fn check_types(
     }
 
     // Type analysis
-    let typing_result = type_check(program_archive);
+    let typing_result = type_check(program_archive, function_main);
     match typing_result {
         Err(mut type_reports) => {
             errors.append(&mut type_reports);
             return Result::Err(errors);
         }
         Ok(info) => {
+            if let Option::Some(lint_config) = lint_config {
+                run_lints(program_archive, &info.reached, lint_config, &mut errors, &mut warnings);
+            }
             for name in program_archive.get_function_names().clone() {
                 if !info.reached.contains(&name) {
                     program_archive.remove_function(&name)
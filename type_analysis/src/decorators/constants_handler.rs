@@ -309,7 +309,7 @@ fn expand_statement(stmt: &mut Statement, environment: &mut ExpressionHolder) {
         Substitution { access, rhe, .. } => expand_substitution(access, rhe, environment),
         ConstraintEquality { lhe, rhe, .. } => expand_constraint_equality(lhe, rhe, environment),
         LogCall { args, .. } => expand_log_call(args, environment),
-        Assert { arg, .. } => expand_assert(arg, environment),
+        Assert { arg, message, .. } => expand_assert(arg, message, environment),
         Block { stmts, .. } => expand_block(stmts, environment),
         MultSubstitution { .. } => unreachable!(),
         UnderscoreSubstitution { rhe, .. } => expand_underscore_substitution(rhe, environment),
@@ -374,11 +374,14 @@ fn expand_substitution(
     rhe: &mut Expression,
     environment: &ExpressionHolder,
 ) {
-    use Access::ArrayAccess;
+    use Access::{ArrayAccess, ArraySlice};
     *rhe = expand_expression(rhe.clone(), environment);
     for a in access {
         if let ArrayAccess(e) = a {
             *e = expand_expression(e.clone(), environment);
+        } else if let ArraySlice(start, length) = a {
+            *start = expand_expression(start.clone(), environment);
+            *length = expand_expression(length.clone(), environment);
         }
     }
 }
@@ -407,8 +410,9 @@ fn expand_log_call(args: &mut Vec<LogArgument>, environment: &ExpressionHolder)
     }
 }
 
-fn expand_assert(arg: &mut Expression, environment: &ExpressionHolder) {
+fn expand_assert(arg: &mut Expression, message: &mut Vec<LogArgument>, environment: &ExpressionHolder) {
     *arg = expand_expression(arg.clone(), environment);
+    expand_log_call(message, environment);
 }
 
 fn expand_block(stmts: &mut [Statement], environment: &mut ExpressionHolder) {
@@ -552,6 +556,10 @@ fn expand_variable(
         for a in old_access {
             let new_access = match a {
                 ArrayAccess(e) => ArrayAccess(expand_expression(e, environment)),
+                ArraySlice(start, length) => ArraySlice(
+                    expand_expression(start, environment),
+                    expand_expression(length, environment),
+                ),
                 component_access => component_access,
             };
             access.push(new_access);
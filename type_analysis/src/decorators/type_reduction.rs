@@ -57,7 +57,11 @@ fn reduce_types_in_statement(stmt: &mut Statement, environment: &mut Environment
                 reduce_types_in_log_call(args, environment,program_archive)
             
         },
-        Assert { arg, .. } => reduce_types_in_expression(arg, environment,program_archive),
+        Assert { arg, message, .. } => {
+            let mut reports = reduce_types_in_expression(arg, environment,program_archive);
+            reports.append(&mut reduce_types_in_log_call(message, environment,program_archive));
+            reports
+        },
         Return { value, .. } => reduce_types_in_expression(value, environment,program_archive),
         ConstraintEquality { lhe, rhe, .. } => {
             reduce_types_in_constraint_equality(lhe, rhe, environment,program_archive)
@@ -278,6 +282,10 @@ fn reduce_types_in_variable(
             ArrayAccess(exp) => {
                 reports.append(&mut reduce_types_in_expression(exp, environment,program_archive));
             },
+            ArraySlice(start, length) => {
+                reports.append(&mut reduce_types_in_expression(start, environment,program_archive));
+                reports.append(&mut reduce_types_in_expression(length, environment,program_archive));
+            },
         }
     }
     meta.get_mut_type_knowledge().set_reduces_to(reduction);
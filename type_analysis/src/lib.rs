@@ -4,3 +4,4 @@ extern crate num_traits;
 mod analyzers;
 pub mod check_types;
 mod decorators;
+pub mod lints;
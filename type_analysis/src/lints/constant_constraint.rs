@@ -0,0 +1,37 @@
+use super::visitor::{visit_statements, visit_subexpressions};
+use super::{LintContext, LintFinding};
+use program_structure::ast::{Expression, Statement};
+
+// A constraint without signals does not restrict the witness: it is either
+// always satisfied or always violated, so an assert expresses it better
+pub fn check(context: &LintContext) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for template in context.reached_templates() {
+        visit_statements(template.get_body(), &mut |stmt| {
+            if let Statement::ConstraintEquality { meta, lhe, rhe } = stmt {
+                if !contains_signals(lhe) && !contains_signals(rhe) {
+                    findings.push(LintFinding {
+                        definition: template.get_name().to_string(),
+                        file_id: template.get_file_id(),
+                        location: meta.file_location(),
+                        message: "Constraint with constant sides".to_string(),
+                        label: "Neither side of this constraint contains signals, consider using assert".to_string(),
+                    });
+                }
+            }
+        });
+    }
+    findings
+}
+
+fn contains_signals(expr: &Expression) -> bool {
+    let mut found = false;
+    visit_subexpressions(expr, &mut |expr| {
+        if let Expression::Variable { meta, .. } = expr {
+            // Without type information the variable is conservatively taken as a signal
+            let knowledge = meta.get_type_knowledge();
+            found = found || !knowledge.is_initialized() || knowledge.is_signal() || knowledge.is_bus();
+        }
+    });
+    found
+}
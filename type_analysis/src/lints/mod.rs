@@ -0,0 +1,232 @@
+// Lints are optional checks that point out suspicious code that is still
+// valid circom. Each rule has a name, a default level and a check over the
+// typed program. Levels can be changed in a configuration file and, for a
+// whole file or a single template or function, with comments of the form
+//
+//     //#![allow(rule_name)]   applies to the whole file
+//     //#[deny(rule_name)]     applies to the next template or function
+//
+// Lints run after the type check, before the unreachable templates and
+// functions are removed from the program archive.
+mod constant_constraint;
+mod pragmas;
+mod shadowed_variable;
+mod unconstrained_assignment;
+mod unused_component_output;
+mod unused_definitions;
+mod visitor;
+
+use pragmas::Pragmas;
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::function_data::FunctionData;
+use program_structure::program_archive::ProgramArchive;
+use program_structure::template_data::TemplateData;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    pub fn from_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+}
+
+pub struct LintContext<'a> {
+    pub program_archive: &'a ProgramArchive,
+    // Templates and functions reached from the main component
+    pub reached: &'a HashSet<String>,
+}
+
+impl<'a> LintContext<'a> {
+    // Only the reached definitions are linted, as the rest are not type checked
+    pub fn reached_templates(&self) -> Vec<&'a TemplateData> {
+        let templates = self.program_archive.get_templates().values();
+        templates.filter(|template| self.reached.contains(template.get_name())).collect()
+    }
+
+    pub fn reached_functions(&self) -> Vec<&'a FunctionData> {
+        let functions = self.program_archive.get_functions().values();
+        functions.filter(|function| self.reached.contains(function.get_name())).collect()
+    }
+}
+
+pub struct LintFinding {
+    // Template or function where the finding was produced
+    pub definition: String,
+    pub file_id: FileID,
+    pub location: FileLocation,
+    pub message: String,
+    pub label: String,
+}
+
+pub struct LintRule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default_level: LintLevel,
+    pub code: ReportCode,
+    check: fn(&LintContext) -> Vec<LintFinding>,
+}
+
+pub const LINT_RULES: &[LintRule] = &[
+    LintRule {
+        name: "unconstrained_assignment",
+        description: "signals assigned with <-- that never appear in a constraint of the same template",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintUnconstrainedAssignment,
+        check: unconstrained_assignment::check,
+    },
+    LintRule {
+        name: "shadowed_variable",
+        description: "declarations that hide a symbol declared in an enclosing scope",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintShadowedVariable,
+        check: shadowed_variable::check,
+    },
+    LintRule {
+        name: "unused_definition",
+        description: "templates and functions of the main file that are never used",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintUnusedDefinition,
+        check: unused_definitions::check_definitions,
+    },
+    LintRule {
+        name: "unused_parameter",
+        description: "template and function parameters that are never used",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintUnusedParameter,
+        check: unused_definitions::check_parameters,
+    },
+    LintRule {
+        name: "unused_component_output",
+        description: "outputs of named subcomponents that are never read",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintUnusedComponentOutput,
+        check: unused_component_output::check,
+    },
+    LintRule {
+        name: "constant_constraint",
+        description: "=== constraints where both sides are constant expressions",
+        default_level: LintLevel::Warn,
+        code: ReportCode::LintConstantConstraint,
+        check: constant_constraint::check,
+    },
+];
+
+pub fn get_rule(name: &str) -> Option<&'static LintRule> {
+    LINT_RULES.iter().find(|rule| rule.name == name)
+}
+
+// Levels chosen by the user, rules not present keep their default level
+#[derive(Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<String, LintLevel>,
+}
+
+impl LintConfig {
+    pub fn new() -> LintConfig {
+        LintConfig::default()
+    }
+
+    // The configuration file contains one `rule_name = level` entry per line.
+    // Empty lines and lines starting with # are ignored.
+    pub fn parse(src: &str) -> Result<LintConfig, String> {
+        let mut config = LintConfig::new();
+        for (index, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry: Vec<&str> = line.splitn(2, '=').map(|part| part.trim()).collect();
+            if entry.len() != 2 {
+                return Err(format!("line {}: expected `rule_name = level`", index + 1));
+            }
+            let level = LintLevel::from_name(entry[1]).ok_or_else(|| {
+                format!("line {}: unknown lint level {}, expected allow, warn or deny", index + 1, entry[1])
+            })?;
+            config.set_level(entry[0], level).map_err(|msg| format!("line {}: {}", index + 1, msg))?;
+        }
+        Ok(config)
+    }
+
+    pub fn set_level(&mut self, rule: &str, level: LintLevel) -> Result<(), String> {
+        if get_rule(rule).is_none() {
+            return Err(format!("unknown lint rule {}", rule));
+        }
+        self.levels.insert(rule.to_string(), level);
+        Ok(())
+    }
+
+    pub fn get_level(&self, rule: &LintRule) -> LintLevel {
+        self.levels.get(rule.name).cloned().unwrap_or(rule.default_level)
+    }
+}
+
+pub fn run_lints(
+    program_archive: &ProgramArchive,
+    reached: &HashSet<String>,
+    config: &LintConfig,
+    errors: &mut ReportCollection,
+    warnings: &mut ReportCollection,
+) {
+    let pragmas = Pragmas::collect(program_archive, warnings);
+    let context = LintContext { program_archive, reached };
+    for rule in LINT_RULES {
+        let mut findings = (rule.check)(&context);
+        findings.sort_by_key(|finding| (finding.file_id, finding.location.start));
+        for finding in findings {
+            let level = pragmas.get_level(rule, &finding, config.get_level(rule));
+            let mut report = match level {
+                LintLevel::Allow => continue,
+                LintLevel::Warn => Report::warning(finding.message, rule.code),
+                LintLevel::Deny => Report::error(finding.message, rule.code),
+            };
+            report.add_primary(finding.location, finding.file_id, finding.label);
+            report.add_note(format!("lint rule {} is set to {}", rule.name, level.name()));
+            if level == LintLevel::Deny {
+                errors.push(report);
+            } else {
+                warnings.push(report);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_config_is_parsed() {
+        let config = LintConfig::parse("# comment\n\nshadowed_variable = deny\nunused_parameter=allow\n").unwrap();
+        assert_eq!(config.get_level(get_rule("shadowed_variable").unwrap()), LintLevel::Deny);
+        assert_eq!(config.get_level(get_rule("unused_parameter").unwrap()), LintLevel::Allow);
+        assert_eq!(config.get_level(get_rule("constant_constraint").unwrap()), LintLevel::Warn);
+    }
+
+    #[test]
+    fn lint_config_rejects_unknown_entries() {
+        assert!(LintConfig::parse("not_a_rule = warn").is_err());
+        assert!(LintConfig::parse("shadowed_variable = forbid").is_err());
+        assert!(LintConfig::parse("shadowed_variable").is_err());
+    }
+}
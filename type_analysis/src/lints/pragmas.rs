@@ -0,0 +1,141 @@
+use super::{get_rule, LintFinding, LintLevel, LintRule};
+use program_structure::error_code::ReportCode;
+use program_structure::error_definition::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::program_archive::ProgramArchive;
+use std::collections::{BTreeSet, HashMap};
+
+type LevelOverrides = HashMap<String, LintLevel>;
+
+// Levels given in the code with //#![level(rules)] for a whole file or
+// //#[level(rules)] in the comment lines just before a template or function
+pub struct Pragmas {
+    files: HashMap<FileID, LevelOverrides>,
+    definitions: HashMap<String, LevelOverrides>,
+}
+
+struct Pragma {
+    whole_file: bool,
+    level: LintLevel,
+    rules: Vec<String>,
+}
+
+impl Pragmas {
+    pub fn collect(program_archive: &ProgramArchive, warnings: &mut ReportCollection) -> Pragmas {
+        let file_library = program_archive.get_file_library();
+        let mut headers = Vec::new();
+        for (name, template) in program_archive.get_templates() {
+            headers.push((name.clone(), template.get_file_id(), template.get_param_location()));
+        }
+        for (name, function) in program_archive.get_functions() {
+            headers.push((name.clone(), function.get_file_id(), function.get_param_location()));
+        }
+        let file_ids: BTreeSet<FileID> = headers.iter().map(|header| header.1).collect();
+
+        let mut pragmas = Pragmas { files: HashMap::new(), definitions: HashMap::new() };
+        for file_id in file_ids {
+            let source = match file_library.get_source(file_id) {
+                Some(source) => source,
+                None => continue,
+            };
+            let mut overrides = LevelOverrides::new();
+            let mut offset = 0;
+            for line in source.split('\n') {
+                if let Some(pragma) = parse_pragma(line) {
+                    let location = offset..offset + line.trim_end().len();
+                    for rule in pragma.rules.iter() {
+                        if get_rule(rule).is_none() {
+                            warnings.push(unknown_rule_warning(rule, location.clone(), file_id));
+                        } else if pragma.whole_file {
+                            overrides.insert(rule.clone(), pragma.level);
+                        }
+                    }
+                }
+                offset += line.len() + 1;
+            }
+            pragmas.files.insert(file_id, overrides);
+        }
+        for (name, file_id, param_location) in headers {
+            if let Some(source) = file_library.get_source(file_id) {
+                let overrides = definition_overrides(source, param_location.start);
+                pragmas.definitions.insert(name, overrides);
+            }
+        }
+        pragmas
+    }
+
+    pub fn get_level(&self, rule: &LintRule, finding: &LintFinding, default: LintLevel) -> LintLevel {
+        let in_definition = self.definitions.get(&finding.definition).and_then(|o| o.get(rule.name));
+        let in_file = self.files.get(&finding.file_id).and_then(|o| o.get(rule.name));
+        in_definition.or(in_file).cloned().unwrap_or(default)
+    }
+}
+
+// Looks at the comment lines placed right before the line of the header
+fn definition_overrides(source: &str, header_location: usize) -> LevelOverrides {
+    let mut overrides = LevelOverrides::new();
+    let header_location = header_location.min(source.len());
+    let header_start = source[..header_location].rfind('\n').map_or(0, |pos| pos + 1);
+    let previous_lines = source[..header_start].lines().rev();
+    for line in previous_lines {
+        if !line.trim_start().starts_with("//") {
+            break;
+        }
+        if let Some(pragma) = parse_pragma(line) {
+            if !pragma.whole_file {
+                for rule in pragma.rules {
+                    // The closest pragma to the definition wins
+                    overrides.entry(rule).or_insert(pragma.level);
+                }
+            }
+        }
+    }
+    overrides
+}
+
+fn parse_pragma(line: &str) -> Option<Pragma> {
+    let comment = line.trim().strip_prefix("//")?.trim();
+    let (whole_file, attribute) = match comment.strip_prefix("#!") {
+        Some(attribute) => (true, attribute),
+        None => (false, comment.strip_prefix('#')?),
+    };
+    let attribute = attribute.trim().strip_prefix('[')?.strip_suffix(']')?.trim();
+    let open = attribute.find('(')?;
+    let level = LintLevel::from_name(attribute[..open].trim())?;
+    let rules = attribute[open + 1..].trim().strip_suffix(')')?;
+    let rules = rules.split(',').map(|rule| rule.trim()).filter(|rule| !rule.is_empty());
+    Some(Pragma { whole_file, level, rules: rules.map(|rule| rule.to_string()).collect() })
+}
+
+fn unknown_rule_warning(rule: &str, location: FileLocation, file_id: FileID) -> Report {
+    let mut report = Report::warning(format!("Unknown lint rule {}", rule), ReportCode::UnknownLintRule);
+    report.add_primary(location, file_id, "This pragma is ignored for this rule".to_string());
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pragmas_are_parsed() {
+        let pragma = parse_pragma("  //#![allow(shadowed_variable, unused_parameter)]").unwrap();
+        assert!(pragma.whole_file);
+        assert_eq!(pragma.level, LintLevel::Allow);
+        assert_eq!(pragma.rules, vec!["shadowed_variable", "unused_parameter"]);
+        let pragma = parse_pragma("// #[deny(constant_constraint)]").unwrap();
+        assert!(!pragma.whole_file);
+        assert_eq!(pragma.level, LintLevel::Deny);
+        assert!(parse_pragma("// allow(constant_constraint)").is_none());
+        assert!(parse_pragma("var x; // #[allow(constant_constraint)]").is_none());
+    }
+
+    #[test]
+    fn definition_pragmas_must_precede_the_header() {
+        let source = "//#[deny(unused_parameter)]\n\ntemplate A(n) {}\n//#[allow(unused_parameter)]\n// Doc comment\ntemplate B(n) {}\n";
+        let a = source.find("(n)").unwrap();
+        let b = source.rfind("(n)").unwrap();
+        assert!(definition_overrides(source, a).is_empty());
+        assert_eq!(definition_overrides(source, b).get("unused_parameter"), Some(&LintLevel::Allow));
+    }
+}
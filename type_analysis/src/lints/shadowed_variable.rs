@@ -0,0 +1,77 @@
+use super::{LintContext, LintFinding};
+use program_structure::ast::Statement;
+use program_structure::file_definition::FileID;
+use std::collections::HashSet;
+
+// Declaring twice the same symbol in a block is an error, but a block can
+// declare again a symbol of an enclosing block hiding the outer one.
+pub fn check(context: &LintContext) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let mut definitions = Vec::new();
+    for template in context.reached_templates() {
+        definitions.push((template.get_name(), template.get_file_id(), template.get_name_of_params(), template.get_body()));
+    }
+    for function in context.reached_functions() {
+        definitions.push((function.get_name(), function.get_file_id(), function.get_name_of_params(), function.get_body()));
+    }
+    for (name, file_id, params, body) in definitions {
+        let mut scopes = vec![params.iter().cloned().collect()];
+        let mut state = State { definition: name, file_id, findings: &mut findings };
+        // The body itself is the block of the parameters
+        if let Statement::Block { stmts, .. } = body {
+            for stmt in stmts {
+                visit_statement(stmt, &mut scopes, &mut state);
+            }
+        }
+    }
+    findings
+}
+
+struct State<'a> {
+    definition: &'a str,
+    file_id: FileID,
+    findings: &'a mut Vec<LintFinding>,
+}
+
+fn visit_statement(stmt: &Statement, scopes: &mut Vec<HashSet<String>>, state: &mut State) {
+    use Statement::*;
+    match stmt {
+        Block { stmts, .. } => {
+            scopes.push(HashSet::new());
+            for stmt in stmts {
+                visit_statement(stmt, scopes, state);
+            }
+            scopes.pop();
+        }
+        IfThenElse { if_case, else_case, .. } => {
+            visit_statement(if_case, scopes, state);
+            if let Option::Some(else_case) = else_case {
+                visit_statement(else_case, scopes, state);
+            }
+        }
+        While { stmt, .. } => visit_statement(stmt, scopes, state),
+        InitializationBlock { initializations, .. } => {
+            for stmt in initializations {
+                visit_statement(stmt, scopes, state);
+            }
+        }
+        Declaration { meta, name, is_anonymous, .. } => {
+            // Symbols generated when removing the syntactic sugar are skipped
+            if *is_anonymous || name.starts_with("anon_var_") {
+                return;
+            }
+            let (current, enclosing) = scopes.split_last_mut().unwrap();
+            if enclosing.iter().any(|scope| scope.contains(name)) {
+                state.findings.push(LintFinding {
+                    definition: state.definition.to_string(),
+                    file_id: state.file_id,
+                    location: meta.file_location(),
+                    message: format!("Declaration of {} shadows a previous declaration", name),
+                    label: format!("{} is already declared in an enclosing block", name),
+                });
+            }
+            current.insert(name.clone());
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,224 @@
+use super::visitor::{access_path, visit_statements, visit_subexpressions};
+use super::{LintContext, LintFinding};
+use num_bigint::BigInt;
+use program_structure::ast::{Access, AssignOp, Expression, Meta, Statement, VariableType};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// A signal assigned with <-- only gets a value in the witness. Unless the
+// template adds a constraint over it, any value can be given to it.
+pub fn check(context: &LintContext) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for template in context.reached_templates() {
+        let body = template.get_body();
+        let flow = VarFlow::new(body);
+        let mut assigned: Vec<(SignalAccess, Meta)> = Vec::new();
+        let mut constrained = Vec::new();
+        visit_statements(body, &mut |stmt| match stmt {
+            Statement::Substitution { meta, var, access, op: AssignOp::AssignSignal, .. } => {
+                assigned.push((SignalAccess::new(var, access), meta.clone()));
+            }
+            Statement::Substitution { var, access, op: AssignOp::AssignConstraintSignal, rhe, .. } => {
+                constrained.push(SignalAccess::new(var, access));
+                flow.collect_signals(rhe, &mut constrained);
+            }
+            Statement::ConstraintEquality { lhe, rhe, .. } => {
+                flow.collect_signals(lhe, &mut constrained);
+                flow.collect_signals(rhe, &mut constrained);
+            }
+            _ => {}
+        });
+        // One finding for each signal, at the first assignment that is not constrained
+        let mut unconstrained = BTreeMap::new();
+        for (signal, meta) in assigned {
+            if !constrained.iter().any(|other| signal.overlaps(other)) {
+                unconstrained.entry(signal.path()).or_insert(meta);
+            }
+        }
+        for (signal, meta) in unconstrained {
+            findings.push(LintFinding {
+                definition: template.get_name().to_string(),
+                file_id: template.get_file_id(),
+                location: meta.file_location(),
+                message: format!("Signal {} is assigned with <-- but never constrained", signal),
+                label: format!("{} does not appear in any constraint of template {}", signal, template.get_name()),
+            });
+        }
+    }
+    findings
+}
+
+// Signals whose values reach each var of a template. The flow is computed
+// for the whole body, regardless of the order of the statements, so a var
+// used in a constraint stands for every signal assigned to it at any point.
+struct VarFlow {
+    signals: HashMap<String, Vec<SignalAccess>>,
+}
+
+impl VarFlow {
+    fn new(body: &Statement) -> VarFlow {
+        let mut vars = HashSet::new();
+        visit_statements(body, &mut |stmt| {
+            if let Statement::Declaration { xtype: VariableType::Var, name, .. } = stmt {
+                vars.insert(name.clone());
+            }
+        });
+        // Direct dependencies of each var: the signals and vars in the expressions assigned to it
+        let mut signals: HashMap<String, Vec<SignalAccess>> = HashMap::new();
+        let mut reads: HashMap<String, HashSet<String>> = HashMap::new();
+        visit_statements(body, &mut |stmt| {
+            if let Statement::Substitution { var, op: AssignOp::AssignVar, rhe, .. } = stmt {
+                if !vars.contains(var) {
+                    return;
+                }
+                visit_subexpressions(rhe, &mut |expr| {
+                    if let Expression::Variable { name, access, .. } = expr {
+                        if vars.contains(name) {
+                            reads.entry(var.clone()).or_default().insert(name.clone());
+                        } else {
+                            signals.entry(var.clone()).or_default().push(SignalAccess::new(name, access));
+                        }
+                    }
+                });
+            }
+        });
+        let mut flow = HashMap::new();
+        for var in &vars {
+            let mut reached = HashSet::new();
+            let mut pending = vec![var.clone()];
+            let mut accesses = Vec::new();
+            while let Some(current) = pending.pop() {
+                if !reached.insert(current.clone()) {
+                    continue;
+                }
+                accesses.extend(signals.get(&current).into_iter().flatten().cloned());
+                pending.extend(reads.get(&current).into_iter().flatten().cloned());
+            }
+            flow.insert(var.clone(), accesses);
+        }
+        VarFlow { signals: flow }
+    }
+
+    // Signals in the expression, replacing each var by the signals that reach it
+    fn collect_signals(&self, expr: &Expression, signals: &mut Vec<SignalAccess>) {
+        visit_subexpressions(expr, &mut |expr| {
+            if let Expression::Variable { name, access, .. } = expr {
+                match self.signals.get(name) {
+                    Some(accesses) => signals.extend(accesses.iter().cloned()),
+                    None => signals.push(SignalAccess::new(name, access)),
+                }
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
+enum Selector {
+    Field(String),
+    // Constant indexes are kept, any other index may select any position
+    Index(Option<BigInt>),
+}
+
+// Accessed signal with its array indexes and bus or component fields
+#[derive(Clone)]
+struct SignalAccess {
+    name: String,
+    selectors: Vec<Selector>,
+}
+
+impl SignalAccess {
+    fn new(name: &str, access: &[Access]) -> SignalAccess {
+        let selectors = access
+            .iter()
+            .map(|acc| match acc {
+                Access::ArrayAccess(Expression::Number(_, value)) => Selector::Index(Some(value.clone())),
+                Access::ArrayAccess(_) | Access::ArraySlice(..) => Selector::Index(None),
+                Access::ComponentAccess(field) => Selector::Field(field.clone()),
+            })
+            .collect();
+        SignalAccess { name: name.to_string(), selectors }
+    }
+
+    fn path(&self) -> String {
+        let fields: Vec<Access> = self
+            .selectors
+            .iter()
+            .filter_map(|selector| match selector {
+                Selector::Field(field) => Some(Access::ComponentAccess(field.clone())),
+                Selector::Index(_) => None,
+            })
+            .collect();
+        access_path(&self.name, &fields)
+    }
+
+    // Two accesses overlap unless they select different fields or different
+    // constant indexes. An access to a whole array or bus overlaps with the
+    // accesses to its elements and fields.
+    fn overlaps(&self, other: &SignalAccess) -> bool {
+        if self.name != other.name {
+            return false;
+        }
+        self.selectors.iter().zip(other.selectors.iter()).all(|pair| match pair {
+            (Selector::Field(a), Selector::Field(b)) => a == b,
+            (Selector::Index(Some(a)), Selector::Index(Some(b))) => a == b,
+            (Selector::Index(_), Selector::Index(_)) => true,
+            _ => false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_structure::constants::UsefulConstants;
+
+    fn findings(name: &str, src: &str) -> Vec<String> {
+        let dir = std::env::temp_dir().join(format!("circom_lint_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let circuit = dir.join("main.circom");
+        std::fs::write(&circuit, src).unwrap();
+        let prime = UsefulConstants::new(&"bn128".to_string()).get_p().clone();
+        let path = circuit.to_str().unwrap().to_string();
+        let parsed = parser::run_parser(path, "2.2.2", vec![], &prime, false, None);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let (program, _) = parsed.map_err(|_| ()).unwrap();
+        let reached = program.get_templates().keys().cloned().collect();
+        let context = LintContext { program_archive: &program, reached: &reached };
+        check(&context).into_iter().map(|finding| finding.message).collect()
+    }
+
+    #[test]
+    fn signals_constrained_through_vars_are_not_reported() {
+        let src = r#"
+            template Sum(n) {
+                signal input in[n];
+                signal output out;
+                signal tmp[n];
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    tmp[i] <-- in[i] * 2;
+                }
+                for (var j = 0; j < n; j++) {
+                    sum += tmp[j];
+                }
+                out <== sum;
+            }
+            component main = Sum(4);
+        "#;
+        assert!(findings("accumulator", src).is_empty());
+    }
+
+    #[test]
+    fn constant_indexes_are_compared() {
+        let src = r#"
+            template Pair() {
+                signal input in;
+                signal output out[2];
+                out[0] <-- in;
+                out[1] <-- in;
+                out[0] === in;
+            }
+            component main = Pair();
+        "#;
+        assert_eq!(findings("indexes", src), vec!["Signal out is assigned with <-- but never constrained"]);
+    }
+}
@@ -0,0 +1,68 @@
+use super::visitor::{visit_expressions, visit_statements};
+use super::{LintContext, LintFinding};
+use program_structure::ast::{Access, Expression, Meta, Statement, VariableType};
+use std::collections::{BTreeMap, HashSet};
+
+// Anonymous components are skipped: their unused outputs are explicitly
+// discarded with _ in the code
+pub fn check(context: &LintContext) -> Vec<LintFinding> {
+    let program_archive = context.program_archive;
+    let mut findings = Vec::new();
+    for template in context.reached_templates() {
+        let mut declarations: BTreeMap<String, Meta> = BTreeMap::new();
+        let mut instances: BTreeMap<String, String> = BTreeMap::new();
+        visit_statements(template.get_body(), &mut |stmt| match stmt {
+            Statement::Declaration { meta, xtype: VariableType::Component, name, is_anonymous: false, .. } => {
+                declarations.insert(name.clone(), meta.clone());
+            }
+            Statement::Substitution { var, rhe, .. } => {
+                if let Some(id) = instantiated_template(rhe) {
+                    instances.insert(var.clone(), id.clone());
+                }
+            }
+            _ => {}
+        });
+
+        let mut read = HashSet::new();
+        visit_expressions(template.get_body(), &mut |expr| {
+            if let Expression::Variable { name, access, .. } = expr {
+                let field = access.iter().find_map(|acc| match acc {
+                    Access::ComponentAccess(field) => Some(field),
+                    Access::ArrayAccess(_) | Access::ArraySlice(..) => None,
+                });
+                if let Some(field) = field {
+                    read.insert((name.clone(), field.clone()));
+                }
+            }
+        });
+
+        for (component, meta) in declarations {
+            let id = match instances.get(&component) {
+                Some(id) if program_archive.contains_template(id) => id,
+                _ => continue,
+            };
+            let outputs = program_archive.get_template_data(id).get_declaration_outputs();
+            for (output, _) in outputs {
+                if read.contains(&(component.clone(), output.clone())) {
+                    continue;
+                }
+                findings.push(LintFinding {
+                    definition: template.get_name().to_string(),
+                    file_id: template.get_file_id(),
+                    location: meta.file_location(),
+                    message: format!("Output {} of component {} is never used", output, component),
+                    label: format!("{}.{} is never read in {}", component, output, template.get_name()),
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn instantiated_template(expr: &Expression) -> Option<&String> {
+    match expr {
+        Expression::Call { id, .. } => Some(id),
+        Expression::ParallelOp { rhe, .. } => instantiated_template(rhe),
+        _ => None,
+    }
+}
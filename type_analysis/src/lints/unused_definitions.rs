@@ -0,0 +1,105 @@
+use super::visitor::visit_expressions;
+use super::{LintContext, LintFinding};
+use program_structure::ast::{Expression, Statement};
+use program_structure::file_definition::{FileID, FileLocation};
+use std::collections::HashSet;
+
+// Only the definitions of the main file are reported, as included libraries
+// usually contain more templates and functions than any circuit uses
+pub fn check_definitions(context: &LintContext) -> Vec<LintFinding> {
+    let program_archive = context.program_archive;
+    let main_file = *program_archive.get_file_id_main();
+    let mut definitions = Vec::new();
+    for template in program_archive.get_templates().values() {
+        definitions.push(("Template", template.get_name(), template.get_file_id(), template.get_param_location()));
+    }
+    for function in program_archive.get_functions().values() {
+        definitions.push(("Function", function.get_name(), function.get_file_id(), function.get_param_location()));
+    }
+    let mut findings = Vec::new();
+    for (kind, name, file_id, param_location) in definitions {
+        if file_id != main_file || context.reached.contains(name) {
+            continue;
+        }
+        let location = name_location(context, file_id, param_location.start, name)
+            .unwrap_or(param_location);
+        findings.push(LintFinding {
+            definition: name.to_string(),
+            file_id,
+            location,
+            message: format!("{} {} is never used", kind, name),
+            label: "Not reachable from the main component".to_string(),
+        });
+    }
+    findings
+}
+
+// Parameters starting with _ are considered intentionally unused
+pub fn check_parameters(context: &LintContext) -> Vec<LintFinding> {
+    let mut definitions = Vec::new();
+    for template in context.reached_templates() {
+        definitions.push((template.get_name(), template.get_file_id(), template.get_param_location(), template.get_name_of_params(), template.get_body()));
+    }
+    for function in context.reached_functions() {
+        definitions.push((function.get_name(), function.get_file_id(), function.get_param_location(), function.get_name_of_params(), function.get_body()));
+    }
+    let mut findings = Vec::new();
+    for (name, file_id, param_location, params, body) in definitions {
+        let used = used_symbols(body);
+        for param in params {
+            if param.starts_with('_') || used.contains(param) {
+                continue;
+            }
+            let location = find_identifier(context, file_id, &param_location, param)
+                .unwrap_or_else(|| param_location.clone());
+            findings.push(LintFinding {
+                definition: name.to_string(),
+                file_id,
+                location,
+                message: format!("Parameter {} is never used", param),
+                label: format!("{} is not used in {}", param, name),
+            });
+        }
+    }
+    findings
+}
+
+fn used_symbols(body: &Statement) -> HashSet<String> {
+    let mut used = HashSet::new();
+    visit_expressions(body, &mut |expr| {
+        if let Expression::Variable { name, .. } = expr {
+            used.insert(name.clone());
+        }
+    });
+    used
+}
+
+// The name of a definition is the identifier placed just before its parameters
+fn name_location(context: &LintContext, file_id: FileID, params_start: usize, name: &str) -> Option<FileLocation> {
+    let source = context.program_archive.get_file_library().get_source(file_id)?;
+    let end = source.get(..params_start)?.trim_end().trim_end_matches('(').trim_end().len();
+    let start = end.checked_sub(name.len())?;
+    if source.get(start..end)? == name {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+fn find_identifier(context: &LintContext, file_id: FileID, range: &FileLocation, name: &str) -> Option<FileLocation> {
+    let source = context.program_archive.get_file_library().get_source(file_id)?;
+    let text = source.get(range.clone())?;
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut offset = 0;
+    while let Some(position) = text[offset..].find(name) {
+        let start = offset + position;
+        let end = start + name.len();
+        let before = text[..start].chars().last().is_some_and(is_identifier_char);
+        let after = text[end..].chars().next().is_some_and(is_identifier_char);
+        if !before && !after {
+            return Some(range.start + start..range.start + end);
+        }
+        offset = end;
+    }
+    None
+}
@@ -0,0 +1,131 @@
+use program_structure::ast::{Access, Expression, LogArgument, Statement};
+
+// Calls f on the statement and on every statement nested in it
+pub fn visit_statements(stmt: &Statement, f: &mut dyn FnMut(&Statement)) {
+    use Statement::*;
+    f(stmt);
+    match stmt {
+        IfThenElse { if_case, else_case, .. } => {
+            visit_statements(if_case, f);
+            if let Option::Some(else_case) = else_case {
+                visit_statements(else_case, f);
+            }
+        }
+        While { stmt, .. } => visit_statements(stmt, f),
+        InitializationBlock { initializations, .. } => {
+            for stmt in initializations {
+                visit_statements(stmt, f);
+            }
+        }
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                visit_statements(stmt, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Calls f on every expression, and every subexpression, that appears in the
+// statement or in the statements nested in it
+pub fn visit_expressions(stmt: &Statement, f: &mut dyn FnMut(&Expression)) {
+    use Statement::*;
+    visit_statements(stmt, &mut |stmt| match stmt {
+        IfThenElse { cond, .. } | While { cond, .. } => visit_subexpressions(cond, f),
+        Return { value, .. } => visit_subexpressions(value, f),
+        Declaration { dimensions, .. } => {
+            for dimension in dimensions {
+                visit_subexpressions(dimension, f);
+            }
+        }
+        Substitution { access, rhe, .. } => {
+            visit_accesses(access, f);
+            visit_subexpressions(rhe, f);
+        }
+        MultSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+            visit_subexpressions(lhe, f);
+            visit_subexpressions(rhe, f);
+        }
+        UnderscoreSubstitution { rhe, .. } => visit_subexpressions(rhe, f),
+        LogCall { args, .. } => {
+            for arg in args {
+                if let LogArgument::LogExp(arg) = arg {
+                    visit_subexpressions(arg, f);
+                }
+            }
+        }
+        Assert { arg, message, .. } => {
+            visit_subexpressions(arg, f);
+            for arg in message {
+                if let LogArgument::LogExp(arg) = arg {
+                    visit_subexpressions(arg, f);
+                }
+            }
+        }
+        InitializationBlock { .. } | Block { .. } => {}
+    });
+}
+
+pub fn visit_subexpressions(expr: &Expression, f: &mut dyn FnMut(&Expression)) {
+    use Expression::*;
+    f(expr);
+    match expr {
+        InfixOp { lhe, rhe, .. } => {
+            visit_subexpressions(lhe, f);
+            visit_subexpressions(rhe, f);
+        }
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => visit_subexpressions(rhe, f),
+        InlineSwitchOp { cond, if_true, if_false, .. } => {
+            visit_subexpressions(cond, f);
+            visit_subexpressions(if_true, f);
+            visit_subexpressions(if_false, f);
+        }
+        Variable { access, .. } => visit_accesses(access, f),
+        Number(..) => {}
+        Call { args, .. } | BusCall { args, .. } => {
+            for arg in args {
+                visit_subexpressions(arg, f);
+            }
+        }
+        AnonymousComp { params, signals, .. } => {
+            for arg in params.iter().chain(signals.iter()) {
+                visit_subexpressions(arg, f);
+            }
+        }
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
+            for value in values {
+                visit_subexpressions(value, f);
+            }
+        }
+        UniformArray { value, dimension, .. } => {
+            visit_subexpressions(value, f);
+            visit_subexpressions(dimension, f);
+        }
+    }
+}
+
+fn visit_accesses(access: &[Access], f: &mut dyn FnMut(&Expression)) {
+    for acc in access {
+        match acc {
+            Access::ArrayAccess(index) => visit_subexpressions(index, f),
+            Access::ArraySlice(start, length) => {
+                visit_subexpressions(start, f);
+                visit_subexpressions(length, f);
+            }
+            Access::ComponentAccess(_) => {}
+        }
+    }
+}
+
+// Name of the accessed symbol followed by the fields selected with `.`,
+// ignoring the array indexes: `c[i].out[0]` gives `c.out`
+pub fn access_path(name: &str, access: &[Access]) -> String {
+    let mut path = name.to_string();
+    for acc in access {
+        if let Access::ComponentAccess(field) = acc {
+            path.push('.');
+            path.push_str(field);
+        }
+    }
+    path
+}